@@ -0,0 +1,454 @@
+//! Checking, describing and generating questions files for unattended installs
+//! (`ins arch exec -f <file>`).
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde_json::{Map, Value, json};
+
+use crate::arch::engine::{InstallContext, Question, QuestionId, SystemInfo};
+use crate::arch::execution::restore;
+
+/// Required by the wizard but not by `ins arch exec`: the mirrorlist falls back to the
+/// default mirrors, and cfdisk only runs interactively.
+const NOT_REQUIRED_IN_FILES: &[QuestionId] = &[QuestionId::MirrorRegion, QuestionId::RunCfdisk];
+
+/// Answers that name block devices on the machine running the install
+const DEVICE_ANSWERS: &[QuestionId] = &[
+    QuestionId::Disk,
+    QuestionId::RootPartition,
+    QuestionId::BootPartition,
+    QuestionId::SwapPartition,
+    QuestionId::HomePartition,
+    QuestionId::DualBootPartition,
+    QuestionId::SecondDisk,
+];
+
+#[derive(Debug, Default)]
+pub(super) struct AnswerReport {
+    pub errors: Vec<String>,
+    /// Answers to questions that do not apply with the other answers
+    pub ignored: Vec<QuestionId>,
+}
+
+/// Check every answer with the validator of its question and report questions that
+/// would have been asked but have no answer.
+pub(super) fn validate_answers(
+    context: &InstallContext,
+    questions: &[Box<dyn Question>],
+    check_devices: bool,
+) -> AnswerReport {
+    let mut report = AnswerReport::default();
+
+    for question in questions.iter().filter(|q| !q.is_info_only()) {
+        let id = question.id();
+        let applies = question.should_ask(context);
+        match context.get_answer(&id) {
+            Some(answer) if applies => {
+                if let Err(message) = question.validate(context, answer) {
+                    report.errors.push(format!("{:?}: {}", id, message));
+                } else if check_devices
+                    && DEVICE_ANSWERS.contains(&id)
+                    && !std::path::Path::new(answer).exists()
+                {
+                    report.errors.push(format!(
+                        "{:?}: {} does not exist on this machine.",
+                        id, answer
+                    ));
+                } else if check_devices
+                    && let Some(path) = restore::local_path(&id, answer)
+                    && !path.exists()
+                {
+                    report.errors.push(format!(
+                        "{:?}: {} does not exist on this machine.",
+                        id, answer
+                    ));
+                }
+            }
+            Some(_) => report.ignored.push(id),
+            None if applies
+                && !question.is_optional()
+                && !NOT_REQUIRED_IN_FILES.contains(&id)
+                && question.get_default(context).is_none() =>
+            {
+                report.errors.push(format!(
+                    "{:?}: missing answer ({}).",
+                    id,
+                    question.description().unwrap_or("required")
+                ));
+            }
+            None => {}
+        }
+    }
+
+    report
+}
+
+pub(super) fn handle_validate_command(
+    file: std::path::PathBuf,
+    offline: bool,
+    questions: Vec<Box<dyn Question>>,
+) -> Result<()> {
+    let context = InstallContext::load(&file)
+        .with_context(|| format!("Failed to load questions file {}", file.display()))?;
+    let report = validate_answers(&context, &questions, !offline);
+
+    for id in &report.ignored {
+        println!(
+            "Note: {:?} does not apply with the other answers and is ignored.",
+            id
+        );
+    }
+    for error in &report.errors {
+        println!("Error: {}", error);
+    }
+
+    if !report.errors.is_empty() {
+        anyhow::bail!(
+            "{} has {} invalid or missing answer(s)",
+            file.display(),
+            report.errors.len()
+        );
+    }
+    println!("{} is valid.", file.display());
+    Ok(())
+}
+
+/// JSON Schema of the questions file, with one property per question
+pub(super) fn answers_schema(questions: &[Box<dyn Question>]) -> Value {
+    let empty = InstallContext::new();
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for id in QuestionId::value_variants() {
+        let name = format!("{:?}", id);
+        let mut property = Map::new();
+        property.insert("type".into(), json!("string"));
+        if let Some(question) = questions.iter().find(|q| &q.id() == id) {
+            if let Some(description) = question.description() {
+                property.insert("description".into(), json!(description));
+            }
+            let allowed = question.allowed_answers();
+            if !allowed.is_empty() {
+                property.insert("enum".into(), json!(allowed));
+            }
+            if question.is_sensitive() {
+                property.insert("writeOnly".into(), json!(true));
+            }
+            if question.should_ask(&empty)
+                && !question.is_optional()
+                && !question.is_info_only()
+                && !NOT_REQUIRED_IN_FILES.contains(id)
+                && question.get_default(&empty).is_none()
+            {
+                required.push(name.clone());
+            }
+        }
+        properties.insert(name, Value::Object(property));
+    }
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "instantOS installer questions file",
+        "description": "Answers for `ins arch exec -f <file>`, written as TOML",
+        "type": "object",
+        "required": ["answers", "system_info"],
+        "properties": {
+            "answers": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": properties,
+                "required": required,
+                "allOf": [
+                    {
+                        "if": {
+                            "properties": { "UseEncryption": { "enum": ["yes", "true"] } },
+                            "required": ["UseEncryption"]
+                        },
+                        "then": { "required": ["EncryptionPassword"] }
+                    },
+                    {
+                        "if": {
+                            "properties": { "PartitioningMethod": { "pattern": "Manual" } },
+                            "required": ["PartitioningMethod"]
+                        },
+                        "then": { "required": ["RootPartition", "BootPartition"] }
+                    },
+                    {
+                        "if": {
+                            "properties": {
+                                "DiskLayout": { "enum": ["btrfs-raid1", "home-disk"] }
+                            },
+                            "required": ["DiskLayout"]
+                        },
+                        "then": { "required": ["SecondDisk"] }
+                    }
+                ]
+            },
+            "system_info": system_info_schema()
+        }
+    })
+}
+
+fn system_info_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "The machine being installed to, as detected by `ins arch install`",
+        "required": [
+            "boot_mode",
+            "has_amd_cpu",
+            "has_intel_cpu",
+            "gpus",
+            "internet_connected",
+            "architecture",
+            "distro"
+        ],
+        "properties": {
+            "boot_mode": { "enum": ["UEFI64", "UEFI32", "BIOS"] },
+            "has_amd_cpu": { "type": "boolean" },
+            "has_intel_cpu": { "type": "boolean" },
+            "gpus": {
+                "type": "array",
+                "items": {
+                    "oneOf": [
+                        { "enum": ["Nvidia", "Amd", "Intel"] },
+                        {
+                            "type": "object",
+                            "properties": { "Other": { "type": "string" } },
+                            "required": ["Other"],
+                            "additionalProperties": false
+                        }
+                    ]
+                }
+            },
+            "vm_type": { "type": "string" },
+            "internet_connected": { "type": "boolean" },
+            "architecture": { "type": "string" },
+            "distro": { "type": "string" },
+            "total_ram_gb": { "type": "integer", "minimum": 0 },
+            "disk_image": { "type": "boolean" }
+        }
+    })
+}
+
+pub(super) fn handle_schema_command(questions: Vec<Box<dyn Question>>) -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&answers_schema(&questions))?
+    );
+    Ok(())
+}
+
+/// Placeholder answers for questions without a default or a fixed set of answers
+fn example_answer(id: &QuestionId) -> Option<&'static str> {
+    Some(match id {
+        QuestionId::Hostname => "instantos",
+        QuestionId::Username => "instant",
+        QuestionId::Password | QuestionId::EncryptionPassword => "changeme",
+        QuestionId::Keymap => "us",
+        QuestionId::Disk => "/dev/sda",
+        QuestionId::SecondDisk => "/dev/sdb",
+        QuestionId::MirrorRegion => "Germany",
+        QuestionId::Timezone => "Europe/Berlin",
+        QuestionId::Locale => "en_US.UTF-8",
+        QuestionId::BootPartition => "/dev/sda1",
+        QuestionId::RootPartition => "/dev/sda2",
+        QuestionId::SwapPartition => "/dev/sda3",
+        QuestionId::HomePartition => "/dev/sda4",
+        QuestionId::DualBootPartition => "/dev/sda3",
+        // Size of the new Linux partition in bytes
+        QuestionId::DualBootSize => "68719476736",
+        _ => return None,
+    })
+}
+
+/// A commented example questions file. Questions that do not apply with the
+/// example answers are included commented out.
+pub(super) fn render_template(questions: &[Box<dyn Question>], system_info: SystemInfo) -> String {
+    let mut context = InstallContext::new();
+    context.system_info = system_info;
+
+    let mut out = String::from(
+        "# instantOS installer questions file\n\
+         #\n\
+         # Install with:   ins arch exec -f <file>\n\
+         # Check it with:  ins arch validate <file>\n\
+         # JSON Schema:    ins arch schema\n\
+         \n\
+         [answers]\n",
+    );
+
+    for question in questions.iter().filter(|q| !q.is_info_only()) {
+        let id = question.id();
+        if id == QuestionId::RunCfdisk {
+            continue;
+        }
+        let value = question
+            .get_default(&context)
+            .or_else(|| question.allowed_answers().first().map(|s| s.to_string()))
+            .or_else(|| example_answer(&id).map(str::to_string));
+        let Some(value) = value else {
+            continue;
+        };
+
+        out.push('\n');
+        if let Some(description) = question.description() {
+            out.push_str(&format!("# {}\n", description));
+        }
+        let allowed = question.allowed_answers();
+        if !allowed.is_empty() {
+            out.push_str(&format!("# One of: {}\n", allowed.join(", ")));
+        }
+        if question.is_sensitive() {
+            out.push_str("# Stored in plain text; keep this file private\n");
+        }
+        if question.is_optional() {
+            out.push_str("# Optional\n");
+        }
+
+        let line = format!("{:?} = {}\n", id, toml::Value::String(value.clone()));
+        if question.should_ask(&context) {
+            out.push_str(&line);
+            context.set_answer(id, value);
+        } else {
+            out.push_str("# Not used with the answers above\n# ");
+            out.push_str(&line);
+        }
+    }
+
+    let system_info = toml::to_string_pretty(&context.system_info).unwrap_or_default();
+    out.push_str(
+        "\n# The machine being installed to. Detected on the machine this template was\n\
+         # generated on; adjust it when installing elsewhere.\n\
+         [system_info]\n",
+    );
+    out.push_str(&system_info);
+    out
+}
+
+pub(super) fn handle_template_command(questions: Vec<Box<dyn Question>>) -> Result<()> {
+    print!("{}", render_template(&questions, SystemInfo::detect()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::engine::BootMode;
+
+    fn questions() -> Vec<Box<dyn Question>> {
+        super::super::build_questions()
+    }
+
+    fn valid_context() -> InstallContext {
+        let mut context = InstallContext::new();
+        context.system_info.boot_mode = BootMode::UEFI64;
+        for (id, answer) in [
+            (QuestionId::Keymap, "us"),
+            (QuestionId::Disk, "/dev/nvme9n1"),
+            (QuestionId::PartitioningMethod, "Automatic (Erase Disk)"),
+            (QuestionId::Hostname, "box"),
+            (QuestionId::Username, "alice"),
+            (QuestionId::Password, "secret"),
+            (QuestionId::UseEncryption, "no"),
+            (QuestionId::Timezone, "Europe/Berlin"),
+            (QuestionId::Locale, "en_US.UTF-8"),
+            (QuestionId::Bootloader, "systemd-boot"),
+        ] {
+            context.set_answer(id, answer.to_string());
+        }
+        context
+    }
+
+    #[test]
+    fn validates_answers_with_the_question_validators() {
+        let questions = questions();
+        let report = validate_answers(&valid_context(), &questions, false);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+        let mut context = valid_context();
+        context.set_answer(QuestionId::Hostname, "my box".to_string());
+        context.set_answer(QuestionId::Username, "root".to_string());
+        context.set_answer(QuestionId::UseEncryption, "yes".to_string());
+        context.set_answer(QuestionId::Kernel, "linux-rt".to_string());
+        context.set_answer(QuestionId::HomePartition, "/dev/nvme9n1p4".to_string());
+        let report = validate_answers(&context, &questions, false);
+        assert_eq!(
+            report.errors,
+            [
+                "Hostname: Hostname cannot contain spaces.",
+                "Username: Username cannot be 'root'.",
+                "EncryptionPassword: missing answer (Set the disk encryption password).",
+                "Kernel: You must select a kernel.",
+            ]
+        );
+        assert_eq!(report.ignored, [QuestionId::HomePartition]);
+
+        let mut context = valid_context();
+        context.set_answer(
+            QuestionId::PartitioningMethod,
+            "Manual (cfdisk)".to_string(),
+        );
+        context.answers.remove(&QuestionId::Hostname);
+        let errors = validate_answers(&context, &questions, false).errors;
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("RootPartition: missing"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("BootPartition: missing"))
+        );
+        assert!(errors.iter().any(|e| e.starts_with("Hostname: missing")));
+        assert!(!errors.iter().any(|e| e.starts_with("RunCfdisk")));
+
+        let mut context = valid_context();
+        context.set_answer(QuestionId::Disk, "/dev/does-not-exist".to_string());
+        assert_eq!(
+            validate_answers(&context, &questions, true).errors,
+            ["Disk: /dev/does-not-exist does not exist on this machine."]
+        );
+    }
+
+    #[test]
+    fn schema_lists_every_question() {
+        let schema = answers_schema(&questions());
+        let answers = &schema["properties"]["answers"];
+        assert_eq!(
+            answers["properties"].as_object().unwrap().len(),
+            QuestionId::value_variants().len()
+        );
+        assert_eq!(
+            answers["properties"]["Bootloader"]["enum"],
+            json!(["grub", "systemd-boot", "limine"])
+        );
+        assert_eq!(answers["properties"]["Password"]["writeOnly"], json!(true));
+        let required = answers["required"].as_array().unwrap();
+        assert!(required.contains(&json!("Hostname")));
+        assert!(!required.contains(&json!("EncryptionPassword")));
+        assert!(!required.contains(&json!("Kernel")));
+    }
+
+    #[test]
+    fn template_is_a_valid_questions_file() {
+        let questions = questions();
+        let template = render_template(&questions, SystemInfo::default());
+        assert!(
+            template.contains("# Set the system's network hostname\nHostname = \"instantos\"\n")
+        );
+        assert!(template.contains("\n# RootPartition = \"/dev/sda2\"\n"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("questions.toml");
+        std::fs::write(&path, &template).unwrap();
+        let mut context = InstallContext::load(&path).unwrap();
+        assert_eq!(context.get_answer(&QuestionId::Disk).unwrap(), "/dev/sda");
+        // The disk validator refuses the disk the test machine runs from
+        context.set_answer(QuestionId::Disk, "/dev/nvme9n1".to_string());
+        assert!(context.get_answer(&QuestionId::RootPartition).is_none());
+        let report = validate_answers(&context, &questions, false);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.ignored.is_empty());
+    }
+}
@@ -0,0 +1,22 @@
+use anyhow::{Context, Result};
+
+use crate::arch::engine::InstallContext;
+
+use super::super::utils::ensure_root;
+
+pub(super) fn handle_cache_build_command(
+    questions_file: std::path::PathBuf,
+    output: std::path::PathBuf,
+    dry_run: bool,
+) -> Result<()> {
+    // pacman refuses to download packages unless run as root
+    if !dry_run {
+        ensure_root()?;
+    }
+
+    let context = InstallContext::load(&questions_file)
+        .with_context(|| format!("Failed to load questions file {}", questions_file.display()))?;
+
+    let executor = crate::arch::execution::CommandExecutor::new(dry_run, None);
+    crate::arch::offline::build_cache(&context, &output, &executor)
+}
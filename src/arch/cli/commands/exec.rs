@@ -6,6 +6,8 @@ pub(super) async fn handle_exec_command(
     step: Option<String>,
     questions_file: std::path::PathBuf,
     dry_run: bool,
+    resume: bool,
+    image: Option<crate::arch::execution::image::ImageOptions>,
 ) -> Result<()> {
     if !dry_run {
         ensure_root()?;
@@ -21,5 +23,13 @@ pub(super) async fn handle_exec_command(
         None
     };
 
-    crate::arch::execution::execute_installation(questions_file, step, dry_run, log_file).await
+    crate::arch::execution::execute_installation(
+        questions_file,
+        step,
+        dry_run,
+        resume,
+        image,
+        log_file,
+    )
+    .await
 }
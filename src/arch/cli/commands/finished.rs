@@ -58,6 +58,18 @@ pub(super) async fn handle_finished_command() -> Result<()> {
         }
     }
 
+    if !state.warnings.is_empty() {
+        println!(
+            "\n{}",
+            "Some parts of the installation need attention:"
+                .yellow()
+                .bold()
+        );
+        for warning in &state.warnings {
+            println!("  {} {}", NerdFont::Warning, warning);
+        }
+    }
+
     println!();
 
     let options = vec![
@@ -78,6 +78,10 @@ pub(super) async fn handle_install_command(debug: bool) -> Result<()> {
             step: None,
             questions_file: std::path::PathBuf::from(DEFAULT_QUESTIONS_FILE),
             dry_run: false,
+            resume: false,
+            image: None,
+            size: "20G".to_string(),
+            qcow2: false,
         },
         debug,
     ))
@@ -1,4 +1,6 @@
+mod answers;
 mod ask;
+mod cache;
 mod dualboot;
 mod exec;
 mod finished;
@@ -9,11 +11,13 @@ mod upload_logs;
 
 use anyhow::Result;
 
-use crate::arch::cli::{ArchCommands, DualbootCommands};
+use crate::arch::cli::{ArchCommands, CacheCommands, DualbootCommands};
 use crate::arch::engine::Question;
 use crate::common::distro::OperatingSystem;
 
+use self::answers::{handle_schema_command, handle_template_command, handle_validate_command};
 use self::ask::{AskOutcome, handle_ask_command};
+use self::cache::handle_cache_build_command;
 use self::dualboot::handle_dualboot_info;
 use self::exec::handle_exec_command;
 use self::finished::handle_finished_command;
@@ -25,8 +29,13 @@ use self::upload_logs::handle_upload_logs;
 pub async fn handle_arch_command(command: ArchCommands, debug: bool) -> Result<()> {
     let os = OperatingSystem::detect();
 
-    // Only warn about non-Arch distros for commands other than Info
-    if !os.in_family(&OperatingSystem::Arch) && !matches!(command, ArchCommands::Info) {
+    // Only warn about non-Arch distros for commands that touch this system
+    if !os.in_family(&OperatingSystem::Arch)
+        && !matches!(
+            command,
+            ArchCommands::Info | ArchCommands::Schema | ArchCommands::Template
+        )
+    {
         eprintln!(
             "Warning: You appear to be running on {}, but this command is intended for Arch Linux.",
             os
@@ -53,12 +62,39 @@ pub async fn handle_arch_command(command: ArchCommands, debug: bool) -> Result<(
             step,
             questions_file,
             dry_run,
-        } => handle_exec_command(step, questions_file, dry_run).await,
+            resume,
+            image,
+            size,
+            qcow2,
+        } => {
+            let image = image
+                .map(|path| {
+                    Ok::<_, anyhow::Error>(crate::arch::execution::image::ImageOptions {
+                        path,
+                        size: crate::arch::execution::image::parse_size(&size)?,
+                        qcow2,
+                    })
+                })
+                .transpose()?;
+            handle_exec_command(step, questions_file, dry_run, resume, image).await
+        }
+        ArchCommands::Validate { file, offline } => {
+            handle_validate_command(file, offline, questions)
+        }
+        ArchCommands::Schema => handle_schema_command(questions),
+        ArchCommands::Template => handle_template_command(questions),
         ArchCommands::UploadLogs { path } => handle_upload_logs(path),
         ArchCommands::Info => handle_info_command(),
         ArchCommands::Dualboot { command } => match command {
             DualbootCommands::Info => handle_dualboot_info().await,
         },
+        ArchCommands::Cache { command } => match command {
+            CacheCommands::Build {
+                questions_file,
+                output,
+                dry_run,
+            } => handle_cache_build_command(questions_file, output, dry_run),
+        },
         ArchCommands::Finished => handle_finished_command().await,
         ArchCommands::Setup { user, dry_run } => handle_setup_command(user, dry_run).await,
     }
@@ -70,16 +106,17 @@ pub(super) fn build_questions() -> Vec<Box<dyn Question>> {
         DualBootPartitionQuestion, DualBootSizeQuestion, EncryptionPasswordQuestion,
         EspPartitionValidator, HostnameQuestion, KernelQuestion, KeymapQuestion, LocaleQuestion,
         MirrorRegionQuestion, PartitionSelectorQuestion, PartitioningMethodQuestion,
-        PasswordQuestion, ResizeInstructionsQuestion, RunCfdiskQuestion, TimezoneQuestion,
-        UsernameQuestion, VirtualBoxWarning, WeakPasswordWarning,
+        PasswordQuestion, ResizeInstructionsQuestion, RestoreGamePasswordQuestion, RestoreQuestion,
+        RunCfdiskQuestion, TimezoneQuestion, UsernameQuestion, VirtualBoxWarning,
+        WeakPasswordWarning,
     };
     use crate::arch::questions::{
-        BtrfsCompressionQuestion, DisplayManagerQuestion, RootFilesystemQuestion,
+        BootloaderQuestion, BtrfsCompressionQuestion, DiskLayoutQuestion, DisplayManagerQuestion,
+        RootFilesystemQuestion, SecondDiskQuestion, SwapTypeQuestion,
     };
 
     vec![
         Box::new(VirtualBoxWarning),
-        Box::new(crate::arch::questions::warnings::LowRamWarning),
         Box::new(KeymapQuestion),
         Box::new(DiskQuestion),
         Box::new(PartitioningMethodQuestion),
@@ -149,9 +186,43 @@ pub(super) fn build_questions() -> Vec<Box<dyn Question>> {
         Box::new(TimezoneQuestion),
         Box::new(LocaleQuestion),
         Box::new(KernelQuestion),
+        Box::new(BootloaderQuestion),
         Box::new(DesktopEnvironmentQuestion),
         Box::new(RootFilesystemQuestion),
         Box::new(BtrfsCompressionQuestion),
+        Box::new(
+            BooleanQuestion::new(
+                crate::arch::engine::QuestionId::BtrfsSnapshots,
+                "Enable btrfs snapshots with snapper (bootable from the boot menu)?",
+                crate::ui::nerd_font::NerdFont::HardDrive,
+            )
+            .optional()
+            .default_yes()
+            .should_ask(|context| {
+                crate::arch::config::RootFilesystem::from_context(context).is_btrfs()
+            }),
+        ),
+        Box::new(DiskLayoutQuestion),
+        Box::new(SecondDiskQuestion),
+        Box::new(SwapTypeQuestion),
+        // After the swap type, so the no-swap case sees its answer or default
+        Box::new(crate::arch::questions::warnings::LowRamWarning),
+        Box::new(
+            BooleanQuestion::new(
+                crate::arch::engine::QuestionId::Hibernation,
+                "Set up hibernation (suspend to disk)?",
+                crate::ui::nerd_font::NerdFont::Moon,
+            )
+            .optional()
+            .should_ask(|context| {
+                crate::arch::config::SwapType::from_context(context)
+                    .supports_hibernation(crate::arch::config::DiskLayout::from_context(context))
+            })
+            .dynamic_default(|context| {
+                // The swap partition is sized from RAM, so it can hold a hibernation image
+                crate::arch::config::SwapType::from_context(context).is_partition()
+            }),
+        ),
         Box::new(DisplayManagerQuestion),
         Box::new(
             BooleanQuestion::new(
@@ -194,5 +265,9 @@ pub(super) fn build_questions() -> Vec<Box<dyn Question>> {
             )
             .optional(),
         ),
+        Box::new(RestoreQuestion::dotfiles()),
+        Box::new(RestoreQuestion::package_list()),
+        Box::new(RestoreQuestion::game_repo()),
+        Box::new(RestoreGamePasswordQuestion),
     ]
 }
@@ -13,6 +13,22 @@ pub enum DualbootCommands {
     Info,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum CacheCommands {
+    /// Download every package a questions file needs into a local repository
+    Build {
+        /// Path to the questions TOML file
+        #[arg(short = 'f', long = "questions-file", default_value = DEFAULT_QUESTIONS_FILE)]
+        questions_file: std::path::PathBuf,
+        /// Directory to write the repository to
+        #[arg(short, long, default_value = crate::arch::offline::REPO_DIR_NAME)]
+        output: std::path::PathBuf,
+        /// Run in dry-run mode (no changes will be made)
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum ArchCommands {
     /// Start the Arch Linux installation wizard
@@ -39,7 +55,33 @@ pub enum ArchCommands {
         /// Run in dry-run mode (no changes will be made)
         #[arg(long)]
         dry_run: bool,
+        /// Continue a failed installation: report what earlier attempts changed and
+        /// restore their mounts (reopening LUKS) before running the remaining steps
+        #[arg(long)]
+        resume: bool,
+        /// Install into this disk image file instead of the selected disk
+        #[arg(long, value_name = "FILE")]
+        image: Option<std::path::PathBuf>,
+        /// Size of the sparse image created for --image (e.g. 20G)
+        #[arg(long, default_value = "20G", requires = "image")]
+        size: String,
+        /// Also convert the finished image to qcow2 (needs qemu-img)
+        #[arg(long, requires = "image")]
+        qcow2: bool,
+    },
+    /// Check a questions file with the same rules as the interactive questions
+    Validate {
+        /// Path to the questions TOML file
+        #[arg(default_value = DEFAULT_QUESTIONS_FILE)]
+        file: std::path::PathBuf,
+        /// Skip checking that the disk and partitions exist on this machine
+        #[arg(long)]
+        offline: bool,
     },
+    /// Print the JSON Schema of questions files
+    Schema,
+    /// Print a commented example questions file
+    Template,
     /// Show installation finished menu
     Finished,
     /// Setup instantOS on an existing Arch Linux installation
@@ -64,4 +106,9 @@ pub enum ArchCommands {
         #[command(subcommand)]
         command: DualbootCommands,
     },
+    /// Local package repositories for offline installs
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
 }
@@ -1,8 +1,25 @@
-use crate::arch::engine::{InstallContext, QuestionId};
+use crate::arch::engine::{BootMode, InstallContext, QuestionId};
 
 /// Subvolume layout created for btrfs installations.
 pub const BTRFS_ROOT_SUBVOLUME: &str = "@";
 pub const BTRFS_HOME_SUBVOLUME: &str = "@home";
+/// No-copy-on-write subvolume holding the swap file, mounted at `/swap`
+pub const BTRFS_SWAP_SUBVOLUME: &str = "@swap";
+
+/// Subvolumes added for snapper, with their mount points. Snapshots live outside `@`
+/// so a rollback does not delete them, and logs and caches are left out of root
+/// snapshots so they survive a rollback too.
+pub const BTRFS_SNAPSHOT_SUBVOLUMES: &[(&str, &str)] = &[
+    ("@snapshots", "/.snapshots"),
+    ("@var_log", "/var/log"),
+    ("@cache", "/var/cache"),
+];
+
+/// Whether the snapper layout, snapper config and bootable snapshots are set up.
+pub fn btrfs_snapshots_enabled(context: &InstallContext) -> bool {
+    RootFilesystem::from_context(context).is_btrfs()
+        && context.get_answer_bool(QuestionId::BtrfsSnapshots)
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DesktopEnvironment {
@@ -10,18 +27,36 @@ pub enum DesktopEnvironment {
     Niri,
     InstantWM,
     Hyprland,
+    Plasma,
+    Gnome,
+    Xfce,
     Tty,
 }
 
 impl DesktopEnvironment {
     pub const DEFAULT: Self = Self::Sway;
 
+    /// Every choice, in the order the installer lists them
+    pub const ALL: [Self; 8] = [
+        Self::Sway,
+        Self::Niri,
+        Self::InstantWM,
+        Self::Hyprland,
+        Self::Plasma,
+        Self::Gnome,
+        Self::Xfce,
+        Self::Tty,
+    ];
+
     pub fn from_answer(answer: &str) -> Self {
         match answer {
             "sway" => Self::Sway,
             "niri" => Self::Niri,
             "instantwm" => Self::InstantWM,
             "hyprland" => Self::Hyprland,
+            "plasma" => Self::Plasma,
+            "gnome" => Self::Gnome,
+            "xfce" => Self::Xfce,
             "none/tty" => Self::Tty,
             _ => Self::DEFAULT,
         }
@@ -40,6 +75,9 @@ impl DesktopEnvironment {
             Self::Niri => "niri",
             Self::InstantWM => "instantwm",
             Self::Hyprland => "hyprland",
+            Self::Plasma => "plasma",
+            Self::Gnome => "gnome",
+            Self::Xfce => "xfce",
             Self::Tty => "none/tty",
         }
     }
@@ -50,6 +88,9 @@ impl DesktopEnvironment {
             Self::Niri => "niri",
             Self::InstantWM => "instantWM",
             Self::Hyprland => "Hyprland",
+            Self::Plasma => "KDE Plasma",
+            Self::Gnome => "GNOME",
+            Self::Xfce => "Xfce",
             Self::Tty => "None / TTY",
         }
     }
@@ -60,6 +101,9 @@ impl DesktopEnvironment {
             Self::Niri => Some("niri"),
             Self::InstantWM => Some("instantwm"),
             Self::Hyprland => Some("hyprland"),
+            Self::Plasma => Some("plasma"),
+            Self::Gnome => Some("gnome"),
+            Self::Xfce => Some("xfce"),
             Self::Tty => None,
         }
     }
@@ -70,6 +114,10 @@ impl DesktopEnvironment {
             Self::Niri => &["niri"],
             Self::InstantWM => &[],
             Self::Hyprland => &["hyprland", "hypridle", "hyprlock"],
+            Self::Plasma => &["plasma-meta", "konsole", "dolphin"],
+            Self::Gnome => &["gnome"],
+            // Xfce is an X11 session; the other desktops only need Xwayland
+            Self::Xfce => &["xfce4", "xfce4-goodies", "xorg-server"],
             Self::Tty => &[],
         }
     }
@@ -78,9 +126,10 @@ impl DesktopEnvironment {
     ///
     /// These ensure that fonts referenced in the DE's config templates
     /// (bars, window titles, terminal emulators, etc.) are available.
+    /// Full desktops bring their own fonts.
     pub fn font_packages(&self) -> &'static [&'static str] {
         match self {
-            Self::Tty => &[],
+            Self::Tty | Self::Plasma | Self::Gnome | Self::Xfce => &[],
             Self::Sway | Self::Niri | Self::InstantWM | Self::Hyprland => {
                 &["ttf-jetbrains-mono-nerd", "inter-font"]
             }
@@ -90,6 +139,34 @@ impl DesktopEnvironment {
     pub fn requires_display_manager(&self) -> bool {
         !matches!(self, Self::Tty)
     }
+
+    /// `XDG_CURRENT_DESKTOP` of full desktops, which bring their own panel,
+    /// settings and session startup that the instantOS dotfiles do not configure
+    pub fn xdg_desktop_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Plasma => Some("KDE"),
+            Self::Gnome => Some("GNOME"),
+            Self::Xfce => Some("XFCE"),
+            _ => None,
+        }
+    }
+
+    /// Display manager installed when none was chosen
+    pub fn default_display_manager(&self) -> DisplayManager {
+        match self {
+            Self::Plasma => DisplayManager::Sddm,
+            Self::Xfce => DisplayManager::Lightdm,
+            _ => DisplayManager::DEFAULT,
+        }
+    }
+
+    /// GNOME's lock screen and session integration only work with gdm
+    pub fn supports_display_manager(&self, display_manager: DisplayManager) -> bool {
+        match self {
+            Self::Gnome => display_manager == DisplayManager::Gdm,
+            _ => true,
+        }
+    }
 }
 
 /// Root filesystem choice for the installation.
@@ -201,46 +278,358 @@ impl BtrfsCompression {
 pub enum DisplayManager {
     Gdm,
     Lightdm,
+    Sddm,
 }
 
 impl DisplayManager {
-    /// gdm is the default.
+    /// gdm is the default for desktops without a display manager of their own.
     pub const DEFAULT: Self = Self::Gdm;
 
+    pub const ALL: [Self; 3] = [Self::Gdm, Self::Sddm, Self::Lightdm];
+
     pub fn from_answer(answer: &str) -> Self {
         match answer {
             "gdm" => Self::Gdm,
             "lightdm" => Self::Lightdm,
+            "sddm" => Self::Sddm,
             _ => Self::DEFAULT,
         }
     }
 
+    /// The chosen display manager, or the selected desktop's default
     pub fn from_context(context: &InstallContext) -> Self {
         context
             .get_answer(&QuestionId::DisplayManager)
             .map(|answer| Self::from_answer(answer))
-            .unwrap_or(Self::DEFAULT)
+            .unwrap_or_else(|| DesktopEnvironment::from_context(context).default_display_manager())
     }
 
     pub fn answer_value(&self) -> &'static str {
         match self {
             Self::Gdm => "gdm",
             Self::Lightdm => "lightdm",
+            Self::Sddm => "sddm",
         }
     }
 
     pub fn label(&self) -> &'static str {
         match self {
-            Self::Gdm => "gdm (default)",
+            Self::Gdm => "gdm",
             Self::Lightdm => "lightdm",
+            Self::Sddm => "sddm",
+        }
+    }
+}
+
+/// Bootloader installed on the target system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bootloader {
+    Grub,
+    SystemdBoot,
+    Limine,
+}
+
+impl Bootloader {
+    /// GRUB works in every boot mode and handles dual boot via os-prober.
+    pub const DEFAULT: Self = Self::Grub;
+
+    /// Free space a dual-boot ESP needs when it holds the kernel, its initramfs and
+    /// the fallback image
+    pub const MIN_KERNEL_ESP_FREE: u64 = 300 * 1024 * 1024;
+
+    pub fn from_answer(answer: &str) -> Self {
+        match answer {
+            "grub" => Self::Grub,
+            "systemd-boot" => Self::SystemdBoot,
+            "limine" => Self::Limine,
+            _ => Self::DEFAULT,
+        }
+    }
+
+    pub fn from_context(context: &InstallContext) -> Self {
+        context
+            .get_answer(&QuestionId::Bootloader)
+            .map(|answer| Self::from_answer(answer))
+            .unwrap_or(Self::DEFAULT)
+    }
+
+    pub fn answer_value(&self) -> &'static str {
+        match self {
+            Self::Grub => "grub",
+            Self::SystemdBoot => "systemd-boot",
+            Self::Limine => "limine",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Grub => "GRUB (default)",
+            Self::SystemdBoot => "systemd-boot",
+            Self::Limine => "Limine",
+        }
+    }
+
+    /// systemd-boot is a UEFI boot manager and cannot boot BIOS systems.
+    pub fn supports(&self, boot_mode: &BootMode) -> bool {
+        match self {
+            Self::Grub | Self::Limine => true,
+            Self::SystemdBoot => !matches!(boot_mode, BootMode::BIOS),
+        }
+    }
+
+    /// Limine only reads FAT, so a BIOS install needs a separate FAT `/boot` partition
+    /// instead of keeping kernels on the root filesystem.
+    pub fn needs_fat_boot_partition(&self, boot_mode: &BootMode) -> bool {
+        matches!(self, Self::Limine) && matches!(boot_mode, BootMode::BIOS)
+    }
+
+    /// systemd-boot and Limine only load kernels from the ESP
+    pub fn keeps_kernels_on_esp(&self) -> bool {
+        !matches!(self, Self::Grub)
+    }
+
+    /// Where the EFI system partition is mounted in the target system.
+    ///
+    /// Dual-boot installs keep the Windows ESP at `/boot/efi` for GRUB. systemd-boot and
+    /// Limine always mount it at `/boot`.
+    pub fn esp_mount_point(&self, dualboot: bool) -> &'static str {
+        if dualboot && !self.keeps_kernels_on_esp() {
+            "/boot/efi"
+        } else {
+            "/boot"
+        }
+    }
+
+    /// Size of the ESP created for dual boot when the disk has none to reuse
+    pub fn dualboot_esp_size(&self) -> u64 {
+        if self.keeps_kernels_on_esp() {
+            512 * 1024 * 1024
+        } else {
+            crate::arch::dualboot::types::MIN_ESP_SIZE
         }
     }
 }
 
+/// Volume layout for automatic partitioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskLayout {
+    /// Boot, swap and root partitions; encryption adds LVM on LUKS (root + swap)
+    Standard,
+    /// LVM with root, home and swap logical volumes, on LUKS when encrypting
+    Lvm,
+    /// LVM with root and home volumes each in their own LUKS container and swap
+    /// encrypted with a random key on every boot
+    LuksOnLvm,
+    /// btrfs root mirrored across the selected disk and a second disk
+    BtrfsRaid1,
+    /// Standard layout with `/home` on a second disk
+    HomeDisk,
+}
+
+impl DiskLayout {
+    pub const DEFAULT: Self = Self::Standard;
+
+    pub const ALL: [Self; 5] = [
+        Self::Standard,
+        Self::Lvm,
+        Self::LuksOnLvm,
+        Self::BtrfsRaid1,
+        Self::HomeDisk,
+    ];
+
+    pub fn from_answer(answer: &str) -> Self {
+        match answer {
+            "standard" => Self::Standard,
+            "lvm" => Self::Lvm,
+            "luks-on-lvm" => Self::LuksOnLvm,
+            "btrfs-raid1" => Self::BtrfsRaid1,
+            "home-disk" => Self::HomeDisk,
+            _ => Self::DEFAULT,
+        }
+    }
+
+    /// Manual and dual boot partitioning always use their own layout
+    pub fn from_context(context: &InstallContext) -> Self {
+        let automatic = context
+            .get_answer(&QuestionId::PartitioningMethod)
+            .map(|method| !method.contains("Manual") && !method.contains("Dual Boot"))
+            .unwrap_or(true);
+        if !automatic {
+            return Self::Standard;
+        }
+        context
+            .get_answer(&QuestionId::DiskLayout)
+            .map(|answer| Self::from_answer(answer))
+            .unwrap_or(Self::DEFAULT)
+    }
+
+    pub fn answer_value(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Lvm => "lvm",
+            Self::LuksOnLvm => "luks-on-lvm",
+            Self::BtrfsRaid1 => "btrfs-raid1",
+            Self::HomeDisk => "home-disk",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard (default)",
+            Self::Lvm => "LVM (root, home and swap volumes)",
+            Self::LuksOnLvm => "LUKS on LVM (encrypted volumes)",
+            Self::BtrfsRaid1 => "btrfs RAID1 (mirror across two disks)",
+            Self::HomeDisk => "Separate /home disk",
+        }
+    }
+
+    pub fn needs_second_disk(&self) -> bool {
+        matches!(self, Self::BtrfsRaid1 | Self::HomeDisk)
+    }
+
+    /// Whether `/home` gets its own volume or disk instead of a btrfs subvolume
+    pub fn separate_home(&self) -> bool {
+        matches!(self, Self::Lvm | Self::LuksOnLvm | Self::HomeDisk)
+    }
+
+    /// Whether the layout can be combined with the encryption answer
+    pub fn supports_encryption(&self, encrypted: bool) -> bool {
+        match self {
+            Self::Standard | Self::Lvm => true,
+            Self::LuksOnLvm => encrypted,
+            Self::BtrfsRaid1 | Self::HomeDisk => !encrypted,
+        }
+    }
+
+    /// Whether the volumes live in the `instantOS` LVM volume group
+    pub fn uses_lvm(&self, encrypted: bool) -> bool {
+        match self {
+            Self::Standard => encrypted,
+            Self::Lvm | Self::LuksOnLvm => true,
+            Self::BtrfsRaid1 | Self::HomeDisk => false,
+        }
+    }
+
+    /// Device-mapper name of the LUKS container the initramfs unlocks
+    pub const fn luks_mapping(&self) -> &'static str {
+        match self {
+            Self::LuksOnLvm => "cryptroot",
+            _ => "cryptlvm",
+        }
+    }
+
+    /// Root kernel parameter once the LUKS container is unlocked
+    pub fn unlocked_root_params(&self) -> &'static str {
+        match self {
+            Self::LuksOnLvm => "root=/dev/mapper/cryptroot",
+            _ => "root=/dev/mapper/instantOS-root",
+        }
+    }
+
+    /// mkinitcpio hooks that assemble the root device, in the order the layers are
+    /// stacked on the disk (they go between `block` and `filesystems`)
+    pub fn storage_hooks(&self, encrypted: bool) -> &'static [&'static str] {
+        match (self, encrypted) {
+            (Self::LuksOnLvm, _) => &["lvm2", "sd-encrypt"],
+            (Self::Standard | Self::Lvm, true) => &["sd-encrypt", "lvm2"],
+            (Self::Lvm, false) => &["lvm2"],
+            // systemd's udev rules assemble multi-device btrfs; the btrfs hook is
+            // only needed with the busybox `udev` hook
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapType {
+    None,
+    /// Compressed swap in RAM set up by zram-generator
+    Zram,
+    /// Swap file on the root filesystem (`/swap/swapfile` on btrfs)
+    Swapfile,
+    /// Swap partition, or swap logical volume on LVM layouts
+    Partition,
+}
+
+impl SwapType {
+    pub const DEFAULT: Self = Self::Partition;
+
+    pub const ALL: [Self; 4] = [Self::Partition, Self::Swapfile, Self::Zram, Self::None];
+
+    pub fn from_answer(answer: &str) -> Self {
+        match answer {
+            "none" => Self::None,
+            "zram" => Self::Zram,
+            "swapfile" => Self::Swapfile,
+            "partition" => Self::Partition,
+            _ => Self::DEFAULT,
+        }
+    }
+
+    pub fn from_context(context: &InstallContext) -> Self {
+        context
+            .get_answer(&QuestionId::SwapType)
+            .map(|answer| Self::from_answer(answer))
+            .unwrap_or_else(|| Self::default_for(context))
+    }
+
+    /// Manual partitioning only gets a swap partition when one was selected
+    pub fn default_for(context: &InstallContext) -> Self {
+        let manual = context
+            .get_answer(&QuestionId::PartitioningMethod)
+            .is_some_and(|method| method.contains("Manual"));
+        if manual && context.get_answer(&QuestionId::SwapPartition).is_none() {
+            Self::None
+        } else {
+            Self::DEFAULT
+        }
+    }
+
+    pub fn answer_value(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Zram => "zram",
+            Self::Swapfile => "swapfile",
+            Self::Partition => "partition",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "No swap",
+            Self::Zram => "zram (compressed swap in RAM)",
+            Self::Swapfile => "Swap file",
+            Self::Partition => "Swap partition (default)",
+        }
+    }
+
+    /// Whether the installer creates a swap partition or swap logical volume
+    pub fn is_partition(&self) -> bool {
+        *self == Self::Partition
+    }
+
+    /// Whether the system can hibernate to this swap. zram lives in RAM, and the
+    /// LUKS on LVM swap volume gets a new random key on every boot.
+    pub fn supports_hibernation(&self, layout: DiskLayout) -> bool {
+        match self {
+            Self::None | Self::Zram => false,
+            Self::Swapfile => true,
+            Self::Partition => layout != DiskLayout::LuksOnLvm,
+        }
+    }
+}
+
+/// Whether resume-from-hibernation is configured (resume hook and kernel parameters)
+pub fn hibernation_enabled(context: &InstallContext) -> bool {
+    SwapType::from_context(context).supports_hibernation(DiskLayout::from_context(context))
+        && context.get_answer_bool(QuestionId::Hibernation)
+}
+
 #[cfg(test)]
 mod tests {
     use super::DesktopEnvironment;
-    use super::{BtrfsCompression, DisplayManager, RootFilesystem};
+    use super::{Bootloader, BtrfsCompression, DiskLayout, DisplayManager, RootFilesystem};
+    use crate::arch::engine::BootMode;
 
     #[test]
     fn display_manager_defaults_to_gdm() {
@@ -267,6 +656,32 @@ mod tests {
             DesktopEnvironment::from_answer("unknown"),
             DesktopEnvironment::Sway
         );
+        for desktop in DesktopEnvironment::ALL {
+            assert_eq!(
+                DesktopEnvironment::from_answer(desktop.answer_value()),
+                desktop
+            );
+        }
+    }
+
+    #[test]
+    fn display_manager_follows_the_desktop() {
+        use crate::arch::engine::{InstallContext, QuestionId};
+
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::DesktopEnvironment, "plasma".to_string());
+        assert_eq!(DisplayManager::from_context(&context), DisplayManager::Sddm);
+        context.set_answer(QuestionId::DesktopEnvironment, "xfce".to_string());
+        assert_eq!(
+            DisplayManager::from_context(&context),
+            DisplayManager::Lightdm
+        );
+        context.set_answer(QuestionId::DisplayManager, "sddm".to_string());
+        assert_eq!(DisplayManager::from_context(&context), DisplayManager::Sddm);
+
+        assert!(!DesktopEnvironment::Gnome.supports_display_manager(DisplayManager::Sddm));
+        assert!(DesktopEnvironment::Gnome.supports_display_manager(DisplayManager::Gdm));
+        assert!(DesktopEnvironment::Sway.supports_display_manager(DisplayManager::Sddm));
     }
 
     #[test]
@@ -291,4 +706,60 @@ mod tests {
         assert_eq!(BtrfsCompression::Lzo.mount_option(), Some("compress=lzo"));
         assert_eq!(BtrfsCompression::Zlib.mount_option(), Some("compress=zlib"));
     }
+
+    #[test]
+    fn bootloader_boot_mode_support() {
+        assert_eq!(Bootloader::from_answer("unknown"), Bootloader::Grub);
+        assert!(!Bootloader::SystemdBoot.supports(&BootMode::BIOS));
+        assert!(Bootloader::SystemdBoot.supports(&BootMode::UEFI64));
+        assert!(Bootloader::Limine.needs_fat_boot_partition(&BootMode::BIOS));
+        assert!(!Bootloader::Limine.needs_fat_boot_partition(&BootMode::UEFI64));
+        assert_eq!(Bootloader::Grub.esp_mount_point(true), "/boot/efi");
+        assert_eq!(Bootloader::Limine.esp_mount_point(true), "/boot");
+    }
+
+    #[test]
+    fn disk_layout_requirements() {
+        assert_eq!(DiskLayout::from_answer("unknown"), DiskLayout::Standard);
+        for layout in DiskLayout::ALL {
+            assert_eq!(DiskLayout::from_answer(layout.answer_value()), layout);
+        }
+        assert!(DiskLayout::HomeDisk.needs_second_disk());
+        assert!(!DiskLayout::LuksOnLvm.supports_encryption(false));
+        assert!(!DiskLayout::BtrfsRaid1.supports_encryption(true));
+        assert!(DiskLayout::Standard.uses_lvm(true));
+        assert!(!DiskLayout::Standard.uses_lvm(false));
+        assert_eq!(DiskLayout::Lvm.storage_hooks(false), ["lvm2"]);
+        assert_eq!(
+            DiskLayout::LuksOnLvm.storage_hooks(true),
+            ["lvm2", "sd-encrypt"]
+        );
+    }
+
+    #[test]
+    fn swap_type_defaults_and_hibernation() {
+        use super::{SwapType, hibernation_enabled};
+        use crate::arch::engine::{InstallContext, QuestionId};
+
+        for swap in SwapType::ALL {
+            assert_eq!(SwapType::from_answer(swap.answer_value()), swap);
+        }
+
+        let mut context = InstallContext::new();
+        assert_eq!(SwapType::from_context(&context), SwapType::Partition);
+        context.set_answer(
+            QuestionId::PartitioningMethod,
+            "Manual (cfdisk)".to_string(),
+        );
+        assert_eq!(SwapType::from_context(&context), SwapType::None);
+        context.set_answer(QuestionId::SwapPartition, "/dev/sda3".to_string());
+        assert_eq!(SwapType::from_context(&context), SwapType::Partition);
+
+        context.set_answer(QuestionId::Hibernation, "yes".to_string());
+        assert!(hibernation_enabled(&context));
+        context.set_answer(QuestionId::SwapType, "zram".to_string());
+        assert!(!hibernation_enabled(&context));
+        assert!(!SwapType::Partition.supports_hibernation(DiskLayout::LuksOnLvm));
+        assert!(SwapType::Swapfile.supports_hibernation(DiskLayout::LuksOnLvm));
+    }
 }
@@ -6,10 +6,12 @@
 use crate::arch::dualboot::os_detection::detect_os_from_info;
 use crate::arch::dualboot::parsing;
 use crate::arch::dualboot::types::{
-    DiskAnalysis, DiskInfo, MIN_ESP_SIZE, PartitionTableType, ResizeInfo,
+    DiskAnalysis, DiskInfo, MIN_ESP_SIZE, PartitionInfo, PartitionTableType, ResizeInfo,
 };
 use crate::common::format::format_size;
 use anyhow::Result;
+use std::path::Path;
+use std::process::Command;
 
 /// Detect all disks and their partitions
 pub fn detect_disks() -> Result<Vec<DiskInfo>> {
@@ -123,6 +125,34 @@ fn get_efi_resize_info(size_bytes: u64) -> ResizeInfo {
     }
 }
 
+/// Free space on an existing EFI partition, mounting it read-only if it is not mounted.
+///
+/// Returns `None` when the filesystem cannot be inspected, e.g. without root.
+pub fn esp_free_space(esp: &PartitionInfo) -> Option<u64> {
+    let free_space = |mount_point: &Path| {
+        nix::sys::statvfs::statvfs(mount_point)
+            .ok()
+            .map(|stat| stat.blocks_available() * stat.fragment_size())
+    };
+    if let Some(mount_point) = &esp.mount_point {
+        return free_space(Path::new(mount_point));
+    }
+
+    let dir = tempfile::tempdir().ok()?;
+    let mounted = Command::new("mount")
+        .args(["-o", "ro"])
+        .arg(&esp.device)
+        .arg(dir.path())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !mounted {
+        return None;
+    }
+    let free = free_space(dir.path());
+    let _ = Command::new("umount").arg(dir.path()).status();
+    free
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
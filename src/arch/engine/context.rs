@@ -30,7 +30,8 @@ pub struct DualBootPartitions;
 pub struct DualBootPartitionPaths {
     pub root: String,
     pub boot: String,
-    pub swap: String,
+    /// Not created when the swap question picks something other than a partition
+    pub swap: Option<String>,
 }
 
 impl DataKey for DualBootPartitions {
@@ -83,6 +83,12 @@ pub trait Question: Send + Sync {
         None
     }
 
+    /// The fixed set of answers this question accepts, if it has one.
+    /// Used for the answer file schema and template.
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
     /// Validate the answer. Returns Ok(()) if valid, or Err(message) if invalid.
     fn validate(&self, _context: &InstallContext, _answer: &str) -> Result<(), String> {
         Ok(())
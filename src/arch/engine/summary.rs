@@ -3,6 +3,8 @@ use crate::ui::catppuccin::colors;
 use crate::ui::nerd_font::NerdFont;
 use crate::ui::preview::PreviewBuilder;
 
+use crate::arch::config::{DiskLayout, SwapType, hibernation_enabled};
+
 use super::context::InstallContext;
 use super::types::{BootMode, QuestionId};
 
@@ -96,15 +98,45 @@ fn format_dualboot_resize_method(context: &InstallContext, uses_free_space: bool
 }
 
 fn format_automatic_layout(context: &InstallContext, use_encryption: bool) -> String {
-    match (context.system_info.boot_mode.clone(), use_encryption) {
-        (BootMode::UEFI64 | BootMode::UEFI32, true) => {
-            "EFI (1 GiB) + LUKS (LVM swap + root)".to_string()
+    let boot = match context.system_info.boot_mode {
+        BootMode::UEFI64 | BootMode::UEFI32 => "EFI (1 GiB)",
+        BootMode::BIOS => "Boot (1 GiB)",
+    };
+    let swap = SwapType::from_context(context).is_partition();
+    let lvm_swap = if swap { "swap + " } else { "" };
+    let plain_root = if swap { "Swap (auto) + Root" } else { "Root" };
+    let standard = match (context.system_info.boot_mode.clone(), use_encryption) {
+        (_, true) => format!("{boot} + LUKS (LVM {lvm_swap}root)"),
+        (BootMode::BIOS, false)
+            if !crate::arch::config::Bootloader::from_context(context)
+                .needs_fat_boot_partition(&BootMode::BIOS) =>
+        {
+            plain_root.to_string()
+        }
+        (_, false) => format!("{boot} + {plain_root}"),
+    };
+
+    match DiskLayout::from_context(context) {
+        DiskLayout::Standard => standard,
+        DiskLayout::Lvm if use_encryption => {
+            format!("{boot} + LUKS (LVM {lvm_swap}root + home)")
         }
-        (BootMode::UEFI64 | BootMode::UEFI32, false) => {
-            "EFI (1 GiB) + Swap (auto) + Root".to_string()
+        DiskLayout::Lvm => format!("{boot} + LVM ({lvm_swap}root + home)"),
+        DiskLayout::LuksOnLvm if swap => {
+            format!("{boot} + LVM (LUKS root + LUKS home + random-key swap)")
         }
-        (BootMode::BIOS, true) => "Boot (1 GiB) + LUKS (LVM swap + root)".to_string(),
-        (BootMode::BIOS, false) => "Swap (auto) + Root".to_string(),
+        DiskLayout::LuksOnLvm => format!("{boot} + LVM (LUKS root + LUKS home)"),
+        DiskLayout::BtrfsRaid1 => format!("{standard}, root mirrored to second disk"),
+        DiskLayout::HomeDisk => format!("{standard}, /home on second disk"),
+    }
+}
+
+fn format_swap(context: &InstallContext) -> String {
+    match SwapType::from_context(context) {
+        SwapType::Partition => "Partition, auto (RAM-based)".to_string(),
+        SwapType::Swapfile => "Swap file, auto (RAM-based)".to_string(),
+        SwapType::Zram => "zram (half of RAM, up to 8 GiB)".to_string(),
+        SwapType::None => "None".to_string(),
     }
 }
 
@@ -138,6 +170,10 @@ pub(crate) fn build_install_summary(context: &InstallContext) -> InstallSummary
         .cloned()
         .unwrap_or_else(|| "linux (default)".to_string());
 
+    let bootloader = crate::arch::config::Bootloader::from_context(context)
+        .label()
+        .to_string();
+
     let desktop_label = if minimal_mode {
         "Skipped (minimal mode)".to_string()
     } else {
@@ -149,16 +185,35 @@ pub(crate) fn build_install_summary(context: &InstallContext) -> InstallSummary
     let root_filesystem = crate::arch::config::RootFilesystem::from_context(context);
     let filesystem_label = if root_filesystem.is_btrfs() {
         let compression = crate::arch::config::BtrfsCompression::from_context(context);
-        let subvolumes = if context.get_answer(&QuestionId::HomePartition).is_some() {
-            "@"
-        } else {
-            "@, @home"
-        };
-        format!("btrfs ({subvolumes}; {})", compression.label())
+        let mut subvolumes = vec![crate::arch::config::BTRFS_ROOT_SUBVOLUME];
+        if context.get_answer(&QuestionId::HomePartition).is_none()
+            && !DiskLayout::from_context(context).separate_home()
+        {
+            subvolumes.push(crate::arch::config::BTRFS_HOME_SUBVOLUME);
+        }
+        if crate::arch::config::btrfs_snapshots_enabled(context) {
+            subvolumes.extend(
+                crate::arch::config::BTRFS_SNAPSHOT_SUBVOLUMES
+                    .iter()
+                    .map(|(subvolume, _)| *subvolume),
+            );
+        }
+        format!("btrfs ({}; {})", subvolumes.join(", "), compression.label())
     } else {
         "ext4".to_string()
     };
 
+    let snapshots_label = if !root_filesystem.is_btrfs() {
+        "Unavailable (requires btrfs)".to_string()
+    } else if crate::arch::config::btrfs_snapshots_enabled(context) {
+        match crate::arch::config::Bootloader::from_context(context) {
+            crate::arch::config::Bootloader::Grub => "snapper + grub-btrfs".to_string(),
+            _ => "snapper + snapshot boot entries".to_string(),
+        }
+    } else {
+        "Disabled".to_string()
+    };
+
     let use_plymouth = context.get_answer_bool(QuestionId::UsePlymouth);
     let plymouth_label = if minimal_mode {
         "Disabled (minimal mode)".to_string()
@@ -248,9 +303,12 @@ pub(crate) fn build_install_summary(context: &InstallContext) -> InstallSummary
                 context,
                 context.get_answer_bool(QuestionId::UseEncryption),
             );
-            builder = builder
-                .field_indented("Layout", &layout)
-                .field_indented("Swap", "Auto (RAM-based)");
+            builder = builder.field_indented("Layout", &layout);
+            if DiskLayout::from_context(context).needs_second_disk() {
+                let second_disk = answer_or(context, QuestionId::SecondDisk, "<not set>");
+                builder = builder.field_indented("Second disk", &second_disk);
+            }
+            builder = builder.field_indented("Swap", &format_swap(context));
         }
         PartitioningKind::DualBoot => {
             let resize_target = match context.get_answer(&QuestionId::DualBootPartition) {
@@ -269,15 +327,18 @@ pub(crate) fn build_install_summary(context: &InstallContext) -> InstallSummary
                 .field_indented("Resize target", &resize_target)
                 .field_indented("Linux size", &linux_size)
                 .field_indented("Resize method", &resize_method)
-                .field_indented("Swap", "Auto (RAM-based)");
+                .field_indented("Swap", &format_swap(context));
         }
         PartitioningKind::Manual => {
             let root_partition = answer_or(context, QuestionId::RootPartition, "<not set>");
             let boot_partition = answer_or(context, QuestionId::BootPartition, "<not set>");
-            let swap_partition = context
-                .get_answer(&QuestionId::SwapPartition)
-                .cloned()
-                .unwrap_or_else(|| "none".to_string());
+            let swap_partition = match SwapType::from_context(context) {
+                SwapType::Partition => context
+                    .get_answer(&QuestionId::SwapPartition)
+                    .cloned()
+                    .unwrap_or_else(|| "none".to_string()),
+                _ => format_swap(context),
+            };
             let home_partition = context
                 .get_answer(&QuestionId::HomePartition)
                 .cloned()
@@ -294,6 +355,17 @@ pub(crate) fn build_install_summary(context: &InstallContext) -> InstallSummary
         PartitioningKind::Unknown => {}
     }
 
+    if partitioning_kind != PartitioningKind::Unknown
+        && SwapType::from_context(context).supports_hibernation(DiskLayout::from_context(context))
+    {
+        let hibernation = if hibernation_enabled(context) {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+        builder = builder.field_indented("Hibernation", hibernation);
+    }
+
     builder = builder
         .blank()
         .line(colors::TEAL, Some(NerdFont::Lock), "Security")
@@ -310,13 +382,35 @@ pub(crate) fn build_install_summary(context: &InstallContext) -> InstallSummary
         .blank()
         .line(colors::TEAL, Some(NerdFont::Sliders), "System Options")
         .field_indented("Kernel", &kernel)
+        .field_indented("Bootloader", &bootloader)
+        .field_indented("Snapshots", &snapshots_label)
         .field_indented("Desktop", &desktop_label)
         .field_indented("Display manager", &dm_label)
         .field_indented("Profile", &profile)
         .field_indented("Plymouth", &plymouth_label)
         .field_indented("Autologin", &autologin_label)
         .field_indented("Log upload", &log_upload_label)
-        .field_indented("Mirror region", &mirror_region);
+        .field_indented("Mirror region", &mirror_region)
+        .field_indented("Packages", &crate::arch::offline::describe(context));
+
+    let restores = [
+        ("Dotfiles", QuestionId::RestoreDotfiles),
+        ("Package list", QuestionId::RestorePackageList),
+        ("Game saves", QuestionId::RestoreGameRepo),
+    ];
+    if restores
+        .iter()
+        .any(|(_, id)| context.is_answered(id.clone()))
+    {
+        builder = builder
+            .blank()
+            .line(colors::TEAL, Some(NerdFont::BackupRestore), "Restore");
+        for (label, id) in restores {
+            if let Some(source) = context.get_answer(&id) {
+                builder = builder.field_indented(label, source);
+            }
+        }
+    }
 
     let summary = builder.build_string();
     let summary = summary.trim_start_matches('\n').to_string();
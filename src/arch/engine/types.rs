@@ -12,9 +12,11 @@ pub enum QuestionId {
     Timezone,
     Locale,
     Kernel,
+    Bootloader,
     DesktopEnvironment,
     RootFilesystem,
     BtrfsCompression,
+    BtrfsSnapshots,
     DisplayManager,
     UseEncryption,
     EncryptionPassword,
@@ -28,6 +30,10 @@ pub enum QuestionId {
     DualBootEspWarning,
     MinimalMode,
     PartitioningMethod,
+    DiskLayout,
+    SecondDisk,
+    SwapType,
+    Hibernation,
     RunCfdisk,
     RootPartition,
     SwapPartition,
@@ -36,6 +42,10 @@ pub enum QuestionId {
     DualBootPartition,
     DualBootSize,
     DualBootInstructions,
+    RestoreDotfiles,
+    RestorePackageList,
+    RestoreGameRepo,
+    RestoreGamePassword,
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -66,4 +76,7 @@ pub struct SystemInfo {
     pub architecture: String,
     pub distro: String,
     pub total_ram_gb: Option<u64>,
+    /// Installing into a disk image (`ins arch exec --image`) rather than onto this machine
+    #[serde(default)]
+    pub disk_image: bool,
 }
@@ -1,18 +1,58 @@
 use super::CommandRunner;
+use super::checkpoint::{Checkpoints, SubStep};
+use super::step::InstallStep;
 use crate::arch::engine::{InstallContext, QuestionId};
 use anyhow::{Context, Result};
 
+const MIRRORS: SubStep = SubStep {
+    name: "mirrors",
+    questions: &[QuestionId::MirrorRegion],
+    changes: "Replaced /etc/pacman.d/mirrorlist on the live system",
+};
+
+const PACMAN: SubStep = SubStep {
+    name: "pacman",
+    questions: &[],
+    changes: "Adjusted /etc/pacman.conf on the live system",
+};
+
+const PACSTRAP: SubStep = SubStep {
+    name: "pacstrap",
+    questions: &[
+        QuestionId::Kernel,
+        QuestionId::MinimalMode,
+        QuestionId::UseEncryption,
+        QuestionId::UsePlymouth,
+    ],
+    changes: "Installed the base system into /mnt",
+};
+
 pub async fn install_base(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
-    println!("Setting up mirrors...");
-    setup_mirrors(context, executor).await?;
+    let checkpoints = Checkpoints::load(InstallStep::Base, context, executor);
 
-    println!("Configuring pacman settings...");
-    crate::common::pacman::configure_pacman_settings(None, executor.dry_run()).await?;
+    // Checked on every attempt: the repository may sit on media that was unplugged
+    let offline = crate::arch::offline::enable(context, executor)?;
 
-    println!("Installing base system...");
-    run_pacstrap(context, executor)?;
+    if checkpoints.pending(&MIRRORS) {
+        if offline {
+            println!("Installing from the local repository, skipping mirror setup.");
+        } else {
+            println!("Setting up mirrors...");
+            setup_mirrors(context, executor).await?;
+        }
+        checkpoints.complete(&MIRRORS);
+    }
 
-    Ok(())
+    if checkpoints.pending(&PACMAN) {
+        println!("Configuring pacman settings...");
+        crate::common::pacman::configure_pacman_settings(None, executor.dry_run()).await?;
+        checkpoints.complete(&PACMAN);
+    }
+
+    checkpoints.run(&PACSTRAP, || {
+        println!("Installing base system...");
+        run_pacstrap(context, executor)
+    })
 }
 
 async fn setup_mirrors(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
@@ -70,15 +110,13 @@ async fn fetch_fallback_mirrorlist() -> Result<String> {
     crate::arch::mirrors::fetch_mirrorlist("").await
 }
 
-fn run_pacstrap(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+/// Packages pacstrap installs into the new root
+pub fn pacstrap_package_list(context: &InstallContext) -> Vec<String> {
     // Get selected kernel or default to "linux"
     let kernel = context
         .get_answer(&QuestionId::Kernel)
         .map(|s| s.as_str())
         .unwrap_or("linux");
-    let use_encryption = context.get_answer_bool(QuestionId::UseEncryption);
-    let use_plymouth = context.get_answer_bool(QuestionId::UsePlymouth);
-    let minimal_mode = context.get_answer_bool(QuestionId::MinimalMode);
 
     let mut packages: Vec<String> = vec!["base", "linux-firmware"]
         .into_iter()
@@ -94,17 +132,30 @@ fn run_pacstrap(context: &InstallContext, executor: &dyn CommandRunner) -> Resul
 
     // CPU Microcode
     if context.system_info.has_amd_cpu {
-        println!("Detected AMD CPU, adding amd-ucode");
         packages.push("amd-ucode".to_string());
     }
     if context.system_info.has_intel_cpu {
-        println!("Detected Intel CPU, adding intel-ucode");
         packages.push("intel-ucode".to_string());
     }
 
     // GPU drivers are installed later in setup.rs after multilib is enabled,
     // allowing lib32-* packages to be installed properly.
 
+    packages
+}
+
+fn run_pacstrap(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    let use_encryption = context.get_answer_bool(QuestionId::UseEncryption);
+    let use_plymouth = context.get_answer_bool(QuestionId::UsePlymouth);
+    let minimal_mode = context.get_answer_bool(QuestionId::MinimalMode);
+
+    if context.system_info.has_amd_cpu {
+        println!("Detected AMD CPU, adding amd-ucode");
+    }
+    if context.system_info.has_intel_cpu {
+        println!("Detected Intel CPU, adding intel-ucode");
+    }
+
     // Encryption support
     if use_encryption {
         println!("Encryption enabled; required packages will be installed inside chroot.");
@@ -115,6 +166,7 @@ fn run_pacstrap(context: &InstallContext, executor: &dyn CommandRunner) -> Resul
         println!("Plymouth enabled; package will be installed after chroot.");
     }
 
+    let packages = pacstrap_package_list(context);
     println!("Packages to install: {}", packages.join(" "));
 
     // Convert Vec<String> to Vec<&str> for pacstrap
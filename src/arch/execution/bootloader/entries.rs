@@ -0,0 +1,251 @@
+//! Boot entries for loaders that read kernels straight from a FAT partition
+//! (systemd-boot and Limine), as opposed to GRUB which generates its own config.
+
+use super::{build_grub_encryption_param, luks_partition_path, read_luks_uuid};
+use crate::arch::config::{BTRFS_ROOT_SUBVOLUME, DiskLayout, RootFilesystem};
+use crate::arch::engine::{InstallContext, QuestionId};
+use crate::arch::execution::CommandRunner;
+use crate::arch::execution::disk::parse_partition_number;
+use crate::arch::execution::swap::resume_params;
+use crate::arch::mkinitcpio::MkinitcpioConfig;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Windows Boot Manager, relative to the EFI system partition
+pub(super) const WINDOWS_BOOT_MANAGER: &str = "EFI/Microsoft/Boot/bootmgfw.efi";
+
+/// How the kernel finds the root filesystem
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum RootDevice {
+    Plain {
+        uuid: String,
+    },
+    /// LVM on LUKS; `sd_encrypt` selects `rd.luks.*` (systemd initramfs) over `cryptdevice=`
+    Luks {
+        uuid: String,
+        sd_encrypt: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct BootEntry {
+    /// File name stem of the entry (`instantos-linux`)
+    pub id: String,
+    pub title: String,
+    /// Paths relative to the boot partition root
+    pub kernel: String,
+    pub initrds: Vec<String>,
+    pub options: String,
+}
+
+/// Where the loader is installed and what it boots
+#[derive(Debug, Clone)]
+pub(super) struct BootLayout {
+    /// Mount point of the FAT partition holding kernels and loader files
+    pub boot_dir: PathBuf,
+    /// Directory for pacman hooks
+    pub hooks_dir: PathBuf,
+    /// Disk and partition number of `boot_dir`, for firmware entries and BIOS installs
+    pub disk: String,
+    pub partition: u32,
+    pub entries: Vec<BootEntry>,
+    /// Windows Boot Manager is present on the boot partition
+    pub windows: bool,
+    /// Install to the removable-media fallback path and leave firmware variables
+    /// alone, for disk images that boot on other machines
+    pub removable: bool,
+}
+
+impl BootLayout {
+    pub(super) fn new(
+        context: &InstallContext,
+        boot_dir: &Path,
+        hooks_dir: &Path,
+        disk: &str,
+        partition: u32,
+        root: &RootDevice,
+        resume: Option<&str>,
+    ) -> Self {
+        let kernel = context
+            .get_answer(&QuestionId::Kernel)
+            .map(|s| s.as_str())
+            .unwrap_or("linux");
+        let name = if context.get_answer_bool(QuestionId::MinimalMode) {
+            "Arch Linux"
+        } else {
+            "instantOS"
+        };
+
+        let mut microcode = Vec::new();
+        if context.system_info.has_amd_cpu {
+            microcode.push("/amd-ucode.img".to_string());
+        }
+        if context.system_info.has_intel_cpu {
+            microcode.push("/intel-ucode.img".to_string());
+        }
+
+        let options = kernel_options(context, root, resume);
+        let entry = |suffix: &str, title_suffix: &str| BootEntry {
+            id: format!("{}-{kernel}{suffix}", name.to_lowercase().replace(' ', "-")),
+            title: format!("{name} ({kernel}{title_suffix})"),
+            kernel: format!("/vmlinuz-{kernel}"),
+            initrds: microcode
+                .iter()
+                .cloned()
+                .chain([format!("/initramfs-{kernel}{suffix}.img")])
+                .collect(),
+            options: options.clone(),
+        };
+
+        let mut entries = vec![entry("", "")];
+        // mkinitcpio only builds the fallback image when its preset asks for it
+        if boot_dir
+            .join(format!("initramfs-{kernel}-fallback.img"))
+            .exists()
+        {
+            entries.push(entry("-fallback", ", fallback initramfs"));
+        }
+
+        Self {
+            boot_dir: boot_dir.to_path_buf(),
+            hooks_dir: hooks_dir.to_path_buf(),
+            disk: disk.to_string(),
+            partition,
+            entries,
+            windows: boot_dir.join(WINDOWS_BOOT_MANAGER).exists(),
+            removable: context.system_info.disk_image,
+        }
+    }
+
+    /// Probe the installed system (inside the chroot) for the root device and boot partition
+    pub(super) fn detect(context: &InstallContext, executor: &dyn CommandRunner) -> Result<Self> {
+        let boot_dir = Path::new("/boot");
+        let hooks_dir = Path::new("/etc/pacman.d/hooks");
+        let disk = context
+            .get_answer(&QuestionId::Disk)
+            .context("Disk not selected")?;
+
+        if executor.dry_run() {
+            let root = RootDevice::Plain {
+                uuid: "<root-uuid>".to_string(),
+            };
+            let resume = resume_params(context, executor, Path::new("/"))?;
+            return Ok(Self::new(
+                context,
+                boot_dir,
+                hooks_dir,
+                disk,
+                1,
+                &root,
+                resume.as_deref(),
+            ));
+        }
+
+        let root = if context.get_answer_bool(QuestionId::UseEncryption) {
+            let uuid = read_luks_uuid(&luks_partition_path(context)?)?;
+            let mkinitcpio = std::fs::read_to_string("/etc/mkinitcpio.conf")
+                .context("Failed to read /etc/mkinitcpio.conf")?;
+            let sd_encrypt = !MkinitcpioConfig::parse(&mkinitcpio)?.contains_hook("encrypt");
+            RootDevice::Luks { uuid, sd_encrypt }
+        } else {
+            RootDevice::Plain {
+                uuid: findmnt("UUID", Path::new("/"))?,
+            }
+        };
+
+        let boot_source = findmnt("SOURCE", boot_dir)?;
+        let partition = parse_partition_number(disk, &boot_source).with_context(|| {
+            format!("/boot ({boot_source}) must be a partition on the selected disk {disk}")
+        })?;
+
+        let resume = resume_params(context, executor, Path::new("/"))?;
+        Ok(Self::new(
+            context,
+            boot_dir,
+            hooks_dir,
+            disk,
+            partition,
+            &root,
+            resume.as_deref(),
+        ))
+    }
+}
+
+/// Kernel command line shared by every Linux entry; `resume` carries the
+/// hibernation parameters from [`resume_params`]
+pub(super) fn kernel_options(
+    context: &InstallContext,
+    root: &RootDevice,
+    resume: Option<&str>,
+) -> String {
+    let layout = DiskLayout::from_context(context);
+    let mut options = vec![match root {
+        RootDevice::Plain { uuid } => format!("root=UUID={uuid}"),
+        RootDevice::Luks {
+            uuid,
+            sd_encrypt: true,
+        } => build_grub_encryption_param(uuid, layout),
+        RootDevice::Luks {
+            uuid,
+            sd_encrypt: false,
+        } => format!(
+            "cryptdevice=UUID={uuid}:{} {}",
+            layout.luks_mapping(),
+            layout.unlocked_root_params()
+        ),
+    }];
+    options.extend(resume.map(str::to_string));
+    options.push("rw".to_string());
+
+    if RootFilesystem::from_context(context).is_btrfs() {
+        options.push(format!("rootflags=subvol={BTRFS_ROOT_SUBVOLUME}"));
+    }
+
+    options.push("loglevel=3 quiet".to_string());
+    if context.get_answer_bool(QuestionId::UsePlymouth)
+        && !context.get_answer_bool(QuestionId::MinimalMode)
+    {
+        options.push("splash".to_string());
+    }
+
+    options.join(" ")
+}
+
+/// Write a loader file, or only announce it in dry-run mode
+pub(super) fn write_boot_file(
+    executor: &dyn CommandRunner,
+    path: &Path,
+    contents: &str,
+) -> Result<()> {
+    if executor.dry_run() {
+        println!("[DRY RUN] Writing {}", path.display());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    executor.log(&format!("Wrote {}", path.display()));
+    Ok(())
+}
+
+fn findmnt(column: &str, target: &Path) -> Result<String> {
+    let output = Command::new("findmnt")
+        .args(["-n", "-o", column])
+        .arg(target)
+        .output()
+        .context("Failed to run findmnt")?;
+    let value = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    if !output.status.success() || value.is_empty() {
+        anyhow::bail!("Could not determine {} of {}", column, target.display());
+    }
+    Ok(value)
+}
@@ -0,0 +1,139 @@
+use super::entries::{BootEntry, BootLayout, WINDOWS_BOOT_MANAGER, write_boot_file};
+use crate::arch::engine::BootMode;
+use crate::arch::execution::CommandRunner;
+use anyhow::Result;
+use std::path::Path;
+use std::process::Command;
+
+/// Files shipped by the `limine` package
+const LIMINE_SHARE: &str = "/usr/share/limine";
+
+pub(super) fn install_limine(
+    boot_mode: &BootMode,
+    executor: &dyn CommandRunner,
+    layout: &BootLayout,
+) -> Result<()> {
+    // Limine looks for /limine/limine.conf on the partition it was loaded from
+    let limine_dir = layout.boot_dir.join("limine");
+
+    let deploy = match boot_mode {
+        BootMode::UEFI64 | BootMode::UEFI32 => {
+            let binary = if matches!(boot_mode, BootMode::UEFI32) {
+                "BOOTIA32.EFI"
+            } else {
+                "BOOTX64.EFI"
+            };
+            println!("Installing Limine for UEFI ({binary})...");
+            // Disk images boot through the fallback path instead of a firmware entry
+            let efi_dir = if layout.removable {
+                "EFI/BOOT"
+            } else {
+                "EFI/limine"
+            };
+            let target = layout.boot_dir.join(efi_dir).join(binary);
+            let copy = install_file(&Path::new(LIMINE_SHARE).join(binary), &target);
+            executor.run(Command::new("install").args(&copy))?;
+
+            if !layout.removable {
+                let mut cmd = Command::new("efibootmgr");
+                cmd.arg("--create")
+                    .arg("--disk")
+                    .arg(&layout.disk)
+                    .arg("--part")
+                    .arg(layout.partition.to_string())
+                    .arg("--label")
+                    .arg("Limine")
+                    .arg("--loader")
+                    .arg(format!("\\EFI\\limine\\{binary}"))
+                    .arg("--unicode");
+                executor.run(&mut cmd)?;
+            }
+
+            format!("/usr/bin/install {}", copy.join(" "))
+        }
+        BootMode::BIOS => {
+            println!("Installing Limine to the MBR of {}...", layout.disk);
+            let copy = install_file(
+                &Path::new(LIMINE_SHARE).join("limine-bios.sys"),
+                &limine_dir.join("limine-bios.sys"),
+            );
+            executor.run(Command::new("install").args(&copy))?;
+            executor.run(Command::new("limine").args(["bios-install", &layout.disk]))?;
+
+            format!(
+                "/bin/sh -c '/usr/bin/install {} && /usr/bin/limine bios-install {}'",
+                copy.join(" "),
+                layout.disk
+            )
+        }
+    };
+
+    write_boot_file(
+        executor,
+        &limine_dir.join("limine.conf"),
+        &render_limine_conf(layout),
+    )?;
+    if layout.windows {
+        println!("Found Windows Boot Manager; added a Windows entry to the Limine menu.");
+    }
+
+    // Limine is not updated in place by pacman; redeploy it after every upgrade
+    write_boot_file(
+        executor,
+        &layout.hooks_dir.join("95-limine.hook"),
+        &render_limine_hook(&deploy),
+    )?;
+
+    Ok(())
+}
+
+fn install_file(source: &Path, target: &Path) -> Vec<String> {
+    vec![
+        "-D".to_string(),
+        "-m".to_string(),
+        "0644".to_string(),
+        source.display().to_string(),
+        target.display().to_string(),
+    ]
+}
+
+fn render_limine_conf(layout: &BootLayout) -> String {
+    let mut config = String::from("timeout: 3\n");
+    for entry in &layout.entries {
+        config.push('\n');
+        config.push_str(&render_entry(entry, "/"));
+    }
+    if layout.windows {
+        config.push_str(&format!(
+            "\n/Windows\n    protocol: efi\n    path: boot():/{WINDOWS_BOOT_MANAGER}\n"
+        ));
+    }
+    config
+}
+
+/// A Linux entry; `prefix` is `/` for top-level entries and `//` inside a directory
+pub(super) fn render_entry(entry: &BootEntry, prefix: &str) -> String {
+    let mut rendered = format!(
+        "{prefix}{}\n    protocol: linux\n    path: boot():{}\n    cmdline: {}\n",
+        entry.title, entry.kernel, entry.options
+    );
+    for initrd in &entry.initrds {
+        rendered.push_str(&format!("    module_path: boot():{initrd}\n"));
+    }
+    rendered
+}
+
+fn render_limine_hook(deploy: &str) -> String {
+    format!(
+        "[Trigger]\n\
+         Type = Package\n\
+         Operation = Install\n\
+         Operation = Upgrade\n\
+         Target = limine\n\
+         \n\
+         [Action]\n\
+         Description = Deploying Limine after upgrade...\n\
+         When = PostTransaction\n\
+         Exec = {deploy}\n"
+    )
+}
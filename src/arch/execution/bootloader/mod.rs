@@ -0,0 +1,849 @@
+mod entries;
+mod limine;
+mod snapshots;
+mod systemd_boot;
+
+pub use self::snapshots::{OVERLAY_HOOK, install_overlay_hook, snapshot_package_list};
+
+use super::CommandRunner;
+use super::checkpoint::{Checkpoints, SubStep};
+use super::step::InstallStep;
+use super::swap::resume_params;
+use crate::arch::config::{Bootloader, DiskLayout, btrfs_snapshots_enabled};
+use crate::arch::engine::{BootMode, InstallContext, QuestionId};
+use anyhow::{Context, Result};
+use std::process::Command;
+
+use self::entries::BootLayout;
+
+const BOOTLOADER: SubStep = SubStep {
+    name: "bootloader",
+    questions: &[
+        QuestionId::Bootloader,
+        QuestionId::Disk,
+        QuestionId::Kernel,
+        QuestionId::MinimalMode,
+        QuestionId::UseEncryption,
+        QuestionId::DiskLayout,
+        QuestionId::SwapType,
+        QuestionId::Hibernation,
+        QuestionId::UsePlymouth,
+        QuestionId::RootFilesystem,
+        QuestionId::BtrfsSnapshots,
+        QuestionId::Username,
+    ],
+    changes: "Installed the bootloader and its boot entries",
+};
+
+pub async fn install_bootloader(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    println!("Installing bootloader (inside chroot)...");
+    let checkpoints = Checkpoints::load(InstallStep::Bootloader, context, executor);
+    checkpoints.run(&BOOTLOADER, || {
+        install_selected_bootloader(context, executor)
+    })
+}
+
+fn install_selected_bootloader(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    let boot_mode = &context.system_info.boot_mode;
+    let bootloader = Bootloader::from_context(context);
+    if !bootloader.supports(boot_mode) {
+        anyhow::bail!(
+            "{} cannot be installed on a BIOS system",
+            bootloader.label()
+        );
+    }
+
+    // Snapper goes first so grub-mkconfig already lists the initial snapshot
+    let snapshots = btrfs_snapshots_enabled(context);
+    let root = std::path::Path::new("/");
+    if snapshots {
+        snapshots::configure_snapper(context, executor, root)?;
+    }
+
+    match bootloader {
+        Bootloader::Grub => {
+            match boot_mode {
+                BootMode::UEFI64 | BootMode::UEFI32 => install_grub_uefi(context, executor)?,
+                BootMode::BIOS => install_grub_bios(context, executor)?,
+            }
+            configure_grub(context, executor)?;
+        }
+        Bootloader::SystemdBoot => {
+            let layout = BootLayout::detect(context, executor)?;
+            systemd_boot::install_systemd_boot(executor, &layout)?;
+            if snapshots {
+                snapshots::install_snapshot_entries(&bootloader, executor, &layout, root)?;
+            }
+        }
+        Bootloader::Limine => {
+            let layout = BootLayout::detect(context, executor)?;
+            limine::install_limine(boot_mode, executor, &layout)?;
+            if snapshots {
+                snapshots::install_snapshot_entries(&bootloader, executor, &layout, root)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Packages needed for bootloader setup (installed in a single batch elsewhere)
+pub fn bootloader_package_list(context: &InstallContext) -> Vec<String> {
+    let uefi = matches!(
+        context.system_info.boot_mode,
+        BootMode::UEFI64 | BootMode::UEFI32
+    );
+
+    // systemd-boot ships with systemd, which is part of base
+    let mut packages = match Bootloader::from_context(context) {
+        Bootloader::Grub => vec!["grub".to_string(), "os-prober".to_string()],
+        Bootloader::SystemdBoot => return Vec::new(),
+        Bootloader::Limine => vec!["limine".to_string()],
+    };
+
+    if uefi {
+        packages.push("efibootmgr".to_string());
+    }
+
+    packages
+}
+
+fn install_grub_uefi(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    println!("Detected UEFI mode. Installing GRUB for UEFI...");
+
+    // Determine the appropriate target based on UEFI mode
+    let target = match context.system_info.boot_mode {
+        BootMode::UEFI64 => "x86_64-efi",
+        BootMode::UEFI32 => "i386-efi",
+        _ => anyhow::bail!("Invalid boot mode for UEFI installation"),
+    };
+
+    println!("Installing GRUB with target: {}", target);
+
+    // Install GRUB for UEFI
+    // Use /boot/efi when present (dual-boot reuse) otherwise /boot (fresh installs)
+    let efi_dir = if std::path::Path::new("/boot/efi").exists() {
+        "/boot/efi"
+    } else {
+        "/boot"
+    };
+
+    let mut cmd = Command::new("grub-install");
+    cmd.arg(format!("--target={}", target))
+        .arg(format!("--efi-directory={}", efi_dir))
+        .arg("--bootloader-id=GRUB")
+        .arg("--recheck"); // Ensure GRUB is properly installed
+
+    // Disk images boot on other machines: use the fallback path, not this machine's NVRAM
+    if context.system_info.disk_image {
+        cmd.arg("--removable").arg("--no-nvram");
+    }
+
+    executor.run(&mut cmd)?;
+
+    Ok(())
+}
+
+fn install_grub_bios(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    println!("Detected BIOS mode. Installing GRUB for BIOS...");
+
+    // disk is now just the device path (e.g., "/dev/sda")
+    let disk = context
+        .get_answer(&QuestionId::Disk)
+        .context("Disk not selected")?;
+
+    println!("Installing GRUB to MBR of {}", disk);
+
+    // grub-install --target=i386-pc /dev/sdX
+    let mut cmd = Command::new("grub-install");
+    cmd.arg("--target=i386-pc").arg(disk);
+
+    executor.run(&mut cmd)?;
+
+    // Either RAID1 disk should boot if the other one fails
+    if DiskLayout::from_context(context) == DiskLayout::BtrfsRaid1
+        && let Some(second_disk) = context.get_answer(&QuestionId::SecondDisk)
+    {
+        println!("Installing GRUB to MBR of {}", second_disk);
+        executor.run(
+            Command::new("grub-install")
+                .arg("--target=i386-pc")
+                .arg(second_disk),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn configure_grub(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    println!("Generating GRUB configuration...");
+
+    if context.get_answer_bool(QuestionId::UseEncryption) {
+        configure_grub_encryption(context, executor)?;
+    }
+
+    configure_grub_resume(context, executor)?;
+
+    if context.get_answer_bool(QuestionId::UsePlymouth)
+        && !context.get_answer_bool(QuestionId::MinimalMode)
+    {
+        configure_grub_plymouth(context, executor)?;
+    }
+
+    if !context.get_answer_bool(QuestionId::MinimalMode) {
+        configure_grub_theme(context, executor)?;
+    }
+
+    // grub-mkconfig -o /boot/grub/grub.cfg
+    let mut cmd = Command::new("grub-mkconfig");
+    cmd.arg("-o").arg("/boot/grub/grub.cfg");
+
+    executor.run(&mut cmd)?;
+
+    Ok(())
+}
+
+fn configure_grub_encryption(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    if executor.dry_run() {
+        println!(
+            "[DRY RUN] Adding 'rd.luks.name=...={}' to GRUB_CMDLINE_LINUX",
+            DiskLayout::from_context(context).luks_mapping()
+        );
+        println!("[DRY RUN] Setting GRUB_ENABLE_CRYPTODISK=y in /etc/default/grub");
+        return Ok(());
+    }
+
+    let luks_part = luks_partition_path(context)?;
+    let uuid = read_luks_uuid(&luks_part)?;
+    println!("Found LUKS UUID: {}", uuid);
+
+    let grub_default = "/etc/default/grub";
+    let content = std::fs::read_to_string(grub_default)?;
+    let param = build_grub_encryption_param(&uuid, DiskLayout::from_context(context));
+    let new_content = set_grub_cryptodisk_enabled(&add_grub_kernel_param(&content, &param));
+    std::fs::write(grub_default, new_content)?;
+
+    Ok(())
+}
+
+/// Device holding the LUKS container the initramfs unlocks: the second partition,
+/// or the root logical volume for LUKS on LVM
+fn luks_partition_path(context: &InstallContext) -> Result<String> {
+    if DiskLayout::from_context(context) == DiskLayout::LuksOnLvm {
+        return Ok("/dev/instantOS/root".to_string());
+    }
+
+    let disk = context
+        .get_answer(&QuestionId::Disk)
+        .context("Disk not selected")?;
+
+    Ok(crate::arch::execution::disk::get_part_path(disk, 2))
+}
+
+fn read_luks_uuid(luks_part: &str) -> Result<String> {
+    println!("Getting UUID for LUKS partition: {}", luks_part);
+
+    let output = Command::new("blkid")
+        .args(["-o", "value", "-s", "UUID", luks_part])
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("blkid failed to get UUID for {}", luks_part);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let uuid = stdout.lines().next().unwrap_or("").trim().to_string();
+
+    if uuid.is_empty() {
+        anyhow::bail!("Could not find UUID for LUKS partition {}", luks_part);
+    }
+
+    Ok(uuid)
+}
+
+fn build_grub_encryption_param(uuid: &str, layout: DiskLayout) -> String {
+    format!(
+        "rd.luks.name={}={} {}",
+        uuid,
+        layout.luks_mapping(),
+        layout.unlocked_root_params()
+    )
+}
+
+fn set_grub_cryptodisk_enabled(content: &str) -> String {
+    if content.contains("GRUB_ENABLE_CRYPTODISK=y") {
+        return content.to_string();
+    }
+
+    if content.contains("GRUB_ENABLE_CRYPTODISK=") {
+        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        for line in &mut lines {
+            if line.trim().starts_with("GRUB_ENABLE_CRYPTODISK=") {
+                *line = "GRUB_ENABLE_CRYPTODISK=y".to_string();
+            }
+        }
+        return lines.join("\n");
+    }
+
+    let mut new_content = content.to_string();
+    new_content.push_str("\nGRUB_ENABLE_CRYPTODISK=y\n");
+    new_content
+}
+
+fn configure_grub_resume(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    let Some(param) = resume_params(context, executor, std::path::Path::new("/"))? else {
+        return Ok(());
+    };
+
+    if executor.dry_run() {
+        println!("[DRY RUN] Adding '{}' to GRUB_CMDLINE_LINUX", param);
+        return Ok(());
+    }
+
+    let grub_default = "/etc/default/grub";
+    let content = std::fs::read_to_string(grub_default)?;
+    std::fs::write(grub_default, add_grub_kernel_param(&content, &param))?;
+
+    Ok(())
+}
+
+fn configure_grub_plymouth(_context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    if executor.dry_run() {
+        println!("[DRY RUN] Adding 'splash quiet' to GRUB_CMDLINE_LINUX");
+        return Ok(());
+    }
+
+    let grub_default = "/etc/default/grub";
+    let content = std::fs::read_to_string(grub_default)?;
+
+    // Add splash and quiet parameters for Plymouth to GRUB_CMDLINE_LINUX_DEFAULT
+    // This is where splash usually goes in Arch
+    let param = "splash";
+    let new_content = add_grub_param(&content, "GRUB_CMDLINE_LINUX_DEFAULT", param);
+
+    std::fs::write(grub_default, new_content)?;
+
+    Ok(())
+}
+
+pub fn configure_grub_theme(_context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    let grub_default = "/etc/default/grub";
+
+    if !std::path::Path::new(grub_default).exists() {
+        println!("GRUB configuration file not found, skipping theme setup.");
+        return Ok(());
+    }
+
+    if executor.dry_run() {
+        println!("[DRY RUN] Setting GRUB_THEME in /etc/default/grub");
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(grub_default)?;
+    // Note: If encryption is used, the theme will not be visible during the initial
+    // boot phase (GRUB password prompt) because /usr is on the encrypted partition.
+    let theme_path = "/usr/share/grub/themes/instantos/theme.txt";
+
+    let mut new_lines = Vec::new();
+    let mut theme_set = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("GRUB_THEME=") || trimmed.starts_with("#GRUB_THEME=") {
+            new_lines.push(format!("GRUB_THEME=\"{}\"", theme_path));
+            theme_set = true;
+        } else {
+            new_lines.push(line.to_string());
+        }
+    }
+
+    if !theme_set {
+        new_lines.push(format!("GRUB_THEME=\"{}\"", theme_path));
+    }
+
+    // Only write if changed to ensure idempotency
+    let new_content = new_lines.join("\n");
+    if new_content != content {
+        std::fs::write(grub_default, new_content)?;
+        println!("Updated GRUB theme configuration.");
+
+        // Update grub config
+        // Try to detect where grub-mkconfig writes to. Usually /boot/grub/grub.cfg
+        let grub_cfg = "/boot/grub/grub.cfg";
+        if std::path::Path::new(grub_cfg).exists() {
+            println!("Regenerating GRUB configuration...");
+            let mut cmd = Command::new("grub-mkconfig");
+            cmd.arg("-o").arg(grub_cfg);
+            executor.run(&mut cmd)?;
+        }
+    } else {
+        println!("GRUB theme already configured.");
+    }
+
+    Ok(())
+}
+
+fn add_grub_param(content: &str, key: &str, param: &str) -> String {
+    let mut new_lines = Vec::new();
+    let mut found = false;
+    let key_eq = format!("{}=", key);
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with(&key_eq) {
+            found = true;
+            // Split key and value
+            let parts: Vec<&str> = line.splitn(2, '=').collect();
+            if parts.len() != 2 {
+                new_lines.push(line.to_string());
+                continue;
+            }
+
+            let val = parts[1];
+
+            // Detect quotes
+            let (_quote_char, inner_val) = if val.starts_with('"') && val.ends_with('"') {
+                ("\"", &val[1..val.len() - 1])
+            } else if val.starts_with('\'') && val.ends_with('\'') {
+                ("'", &val[1..val.len() - 1])
+            } else {
+                ("", val)
+            };
+
+            let new_val = if inner_val.is_empty() {
+                param.to_string()
+            } else {
+                // Check if param is already present to avoid duplication
+                if inner_val.contains(param) {
+                    inner_val.to_string()
+                } else {
+                    format!("{} {}", inner_val, param)
+                }
+            };
+
+            // Reconstruct with double quotes for safety
+            new_lines.push(format!("{}=\"{}\"", parts[0], new_val));
+        } else {
+            new_lines.push(line.to_string());
+        }
+    }
+
+    if !found {
+        // If not found, add it
+        new_lines.push(format!("{}=\"{}\"", key, param));
+    }
+
+    new_lines.join("\n")
+}
+
+fn add_grub_kernel_param(content: &str, param: &str) -> String {
+    add_grub_param(content, "GRUB_CMDLINE_LINUX", param)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_grub_param() {
+        let param = "test_param";
+        let key = "TEST_KEY";
+
+        // Case 1: Empty value
+        let input = "TEST_KEY=\"\"";
+        let expected = format!("TEST_KEY=\"{}\"", param);
+        assert_eq!(add_grub_param(input, key, param), expected);
+
+        // Case 2: Existing value
+        let input = "TEST_KEY=\"existing\"";
+        let expected = format!("TEST_KEY=\"existing {}\"", param);
+        assert_eq!(add_grub_param(input, key, param), expected);
+
+        // Case 3: Already present
+        let input = "TEST_KEY=\"existing test_param\"";
+        let expected = "TEST_KEY=\"existing test_param\"";
+        assert_eq!(add_grub_param(input, key, param), expected);
+
+        // Case 4: Not present in file
+        let input = "OTHER_KEY=1";
+        let expected = format!("OTHER_KEY=1\nTEST_KEY=\"{}\"", param);
+        assert_eq!(add_grub_param(input, key, param), expected);
+
+        // Case 5: Existing value with single quotes
+        let input = "TEST_KEY='existing'";
+        let expected = format!("TEST_KEY=\"existing {}\"", param);
+        assert_eq!(add_grub_param(input, key, param), expected);
+
+        // Case 6: No quotes
+        let input = "TEST_KEY=existing";
+        let expected = format!("TEST_KEY=\"existing {}\"", param);
+        assert_eq!(add_grub_param(input, key, param), expected);
+
+        // Case 7: Multiple lines
+        let input = "GRUB_DEFAULT=0\nTEST_KEY=\"\"\nGRUB_TIMEOUT=5";
+        let expected = format!("GRUB_DEFAULT=0\nTEST_KEY=\"{}\"\nGRUB_TIMEOUT=5", param);
+        assert_eq!(add_grub_param(input, key, param), expected);
+    }
+
+    #[test]
+    fn test_add_grub_kernel_param() {
+        let param = "rd.luks.name=123=cryptlvm root=/dev/mapper/instantOS-root resume=/dev/mapper/instantOS-swap";
+        let input = "GRUB_CMDLINE_LINUX=\"\"";
+        let expected = format!("GRUB_CMDLINE_LINUX=\"{}\"", param);
+        assert_eq!(add_grub_kernel_param(input, param), expected);
+
+        let input = "GRUB_CMDLINE_LINUX=\"quiet splash\"";
+        let expected = format!("GRUB_CMDLINE_LINUX=\"quiet splash {}\"", param);
+        assert_eq!(add_grub_kernel_param(input, param), expected);
+    }
+
+    use super::entries::{RootDevice, kernel_options};
+    use crate::arch::execution::mock::MockRunner;
+    use std::path::Path;
+
+    fn layout_context() -> InstallContext {
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::Kernel, "linux-lts".to_string());
+        context.set_answer(QuestionId::UsePlymouth, "true".to_string());
+        context.system_info.has_intel_cpu = true;
+        context.system_info.boot_mode = BootMode::UEFI64;
+        context
+    }
+
+    fn boot_layout(context: &InstallContext, boot_dir: &Path) -> BootLayout {
+        let root = RootDevice::Plain {
+            uuid: "1234".to_string(),
+        };
+        BootLayout::new(
+            context,
+            boot_dir,
+            &boot_dir.join("hooks"),
+            "/dev/nvme0n1",
+            1,
+            &root,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_kernel_options_for_luks_and_btrfs() {
+        let mut context = layout_context();
+        let sd_encrypt = RootDevice::Luks {
+            uuid: "abcd".to_string(),
+            sd_encrypt: true,
+        };
+        let resume = Some("resume=/dev/mapper/instantOS-swap");
+        assert_eq!(
+            kernel_options(&context, &sd_encrypt, resume),
+            "rd.luks.name=abcd=cryptlvm root=/dev/mapper/instantOS-root resume=/dev/mapper/instantOS-swap rw rootflags=subvol=@ loglevel=3 quiet splash"
+        );
+
+        context.set_answer(QuestionId::RootFilesystem, "ext4".to_string());
+        context.set_answer(QuestionId::MinimalMode, "true".to_string());
+        let encrypt_hook = RootDevice::Luks {
+            uuid: "abcd".to_string(),
+            sd_encrypt: false,
+        };
+        assert_eq!(
+            kernel_options(&context, &encrypt_hook, resume),
+            "cryptdevice=UUID=abcd:cryptlvm root=/dev/mapper/instantOS-root resume=/dev/mapper/instantOS-swap rw loglevel=3 quiet"
+        );
+
+        context.set_answer(QuestionId::DiskLayout, "luks-on-lvm".to_string());
+        assert_eq!(
+            kernel_options(&context, &sd_encrypt, None),
+            "rd.luks.name=abcd=cryptroot root=/dev/mapper/cryptroot rw loglevel=3 quiet"
+        );
+    }
+
+    #[test]
+    fn test_grub_bios_installs_to_both_raid1_disks() {
+        let mut context = layout_context();
+        context.set_answer(QuestionId::Disk, "/dev/sda".to_string());
+        context.set_answer(QuestionId::DiskLayout, "btrfs-raid1".to_string());
+        context.set_answer(QuestionId::SecondDisk, "/dev/sdb".to_string());
+
+        let mock = MockRunner::new();
+        install_grub_bios(&context, &mock).unwrap();
+        assert_eq!(
+            mock.command_log(),
+            [
+                "grub-install --target=i386-pc /dev/sda",
+                "grub-install --target=i386-pc /dev/sdb"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_systemd_boot_install_writes_entries_and_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("initramfs-linux-lts-fallback.img"), "").unwrap();
+        let context = layout_context();
+        let layout = boot_layout(&context, dir.path());
+
+        let mock = MockRunner::new();
+        systemd_boot::install_systemd_boot(&mock, &layout).unwrap();
+        assert_eq!(
+            mock.command_log(),
+            vec![format!(
+                "bootctl --esp-path={} install",
+                dir.path().display()
+            )]
+        );
+
+        let loader = dir.path().join("loader");
+        let loader_conf = std::fs::read_to_string(loader.join("loader.conf")).unwrap();
+        assert!(loader_conf.starts_with("default instantos-linux-lts.conf\n"));
+
+        let entry =
+            std::fs::read_to_string(loader.join("entries/instantos-linux-lts.conf")).unwrap();
+        assert_eq!(
+            entry,
+            "title   instantOS (linux-lts)\n\
+             linux   /vmlinuz-linux-lts\n\
+             initrd  /intel-ucode.img\n\
+             initrd  /initramfs-linux-lts.img\n\
+             options root=UUID=1234 rw rootflags=subvol=@ loglevel=3 quiet splash\n"
+        );
+        let fallback =
+            std::fs::read_to_string(loader.join("entries/instantos-linux-lts-fallback.conf"))
+                .unwrap();
+        assert!(fallback.contains("initrd  /initramfs-linux-lts-fallback.img"));
+
+        let hook = std::fs::read_to_string(dir.path().join("hooks/95-systemd-boot.hook")).unwrap();
+        assert!(hook.contains("systemd-boot-update.service"));
+    }
+
+    #[test]
+    fn test_limine_uefi_install_with_windows_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let windows = dir.path().join(entries::WINDOWS_BOOT_MANAGER);
+        std::fs::create_dir_all(windows.parent().unwrap()).unwrap();
+        std::fs::write(&windows, "").unwrap();
+        let context = layout_context();
+        let layout = boot_layout(&context, dir.path());
+        assert!(layout.windows);
+
+        let mock = MockRunner::new();
+        limine::install_limine(&BootMode::UEFI64, &mock, &layout).unwrap();
+        let log = mock.command_log();
+        assert_eq!(
+            log[0],
+            format!(
+                "install -D -m 0644 /usr/share/limine/BOOTX64.EFI {}/EFI/limine/BOOTX64.EFI",
+                dir.path().display()
+            )
+        );
+        assert_eq!(
+            log[1],
+            "efibootmgr --create --disk /dev/nvme0n1 --part 1 --label Limine --loader \\EFI\\limine\\BOOTX64.EFI --unicode"
+        );
+
+        let config = std::fs::read_to_string(dir.path().join("limine/limine.conf")).unwrap();
+        assert!(config.contains("/instantOS (linux-lts)\n    protocol: linux\n"));
+        assert!(config.contains(
+            "    module_path: boot():/intel-ucode.img\n    module_path: boot():/initramfs-linux-lts.img\n"
+        ));
+        assert!(config.ends_with(
+            "/Windows\n    protocol: efi\n    path: boot():/EFI/Microsoft/Boot/bootmgfw.efi\n"
+        ));
+        let hook = std::fs::read_to_string(dir.path().join("hooks/95-limine.hook")).unwrap();
+        assert!(hook.contains("Exec = /usr/bin/install -D -m 0644 /usr/share/limine/BOOTX64.EFI"));
+    }
+
+    #[test]
+    fn test_disk_image_boot_install_skips_firmware_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut context = layout_context();
+        context.system_info.disk_image = true;
+        let layout = boot_layout(&context, dir.path());
+
+        let mock = MockRunner::new();
+        limine::install_limine(&BootMode::UEFI64, &mock, &layout).unwrap();
+        systemd_boot::install_systemd_boot(&mock, &layout).unwrap();
+        assert_eq!(
+            mock.command_log(),
+            vec![
+                format!(
+                    "install -D -m 0644 /usr/share/limine/BOOTX64.EFI {}/EFI/BOOT/BOOTX64.EFI",
+                    dir.path().display()
+                ),
+                format!(
+                    "bootctl --esp-path={} --no-variables install",
+                    dir.path().display()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_limine_bios_install_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut context = layout_context();
+        context.system_info.boot_mode = BootMode::BIOS;
+        let layout = boot_layout(&context, dir.path());
+
+        let mock = MockRunner::new();
+        limine::install_limine(&BootMode::BIOS, &mock, &layout).unwrap();
+        let log = mock.command_log();
+        assert_eq!(log.len(), 2);
+        assert!(log[0].ends_with("/limine/limine-bios.sys"));
+        assert_eq!(log[1], "limine bios-install /dev/nvme0n1");
+        assert!(dir.path().join("limine/limine.conf").exists());
+    }
+
+    #[test]
+    fn test_configure_snapper_writes_config_and_enables_timers() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut context = layout_context();
+        context.set_answer(QuestionId::Username, "alice".to_string());
+        context.set_answer(QuestionId::BtrfsSnapshots, "true".to_string());
+
+        let mock = MockRunner::new();
+        snapshots::configure_snapper(&context, &mock, dir.path()).unwrap();
+
+        let config = std::fs::read_to_string(dir.path().join("etc/snapper/configs/root")).unwrap();
+        assert!(config.contains("SUBVOLUME=\"/\"\n"));
+        assert!(config.contains("ALLOW_USERS=\"alice\"\n"));
+        assert!(config.contains("TIMELINE_LIMIT_DAILY=\"7\"\n"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("etc/conf.d/snapper")).unwrap(),
+            "SNAPPER_CONFIGS=\"root\"\n"
+        );
+
+        let log = mock.command_log();
+        assert!(log.contains(&"systemctl enable snapper-timeline.timer".to_string()));
+        assert!(log.contains(&"systemctl enable grub-btrfsd.service".to_string()));
+        assert!(
+            log.last()
+                .unwrap()
+                .starts_with("snapper --no-dbus -c root create")
+        );
+        assert_eq!(
+            snapshot_package_list(&context),
+            ["snapper", "snap-pac", "grub-btrfs", "inotify-tools"]
+        );
+
+        context.set_answer(QuestionId::Bootloader, "limine".to_string());
+        assert_eq!(snapshot_package_list(&context), ["snapper", "snap-pac"]);
+    }
+
+    #[test]
+    fn test_snapshot_overlay_hook_files() {
+        let dir = tempfile::tempdir().unwrap();
+        install_overlay_hook(&MockRunner::new(), dir.path()).unwrap();
+
+        let install = dir.path().join("etc/initcpio/install/snapshot-overlay");
+        let script = dir
+            .path()
+            .join("etc/initcpio/snapshot-overlay/snapshot-overlay.sh");
+        let service = std::fs::read_to_string(
+            dir.path()
+                .join("etc/initcpio/snapshot-overlay/snapshot-overlay.service"),
+        )
+        .unwrap();
+
+        assert!(
+            Command::new("bash")
+                .arg("-n")
+                .arg(&install)
+                .status()
+                .unwrap()
+                .success()
+        );
+        assert!(
+            Command::new("sh")
+                .arg("-n")
+                .arg(&script)
+                .status()
+                .unwrap()
+                .success()
+        );
+        let install = std::fs::read_to_string(&install).unwrap();
+        assert!(install.contains(
+            "add_file /etc/initcpio/snapshot-overlay/snapshot-overlay.sh /usr/local/bin/snapshot-overlay 755"
+        ));
+        assert!(install.contains("initrd-root-fs.target.wants/snapshot-overlay.service"));
+        assert!(service.contains("After=sysroot.mount\nBefore=initrd-root-fs.target\n"));
+        assert!(service.contains("ExecStart=/usr/local/bin/snapshot-overlay\n"));
+        let script = std::fs::read_to_string(&script).unwrap();
+        assert!(script.contains("grep -qx 'ro=true' || exit 0"));
+    }
+
+    #[test]
+    fn test_systemd_boot_snapshot_entries_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = layout_context();
+        let layout = boot_layout(&context, dir.path());
+        for number in 1..=7 {
+            let snapshot = dir.path().join(format!(".snapshots/{number}"));
+            std::fs::create_dir_all(snapshot.join("snapshot")).unwrap();
+            std::fs::write(
+                snapshot.join("info.xml"),
+                format!("<snapshot>\n  <date>2026-01-0{number} 10:00:00</date>\n</snapshot>\n"),
+            )
+            .unwrap();
+        }
+        let entries = dir.path().join("loader/entries");
+        std::fs::create_dir_all(&entries).unwrap();
+        std::fs::write(entries.join("snapshot-1.conf"), "stale").unwrap();
+
+        let mock = MockRunner::new();
+        snapshots::install_snapshot_entries(&Bootloader::SystemdBoot, &mock, &layout, dir.path())
+            .unwrap();
+        let script = snapshots::entry_script_path(dir.path());
+        assert_eq!(
+            mock.command_log().last().unwrap().trim_end(),
+            script.display().to_string()
+        );
+        let hook =
+            std::fs::read_to_string(dir.path().join("hooks/zzzz-snapshot-boot-entries.hook"))
+                .unwrap();
+        assert!(hook.contains(&format!("Exec = {}", script.display())));
+
+        let status = Command::new("sh").arg(&script).status().unwrap();
+        assert!(status.success());
+        assert!(!entries.join("snapshot-1.conf").exists());
+        assert!(!entries.join("snapshot-2.conf").exists());
+        let entry = std::fs::read_to_string(entries.join("snapshot-7.conf")).unwrap();
+        assert_eq!(
+            entry,
+            "title   Snapshot 7 (2026-01-07 10:00:00) - instantOS (linux-lts)\n\
+             linux   /vmlinuz-linux-lts\n\
+             initrd  /intel-ucode.img\n\
+             initrd  /initramfs-linux-lts.img\n\
+             options root=UUID=1234 ro rootflags=subvol=@snapshots/7/snapshot loglevel=3 quiet splash\n"
+        );
+    }
+
+    #[test]
+    fn test_limine_snapshot_entries_replace_marked_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = layout_context();
+        let layout = boot_layout(&context, dir.path());
+        std::fs::create_dir_all(dir.path().join(".snapshots/3/snapshot")).unwrap();
+
+        let mock = MockRunner::new();
+        limine::install_limine(&BootMode::UEFI64, &mock, &layout).unwrap();
+        snapshots::install_snapshot_entries(&Bootloader::Limine, &mock, &layout, dir.path())
+            .unwrap();
+
+        let script = snapshots::entry_script_path(dir.path());
+        for _ in 0..2 {
+            assert!(Command::new("sh").arg(&script).status().unwrap().success());
+        }
+        let config = std::fs::read_to_string(dir.path().join("limine/limine.conf")).unwrap();
+        assert!(config.starts_with("timeout: 3\n"));
+        assert_eq!(config.matches("/Snapshots\n").count(), 1);
+        assert!(config.contains(
+            "//Snapshot 3 () - instantOS (linux-lts)\n    protocol: linux\n    path: boot():/vmlinuz-linux-lts\n    cmdline: root=UUID=1234 ro rootflags=subvol=@snapshots/3/snapshot loglevel=3 quiet splash\n"
+        ));
+        assert!(config.ends_with("# END instantOS snapshots\n"));
+    }
+}
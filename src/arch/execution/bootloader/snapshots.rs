@@ -0,0 +1,339 @@
+//! Snapper setup for btrfs installs and boot menu entries for its snapshots.
+//!
+//! GRUB gets its snapshot submenu from grub-btrfs. systemd-boot and Limine read
+//! static files, so a generated script rewrites their entries for the newest
+//! snapshots after every pacman transaction (after snap-pac has taken its
+//! post-transaction snapshot).
+//!
+//! Snapshots are read-only, so an initramfs hook mounts a RAM overlay on top of a
+//! snapshot root before the system starts.
+
+use super::entries::{BootEntry, BootLayout, write_boot_file};
+use super::{limine, systemd_boot};
+use crate::arch::config::{BTRFS_ROOT_SUBVOLUME, Bootloader, btrfs_snapshots_enabled};
+use crate::arch::engine::{InstallContext, QuestionId};
+use crate::arch::execution::CommandRunner;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Number of snapshots listed in the systemd-boot and Limine menus
+const SNAPSHOT_BOOT_ENTRIES: usize = 5;
+
+/// Sorts after snap-pac's `zz-snap-pac-post.hook`, so the post snapshot is listed
+const SNAPSHOT_ENTRIES_HOOK: &str = "zzzz-snapshot-boot-entries.hook";
+
+/// mkinitcpio hook with the snapshot overlay. grub-btrfs ships the same for busybox
+/// images as `grub-btrfs-overlayfs`, but the installer builds systemd images.
+pub const OVERLAY_HOOK: &str = "snapshot-overlay";
+
+/// Where the overlay script and unit are kept for the hook to copy into the image
+const OVERLAY_DIR: &str = "etc/initcpio/snapshot-overlay";
+
+const LIMINE_BEGIN_MARKER: &str = "# BEGIN instantOS snapshots";
+const LIMINE_END_MARKER: &str = "# END instantOS snapshots";
+
+/// Packages for snapper and the snapshot boot menu
+pub fn snapshot_package_list(context: &InstallContext) -> Vec<String> {
+    if !btrfs_snapshots_enabled(context) {
+        return Vec::new();
+    }
+
+    let mut packages = vec!["snapper".to_string(), "snap-pac".to_string()];
+    if Bootloader::from_context(context) == Bootloader::Grub {
+        // grub-btrfsd watches /.snapshots with inotify and regenerates grub.cfg
+        packages.push("grub-btrfs".to_string());
+        packages.push("inotify-tools".to_string());
+    }
+    packages
+}
+
+/// Where the snapshot entry script is installed, relative to the system root
+pub(super) fn entry_script_path(root: &Path) -> PathBuf {
+    root.join("usr/local/bin/instantos-snapshot-entries")
+}
+
+/// Write the snapper config for `/`, enable its timers and take the first snapshot.
+///
+/// `snapper create-config` refuses to run when `/.snapshots` already exists, and here
+/// it is the mounted `@snapshots` subvolume, so the config is written directly.
+pub(super) fn configure_snapper(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    root: &Path,
+) -> Result<()> {
+    println!("Configuring snapper for btrfs snapshots...");
+
+    let user = context
+        .get_answer(&QuestionId::Username)
+        .map(|s| s.as_str())
+        .unwrap_or("");
+    write_boot_file(
+        executor,
+        &root.join("etc/snapper/configs/root"),
+        &render_snapper_config(user),
+    )?;
+    write_boot_file(
+        executor,
+        &root.join("etc/conf.d/snapper"),
+        "SNAPPER_CONFIGS=\"root\"\n",
+    )?;
+
+    let snapshots_dir = root.join(".snapshots");
+    executor.run(Command::new("chmod").arg("750").arg(&snapshots_dir))?;
+
+    let mut services = vec!["snapper-timeline.timer", "snapper-cleanup.timer"];
+    if Bootloader::from_context(context) == Bootloader::Grub {
+        services.push("grub-btrfsd.service");
+    }
+    for service in services {
+        executor.run(Command::new("systemctl").args(["enable", service]))?;
+    }
+
+    // There is no D-Bus daemon inside the chroot
+    executor.run(Command::new("snapper").args([
+        "--no-dbus",
+        "-c",
+        "root",
+        "create",
+        "--description",
+        "Fresh install",
+    ]))?;
+
+    Ok(())
+}
+
+/// Install the script and pacman hook that list snapshots in the systemd-boot or
+/// Limine menu, and generate the entries for the snapshots taken so far.
+pub(super) fn install_snapshot_entries(
+    bootloader: &Bootloader,
+    executor: &dyn CommandRunner,
+    layout: &BootLayout,
+    root: &Path,
+) -> Result<()> {
+    let entry = layout
+        .entries
+        .first()
+        .context("No boot entry to base snapshot entries on")?;
+    let snapshots_dir = root.join(".snapshots");
+    let script = match bootloader {
+        Bootloader::Grub => return Ok(()),
+        Bootloader::SystemdBoot => render_systemd_boot_script(layout, entry, &snapshots_dir),
+        Bootloader::Limine => render_limine_script(layout, entry, &snapshots_dir),
+    };
+
+    println!("Adding snapper snapshots to the boot menu...");
+    let script_path = entry_script_path(root);
+    write_boot_file(executor, &script_path, &script)?;
+    executor.run(Command::new("chmod").arg("755").arg(&script_path))?;
+    write_boot_file(
+        executor,
+        &layout.hooks_dir.join(SNAPSHOT_ENTRIES_HOOK),
+        &render_snapshot_hook(&script_path),
+    )?;
+    executor.run(&mut Command::new(&script_path))?;
+
+    Ok(())
+}
+
+/// Write the [`OVERLAY_HOOK`] mkinitcpio hook below `root`
+pub fn install_overlay_hook(executor: &dyn CommandRunner, root: &Path) -> Result<()> {
+    let dir = root.join(OVERLAY_DIR);
+    write_boot_file(
+        executor,
+        &dir.join(format!("{OVERLAY_HOOK}.sh")),
+        &render_overlay_script(),
+    )?;
+    write_boot_file(
+        executor,
+        &dir.join(format!("{OVERLAY_HOOK}.service")),
+        &render_overlay_service(),
+    )?;
+    write_boot_file(
+        executor,
+        &root.join("etc/initcpio/install").join(OVERLAY_HOOK),
+        &render_overlay_install(),
+    )
+}
+
+/// Runs in the initramfs once the root is mounted at /sysroot. Only a read-only
+/// root, i.e. a snapshot, is moved below a tmpfs-backed overlay.
+fn render_overlay_script() -> String {
+    "#!/bin/sh\n\
+     # Generated by the instantOS installer: boot read-only snapshots with a RAM overlay\n\
+     root=/sysroot\n\
+     btrfs property get \"$root\" ro 2>/dev/null | grep -qx 'ro=true' || exit 0\n\
+     overlay=/run/snapshot-overlay\n\
+     mkdir -p \"$overlay/lower\" \"$overlay/ram\"\n\
+     mount --move \"$root\" \"$overlay/lower\"\n\
+     mount -t tmpfs -o mode=0755 snapshot-overlay \"$overlay/ram\"\n\
+     mkdir -p \"$overlay/ram/upper\" \"$overlay/ram/work\"\n\
+     mount -t overlay -o \"lowerdir=$overlay/lower,upperdir=$overlay/ram/upper,workdir=$overlay/ram/work\" rootfs \"$root\"\n"
+        .to_string()
+}
+
+fn render_overlay_service() -> String {
+    format!(
+        "[Unit]\n\
+         Description=Writable overlay for read-only snapshot roots\n\
+         DefaultDependencies=no\n\
+         Requires=sysroot.mount\n\
+         After=sysroot.mount\n\
+         Before=initrd-root-fs.target\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         RemainAfterExit=yes\n\
+         ExecStart=/usr/local/bin/{OVERLAY_HOOK}\n"
+    )
+}
+
+fn render_overlay_install() -> String {
+    format!(
+        "#!/bin/bash\n\
+         # Generated by the instantOS installer\n\
+         \n\
+         build() {{\n\
+         \x20   add_module overlay\n\
+         \x20   add_binary btrfs\n\
+         \x20   add_file /{OVERLAY_DIR}/{OVERLAY_HOOK}.sh /usr/local/bin/{OVERLAY_HOOK} 755\n\
+         \x20   add_file /{OVERLAY_DIR}/{OVERLAY_HOOK}.service /etc/systemd/system/{OVERLAY_HOOK}.service\n\
+         \x20   add_symlink /etc/systemd/system/initrd-root-fs.target.wants/{OVERLAY_HOOK}.service \\\n\
+         \x20       /etc/systemd/system/{OVERLAY_HOOK}.service\n\
+         }}\n\
+         \n\
+         help() {{\n\
+         \x20   echo 'Boots read-only btrfs snapshots with a writable overlay in RAM (systemd images).'\n\
+         }}\n"
+    )
+}
+
+fn render_snapper_config(user: &str) -> String {
+    format!(
+        "SUBVOLUME=\"/\"\n\
+         FSTYPE=\"btrfs\"\n\
+         QGROUP=\"\"\n\
+         SPACE_LIMIT=\"0.5\"\n\
+         FREE_LIMIT=\"0.2\"\n\
+         ALLOW_USERS=\"{user}\"\n\
+         ALLOW_GROUPS=\"\"\n\
+         SYNC_ACL=\"no\"\n\
+         BACKGROUND_COMPARISON=\"yes\"\n\
+         NUMBER_CLEANUP=\"yes\"\n\
+         NUMBER_MIN_AGE=\"1800\"\n\
+         NUMBER_LIMIT=\"10\"\n\
+         NUMBER_LIMIT_IMPORTANT=\"5\"\n\
+         TIMELINE_CREATE=\"yes\"\n\
+         TIMELINE_CLEANUP=\"yes\"\n\
+         TIMELINE_MIN_AGE=\"1800\"\n\
+         TIMELINE_LIMIT_HOURLY=\"5\"\n\
+         TIMELINE_LIMIT_DAILY=\"7\"\n\
+         TIMELINE_LIMIT_WEEKLY=\"0\"\n\
+         TIMELINE_LIMIT_MONTHLY=\"0\"\n\
+         TIMELINE_LIMIT_YEARLY=\"0\"\n\
+         EMPTY_PRE_POST_CLEANUP=\"yes\"\n\
+         EMPTY_PRE_POST_MIN_AGE=\"1800\"\n"
+    )
+}
+
+/// The default entry booting `@snapshots/$number/snapshot` read-only.
+///
+/// `$number` and `$date` are expanded by the generated shell script. The kernel on
+/// the boot partition is always the current one, as with grub-btrfs.
+fn snapshot_entry(entry: &BootEntry) -> BootEntry {
+    let root_flag = format!("rootflags=subvol={BTRFS_ROOT_SUBVOLUME}");
+    let options = entry
+        .options
+        .split_whitespace()
+        .map(|option| match option {
+            "rw" => "ro".to_string(),
+            option if option == root_flag => "rootflags=subvol=@snapshots/$number/snapshot".into(),
+            option => option.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    BootEntry {
+        id: "snapshot-$number".to_string(),
+        title: format!("Snapshot $number ($date) - {}", entry.title),
+        kernel: entry.kernel.clone(),
+        initrds: entry.initrds.clone(),
+        options,
+    }
+}
+
+/// Shell prelude that sets `$numbers` to the newest snapshot numbers
+fn script_header(snapshots_dir: &Path) -> String {
+    format!(
+        "#!/bin/sh\n\
+         # Generated by the instantOS installer: boot entries for the newest snapper snapshots\n\
+         set -eu\n\
+         snapshots='{dir}'\n\
+         numbers=$(ls -1 \"$snapshots\" 2>/dev/null | grep -E '^[0-9]+$' | sort -n | tail -n {SNAPSHOT_BOOT_ENTRIES})\n\
+         snapshot_date() {{\n\
+         \x20   sed -n 's:.*<date>\\(.*\\)</date>.*:\\1:p' \"$snapshots/$1/info.xml\" 2>/dev/null || true\n\
+         }}\n",
+        dir = snapshots_dir.display()
+    )
+}
+
+fn render_systemd_boot_script(
+    layout: &BootLayout,
+    entry: &BootEntry,
+    snapshots_dir: &Path,
+) -> String {
+    format!(
+        "{header}\
+         entries='{entries}'\n\
+         rm -f \"$entries\"/snapshot-*.conf\n\
+         for number in $numbers; do\n\
+         \x20   [ -d \"$snapshots/$number/snapshot\" ] || continue\n\
+         \x20   date=$(snapshot_date \"$number\")\n\
+         \x20   cat > \"$entries/snapshot-$number.conf\" <<EOF\n\
+         {body}EOF\n\
+         done\n",
+        header = script_header(snapshots_dir),
+        entries = layout.boot_dir.join("loader/entries").display(),
+        body = systemd_boot::render_entry(&snapshot_entry(entry)),
+    )
+}
+
+fn render_limine_script(layout: &BootLayout, entry: &BootEntry, snapshots_dir: &Path) -> String {
+    format!(
+        "{header}\
+         conf='{conf}'\n\
+         sed -i '/^{LIMINE_BEGIN_MARKER}$/,/^{LIMINE_END_MARKER}$/d' \"$conf\"\n\
+         [ -n \"$numbers\" ] || exit 0\n\
+         {{\n\
+         \x20   echo '{LIMINE_BEGIN_MARKER}'\n\
+         \x20   echo '/Snapshots'\n\
+         \x20   for number in $numbers; do\n\
+         \x20       [ -d \"$snapshots/$number/snapshot\" ] || continue\n\
+         \x20       date=$(snapshot_date \"$number\")\n\
+         \x20       cat <<EOF\n\
+         {body}EOF\n\
+         \x20   done\n\
+         \x20   echo '{LIMINE_END_MARKER}'\n\
+         }} >> \"$conf\"\n",
+        header = script_header(snapshots_dir),
+        conf = layout.boot_dir.join("limine/limine.conf").display(),
+        body = limine::render_entry(&snapshot_entry(entry), "//"),
+    )
+}
+
+fn render_snapshot_hook(script: &Path) -> String {
+    format!(
+        "[Trigger]\n\
+         Type = Package\n\
+         Operation = Install\n\
+         Operation = Upgrade\n\
+         Operation = Remove\n\
+         Target = *\n\
+         \n\
+         [Action]\n\
+         Description = Updating snapshot boot entries...\n\
+         When = PostTransaction\n\
+         Exec = {}\n",
+        script.display()
+    )
+}
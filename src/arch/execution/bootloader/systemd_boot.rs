@@ -0,0 +1,85 @@
+use super::entries::{BootEntry, BootLayout, write_boot_file};
+use crate::arch::execution::CommandRunner;
+use anyhow::Result;
+use std::process::Command;
+
+pub(super) fn install_systemd_boot(
+    executor: &dyn CommandRunner,
+    layout: &BootLayout,
+) -> Result<()> {
+    println!(
+        "Installing systemd-boot to {}...",
+        layout.boot_dir.display()
+    );
+
+    let mut cmd = Command::new("bootctl");
+    cmd.arg(format!("--esp-path={}", layout.boot_dir.display()));
+    if layout.removable {
+        // bootctl always installs the fallback BOOTX64.EFI; only skip the NVRAM entry
+        cmd.arg("--no-variables");
+    }
+    cmd.arg("install");
+    executor.run(&mut cmd)?;
+
+    let loader_dir = layout.boot_dir.join("loader");
+    for entry in &layout.entries {
+        let path = loader_dir
+            .join("entries")
+            .join(format!("{}.conf", entry.id));
+        write_boot_file(executor, &path, &render_entry(entry))?;
+    }
+    write_boot_file(
+        executor,
+        &loader_dir.join("loader.conf"),
+        &render_loader_conf(layout),
+    )?;
+
+    if layout.windows {
+        println!("Found Windows Boot Manager; systemd-boot lists it automatically.");
+    }
+
+    // systemd-boot-update.service copies the new bootloader from /usr/lib to the ESP
+    write_boot_file(
+        executor,
+        &layout.hooks_dir.join("95-systemd-boot.hook"),
+        SYSTEMD_BOOT_HOOK,
+    )?;
+
+    Ok(())
+}
+
+const SYSTEMD_BOOT_HOOK: &str = "\
+[Trigger]
+Type = Package
+Operation = Upgrade
+Target = systemd
+
+[Action]
+Description = Gracefully upgrading systemd-boot...
+When = PostTransaction
+Exec = /usr/bin/systemctl restart systemd-boot-update.service
+";
+
+fn render_loader_conf(layout: &BootLayout) -> String {
+    let default = layout
+        .entries
+        .first()
+        .map(|entry| format!("default {}.conf\n", entry.id))
+        .unwrap_or_default();
+    format!("{default}timeout 3\nconsole-mode max\neditor no\n")
+}
+
+pub(super) fn render_entry(entry: &BootEntry) -> String {
+    let mut lines = vec![
+        format!("title   {}", entry.title),
+        format!("linux   {}", entry.kernel),
+    ];
+    lines.extend(
+        entry
+            .initrds
+            .iter()
+            .map(|initrd| format!("initrd  {initrd}")),
+    );
+    lines.push(format!("options {}", entry.options));
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,185 @@
+//! Sub-step checkpoints, so a step that failed halfway resumes where it stopped.
+//!
+//! A checkpoint is skipped on the next attempt only while the answers it read are
+//! unchanged; editing one of them in the questions file makes it run again.
+
+use super::CommandRunner;
+use super::paths;
+use super::state::{Checkpoint, InstallState, answers_hash};
+use super::step::InstallStep;
+use crate::arch::engine::{InstallContext, QuestionId};
+use anyhow::Result;
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+/// A resumable part of an install step
+pub struct SubStep {
+    pub name: &'static str,
+    /// Answers the sub-step reads
+    pub questions: &'static [QuestionId],
+    /// What the sub-step changes, for the resume report
+    pub changes: &'static str,
+}
+
+pub struct Checkpoints<'a> {
+    step: InstallStep,
+    context: &'a InstallContext,
+    /// Dry runs neither skip nor record sub-steps
+    enabled: bool,
+    state_file: PathBuf,
+    state: RefCell<InstallState>,
+}
+
+impl<'a> Checkpoints<'a> {
+    pub fn load(
+        step: InstallStep,
+        context: &'a InstallContext,
+        executor: &dyn CommandRunner,
+    ) -> Self {
+        Self::at(
+            PathBuf::from(paths::STATE_FILE),
+            step,
+            context,
+            !executor.dry_run(),
+        )
+    }
+
+    fn at(
+        state_file: PathBuf,
+        step: InstallStep,
+        context: &'a InstallContext,
+        enabled: bool,
+    ) -> Self {
+        let state = if enabled {
+            InstallState::load_from(&state_file).unwrap_or_else(|e| {
+                println!("Warning: Failed to load install state: {}", e);
+                InstallState::new()
+            })
+        } else {
+            InstallState::new()
+        };
+        Self {
+            step,
+            context,
+            enabled,
+            state_file,
+            state: RefCell::new(state),
+        }
+    }
+
+    /// Whether the sub-step still has to run
+    pub fn pending(&self, sub_step: &SubStep) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let inputs = answers_hash(self.context, sub_step.questions);
+        match self.state.borrow().checkpoint(self.step, sub_step.name) {
+            Some(checkpoint) if checkpoint.inputs == inputs => {
+                println!(
+                    "Skipping {} (completed in a previous attempt).",
+                    sub_step.name
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    pub fn complete(&self, sub_step: &SubStep) {
+        if !self.enabled {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+        state.record_checkpoint(Checkpoint {
+            step: self.step,
+            name: sub_step.name.to_string(),
+            questions: sub_step.questions.to_vec(),
+            inputs: answers_hash(self.context, sub_step.questions),
+            changes: sub_step.changes.to_string(),
+        });
+        if let Err(e) = state.save_to(&self.state_file) {
+            println!(
+                "Warning: Failed to save checkpoint {}: {}",
+                sub_step.name, e
+            );
+        }
+    }
+
+    /// Keep a non-fatal problem for the finished screen
+    pub fn warn(&self, warning: &str) {
+        println!("Warning: {}", warning);
+        if !self.enabled {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+        state.record_warning(warning.to_string());
+        if let Err(e) = state.save_to(&self.state_file) {
+            println!("Warning: Failed to save install state: {}", e);
+        }
+    }
+
+    /// Run a synchronous sub-step unless it completed in a previous attempt
+    pub fn run(&self, sub_step: &SubStep, f: impl FnOnce() -> Result<()>) -> Result<()> {
+        if self.pending(sub_step) {
+            f()?;
+            self.complete(sub_step);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTNAME: SubStep = SubStep {
+        name: "network",
+        questions: &[QuestionId::Hostname],
+        changes: "Wrote /etc/hostname",
+    };
+
+    #[test]
+    fn skips_completed_sub_steps_until_their_answers_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_file = dir.path().join("install_state.toml");
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::Hostname, "box".to_string());
+
+        let runs = RefCell::new(0);
+        let attempt = |context: &InstallContext| {
+            let checkpoints =
+                Checkpoints::at(state_file.clone(), InstallStep::Config, context, true);
+            checkpoints
+                .run(&HOSTNAME, || {
+                    *runs.borrow_mut() += 1;
+                    Ok(())
+                })
+                .unwrap();
+        };
+
+        attempt(&context);
+        attempt(&context);
+        assert_eq!(*runs.borrow(), 1);
+
+        context.set_answer(QuestionId::Hostname, "other".to_string());
+        attempt(&context);
+        assert_eq!(*runs.borrow(), 2);
+
+        // A failed sub-step is not recorded
+        let checkpoints = Checkpoints::at(state_file.clone(), InstallStep::Base, &context, true);
+        assert!(
+            checkpoints
+                .run(&HOSTNAME, || anyhow::bail!("boom"))
+                .is_err()
+        );
+        let state = InstallState::load_from(&state_file).unwrap();
+        assert!(state.checkpoint(InstallStep::Base, "network").is_none());
+        assert_eq!(
+            state
+                .checkpoint(InstallStep::Config, "network")
+                .unwrap()
+                .changes,
+            "Wrote /etc/hostname"
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use super::CommandRunner;
+use super::checkpoint::{Checkpoints, SubStep};
+use super::step::InstallStep;
 use crate::arch::engine::{InstallContext, QuestionId};
 use crate::arch::mkinitcpio::MkinitcpioConfig;
 use anyhow::{Context, Result};
@@ -50,27 +52,133 @@ pub fn add_user_to_groups(username: &str, executor: &dyn CommandRunner) -> Resul
     Ok(())
 }
 
+const REPOSITORIES: SubStep = SubStep {
+    name: "repositories",
+    questions: &[],
+    changes: "Enabled multilib and the local repository, and synced the package databases",
+};
+
+const PACMAN: SubStep = SubStep {
+    name: "pacman",
+    questions: &[],
+    changes: "Adjusted /etc/pacman.conf",
+};
+
+const PACKAGES: SubStep = SubStep {
+    name: "packages",
+    questions: &[
+        QuestionId::Kernel,
+        QuestionId::MinimalMode,
+        QuestionId::DesktopEnvironment,
+        QuestionId::DisplayManager,
+        QuestionId::Locale,
+        QuestionId::UseEncryption,
+        QuestionId::DiskLayout,
+        QuestionId::SwapType,
+        QuestionId::UsePlymouth,
+        QuestionId::Bootloader,
+        QuestionId::RootFilesystem,
+        QuestionId::BtrfsSnapshots,
+    ],
+    changes: "Installed the standard package set",
+};
+
+const TIMEZONE: SubStep = SubStep {
+    name: "timezone",
+    questions: &[QuestionId::Timezone],
+    changes: "Linked /etc/localtime and set the hardware clock",
+};
+
+const LOCALE: SubStep = SubStep {
+    name: "locale",
+    questions: &[QuestionId::Locale],
+    changes: "Generated locales and wrote /etc/locale.conf",
+};
+
+const NETWORK: SubStep = SubStep {
+    name: "network",
+    questions: &[QuestionId::Hostname],
+    changes: "Wrote /etc/hostname and /etc/hosts",
+};
+
+const USERS: SubStep = SubStep {
+    name: "users",
+    questions: &[QuestionId::Username, QuestionId::Password],
+    changes: "Created the user and set passwords",
+};
+
+const ENVIRONMENT: SubStep = SubStep {
+    name: "environment",
+    questions: &[],
+    changes: "Wrote /etc/profile.d/instantos.sh",
+};
+
+const VCONSOLE: SubStep = SubStep {
+    name: "vconsole",
+    questions: &[QuestionId::Keymap],
+    changes: "Wrote /etc/vconsole.conf",
+};
+
+const SUDO: SubStep = SubStep {
+    name: "sudo",
+    questions: &[],
+    changes: "Allowed the wheel group to use sudo",
+};
+
+const ZRAM: SubStep = SubStep {
+    name: "zram",
+    questions: &[QuestionId::SwapType],
+    changes: "Wrote /etc/systemd/zram-generator.conf",
+};
+
+const MKINITCPIO: SubStep = SubStep {
+    name: "mkinitcpio",
+    questions: &[
+        QuestionId::UseEncryption,
+        QuestionId::DiskLayout,
+        QuestionId::SwapType,
+        QuestionId::Hibernation,
+        QuestionId::UsePlymouth,
+        QuestionId::MinimalMode,
+        QuestionId::RootFilesystem,
+        QuestionId::BtrfsSnapshots,
+    ],
+    changes: "Updated /etc/mkinitcpio.conf hooks and rebuilt the initramfs",
+};
+
 pub async fn install_config(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
     println!("Configuring system (inside chroot)...");
+    let checkpoints = Checkpoints::load(InstallStep::Config, context, executor);
+
+    if checkpoints.pending(&REPOSITORIES) {
+        // Enable multilib for 32-bit support (needed for lib32-vulkan-* GPU drivers)
+        // This runs before package installation so lib32 packages can be installed.
+        println!("Enabling multilib repository...");
+        crate::common::pacman::enable_multilib(executor.dry_run()).await?;
+        crate::arch::offline::enable(context, executor)?;
+
+        // Update repos after enabling multilib
+        sync_repos(executor)?;
+        checkpoints.complete(&REPOSITORIES);
+    }
 
-    // Enable multilib for 32-bit support (needed for lib32-vulkan-* GPU drivers)
-    // This runs before package installation so lib32 packages can be installed.
-    println!("Enabling multilib repository...");
-    crate::common::pacman::enable_multilib(executor.dry_run()).await?;
-
-    // Update repos after enabling multilib
-    sync_repos(executor)?;
-
-    configure_pacman_target(executor).await?;
-    install_standard_packages(context, executor)?;
-    configure_timezone(context, executor)?;
-    configure_locale(context, executor)?;
-    configure_network(context, executor)?;
-    configure_users(context, executor)?;
-    configure_environment(executor)?;
-    configure_vconsole(context, executor)?;
-    configure_sudo(context, executor)?;
-    configure_mkinitcpio(context, executor)?;
+    if checkpoints.pending(&PACMAN) {
+        configure_pacman_target(executor).await?;
+        checkpoints.complete(&PACMAN);
+    }
+
+    checkpoints.run(&PACKAGES, || install_standard_packages(context, executor))?;
+    checkpoints.run(&TIMEZONE, || configure_timezone(context, executor))?;
+    checkpoints.run(&LOCALE, || configure_locale(context, executor))?;
+    checkpoints.run(&NETWORK, || configure_network(context, executor))?;
+    checkpoints.run(&USERS, || configure_users(context, executor))?;
+    checkpoints.run(&ENVIRONMENT, || configure_environment(executor))?;
+    checkpoints.run(&VCONSOLE, || configure_vconsole(context, executor))?;
+    checkpoints.run(&SUDO, || configure_sudo(context, executor))?;
+    checkpoints.run(&ZRAM, || {
+        super::swap::configure_zram(context, executor, std::path::Path::new("/"))
+    })?;
+    checkpoints.run(&MKINITCPIO, || configure_mkinitcpio(context, executor))?;
 
     Ok(())
 }
@@ -140,8 +248,11 @@ async fn configure_pacman_target(executor: &dyn CommandRunner) -> Result<()> {
 pub fn config_package_list(context: &InstallContext) -> Vec<String> {
     let mut packages = Vec::new();
 
-    if context.get_answer_bool(QuestionId::UseEncryption) {
+    let use_encryption = context.get_answer_bool(QuestionId::UseEncryption);
+    if crate::arch::config::DiskLayout::from_context(context).uses_lvm(use_encryption) {
         packages.push("lvm2".to_string());
+    }
+    if use_encryption {
         packages.push("cryptsetup".to_string());
     }
 
@@ -158,13 +269,20 @@ fn configure_mkinitcpio(context: &InstallContext, executor: &dyn CommandRunner)
     let use_encryption = context.get_answer_bool(QuestionId::UseEncryption);
     let use_plymouth = context.get_answer_bool(QuestionId::UsePlymouth);
     let use_btrfs = crate::arch::config::RootFilesystem::from_context(context).is_btrfs();
+    let disk_image = context.system_info.disk_image;
+    let layout = crate::arch::config::DiskLayout::from_context(context);
+    let storage_hooks = layout.storage_hooks(use_encryption);
+    let hibernation = crate::arch::config::hibernation_enabled(context);
+    let snapshots = crate::arch::config::btrfs_snapshots_enabled(context);
 
-    if !use_encryption && !use_plymouth && !use_btrfs {
+    if storage_hooks.is_empty() && !use_plymouth && !use_btrfs && !disk_image && !hibernation {
         return Ok(());
     }
 
     if use_encryption {
         println!("Configuring mkinitcpio for encryption...");
+    } else if !storage_hooks.is_empty() {
+        println!("Configuring mkinitcpio for LVM...");
     }
     if use_plymouth && !context.get_answer_bool(QuestionId::MinimalMode) {
         println!("Configuring mkinitcpio for Plymouth...");
@@ -177,8 +295,23 @@ fn configure_mkinitcpio(context: &InstallContext, executor: &dyn CommandRunner)
         if use_plymouth && !context.get_answer_bool(QuestionId::MinimalMode) {
             println!("[DRY RUN] Adding 'plymouth' to HOOKS in /etc/mkinitcpio.conf");
         }
-        if use_encryption {
-            println!("[DRY RUN] Adding 'encrypt lvm2' to HOOKS in /etc/mkinitcpio.conf");
+        if !storage_hooks.is_empty() {
+            println!(
+                "[DRY RUN] Adding '{}' to HOOKS in /etc/mkinitcpio.conf",
+                storage_hooks.join(" ")
+            );
+        }
+        if hibernation {
+            println!("[DRY RUN] Adding 'resume' to HOOKS unless the systemd hook is used");
+        }
+        if disk_image {
+            println!("[DRY RUN] Removing 'autodetect' from HOOKS in /etc/mkinitcpio.conf");
+        }
+        if snapshots {
+            println!(
+                "[DRY RUN] Adding '{}' to HOOKS in /etc/mkinitcpio.conf",
+                super::bootloader::OVERLAY_HOOK
+            );
         }
         println!("[DRY RUN] mkinitcpio -P");
         return Ok(());
@@ -221,19 +354,39 @@ fn configure_mkinitcpio(context: &InstallContext, executor: &dyn CommandRunner)
     // Remove consolefont if present as sd-vconsole handles it
     config.remove_hook("consolefont");
 
-    // Add encryption hooks in correct order: block -> sd-encrypt -> lvm2 -> resume -> filesystems
-    if use_encryption && config.contains_hook("block") && config.contains_hook("filesystems") {
+    // autodetect keeps only the build machine's drivers; images boot elsewhere
+    if disk_image {
+        config.remove_hook("autodetect");
+    }
+
+    // Add storage hooks in the order the layers are stacked, e.g.
+    // block -> sd-encrypt -> lvm2 -> resume -> filesystems for LVM on LUKS
+    if config.contains_hook("block") && config.contains_hook("filesystems") {
         // Replace legacy encrypt hook if present
-        if config.contains_hook("encrypt") {
+        if use_encryption && config.contains_hook("encrypt") {
             config.replace_hook("encrypt", "sd-encrypt");
-        } else {
-            config.ensure_hook("sd-encrypt");
         }
+        config.ensure_storage_hooks(storage_hooks);
+
+        // The busybox initramfs needs the btrfs hook to assemble multi-device
+        // filesystems; systemd's udev rules already do it
+        if layout == crate::arch::config::DiskLayout::BtrfsRaid1 && !config.contains_hook("systemd")
+        {
+            config.ensure_storage_hooks(&["btrfs"]);
+        }
+
+        // The systemd hook resumes from the resume= kernel parameter by itself
+        if hibernation && !config.contains_hook("systemd") {
+            let mut hooks = storage_hooks.to_vec();
+            hooks.push("resume");
+            config.ensure_storage_hooks(&hooks);
+        }
+    }
 
-        // Ensure correct ordering for full disk encryption with LVM and resume
-        config.ensure_hook_position("sd-encrypt", &["block"], &["filesystems"]);
-        config.ensure_hook_position("lvm2", &["sd-encrypt"], &["filesystems"]);
-        config.ensure_hook_position("resume", &["lvm2"], &["filesystems"]);
+    // Snapshot roots are read-only and only boot with an overlay on top
+    if snapshots {
+        super::bootloader::install_overlay_hook(executor, std::path::Path::new("/"))?;
+        config.ensure_hook_position(super::bootloader::OVERLAY_HOOK, &["filesystems"], &[]);
     }
 
     std::fs::write(conf_path, config.to_string())?;
@@ -1,19 +1,26 @@
 use super::filesystem;
 use super::util::get_part_path;
-use crate::arch::engine::InstallContext;
+use crate::arch::config::{Bootloader, SwapType};
+use crate::arch::engine::{BootMode, InstallContext};
 use crate::arch::execution::CommandRunner;
 use anyhow::Result;
 use std::process::Command;
 
-pub fn partition_uefi(disk: &str, executor: &dyn CommandRunner, swap_size_gb: u64) -> Result<()> {
+pub fn partition_uefi(
+    disk: &str,
+    executor: &dyn CommandRunner,
+    swap_size_gb: Option<u64>,
+) -> Result<()> {
     println!("Partitioning for UEFI...");
 
+    let swap = swap_size_gb
+        .map(|size| format!("size={size}G, type=S\n"))
+        .unwrap_or_default();
     let script = format!(
         "label: gpt\n\
          size=1G, type=U\n\
-         size={}G, type=S\n\
-         type=L\n",
-        swap_size_gb
+         {swap}\
+         type=L\n"
     );
 
     executor.run_with_input(Command::new("sfdisk").arg(disk), &script)?;
@@ -26,14 +33,36 @@ pub fn partition_uefi(disk: &str, executor: &dyn CommandRunner, swap_size_gb: u6
     Ok(())
 }
 
-pub fn partition_bios(disk: &str, executor: &dyn CommandRunner, swap_size_gb: u64) -> Result<()> {
+/// Whether the automatic BIOS layout starts with a FAT `/boot` partition (Limine)
+fn has_bios_boot_partition(context: &InstallContext) -> bool {
+    Bootloader::from_context(context).needs_fat_boot_partition(&BootMode::BIOS)
+}
+
+fn has_swap_partition(context: &InstallContext) -> bool {
+    SwapType::from_context(context).is_partition()
+}
+
+pub fn partition_bios(
+    disk: &str,
+    executor: &dyn CommandRunner,
+    swap_size_gb: Option<u64>,
+    boot_partition: bool,
+) -> Result<()> {
     println!("Partitioning for BIOS...");
 
+    let boot = if boot_partition {
+        "size=1G, type=83, bootable\n"
+    } else {
+        ""
+    };
+    let swap = swap_size_gb
+        .map(|size| format!("size={size}G, type=82\n"))
+        .unwrap_or_default();
     let script = format!(
         "label: dos\n\
-         size={}G, type=82\n\
-         type=83\n",
-        swap_size_gb
+         {boot}\
+         {swap}\
+         type=83\n"
     );
 
     executor.run_with_input(Command::new("sfdisk").arg(disk), &script)?;
@@ -46,22 +75,81 @@ pub fn partition_bios(disk: &str, executor: &dyn CommandRunner, swap_size_gb: u6
     Ok(())
 }
 
-pub fn format_uefi(
+/// Partition `disk` with the standard layout for the current boot mode
+pub(super) fn partition_automatic(
     context: &InstallContext,
     disk: &str,
     executor: &dyn CommandRunner,
-) -> Result<()> {
-    let p1 = get_part_path(disk, 1);
-    let p2 = get_part_path(disk, 2);
-    let p3 = get_part_path(disk, 3);
+    swap_size_gb: Option<u64>,
+) -> Result<StandardPartitions> {
+    let partitions = StandardPartitions::of(context, disk);
+    match context.system_info.boot_mode {
+        BootMode::UEFI64 | BootMode::UEFI32 => partition_uefi(disk, executor, swap_size_gb)?,
+        BootMode::BIOS => partition_bios(disk, executor, swap_size_gb, partitions.boot.is_some())?,
+    }
+    Ok(partitions)
+}
 
-    println!("Formatting partitions...");
+/// Partitions created by [`partition_uefi`] or [`partition_bios`]
+pub(super) struct StandardPartitions {
+    /// EFI system partition, or the FAT `/boot` partition of BIOS Limine installs
+    pub boot: Option<String>,
+    pub swap: Option<String>,
+    pub root: String,
+}
+
+impl StandardPartitions {
+    pub(super) fn new(disk: &str, boot_partition: bool, swap: bool) -> Self {
+        let first = u32::from(boot_partition) + 1;
+        Self {
+            boot: boot_partition.then(|| get_part_path(disk, 1)),
+            swap: swap.then(|| get_part_path(disk, first)),
+            root: get_part_path(disk, first + u32::from(swap)),
+        }
+    }
 
-    executor.run(Command::new("mkfs.fat").args(["-F32", &p1]))?;
-    executor.run(Command::new("mkswap").arg(&p2))?;
-    filesystem::format_root(context, &p3, executor)?;
+    /// The partitions [`partition_automatic`] creates for the current boot mode
+    pub(super) fn of(context: &InstallContext, disk: &str) -> Self {
+        let boot_partition = match context.system_info.boot_mode {
+            BootMode::UEFI64 | BootMode::UEFI32 => true,
+            BootMode::BIOS => has_bios_boot_partition(context),
+        };
+        Self::new(disk, boot_partition, has_swap_partition(context))
+    }
 
-    Ok(())
+    /// Format the boot and swap partitions, leaving the root partition to the caller
+    pub(super) fn format_boot_and_swap(&self, executor: &dyn CommandRunner) -> Result<()> {
+        if let Some(boot) = &self.boot {
+            executor.run(Command::new("mkfs.fat").args(["-F32", boot]))?;
+        }
+        if let Some(swap) = &self.swap {
+            executor.run(Command::new("mkswap").arg(swap))?;
+        }
+        Ok(())
+    }
+
+    /// Mount `/boot` and enable swap once the root filesystem is mounted
+    pub(super) fn mount_boot_and_swap(&self, executor: &dyn CommandRunner) -> Result<()> {
+        if let Some(boot) = &self.boot {
+            executor.run(Command::new("mount").args(["--mkdir", boot, "/mnt/boot"]))?;
+        }
+        if let Some(swap) = &self.swap {
+            executor.run(Command::new("swapon").arg(swap))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn format_uefi(
+    context: &InstallContext,
+    disk: &str,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    format_standard(
+        context,
+        &StandardPartitions::new(disk, true, has_swap_partition(context)),
+        executor,
+    )
 }
 
 pub fn format_bios(
@@ -69,13 +157,23 @@ pub fn format_bios(
     disk: &str,
     executor: &dyn CommandRunner,
 ) -> Result<()> {
-    let p1 = get_part_path(disk, 1);
-    let p2 = get_part_path(disk, 2);
+    let partitions = StandardPartitions::new(
+        disk,
+        has_bios_boot_partition(context),
+        has_swap_partition(context),
+    );
+    format_standard(context, &partitions, executor)
+}
 
+fn format_standard(
+    context: &InstallContext,
+    partitions: &StandardPartitions,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
     println!("Formatting partitions...");
 
-    executor.run(Command::new("mkswap").arg(&p1))?;
-    filesystem::format_root(context, &p2, executor)?;
+    partitions.format_boot_and_swap(executor)?;
+    filesystem::format_root(context, &partitions.root, executor)?;
 
     Ok(())
 }
@@ -85,17 +183,11 @@ pub fn mount_uefi(
     disk: &str,
     executor: &dyn CommandRunner,
 ) -> Result<()> {
-    let p1 = get_part_path(disk, 1);
-    let p2 = get_part_path(disk, 2);
-    let p3 = get_part_path(disk, 3);
-
-    println!("Mounting partitions...");
-
-    filesystem::mount_root(context, &p3, true, executor)?;
-    executor.run(Command::new("mount").args(["--mkdir", &p1, "/mnt/boot"]))?;
-    executor.run(Command::new("swapon").arg(&p2))?;
-
-    Ok(())
+    mount_standard(
+        context,
+        &StandardPartitions::new(disk, true, has_swap_partition(context)),
+        executor,
+    )
 }
 
 pub fn mount_bios(
@@ -103,13 +195,23 @@ pub fn mount_bios(
     disk: &str,
     executor: &dyn CommandRunner,
 ) -> Result<()> {
-    let p1 = get_part_path(disk, 1);
-    let p2 = get_part_path(disk, 2);
+    let partitions = StandardPartitions::new(
+        disk,
+        has_bios_boot_partition(context),
+        has_swap_partition(context),
+    );
+    mount_standard(context, &partitions, executor)
+}
 
+fn mount_standard(
+    context: &InstallContext,
+    partitions: &StandardPartitions,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
     println!("Mounting partitions...");
 
-    filesystem::mount_root(context, &p2, true, executor)?;
-    executor.run(Command::new("swapon").arg(&p1))?;
+    filesystem::mount_root(context, &partitions.root, true, executor)?;
+    partitions.mount_boot_and_swap(executor)?;
 
     Ok(())
 }
@@ -147,7 +249,7 @@ mod tests {
     #[test]
     fn test_partition_uefi_commands() {
         let mock = crate::arch::execution::mock::MockRunner::new();
-        super::partition_uefi("/dev/sda", &mock, 4).unwrap();
+        super::partition_uefi("/dev/sda", &mock, Some(4)).unwrap();
 
         let log = mock.command_log();
         // Should have: sfdisk /dev/sda, udevadm settle
@@ -177,4 +279,45 @@ mod tests {
                 .any(|c| c.contains("mkfs.ext4") && c.contains("-F"))
         );
     }
+
+    #[test]
+    fn test_bios_layout_with_limine_boot_partition() {
+        let mock = crate::arch::execution::mock::MockRunner::new();
+        super::partition_bios("/dev/sda", &mock, Some(4), true).unwrap();
+        assert!(mock.command_log()[0].contains("size=1G, type=83, bootable\nsize=4G, type=82"));
+
+        let mut context = crate::arch::engine::InstallContext::new();
+        context.set_answer(
+            crate::arch::engine::QuestionId::Bootloader,
+            "limine".to_string(),
+        );
+        let mock = crate::arch::execution::mock::MockRunner::new();
+        super::mount_bios(&context, "/dev/sda", &mock).unwrap();
+        let log = mock.command_log();
+        assert!(log.contains(&"mount --mkdir /dev/sda1 /mnt/boot".to_string()));
+        assert!(log.contains(&"swapon /dev/sda2".to_string()));
+    }
+
+    #[test]
+    fn test_layout_without_swap_partition() {
+        let mock = crate::arch::execution::mock::MockRunner::new();
+        super::partition_uefi("/dev/sda", &mock, None).unwrap();
+        assert!(mock.command_log()[0].contains("size=1G, type=U\ntype=L\n"));
+
+        let mut context = crate::arch::engine::InstallContext::new();
+        context.set_answer(
+            crate::arch::engine::QuestionId::SwapType,
+            "zram".to_string(),
+        );
+        context.set_answer(
+            crate::arch::engine::QuestionId::RootFilesystem,
+            "ext4".to_string(),
+        );
+        let mock = crate::arch::execution::mock::MockRunner::new();
+        super::format_uefi(&context, "/dev/sda", &mock).unwrap();
+        assert_eq!(
+            mock.command_log(),
+            ["mkfs.fat -F32 /dev/sda1", "mkfs.ext4 -F /dev/sda2"]
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use super::mount;
 use super::probe::{get_current_partitions, get_partition_size_bytes};
 use super::util::{align_down, parse_partition_number};
+use crate::arch::config::Bootloader;
 use crate::arch::dualboot::parsing::{PartitionLayout, get_free_regions, get_partition_layout};
-use crate::arch::dualboot::types::{FreeRegion, MIN_ESP_SIZE};
+use crate::arch::dualboot::types::FreeRegion;
 use crate::arch::dualboot::{DisksKey, PartitionTableType};
 use crate::arch::engine::{
     DualBootPartitionPaths, DualBootPartitions, EspNeedsFormat, InstallContext, QuestionId,
@@ -20,7 +21,7 @@ pub fn prepare_dualboot_disk(
     context: &InstallContext,
     executor: &dyn CommandRunner,
     disk_path: &str,
-    mut swap_size_gb: u64,
+    mut swap_size_gb: Option<u64>,
 ) -> Result<()> {
     println!("Preparing dual boot installation...");
 
@@ -82,7 +83,8 @@ pub fn prepare_dualboot_disk(
         esp.device.clone()
     } else {
         println!("No suitable ESP found (need >= 260MB). Creating a new EFI System Partition...");
-        let new_esp = create_esp_partition(disk_path, disk_info, executor)?;
+        let esp_size = Bootloader::from_context(context).dualboot_esp_size();
+        let new_esp = create_esp_partition(disk_path, disk_info, esp_size, executor)?;
         println!("Created new ESP: {}", new_esp);
         esp_needs_format = true;
         new_esp
@@ -125,7 +127,7 @@ pub fn prepare_dualboot_disk(
         .map(|region| region.size_bytes)
         .unwrap_or(disk_info.max_contiguous_free_space_bytes);
     const GB: u64 = 1024 * 1024 * 1024;
-    let mut swap_size_bytes = swap_size_gb * GB;
+    let mut swap_size_bytes = swap_size_gb.unwrap_or(0) * GB;
 
     if available_space <= crate::arch::dualboot::MIN_LINUX_SIZE {
         anyhow::bail!("Not enough contiguous free space for minimum root");
@@ -136,16 +138,18 @@ pub fn prepare_dualboot_disk(
         available_space.saturating_sub(crate::arch::dualboot::MIN_LINUX_SIZE);
     let swap_cap = swap_cap_by_ratio.min(swap_cap_by_root_min);
 
-    if swap_size_bytes > swap_cap {
+    if let Some(requested_gb) = swap_size_gb
+        && swap_size_bytes > swap_cap
+    {
         swap_size_bytes = swap_cap;
         let adjusted_swap_gb = (swap_size_bytes / GB).max(1);
         println!(
             "Capping swap to {} (was {} GiB) to keep swap <= half of root",
             format_size(adjusted_swap_gb * GB),
-            swap_size_gb
+            requested_gb
         );
         swap_size_bytes = adjusted_swap_gb * GB;
-        swap_size_gb = adjusted_swap_gb;
+        swap_size_gb = Some(adjusted_swap_gb);
     }
 
     let min_required = crate::arch::dualboot::MIN_LINUX_SIZE + swap_size_bytes;
@@ -373,11 +377,11 @@ fn find_next_free_region(
 fn create_esp_partition(
     disk_path: &str,
     disk_info: &crate::arch::dualboot::DiskInfo,
+    esp_size_bytes: u64,
     executor: &dyn CommandRunner,
 ) -> Result<String> {
     let partitions_before = get_current_partitions(disk_path)?;
 
-    let esp_size_bytes = MIN_ESP_SIZE;
     let esp_sectors = esp_size_bytes.div_ceil(512);
 
     let regions = get_free_regions(disk_path, Some(disk_info.size_bytes))
@@ -386,7 +390,12 @@ fn create_esp_partition(
     let region = regions
         .iter()
         .find(|r| r.sectors >= esp_sectors)
-        .context("No free region large enough to create an EFI System Partition (need >= 260MB)")?;
+        .with_context(|| {
+            format!(
+                "No free region large enough to create an EFI System Partition (need >= {})",
+                format_size(esp_size_bytes)
+            )
+        })?;
 
     let start_sector = region.start;
 
@@ -427,11 +436,11 @@ fn create_esp_partition(
 
 fn create_dualboot_partitions(
     disk_path: &str,
-    swap_size_gb: u64,
+    swap_size_gb: Option<u64>,
     disk_size_bytes: u64,
     executor: &dyn CommandRunner,
     preferred_region: Option<FreeRegion>,
-) -> Result<(String, String)> {
+) -> Result<(String, Option<String>)> {
     println!("Creating partitions in free space (optimal placement)...");
 
     let partitions_before = get_current_partitions(disk_path)?;
@@ -447,6 +456,12 @@ fn create_dualboot_partitions(
         anyhow::bail!("No free space regions detected!");
     }
 
+    let Some(swap_size_gb) = swap_size_gb else {
+        let root_path =
+            create_dualboot_root_partition(disk_path, &regions, &partitions_before, executor)?;
+        return Ok((root_path, None));
+    };
+
     let swap_size_bytes = swap_size_gb * 1024 * 1024 * 1024;
     let swap_sectors = swap_size_bytes.div_ceil(512);
 
@@ -584,5 +599,59 @@ fn create_dualboot_partitions(
         format_size(root_size_bytes)
     );
 
-    Ok((root_path, swap_path))
+    Ok((root_path, Some(swap_path)))
+}
+
+/// Root partition in the largest free region, for installs without a swap partition
+fn create_dualboot_root_partition(
+    disk_path: &str,
+    regions: &[FreeRegion],
+    partitions_before: &std::collections::HashSet<String>,
+    executor: &dyn CommandRunner,
+) -> Result<String> {
+    let root_region = regions
+        .iter()
+        .max_by_key(|r| r.sectors)
+        .context("No free regions left for Root partition")?;
+
+    let root_size_bytes = root_region.sectors * 512;
+    if root_size_bytes < crate::arch::dualboot::MIN_LINUX_SIZE {
+        anyhow::bail!(
+            "Largest free space is too small for Root: {}",
+            format_size(root_size_bytes)
+        );
+    }
+
+    println!("Placement:");
+    println!(
+        "  Root: Start Sector {}, Size {} (approx)",
+        root_region.start,
+        format_size(root_size_bytes)
+    );
+
+    let script = format!(
+        "start={}, size={}, type=L\n",
+        root_region.start, root_region.sectors
+    );
+    executor.run_with_input(
+        Command::new("sfdisk").arg("--append").arg(disk_path),
+        &script,
+    )?;
+
+    if !executor.dry_run() {
+        executor.run(Command::new("udevadm").arg("settle"))?;
+        std::thread::sleep(std::time::Duration::from_secs(2));
+    }
+
+    let root_path = get_current_partitions(disk_path)?
+        .into_iter()
+        .find(|p| !partitions_before.contains(p))
+        .context("Could not find the newly created root partition")?;
+    println!(
+        "Created root partition: {} ({})",
+        root_path,
+        format_size(root_size_bytes)
+    );
+
+    Ok(root_path)
 }
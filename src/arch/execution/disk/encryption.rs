@@ -1,10 +1,16 @@
 use super::filesystem;
 use super::util::get_part_path;
+use crate::arch::config::{Bootloader, SwapType};
 use crate::arch::engine::{InstallContext, QuestionId};
 use crate::arch::execution::CommandRunner;
 use anyhow::{Context, Result};
 use std::process::Command;
 
+pub(super) const LVM_VOLUME_GROUP: &str = "instantOS";
+pub(super) const LVM_ROOT: &str = "/dev/instantOS/root";
+pub(super) const LVM_HOME: &str = "/dev/instantOS/home";
+pub(super) const LVM_SWAP: &str = "/dev/instantOS/swap";
+
 pub fn partition_uefi_luks(disk: &str, executor: &dyn CommandRunner) -> Result<()> {
     println!("Partitioning for UEFI with Encryption...");
 
@@ -44,7 +50,8 @@ pub fn format_luks(
     disk: &str,
     executor: &dyn CommandRunner,
     is_uefi: bool,
-    swap_size_gb: u64,
+    swap_size_gb: Option<u64>,
+    home: bool,
 ) -> Result<()> {
     let p1 = get_part_path(disk, 1);
     let p2 = get_part_path(disk, 2);
@@ -55,11 +62,7 @@ pub fn format_luks(
 
     println!("Formatting partitions (LVM on LUKS)...");
 
-    if is_uefi {
-        executor.run(Command::new("mkfs.fat").args(["-F32", &p1]))?;
-    } else {
-        executor.run(Command::new("mkfs.ext4").args(["-F", &p1]))?;
-    }
+    format_boot(context, &p1, executor, is_uefi)?;
 
     println!("Setting up LUKS container on {}...", p2);
     let mut cmd = Command::new("cryptsetup");
@@ -76,29 +79,93 @@ pub fn format_luks(
     cmd_open.arg("open").arg(&p2).arg("cryptlvm").arg("-");
     executor.run_with_input(&mut cmd_open, password)?;
 
-    println!("Setting up LVM...");
-    executor.run(Command::new("pvcreate").arg("/dev/mapper/cryptlvm"))?;
-    executor.run(Command::new("vgcreate").args(["instantOS", "/dev/mapper/cryptlvm"]))?;
+    create_volume_group(executor, "/dev/mapper/cryptlvm", swap_size_gb, home)?;
+
+    println!("Formatting Logical Volumes...");
+    if swap_size_gb.is_some() {
+        executor.run(Command::new("mkswap").arg(LVM_SWAP))?;
+    }
+    filesystem::format_root(context, LVM_ROOT, executor)?;
+    if home {
+        executor.run(Command::new("mkfs.ext4").args(["-F", LVM_HOME]))?;
+    }
+
+    Ok(())
+}
+
+/// Format the first partition of the two-partition LVM layouts as `/boot`
+///
+/// UEFI and Limine need FAT; GRUB on BIOS reads ext4.
+pub(super) fn format_boot(
+    context: &InstallContext,
+    partition: &str,
+    executor: &dyn CommandRunner,
+    is_uefi: bool,
+) -> Result<()> {
+    let fat_boot =
+        Bootloader::from_context(context).needs_fat_boot_partition(&context.system_info.boot_mode);
+    if is_uefi || fat_boot {
+        executor.run(Command::new("mkfs.fat").args(["-F32", partition]))?;
+    } else {
+        executor.run(Command::new("mkfs.ext4").args(["-F", partition]))?;
+    }
+    Ok(())
+}
 
-    executor.run(Command::new("lvcreate").args([
-        "-L",
-        &format!("{}G", swap_size_gb),
-        "instantOS",
-        "-n",
-        "swap",
-    ]))?;
+/// Create the `instantOS` volume group on `physical_volume` with root and
+/// optionally swap and home logical volumes
+pub(super) fn create_volume_group(
+    executor: &dyn CommandRunner,
+    physical_volume: &str,
+    swap_size_gb: Option<u64>,
+    home: bool,
+) -> Result<()> {
+    println!("Setting up LVM...");
+    executor.run(Command::new("pvcreate").arg(physical_volume))?;
+    executor.run(Command::new("vgcreate").args([LVM_VOLUME_GROUP, physical_volume]))?;
+
+    if let Some(swap_size_gb) = swap_size_gb {
+        executor.run(Command::new("lvcreate").args([
+            "-L",
+            &format!("{}G", swap_size_gb),
+            LVM_VOLUME_GROUP,
+            "-n",
+            "swap",
+        ]))?;
+    }
 
-    executor.run(Command::new("lvcreate").args(["-l", "100%FREE", "instantOS", "-n", "root"]))?;
+    if home {
+        // Percentages of the remaining space: root gets 40% of the disk, home
+        // the rest.
+        executor.run(Command::new("lvcreate").args([
+            "-l",
+            "40%FREE",
+            LVM_VOLUME_GROUP,
+            "-n",
+            "root",
+        ]))?;
+        executor.run(Command::new("lvcreate").args([
+            "-l",
+            "100%FREE",
+            LVM_VOLUME_GROUP,
+            "-n",
+            "home",
+        ]))?;
+    } else {
+        executor.run(Command::new("lvcreate").args([
+            "-l",
+            "100%FREE",
+            LVM_VOLUME_GROUP,
+            "-n",
+            "root",
+        ]))?;
+    }
 
     if !executor.dry_run() {
         executor.run(Command::new("udevadm").arg("settle"))?;
-        executor.run(Command::new("vgchange").args(["-ay", "instantOS"]))?;
+        executor.run(Command::new("vgchange").args(["-ay", LVM_VOLUME_GROUP]))?;
     }
 
-    println!("Formatting Logical Volumes...");
-    executor.run(Command::new("mkswap").arg("/dev/instantOS/swap"))?;
-    filesystem::format_root(context, "/dev/instantOS/root", executor)?;
-
     Ok(())
 }
 
@@ -106,15 +173,21 @@ pub fn mount_luks(
     context: &InstallContext,
     executor: &dyn CommandRunner,
     disk: &str,
+    home: bool,
 ) -> Result<()> {
     println!("Mounting LVM volumes...");
 
-    filesystem::mount_root(context, "/dev/instantOS/root", true, executor)?;
+    filesystem::mount_root(context, LVM_ROOT, !home, executor)?;
+    if home {
+        executor.run(Command::new("mount").args(["--mkdir", LVM_HOME, "/mnt/home"]))?;
+    }
 
     let p1 = get_part_path(disk, 1);
     executor.run(Command::new("mount").args(["--mkdir", &p1, "/mnt/boot"]))?;
 
-    executor.run(Command::new("swapon").arg("/dev/instantOS/swap"))?;
+    if SwapType::from_context(context).is_partition() {
+        executor.run(Command::new("swapon").arg(LVM_SWAP))?;
+    }
 
     Ok(())
 }
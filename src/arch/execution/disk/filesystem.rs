@@ -1,8 +1,10 @@
 use crate::arch::config::{
-    BTRFS_HOME_SUBVOLUME, BTRFS_ROOT_SUBVOLUME, BtrfsCompression, RootFilesystem,
+    BTRFS_HOME_SUBVOLUME, BTRFS_ROOT_SUBVOLUME, BTRFS_SNAPSHOT_SUBVOLUMES, BTRFS_SWAP_SUBVOLUME,
+    BtrfsCompression, RootFilesystem, SwapType, btrfs_snapshots_enabled,
 };
 use crate::arch::engine::InstallContext;
 use crate::arch::execution::CommandRunner;
+use crate::arch::execution::swap::SWAP_MOUNT_POINT;
 use anyhow::Result;
 use std::process::Command;
 
@@ -22,6 +24,16 @@ pub fn format_root(
     Ok(())
 }
 
+/// Create one btrfs filesystem mirroring data and metadata across `devices`
+pub fn format_btrfs_raid1(devices: &[&str], executor: &dyn CommandRunner) -> Result<()> {
+    executor.run(
+        Command::new("mkfs.btrfs")
+            .args(["-f", "-d", "raid1", "-m", "raid1"])
+            .args(devices),
+    )?;
+    Ok(())
+}
+
 pub fn mount_root(
     context: &InstallContext,
     device: &str,
@@ -36,6 +48,15 @@ pub fn mount_root(
     // Create subvolumes from the top-level btrfs tree, then remount the root
     // subvolume. Keeping @home separate allows snapshots of @ without rolling
     // back user data.
+    let mut extra_subvolumes: Vec<(&str, &str)> = Vec::new();
+    if btrfs_snapshots_enabled(context) {
+        extra_subvolumes.extend_from_slice(BTRFS_SNAPSHOT_SUBVOLUMES);
+    }
+    // Swap files cannot live in a snapshotted subvolume
+    if SwapType::from_context(context) == SwapType::Swapfile {
+        extra_subvolumes.push((BTRFS_SWAP_SUBVOLUME, SWAP_MOUNT_POINT));
+    }
+
     executor.run(Command::new("mount").args([device, "/mnt"]))?;
     let create_result = (|| -> Result<()> {
         let root_path = format!("/mnt/{BTRFS_ROOT_SUBVOLUME}");
@@ -44,6 +65,10 @@ pub fn mount_root(
             let home_path = format!("/mnt/{BTRFS_HOME_SUBVOLUME}");
             executor.run(Command::new("btrfs").args(["subvolume", "create", &home_path]))?;
         }
+        for (subvolume, _) in &extra_subvolumes {
+            let path = format!("/mnt/{subvolume}");
+            executor.run(Command::new("btrfs").args(["subvolume", "create", &path]))?;
+        }
         Ok(())
     })();
     let unmount_result = executor.run(Command::new("umount").arg("/mnt"));
@@ -57,7 +82,7 @@ pub fn mount_root(
     unmount_result?;
 
     let compression = BtrfsCompression::from_context(context);
-    let mount_options = |subvolume| {
+    let mount_options = |subvolume: &str| {
         let mut options = vec![subvolume, "noatime"];
         if let Some(option) = compression.mount_option() {
             options.push(option);
@@ -81,6 +106,13 @@ pub fn mount_root(
         ]))?;
     }
 
+    for (subvolume, mount_point) in &extra_subvolumes {
+        let subvolume = format!("subvol={subvolume}");
+        let options = mount_options(&subvolume);
+        let target = format!("/mnt{mount_point}");
+        executor.run(Command::new("mount").args(["--mkdir", "-o", &options, device, &target]))?;
+    }
+
     Ok(())
 }
 
@@ -152,6 +184,30 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn creates_and_mounts_snapper_layout() {
+        let runner = MockRunner::new();
+        let mut context = context("btrfs", "none");
+        context
+            .answers
+            .insert(QuestionId::BtrfsSnapshots, "true".into());
+
+        mount_root(&context, "/dev/root", true, &runner).unwrap();
+
+        let log = runner.command_log();
+        for subvolume in ["@snapshots", "@var_log", "@cache"] {
+            let create = format!("btrfs subvolume create /mnt/{subvolume}");
+            assert!(log.contains(&create), "missing {create}");
+        }
+        assert!(log.contains(
+            &"mount --mkdir -o subvol=@snapshots,noatime /dev/root /mnt/.snapshots".to_string()
+        ));
+        assert_eq!(
+            log.last().unwrap(),
+            "mount --mkdir -o subvol=@cache,noatime /dev/root /mnt/var/cache"
+        );
+    }
+
     #[test]
     fn ext4_does_not_create_subvolumes() {
         let runner = MockRunner::new();
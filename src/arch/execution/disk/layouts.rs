@@ -0,0 +1,340 @@
+use super::automatic;
+use super::encryption::{self, LVM_HOME, LVM_ROOT, LVM_SWAP};
+use super::filesystem;
+use super::util::get_part_path;
+use crate::arch::config::DiskLayout;
+use crate::arch::engine::{BootMode, InstallContext, QuestionId};
+use crate::arch::execution::CommandRunner;
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Device-mapper name of the home volume in the LUKS on LVM layout; root uses
+/// [`DiskLayout::luks_mapping`]
+pub const CRYPT_HOME: &str = "crypthome";
+const CRYPT_ROOT: &str = DiskLayout::LuksOnLvm.luks_mapping();
+
+/// Partition, format and mount `disk` (and the second disk, if the layout uses
+/// one) for any layout other than [`DiskLayout::Standard`]
+pub fn prepare_layout(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    disk: &str,
+    layout: DiskLayout,
+    swap_size_gb: Option<u64>,
+) -> Result<()> {
+    let is_uefi = matches!(
+        context.system_info.boot_mode,
+        BootMode::UEFI64 | BootMode::UEFI32
+    );
+    let encrypted = context.get_answer_bool(QuestionId::UseEncryption);
+
+    match layout {
+        DiskLayout::Standard => unreachable!("the standard layout is prepared by prepare_disk"),
+        DiskLayout::Lvm if encrypted => {
+            if is_uefi {
+                encryption::partition_uefi_luks(disk, executor)?;
+            } else {
+                encryption::partition_bios_luks(disk, executor)?;
+            }
+            encryption::format_luks(context, disk, executor, is_uefi, swap_size_gb, true)?;
+            encryption::mount_luks(context, executor, disk, true)?;
+        }
+        DiskLayout::Lvm => {
+            partition_lvm(disk, executor, is_uefi)?;
+            format_lvm(context, disk, executor, is_uefi, swap_size_gb)?;
+            encryption::mount_luks(context, executor, disk, true)?;
+        }
+        DiskLayout::LuksOnLvm => {
+            partition_lvm(disk, executor, is_uefi)?;
+            format_luks_on_lvm(context, disk, executor, is_uefi, swap_size_gb)?;
+            mount_luks_on_lvm(context, disk, executor)?;
+        }
+        DiskLayout::BtrfsRaid1 | DiskLayout::HomeDisk => {
+            let second_disk = context
+                .get_answer(&QuestionId::SecondDisk)
+                .context("No second disk selected")?;
+            prepare_two_disks(context, executor, disk, second_disk, layout, swap_size_gb)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Boot partition followed by one LVM physical volume spanning the rest of the disk
+fn partition_lvm(disk: &str, executor: &dyn CommandRunner, is_uefi: bool) -> Result<()> {
+    println!("Partitioning for LVM...");
+
+    let script = if is_uefi {
+        "label: gpt\n\
+         size=1G, type=U\n\
+         type=V\n"
+    } else {
+        "label: dos\n\
+         size=1G, type=83\n\
+         type=8e\n"
+    };
+
+    executor.run_with_input(Command::new("sfdisk").arg(disk), script)?;
+
+    if !executor.dry_run() {
+        executor.run(Command::new("udevadm").arg("settle"))?;
+        std::thread::sleep(std::time::Duration::from_secs(2));
+    }
+
+    Ok(())
+}
+
+fn format_lvm(
+    context: &InstallContext,
+    disk: &str,
+    executor: &dyn CommandRunner,
+    is_uefi: bool,
+    swap_size_gb: Option<u64>,
+) -> Result<()> {
+    println!("Formatting partitions (LVM)...");
+
+    encryption::format_boot(context, &get_part_path(disk, 1), executor, is_uefi)?;
+    encryption::create_volume_group(executor, &get_part_path(disk, 2), swap_size_gb, true)?;
+
+    println!("Formatting Logical Volumes...");
+    if swap_size_gb.is_some() {
+        executor.run(Command::new("mkswap").arg(LVM_SWAP))?;
+    }
+    filesystem::format_root(context, LVM_ROOT, executor)?;
+    executor.run(Command::new("mkfs.ext4").args(["-F", LVM_HOME]))?;
+
+    Ok(())
+}
+
+/// Encrypt the root and home logical volumes separately. The swap volume is
+/// left for crypttab to encrypt with a random key on every boot.
+fn format_luks_on_lvm(
+    context: &InstallContext,
+    disk: &str,
+    executor: &dyn CommandRunner,
+    is_uefi: bool,
+    swap_size_gb: Option<u64>,
+) -> Result<()> {
+    let password = context
+        .get_answer(&QuestionId::EncryptionPassword)
+        .context("Encryption password not set")?;
+
+    println!("Formatting partitions (LUKS on LVM)...");
+
+    encryption::format_boot(context, &get_part_path(disk, 1), executor, is_uefi)?;
+    encryption::create_volume_group(executor, &get_part_path(disk, 2), swap_size_gb, true)?;
+
+    for (volume, mapping) in [(LVM_ROOT, CRYPT_ROOT), (LVM_HOME, CRYPT_HOME)] {
+        println!("Setting up LUKS container on {}...", volume);
+        executor.run_with_input(
+            Command::new("cryptsetup").args(["-q", "luksFormat", volume, "-"]),
+            password,
+        )?;
+        executor.run_with_input(
+            Command::new("cryptsetup").args(["open", volume, mapping, "-"]),
+            password,
+        )?;
+    }
+
+    println!("Formatting encrypted volumes...");
+    filesystem::format_root(context, &format!("/dev/mapper/{CRYPT_ROOT}"), executor)?;
+    executor.run(Command::new("mkfs.ext4").args(["-F", &format!("/dev/mapper/{CRYPT_HOME}")]))?;
+
+    Ok(())
+}
+
+fn mount_luks_on_lvm(
+    context: &InstallContext,
+    disk: &str,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    println!("Mounting encrypted volumes...");
+
+    filesystem::mount_root(
+        context,
+        &format!("/dev/mapper/{CRYPT_ROOT}"),
+        false,
+        executor,
+    )?;
+    let home = format!("/dev/mapper/{CRYPT_HOME}");
+    executor.run(Command::new("mount").args(["--mkdir", &home, "/mnt/home"]))?;
+
+    let p1 = get_part_path(disk, 1);
+    executor.run(Command::new("mount").args(["--mkdir", &p1, "/mnt/boot"]))?;
+
+    Ok(())
+}
+
+/// Standard layout on the first disk plus a single data partition on the
+/// second disk, used either as the RAID1 mirror of root or as `/home`
+fn prepare_two_disks(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    disk: &str,
+    second_disk: &str,
+    layout: DiskLayout,
+    swap_size_gb: Option<u64>,
+) -> Result<()> {
+    let partitions = automatic::partition_automatic(context, disk, executor, swap_size_gb)?;
+    let data = partition_data_disk(context, second_disk, executor)?;
+
+    println!("Formatting partitions...");
+    partitions.format_boot_and_swap(executor)?;
+    if layout == DiskLayout::BtrfsRaid1 {
+        filesystem::format_btrfs_raid1(&[&partitions.root, &data], executor)?;
+    } else {
+        filesystem::format_root(context, &partitions.root, executor)?;
+        executor.run(Command::new("mkfs.ext4").args(["-F", &data]))?;
+    }
+
+    println!("Mounting partitions...");
+    if layout == DiskLayout::BtrfsRaid1 {
+        filesystem::mount_root(context, &partitions.root, true, executor)?;
+    } else {
+        filesystem::mount_root(context, &partitions.root, false, executor)?;
+        executor.run(Command::new("mount").args(["--mkdir", &data, "/mnt/home"]))?;
+    }
+    partitions.mount_boot_and_swap(executor)?;
+
+    Ok(())
+}
+
+/// Erase `disk` and create one partition spanning all of it
+fn partition_data_disk(
+    context: &InstallContext,
+    disk: &str,
+    executor: &dyn CommandRunner,
+) -> Result<String> {
+    println!("Partitioning second disk {}...", disk);
+
+    let script = match context.system_info.boot_mode {
+        BootMode::UEFI64 | BootMode::UEFI32 => "label: gpt\ntype=L\n",
+        BootMode::BIOS => "label: dos\ntype=83\n",
+    };
+
+    executor.run_with_input(Command::new("sfdisk").arg(disk), script)?;
+
+    if !executor.dry_run() {
+        executor.run(Command::new("udevadm").arg("settle"))?;
+        std::thread::sleep(std::time::Duration::from_secs(2));
+    }
+
+    Ok(get_part_path(disk, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::config::SwapType;
+    use crate::arch::execution::mock::MockRunner;
+
+    fn context(layout: &str, encrypted: bool) -> InstallContext {
+        let mut context = InstallContext::new();
+        context.system_info.boot_mode = BootMode::UEFI64;
+        context
+            .answers
+            .insert(QuestionId::RootFilesystem, "ext4".into());
+        context
+            .answers
+            .insert(QuestionId::DiskLayout, layout.into());
+        context.answers.insert(
+            QuestionId::UseEncryption,
+            if encrypted { "true" } else { "false" }.into(),
+        );
+        context
+            .answers
+            .insert(QuestionId::EncryptionPassword, "secret".into());
+        context
+            .answers
+            .insert(QuestionId::SecondDisk, "/dev/sdb".into());
+        context
+    }
+
+    fn prepare(context: &InstallContext) -> Vec<String> {
+        let runner = MockRunner::new();
+        let layout = DiskLayout::from_context(context);
+        let swap = SwapType::from_context(context).is_partition().then_some(8);
+        prepare_layout(context, &runner, "/dev/sda", layout, swap).unwrap();
+        runner.command_log()
+    }
+
+    #[test]
+    fn lvm_creates_root_home_and_swap_volumes() {
+        let log = prepare(&context("lvm", false));
+
+        assert_eq!(
+            log[..12],
+            [
+                "sfdisk /dev/sda <<< 'label: gpt\nsize=1G, type=U\ntype=V\n'",
+                "udevadm settle",
+                "mkfs.fat -F32 /dev/sda1",
+                "pvcreate /dev/sda2",
+                "vgcreate instantOS /dev/sda2",
+                "lvcreate -L 8G instantOS -n swap",
+                "lvcreate -l 40%FREE instantOS -n root",
+                "lvcreate -l 100%FREE instantOS -n home",
+                "udevadm settle",
+                "vgchange -ay instantOS",
+                "mkswap /dev/instantOS/swap",
+                "mkfs.ext4 -F /dev/instantOS/root",
+            ]
+        );
+        assert!(log.contains(&"mount --mkdir /dev/instantOS/home /mnt/home".to_string()));
+        assert_eq!(log.last().unwrap(), "swapon /dev/instantOS/swap");
+    }
+
+    #[test]
+    fn encrypted_lvm_adds_home_inside_the_luks_container() {
+        let log = prepare(&context("lvm", true));
+
+        assert!(log.contains(&"cryptsetup open /dev/sda2 cryptlvm - <<< 'secret'".to_string()));
+        assert!(log.contains(&"vgcreate instantOS /dev/mapper/cryptlvm".to_string()));
+        assert!(log.contains(&"mkfs.ext4 -F /dev/instantOS/home".to_string()));
+        assert!(log.contains(&"mount --mkdir /dev/instantOS/home /mnt/home".to_string()));
+    }
+
+    #[test]
+    fn luks_on_lvm_encrypts_each_volume() {
+        let log = prepare(&context("luks-on-lvm", true));
+
+        for expected in [
+            "vgcreate instantOS /dev/sda2",
+            "cryptsetup -q luksFormat /dev/instantOS/root - <<< 'secret'",
+            "cryptsetup open /dev/instantOS/root cryptroot - <<< 'secret'",
+            "cryptsetup open /dev/instantOS/home crypthome - <<< 'secret'",
+            "mkfs.ext4 -F /dev/mapper/cryptroot",
+            "mount /dev/mapper/cryptroot /mnt",
+            "mount --mkdir /dev/mapper/crypthome /mnt/home",
+        ] {
+            assert!(log.contains(&expected.to_string()), "missing {expected}");
+        }
+        assert!(!log.iter().any(|line| line.contains("mkswap")));
+        assert!(!log.iter().any(|line| line.starts_with("swapon")));
+    }
+
+    #[test]
+    fn btrfs_raid1_mirrors_root_across_both_disks() {
+        let mut context = context("btrfs-raid1", false);
+        context
+            .answers
+            .insert(QuestionId::RootFilesystem, "btrfs".into());
+        let log = prepare(&context);
+
+        assert!(log.contains(&"sfdisk /dev/sdb <<< 'label: gpt\ntype=L\n'".to_string()));
+        assert!(log.contains(&"mkfs.btrfs -f -d raid1 -m raid1 /dev/sda3 /dev/sdb1".to_string()));
+        assert!(
+            log.iter()
+                .any(|line| line.contains("subvol=@home") && line.ends_with("/dev/sda3 /mnt/home"))
+        );
+    }
+
+    #[test]
+    fn home_disk_mounts_second_disk_at_home() {
+        let log = prepare(&context("home-disk", false));
+
+        assert!(log.contains(&"mkfs.ext4 -F /dev/sda3".to_string()));
+        assert!(log.contains(&"mkfs.ext4 -F /dev/sdb1".to_string()));
+        assert!(log.contains(&"mount --mkdir /dev/sdb1 /mnt/home".to_string()));
+        assert_eq!(log.last().unwrap(), "swapon /dev/sda2");
+    }
+}
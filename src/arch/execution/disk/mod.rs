@@ -2,15 +2,18 @@ mod automatic;
 mod dualboot;
 mod encryption;
 mod filesystem;
+mod layouts;
 mod mount;
 mod probe;
 mod util;
 
 use super::CommandRunner;
+use crate::arch::config::{DiskLayout, SwapType};
 use crate::arch::engine::{BootMode, InstallContext, QuestionId};
 use anyhow::{Context, Result};
 
-pub use util::get_part_path;
+pub use layouts::CRYPT_HOME;
+pub use util::{get_part_path, parse_partition_number};
 
 pub fn prepare_disk(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
     let disk_path = context
@@ -21,12 +24,24 @@ pub fn prepare_disk(context: &InstallContext, executor: &dyn CommandRunner) -> R
 
     let boot_mode = &context.system_info.boot_mode;
 
-    let ram_size_gb = probe::get_total_ram_gb().unwrap_or(4);
-    let swap_size_gb = std::cmp::max(4, ram_size_gb);
-    println!(
-        "Detected RAM: {} GiB, setting Swap: {} GiB",
-        ram_size_gb, swap_size_gb
-    );
+    let swap_size_gb = if context.system_info.disk_image {
+        println!(
+            "Building a disk image, setting Swap: {} GiB",
+            super::image::IMAGE_SWAP_GB
+        );
+        super::image::IMAGE_SWAP_GB
+    } else {
+        let ram_size_gb = probe::get_total_ram_gb().unwrap_or(4);
+        let swap_size_gb = std::cmp::max(4, ram_size_gb);
+        println!(
+            "Detected RAM: {} GiB, setting Swap: {} GiB",
+            ram_size_gb, swap_size_gb
+        );
+        swap_size_gb
+    };
+
+    let swap_type = SwapType::from_context(context);
+    let partition_swap_gb = swap_type.is_partition().then_some(swap_size_gb);
 
     let partitioning_method = context
         .get_answer(&QuestionId::PartitioningMethod)
@@ -34,35 +49,62 @@ pub fn prepare_disk(context: &InstallContext, executor: &dyn CommandRunner) -> R
         .unwrap_or("Automatic");
 
     if partitioning_method.contains("Dual Boot") {
-        dualboot::prepare_dualboot_disk(context, executor, disk_path, swap_size_gb)?;
+        dualboot::prepare_dualboot_disk(context, executor, disk_path, partition_swap_gb)?;
     } else if partitioning_method.contains("Manual") {
         mount::format_and_mount_partitions(context, executor)?;
+    } else if let layout @ (DiskLayout::Lvm
+    | DiskLayout::LuksOnLvm
+    | DiskLayout::BtrfsRaid1
+    | DiskLayout::HomeDisk) = DiskLayout::from_context(context)
+    {
+        println!("Disk layout: {}", layout.label());
+        layouts::prepare_layout(context, executor, disk_path, layout, partition_swap_gb)?;
     } else {
         let use_encryption = context.get_answer_bool(QuestionId::UseEncryption);
 
         match (boot_mode, use_encryption) {
             (BootMode::UEFI64 | BootMode::UEFI32, false) => {
-                automatic::partition_uefi(disk_path, executor, swap_size_gb)?;
+                automatic::partition_uefi(disk_path, executor, partition_swap_gb)?;
                 automatic::format_uefi(context, disk_path, executor)?;
                 automatic::mount_uefi(context, disk_path, executor)?;
             }
             (BootMode::BIOS, false) => {
-                automatic::partition_bios(disk_path, executor, swap_size_gb)?;
+                let boot_partition = crate::arch::config::Bootloader::from_context(context)
+                    .needs_fat_boot_partition(boot_mode);
+                automatic::partition_bios(disk_path, executor, partition_swap_gb, boot_partition)?;
                 automatic::format_bios(context, disk_path, executor)?;
                 automatic::mount_bios(context, disk_path, executor)?;
             }
             (BootMode::UEFI64 | BootMode::UEFI32, true) => {
                 encryption::partition_uefi_luks(disk_path, executor)?;
-                encryption::format_luks(context, disk_path, executor, true, swap_size_gb)?;
-                encryption::mount_luks(context, executor, disk_path)?;
+                encryption::format_luks(
+                    context,
+                    disk_path,
+                    executor,
+                    true,
+                    partition_swap_gb,
+                    false,
+                )?;
+                encryption::mount_luks(context, executor, disk_path, false)?;
             }
             (BootMode::BIOS, true) => {
                 encryption::partition_bios_luks(disk_path, executor)?;
-                encryption::format_luks(context, disk_path, executor, false, swap_size_gb)?;
-                encryption::mount_luks(context, executor, disk_path)?;
+                encryption::format_luks(
+                    context,
+                    disk_path,
+                    executor,
+                    false,
+                    partition_swap_gb,
+                    false,
+                )?;
+                encryption::mount_luks(context, executor, disk_path, false)?;
             }
         }
     }
 
+    if swap_type == SwapType::Swapfile {
+        super::swap::create_swapfile(context, executor, swap_size_gb)?;
+    }
+
     Ok(())
 }
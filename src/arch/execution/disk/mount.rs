@@ -1,8 +1,10 @@
 use super::filesystem;
+use crate::arch::config::{Bootloader, SwapType};
 use crate::arch::engine::{
     BootMode, DualBootPartitions, EspNeedsFormat, InstallContext, QuestionId,
 };
 use crate::arch::execution::CommandRunner;
+use crate::common::format::format_size;
 use anyhow::{Context, Result};
 use std::process::Command;
 
@@ -43,11 +45,11 @@ pub fn format_and_mount_partitions(
     if let Some(boot_path) = boot_path {
         let should_format = context.get::<EspNeedsFormat>().unwrap_or(true);
 
-        let boot_mount_point = if dualboot_paths.is_some() {
-            "/mnt/boot/efi"
-        } else {
-            "/mnt/boot"
-        };
+        let bootloader = Bootloader::from_context(context);
+        let boot_mount_point = format!(
+            "/mnt{}",
+            bootloader.esp_mount_point(dualboot_paths.is_some())
+        );
 
         if should_format {
             println!("Formatting Boot partition: {}", boot_path);
@@ -55,6 +57,9 @@ pub fn format_and_mount_partitions(
                 BootMode::UEFI64 | BootMode::UEFI32 => {
                     executor.run(Command::new("mkfs.fat").args(["-F32", &boot_path]))?;
                 }
+                BootMode::BIOS if bootloader.needs_fat_boot_partition(boot_mode) => {
+                    executor.run(Command::new("mkfs.fat").args(["-F32", &boot_path]))?;
+                }
                 BootMode::BIOS => {
                     executor.run(Command::new("mkfs.ext4").args(["-F", &boot_path]))?;
                 }
@@ -67,15 +72,26 @@ pub fn format_and_mount_partitions(
         }
 
         println!("Mounting Boot partition...");
-        executor.run(Command::new("mount").args(["--mkdir", &boot_path, boot_mount_point]))?;
+        executor.run(Command::new("mount").args(["--mkdir", &boot_path, &boot_mount_point]))?;
+
+        // A reused Windows ESP becomes /boot and has to fit the kernels next to Windows
+        if dualboot_paths.is_some()
+            && !should_format
+            && bootloader.keeps_kernels_on_esp()
+            && !executor.dry_run()
+        {
+            ensure_kernel_space(&boot_path, &boot_mount_point, bootloader)?;
+        }
     }
 
     let swap_path = if let Some(ref paths) = dualboot_paths {
-        Some(paths.swap.clone())
-    } else {
+        paths.swap.clone()
+    } else if SwapType::from_context(context).is_partition() {
         context
             .get_answer(&QuestionId::SwapPartition)
             .map(|s| s.to_string())
+    } else {
+        None
     };
 
     if let Some(swap_path) = swap_path {
@@ -94,3 +110,19 @@ pub fn format_and_mount_partitions(
 
     Ok(())
 }
+
+fn ensure_kernel_space(device: &str, mount_point: &str, bootloader: Bootloader) -> Result<()> {
+    let stat = nix::sys::statvfs::statvfs(mount_point)
+        .with_context(|| format!("Failed to read free space of {}", mount_point))?;
+    let free = stat.blocks_available() * stat.fragment_size();
+    if free < Bootloader::MIN_KERNEL_ESP_FREE {
+        anyhow::bail!(
+            "The EFI partition {} has {} free, but {} needs {} for the kernels. Choose GRUB, or free up space on the EFI partition, then start the install again.",
+            device,
+            format_size(free),
+            bootloader.label(),
+            format_size(Bootloader::MIN_KERNEL_ESP_FREE)
+        );
+    }
+    Ok(())
+}
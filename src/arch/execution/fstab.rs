@@ -1,15 +1,21 @@
 use super::CommandRunner;
-use crate::arch::engine::InstallContext;
-use anyhow::Result;
+use super::disk::CRYPT_HOME;
+use crate::arch::config::{DiskLayout, SwapType};
+use crate::arch::engine::{InstallContext, QuestionId};
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
 use std::process::Command;
 
-pub fn generate_fstab(_context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+/// Key file that unlocks the home volume of the LUKS on LVM layout once root is mounted
+const HOME_KEY_FILE: &str = "/etc/cryptsetup-keys.d/crypthome.key";
+
+pub fn generate_fstab(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
     println!("Generating fstab...");
 
     let output_opt = executor.run_with_output(Command::new("genfstab").arg("-U").arg("/mnt"))?;
 
     if let Some(output) = output_opt {
-        use std::io::Write;
         let mut file = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
@@ -22,6 +28,132 @@ pub fn generate_fstab(_context: &InstallContext, executor: &dyn CommandRunner) -
         println!("[DRY RUN] Writing output to /mnt/etc/fstab");
     }
 
+    if DiskLayout::from_context(context) == DiskLayout::LuksOnLvm {
+        write_luks_on_lvm_crypttab(context, executor, Path::new("/mnt"))?;
+    }
+
     println!("Fstab generated.");
     Ok(())
 }
+
+/// Unlock home with a key file stored on the encrypted root and encrypt the swap
+/// volume, if any, with a fresh random key on every boot. Root itself is unlocked
+/// by the initramfs from the kernel command line.
+fn write_luks_on_lvm_crypttab(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    root: &Path,
+) -> Result<()> {
+    let swap = SwapType::from_context(context).is_partition();
+    if swap {
+        println!("Writing crypttab for {} and encrypted swap...", CRYPT_HOME);
+    } else {
+        println!("Writing crypttab for {}...", CRYPT_HOME);
+    }
+
+    let password = context
+        .get_answer(&QuestionId::EncryptionPassword)
+        .context("Encryption password not set")?;
+    let key_file = root.join(HOME_KEY_FILE.trim_start_matches('/'));
+    let key_path = key_file.to_string_lossy();
+
+    if !executor.dry_run()
+        && let Some(parent) = key_file.parent()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    executor.run(Command::new("dd").args([
+        "if=/dev/urandom",
+        &format!("of={key_path}"),
+        "bs=512",
+        "count=4",
+        "iflag=fullblock",
+    ]))?;
+    executor.run(Command::new("chmod").args(["600", &key_path]))?;
+    executor.run_with_input(
+        Command::new("cryptsetup").args([
+            "luksAddKey",
+            "--key-file",
+            "-",
+            "/dev/instantOS/home",
+            &key_path,
+        ]),
+        password,
+    )?;
+
+    let mut crypttab = format!("{CRYPT_HOME} /dev/instantOS/home {HOME_KEY_FILE} luks\n");
+    let mut fstab = String::new();
+    if swap {
+        crypttab.push_str(
+            "cryptswap /dev/instantOS/swap /dev/urandom swap,cipher=aes-xts-plain64,size=512\n",
+        );
+        fstab.push_str("/dev/mapper/cryptswap none swap defaults 0 0\n");
+    }
+
+    if executor.dry_run() {
+        println!("[DRY RUN] Appending {} to /etc/crypttab", CRYPT_HOME);
+        if swap {
+            println!("[DRY RUN] Appending cryptswap to /etc/crypttab and /etc/fstab");
+        }
+        return Ok(());
+    }
+
+    for (file, content) in [("etc/crypttab", crypttab), ("etc/fstab", fstab)] {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(root.join(file))?
+            .write_all(content.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::execution::mock::MockRunner;
+
+    #[test]
+    fn luks_on_lvm_adds_home_key_and_random_swap() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("etc")).unwrap();
+        let mut context = InstallContext::new();
+        context
+            .answers
+            .insert(QuestionId::EncryptionPassword, "secret".into());
+        let runner = MockRunner::new();
+
+        write_luks_on_lvm_crypttab(&context, &runner, root.path()).unwrap();
+
+        let key = root.path().join("etc/cryptsetup-keys.d/crypthome.key");
+        let log = runner.command_log();
+        assert_eq!(log[1], format!("chmod 600 {}", key.display()));
+        assert_eq!(
+            log[2],
+            format!(
+                "cryptsetup luksAddKey --key-file - /dev/instantOS/home {} <<< 'secret'",
+                key.display()
+            )
+        );
+
+        let crypttab = std::fs::read_to_string(root.path().join("etc/crypttab")).unwrap();
+        assert!(
+            crypttab.contains(
+                "crypthome /dev/instantOS/home /etc/cryptsetup-keys.d/crypthome.key luks\n"
+            )
+        );
+        assert!(crypttab.contains("cryptswap /dev/instantOS/swap /dev/urandom swap,"));
+        let fstab = std::fs::read_to_string(root.path().join("etc/fstab")).unwrap();
+        assert_eq!(fstab, "/dev/mapper/cryptswap none swap defaults 0 0\n");
+
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("etc")).unwrap();
+        context.set_answer(QuestionId::SwapType, "zram".to_string());
+        write_luks_on_lvm_crypttab(&context, &runner, root.path()).unwrap();
+        let crypttab = std::fs::read_to_string(root.path().join("etc/crypttab")).unwrap();
+        assert!(!crypttab.contains("cryptswap"));
+        let fstab = std::fs::read_to_string(root.path().join("etc/fstab")).unwrap();
+        assert!(fstab.is_empty());
+    }
+}
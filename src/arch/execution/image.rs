@@ -0,0 +1,256 @@
+//! Installing into a disk image file instead of a block device.
+//!
+//! The image is created sparse, attached as a loop device and then used as the
+//! `Disk` answer, so the regular partition/format/pacstrap/bootloader pipeline runs
+//! against it unchanged. Bootloaders are installed to the removable-media fallback
+//! path without touching this machine's firmware variables.
+
+use super::CommandRunner;
+use crate::arch::config::{DiskLayout, SwapType};
+use crate::arch::engine::{InstallContext, QuestionId};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Swap size for images; RAM-based sizing describes the build machine, not the VM
+pub const IMAGE_SWAP_GB: u64 = 1;
+
+/// Smallest image a base system plus bootloader fits into
+const MIN_IMAGE_BYTES: u64 = 4 << 30;
+
+#[derive(Debug, Clone)]
+pub struct ImageOptions {
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+    /// Also write `<image>.qcow2` after a successful install
+    pub qcow2: bool,
+}
+
+/// Parse sizes like `20G`, `512M` or `21474836480` (binary units)
+pub fn parse_size(size: &str) -> Result<u64> {
+    let size = size.trim();
+    let (number, unit) = match size.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => size.split_at(index),
+        None => (size, ""),
+    };
+    let shift = match unit
+        .trim_end_matches(['B', 'b', 'i'])
+        .to_ascii_uppercase()
+        .as_str()
+    {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => anyhow::bail!("Invalid image size '{}' (use e.g. 20G or 512M)", size),
+    };
+    let number: u64 = number
+        .parse()
+        .with_context(|| format!("Invalid image size '{}'", size))?;
+    number
+        .checked_mul(1 << shift)
+        .with_context(|| format!("Image size '{}' is too large", size))
+}
+
+/// Point the questions at the loop device and check they make sense for an image
+pub fn prepare_context(context: &mut InstallContext, loop_device: &str) -> Result<()> {
+    let method = context
+        .get_answer(&QuestionId::PartitioningMethod)
+        .map(|s| s.as_str())
+        .unwrap_or("Automatic");
+    if method.contains("Manual") || method.contains("Dual Boot") {
+        anyhow::bail!(
+            "Disk images are partitioned automatically; '{}' partitioning is not supported",
+            method
+        );
+    }
+
+    let layout = DiskLayout::from_context(context);
+    if layout.needs_second_disk() {
+        anyhow::bail!(
+            "Disk images are a single disk; the '{}' layout is not supported",
+            layout.answer_value()
+        );
+    }
+
+    context.set_answer(QuestionId::Disk, loop_device.to_string());
+    context.system_info.disk_image = true;
+    Ok(())
+}
+
+/// Create the sparse image and attach it, returning the loop device
+pub fn create_and_attach(options: &ImageOptions, executor: &dyn CommandRunner) -> Result<String> {
+    if options.size < MIN_IMAGE_BYTES {
+        anyhow::bail!("Image size must be at least {} GiB", MIN_IMAGE_BYTES >> 30);
+    }
+    if options.path.exists() && !executor.dry_run() {
+        anyhow::bail!(
+            "Image {} already exists; remove it or choose another path",
+            options.path.display()
+        );
+    }
+
+    println!(
+        "Creating {} GiB sparse image {}...",
+        options.size >> 30,
+        options.path.display()
+    );
+    executor.run(
+        Command::new("truncate")
+            .arg("--size")
+            .arg(options.size.to_string())
+            .arg(&options.path),
+    )?;
+
+    let output = executor.run_with_output(
+        Command::new("losetup")
+            .args(["--find", "--show", "--partscan"])
+            .arg(&options.path),
+    )?;
+    let loop_device = match output {
+        Some(output) => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        // Dry run
+        None => "/dev/loop0".to_string(),
+    };
+    if loop_device.is_empty() {
+        anyhow::bail!("losetup did not report a loop device");
+    }
+    println!("Attached {} as {}", options.path.display(), loop_device);
+    Ok(loop_device)
+}
+
+/// Unmount the target, close LVM/LUKS and detach the loop device.
+///
+/// Runs after failed installs too, so every step before the detach is best-effort.
+pub fn detach(
+    context: &InstallContext,
+    loop_device: &str,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    println!("Detaching image from {}...", loop_device);
+    let best_effort = |command: &mut Command| {
+        if let Err(e) = executor.run(command) {
+            println!("Warning: {}", e);
+        }
+    };
+
+    let swap = SwapType::from_context(context);
+    // The swap file keeps its filesystem busy
+    if swap == SwapType::Swapfile {
+        let swapfile = super::paths::chroot_path(super::swap::swapfile_path(context));
+        best_effort(Command::new("swapoff").arg(swapfile));
+    }
+    best_effort(Command::new("umount").args(["-R", super::paths::CHROOT_MOUNT]));
+
+    let encrypted = context.get_answer_bool(QuestionId::UseEncryption);
+    let layout = DiskLayout::from_context(context);
+    // LUKS on LVM only swaps through crypttab inside the installed system
+    if swap.is_partition() && layout.uses_lvm(encrypted) && layout != DiskLayout::LuksOnLvm {
+        best_effort(Command::new("swapoff").arg("/dev/instantOS/swap"));
+    }
+    let swaps = std::fs::read_to_string("/proc/swaps").unwrap_or_default();
+    for device in swaps
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+    {
+        if device == loop_device || device.starts_with(&format!("{loop_device}p")) {
+            best_effort(Command::new("swapoff").arg(device));
+        }
+    }
+    if layout == DiskLayout::LuksOnLvm {
+        // The containers sit on the logical volumes, so close them first
+        best_effort(Command::new("cryptsetup").args(["close", super::disk::CRYPT_HOME]));
+        best_effort(Command::new("cryptsetup").args(["close", layout.luks_mapping()]));
+        best_effort(Command::new("vgchange").args(["-an", "instantOS"]));
+    } else if layout.uses_lvm(encrypted) {
+        best_effort(Command::new("vgchange").args(["-an", "instantOS"]));
+        if encrypted {
+            best_effort(Command::new("cryptsetup").args(["close", layout.luks_mapping()]));
+        }
+    }
+
+    executor.run(Command::new("losetup").args(["--detach", loop_device]))?;
+    Ok(())
+}
+
+/// Write a qcow2 copy next to the raw image, returning its path
+pub fn convert_to_qcow2(image: &Path, executor: &dyn CommandRunner) -> Result<PathBuf> {
+    let target = image.with_extension("qcow2");
+    println!("Converting {} to {}...", image.display(), target.display());
+    executor.run(
+        Command::new("qemu-img")
+            .args(["convert", "-f", "raw", "-O", "qcow2"])
+            .arg(image)
+            .arg(&target),
+    )?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::execution::mock::MockRunner;
+
+    #[test]
+    fn parses_image_sizes() {
+        assert_eq!(parse_size("20G").unwrap(), 20 << 30);
+        assert_eq!(parse_size("512MiB").unwrap(), 512 << 20);
+        assert_eq!(parse_size("1t").unwrap(), 1 << 40);
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert!(parse_size("20X").is_err());
+        assert!(parse_size("G").is_err());
+    }
+
+    #[test]
+    fn image_lifecycle_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = ImageOptions {
+            path: dir.path().join("instantos.img"),
+            size: 20 << 30,
+            qcow2: true,
+        };
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::Disk, "/dev/sda".to_string());
+        context.set_answer(QuestionId::UseEncryption, "true".to_string());
+
+        let mock = MockRunner::new();
+        let loop_device = create_and_attach(&options, &mock).unwrap();
+        prepare_context(&mut context, &loop_device).unwrap();
+        assert_eq!(context.get_answer(&QuestionId::Disk).unwrap(), "/dev/loop0");
+        assert!(context.system_info.disk_image);
+
+        detach(&context, &loop_device, &mock).unwrap();
+        let qcow2 = convert_to_qcow2(&options.path, &mock).unwrap();
+        assert_eq!(qcow2, dir.path().join("instantos.qcow2"));
+
+        let image = options.path.display().to_string();
+        let log = mock.command_log();
+        assert_eq!(log[0], format!("truncate --size 21474836480 {image}"));
+        assert_eq!(log[1], format!("losetup --find --show --partscan {image}"));
+        assert_eq!(log[2], "umount -R /mnt");
+        assert_eq!(log[3], "swapoff /dev/instantOS/swap");
+        assert!(log.contains(&"cryptsetup close cryptlvm".to_string()));
+        assert_eq!(log[log.len() - 2], "losetup --detach /dev/loop0");
+        assert_eq!(
+            log.last().unwrap(),
+            &format!(
+                "qemu-img convert -f raw -O qcow2 {image} {}",
+                qcow2.display()
+            )
+        );
+
+        let too_small = ImageOptions {
+            size: 1 << 30,
+            ..options
+        };
+        assert!(create_and_attach(&too_small, &mock).is_err());
+
+        context.set_answer(QuestionId::DiskLayout, "btrfs-raid1".to_string());
+        assert!(prepare_context(&mut context, "/dev/loop0").is_err());
+
+        context.set_answer(QuestionId::PartitioningMethod, "Manual".to_string());
+        assert!(prepare_context(&mut context, "/dev/loop0").is_err());
+    }
+}
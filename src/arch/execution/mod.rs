@@ -4,16 +4,21 @@ use std::path::PathBuf;
 
 pub mod base;
 pub mod bootloader;
+pub mod checkpoint;
 pub mod config;
 pub mod disk;
 pub mod fstab;
+pub mod image;
 pub mod packages;
 pub mod pacman;
 pub mod paths;
 pub mod post;
+pub mod restore;
+pub mod resume;
 pub mod setup;
 pub mod state;
 pub mod step;
+pub mod swap;
 
 use self::state::InstallState;
 use self::step::InstallStep;
@@ -280,6 +285,8 @@ pub async fn execute_installation(
     config_path: PathBuf,
     step: Option<String>,
     mut dry_run: bool,
+    resume: bool,
+    image: Option<image::ImageOptions>,
     log_file: Option<PathBuf>,
 ) -> Result<()> {
     // Check for force dry-run file
@@ -328,6 +335,20 @@ pub async fn execute_installation(
         context.get_answer(&crate::arch::engine::QuestionId::Username)
     );
 
+    if let Some(image) = image {
+        if step.is_some() || resume {
+            anyhow::bail!(
+                "--image always builds the whole image; it cannot be combined with a single step or --resume"
+            );
+        }
+        return build_image(image, context, &executor, dry_run).await;
+    }
+
+    // Steps run inside the chroot are driven by the host, which already did this
+    if !is_chroot() {
+        prepare_attempt(&context, &executor, resume)?;
+    }
+
     if let Some(step_name) = step {
         // Try to parse the step name
         // In a real implementation we might use clap's value parser if we exposed the enum directly in CLI,
@@ -346,44 +367,101 @@ pub async fn execute_installation(
         println!("Executing single step: {:?}", step_enum);
         execute_step(step_enum, &context, &executor, &config_path).await?;
     } else {
-        println!("Executing all steps...");
-        let steps = vec![
-            InstallStep::Disk,
-            InstallStep::Base,
-            InstallStep::Fstab,
-            InstallStep::Config,
-            InstallStep::Bootloader,
-            InstallStep::Post,
-        ];
+        run_all_steps(&context, &executor, &config_path, dry_run).await?;
+    }
 
-        for step in steps {
-            execute_step(step, &context, &executor, &config_path).await?;
-        }
+    Ok(())
+}
 
-        // Remove the config file from the chroot to prevent leaking sensitive data (passwords)
-        if !dry_run {
-            let chroot_config = paths::chroot_path(paths::CONFIG_FILE);
-            if chroot_config.exists() {
-                println!(
-                    "Securing installation: Removing configuration file from target system..."
-                );
-                if let Err(e) = std::fs::remove_file(&chroot_config) {
-                    println!("Warning: Failed to remove config file from chroot: {}", e);
-                }
+async fn run_all_steps(
+    context: &crate::arch::engine::InstallContext,
+    executor: &dyn CommandRunner,
+    config_path: &std::path::Path,
+    dry_run: bool,
+) -> Result<()> {
+    println!("Executing all steps...");
+    let steps = vec![
+        InstallStep::Disk,
+        InstallStep::Base,
+        InstallStep::Fstab,
+        InstallStep::Config,
+        InstallStep::Bootloader,
+        InstallStep::Post,
+    ];
+
+    for step in steps {
+        execute_step(step, context, executor, config_path).await?;
+    }
+
+    // Remove the config file from the chroot to prevent leaking sensitive data (passwords)
+    if !dry_run {
+        let chroot_config = paths::chroot_path(paths::CONFIG_FILE);
+        if chroot_config.exists() {
+            println!("Securing installation: Removing configuration file from target system...");
+            if let Err(e) = std::fs::remove_file(&chroot_config) {
+                println!("Warning: Failed to remove config file from chroot: {}", e);
             }
+        }
 
-            let chroot_bin = paths::chroot_path("/usr/bin/ins-install");
-            if chroot_bin.exists()
-                && let Err(e) = std::fs::remove_file(&chroot_bin)
-            {
-                println!(
-                    "Warning: Failed to remove installer binary from chroot: {}",
-                    e
-                );
-            }
+        // The state only matters to the installer; the host keeps its own copy
+        let chroot_state = paths::chroot_path(paths::STATE_FILE);
+        if chroot_state.exists()
+            && let Err(e) = std::fs::remove_file(&chroot_state)
+        {
+            println!("Warning: Failed to remove install state from chroot: {}", e);
+        }
+
+        if let Err(e) = restore::unmount_from_chroot(context, executor) {
+            println!("Warning: Failed to unmount the game save repository: {}", e);
+        }
+
+        let chroot_bin = paths::chroot_path("/usr/bin/ins-install");
+        if chroot_bin.exists()
+            && let Err(e) = std::fs::remove_file(&chroot_bin)
+        {
+            println!(
+                "Warning: Failed to remove installer binary from chroot: {}",
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Run every step against a loop-attached image file, detaching it even on failure
+async fn build_image(
+    options: image::ImageOptions,
+    mut context: crate::arch::engine::InstallContext,
+    executor: &dyn CommandRunner,
+    dry_run: bool,
+) -> Result<()> {
+    let loop_device = image::create_and_attach(&options, executor)?;
+
+    let result = async {
+        image::prepare_context(&mut context, &loop_device)?;
+
+        // Chroot steps re-read the questions file, so it has to name the loop device
+        let mut questions = tempfile::NamedTempFile::new()?;
+        std::io::Write::write_all(&mut questions, context.to_toml()?.as_bytes())?;
+
+        if !dry_run {
+            println!("Starting a fresh image build; any previous install state is discarded.");
+            InstallState::new().save()?;
         }
+        run_all_steps(&context, executor, questions.path(), dry_run).await
     }
+    .await;
+
+    let detached = image::detach(&context, &loop_device, executor);
+    result?;
+    detached?;
 
+    if options.qcow2 {
+        let qcow2 = image::convert_to_qcow2(&options.path, executor)?;
+        println!("qcow2 image written to {}", qcow2.display());
+    }
+    println!("Image ready: {}", options.path.display());
     Ok(())
 }
 
@@ -425,7 +503,7 @@ async fn execute_step(
             "Step {:?} requires chroot, setting up and entering...",
             step
         );
-        setup_chroot(executor, config_path)?;
+        setup_chroot(context, executor, config_path)?;
 
         // Construct command to run inside chroot
         // arch-chroot /mnt /usr/bin/ins arch exec <step> --config /etc/instant/install_config.toml
@@ -441,14 +519,27 @@ async fn execute_step(
             .arg("exec")
             .arg(step_name)
             .arg("--questions-file")
-            .arg(paths::CONFIG_FILE);
+            .arg(paths::CONFIG_FILE)
+            .env(state::ANSWER_KEY_ENV, state::answer_key());
 
         if executor.dry_run() {
             // Pass dry-run flag if we are dry-running
             cmd.arg("--dry-run");
         }
 
-        executor.run(&mut cmd)?;
+        let chroot_result = executor.run(&mut cmd);
+
+        // Keep the sub-steps that finished inside the chroot, even if the step failed
+        if !executor.dry_run() {
+            match InstallState::load_from(&paths::chroot_path(paths::STATE_FILE)) {
+                Ok(chroot_state) => state.merge_checkpoints(&chroot_state),
+                Err(e) => println!("Warning: Failed to read chroot install state: {}", e),
+            }
+            if let Err(e) = state.save() {
+                println!("Warning: Failed to save install state on host: {}", e);
+            }
+        }
+        chroot_result?;
 
         // Collect logs from chroot
         if !executor.dry_run() {
@@ -497,6 +588,14 @@ async fn execute_step(
     }
 
     if !executor.dry_run() {
+        // Sub-steps saved their checkpoints to the state file while the step ran
+        let mut state = InstallState::load().unwrap_or(state);
+        if step == InstallStep::Disk {
+            resume::record_disk_checkpoint(context, &mut state);
+            if let Err(e) = resume::record_mounts(context, &mut state) {
+                println!("Warning: Failed to record mounts for resuming: {}", e);
+            }
+        }
         state.mark_complete(step);
         if let Err(e) = state.save() {
             println!("Warning: Failed to save install state: {}", e);
@@ -514,9 +613,53 @@ async fn execute_step(
     Ok(())
 }
 
-fn setup_chroot(executor: &dyn CommandRunner, config_path: &std::path::Path) -> Result<()> {
+/// Check the questions file against the previous attempt and, when resuming, restore
+/// the mounts the Disk step left behind
+fn prepare_attempt(
+    context: &crate::arch::engine::InstallContext,
+    executor: &dyn CommandRunner,
+    resume: bool,
+) -> Result<()> {
+    let mut state = InstallState::load().unwrap_or_else(|e| {
+        println!("Warning: Failed to load install state: {}", e);
+        InstallState::new()
+    });
+
+    resume::reconcile_answers(context, &mut state)?;
+
+    if resume {
+        println!("Resuming installation. Changes made by previous attempts:");
+        println!("{}", resume::report(&state));
+
+        let mounted = std::process::Command::new("mountpoint")
+            .arg("-q")
+            .arg(paths::CHROOT_MOUNT)
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        if state.is_complete(InstallStep::Disk) && !mounted {
+            resume::restore_mounts(context, &state, executor)?;
+        }
+    }
+
+    if !executor.dry_run()
+        && let Err(e) = state.save()
+    {
+        println!("Warning: Failed to save install state: {}", e);
+    }
+    Ok(())
+}
+
+fn setup_chroot(
+    context: &crate::arch::engine::InstallContext,
+    executor: &dyn CommandRunner,
+    config_path: &std::path::Path,
+) -> Result<()> {
     println!("Setting up chroot environment...");
 
+    crate::arch::offline::mount_into_chroot(executor)?;
+    restore::stage_into_chroot(context, executor)?;
+
     // Copy binary
     let current_exe = std::env::current_exe()?;
     let target_bin = paths::chroot_path("/usr/bin/ins-install");
@@ -17,7 +17,7 @@ pub fn dedup_preserve(vec: &mut Vec<String>) {
 ///
 /// This collects:
 /// - Extended/system packages (drivers, tools, DE) derived from answers and detected hardware
-/// - Config-required packages (encryption, plymouth)
+/// - Config-required packages (encryption, plymouth, zram)
 /// - Bootloader packages (grub, efibootmgr/os-prober)
 ///
 /// Note: instantOS packages (instantdepend, instantos, instantextra) are NOT included here.
@@ -29,6 +29,10 @@ pub fn build_standard_package_plan(context: &InstallContext) -> Result<Vec<Strin
     packages.extend(crate::arch::execution::bootloader::bootloader_package_list(
         context,
     ));
+    packages.extend(crate::arch::execution::bootloader::snapshot_package_list(
+        context,
+    ));
+    packages.extend(crate::arch::execution::swap::swap_package_list(context));
 
     dedup_preserve(&mut packages);
     Ok(packages)
@@ -142,6 +146,9 @@ fn collect_extended_packages(context: &InstallContext) -> Result<Vec<String>> {
                     packages.push("lightdm".to_string());
                     packages.push("lightdm-gtk-greeter".to_string());
                 }
+                crate::arch::config::DisplayManager::Sddm => {
+                    packages.push("sddm".to_string());
+                }
             }
         }
 
@@ -189,6 +196,11 @@ fn collect_extended_packages(context: &InstallContext) -> Result<Vec<String>> {
         packages.push("plymouth".to_owned());
     }
 
+    // Game saves are restored with restic
+    if context.get_answer(&QuestionId::RestoreGameRepo).is_some() {
+        packages.push("restic".to_owned());
+    }
+
     // Append language-specific packages (only if GUI is installed and not in minimal mode)
     if !minimal_mode {
         let desktop = crate::arch::config::DesktopEnvironment::from_context(context);
@@ -264,6 +276,22 @@ mod tests {
         assert!(!packages.iter().any(|pkg| pkg == "gdm"));
     }
 
+    #[test]
+    fn plasma_selection_adds_plasma_and_sddm_by_default() {
+        let mut context = base_context();
+        context.set_answer(
+            QuestionId::DesktopEnvironment,
+            DesktopEnvironment::Plasma.answer_value().to_string(),
+        );
+
+        let packages = build_standard_package_plan(&context).unwrap();
+
+        assert!(packages.iter().any(|pkg| pkg == "plasma-meta"));
+        assert!(packages.iter().any(|pkg| pkg == "sddm"));
+        assert!(!packages.iter().any(|pkg| pkg == "gdm"));
+        assert!(!packages.iter().any(|pkg| pkg == "ttf-jetbrains-mono-nerd"));
+    }
+
     #[test]
     fn selecting_german_locale_adds_german_firefox_i18n_package() {
         let mut context = base_context();
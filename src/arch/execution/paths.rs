@@ -2,6 +2,8 @@ use std::path::PathBuf;
 
 pub const STATE_FILE: &str = "/etc/instant/install_state.toml";
 pub const CONFIG_FILE: &str = "/etc/instant/install_config.toml";
+/// Key for hashing sensitive answers, never copied to the target
+pub const ANSWER_KEY_FILE: &str = "/etc/instant/answer_key";
 pub const LOG_FILE: &str = "/var/log/instantos/install.log";
 pub const DRY_RUN_FLAG: &str = "/etc/instant/installdryrun";
 pub const CHROOT_MOUNT: &str = "/mnt";
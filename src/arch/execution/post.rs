@@ -1,15 +1,127 @@
 use super::CommandRunner;
+use super::checkpoint::{Checkpoints, SubStep};
+use super::step::InstallStep;
+use crate::arch::config::DesktopEnvironment;
 use crate::arch::engine::{InstallContext, QuestionId};
 use anyhow::{Context, Result};
 
+const SETUP: SubStep = SubStep {
+    name: "instantos",
+    questions: &[
+        QuestionId::Username,
+        QuestionId::MinimalMode,
+        QuestionId::Autologin,
+    ],
+    changes: "Set up instantOS dotfiles and services for the user",
+};
+
+const DESKTOP: SubStep = SubStep {
+    name: "desktop",
+    questions: &[QuestionId::DesktopEnvironment, QuestionId::MinimalMode],
+    changes: "Start instantOS autostart from the desktop session",
+};
+
+const RESTORE: SubStep = SubStep {
+    name: "restore",
+    questions: &[
+        QuestionId::Username,
+        QuestionId::RestoreDotfiles,
+        QuestionId::RestorePackageList,
+        QuestionId::RestoreGameRepo,
+        QuestionId::RestoreGamePassword,
+    ],
+    changes: "Restored the user's dotfiles, packages and game save repository",
+};
+
+/// XDG autostart entry that runs `ins autostart` in full desktop sessions
+const AUTOSTART_ENTRY_PATH: &str = "/etc/xdg/autostart/instantos-autostart.desktop";
+
 pub async fn install_post(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
     println!("Running post-installation setup (inside chroot)...");
+    let checkpoints = Checkpoints::load(InstallStep::Post, context, executor);
 
     let username = context
         .get_answer(&QuestionId::Username)
         .context("Username not set")?;
 
-    super::setup::setup_instantos(context, executor, Some(username.clone())).await?;
+    if checkpoints.pending(&SETUP) {
+        super::setup::setup_instantos(context, executor, Some(username.clone())).await?;
+        checkpoints.complete(&SETUP);
+    }
+
+    if checkpoints.pending(&DESKTOP) {
+        integrate_desktop(context, executor)?;
+        checkpoints.complete(&DESKTOP);
+    }
+
+    // The local repository is only mounted during the install. Removing it first also
+    // re-enables the online repositories an offline install commented out, which the
+    // restored package list is resolved against.
+    crate::arch::offline::disable(executor)?;
+
+    // Failures only warn, so this never stops the install
+    if checkpoints.pending(&RESTORE) {
+        super::restore::restore_user_data(context, executor, &checkpoints, username);
+        checkpoints.complete(&RESTORE);
+    }
 
     Ok(())
 }
+
+/// Hook instantOS into desktops the dotfiles do not configure.
+///
+/// The window manager configs in the dotfiles start `ins autostart` themselves;
+/// Plasma, GNOME and Xfce only run what is listed in XDG autostart.
+fn integrate_desktop(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    if context.get_answer_bool(QuestionId::MinimalMode) {
+        return Ok(());
+    }
+    let desktop = DesktopEnvironment::from_context(context);
+    let Some(desktop_name) = desktop.xdg_desktop_name() else {
+        return Ok(());
+    };
+
+    println!(
+        "Adding instantOS autostart to the {} session...",
+        desktop.label()
+    );
+
+    if executor.dry_run() {
+        println!("[DRY RUN] Write {}", AUTOSTART_ENTRY_PATH);
+        return Ok(());
+    }
+
+    if let Some(parent) = std::path::Path::new(AUTOSTART_ENTRY_PATH).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(AUTOSTART_ENTRY_PATH, autostart_entry(desktop_name))?;
+    println!("Created {}", AUTOSTART_ENTRY_PATH);
+
+    Ok(())
+}
+
+fn autostart_entry(desktop_name: &str) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=instantOS autostart\n\
+         Exec=ins autostart\n\
+         OnlyShowIn={};\n\
+         NoDisplay=true\n\
+         X-GNOME-Autostart-Phase=Applications\n",
+        desktop_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autostart_entry_is_limited_to_the_desktop() {
+        let entry = autostart_entry("KDE");
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=ins autostart\n"));
+        assert!(entry.contains("OnlyShowIn=KDE;\n"));
+    }
+}
@@ -0,0 +1,383 @@
+//! Restore the user's dotfiles, packages and game saves at the end of an install.
+//!
+//! Every part is optional and a failure only produces a warning, so a broken
+//! dotfile repository never costs the user a finished installation.
+
+use super::CommandRunner;
+use super::checkpoint::Checkpoints;
+use super::paths;
+use crate::arch::engine::{InstallContext, QuestionId};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+
+/// Where local restore sources are copied so they are reachable inside the chroot
+const RESTORE_DIR: &str = "/etc/instant/restore";
+
+/// Restore answers that may name a file on the installer's machine
+const LOCAL_SOURCES: [(QuestionId, &str); 2] = [
+    (QuestionId::RestoreDotfiles, "dotfiles.bundle"),
+    (QuestionId::RestorePackageList, "packages.txt"),
+];
+
+/// Whether `source` is fetched over the network rather than read from a file
+pub fn is_remote(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@")
+}
+
+/// Local file named by a restore answer, if the answer is one
+pub fn local_source<'a>(id: &QuestionId, answer: &'a str) -> Option<&'a Path> {
+    LOCAL_SOURCES
+        .iter()
+        .any(|(local_id, _)| local_id == id)
+        .then_some(answer)
+        .filter(|answer| !is_remote(answer))
+        .map(Path::new)
+}
+
+/// Game repository on a local drive, as opposed to a restic backend such as `sftp:`
+pub fn local_game_repo(answer: &str) -> Option<&Path> {
+    answer.starts_with('/').then(|| Path::new(answer))
+}
+
+/// File or directory on the installer's machine that a restore answer needs
+pub fn local_path<'a>(id: &QuestionId, answer: &'a str) -> Option<&'a Path> {
+    match id {
+        QuestionId::RestoreGameRepo => local_game_repo(answer),
+        _ => local_source(id, answer),
+    }
+}
+
+fn staged_path(id: &QuestionId) -> Option<String> {
+    LOCAL_SOURCES
+        .iter()
+        .find(|(local_id, _)| local_id == id)
+        .map(|(_, name)| format!("{}/{}", RESTORE_DIR, name))
+}
+
+/// Copy local restore sources into the target system before entering the chroot
+pub fn stage_into_chroot(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    for (id, _) in &LOCAL_SOURCES {
+        let Some(source) = context
+            .get_answer(id)
+            .and_then(|answer| local_source(id, answer))
+        else {
+            continue;
+        };
+        let Some(staged) = staged_path(id) else {
+            continue;
+        };
+        let target = paths::chroot_path(&staged);
+
+        if executor.dry_run() {
+            println!("[DRY RUN] cp {} {}", source.display(), target.display());
+            continue;
+        }
+        if !source.exists() {
+            // Reported as a warning by the restore itself
+            println!("Warning: {} does not exist, skipping it", source.display());
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::copy(source, &target).with_context(|| {
+            format!(
+                "Failed to copy {} to {}",
+                source.display(),
+                target.display()
+            )
+        })?;
+    }
+    mount_game_repo(context, executor)
+}
+
+fn is_mountpoint(path: &Path) -> bool {
+    Command::new("mountpoint")
+        .arg("-q")
+        .arg(path)
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Bind-mount a local game repository at the same path inside the chroot, so the
+/// repository path saved in the user's game config stays valid after the install
+fn mount_game_repo(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    let Some(repo) = context
+        .get_answer(&QuestionId::RestoreGameRepo)
+        .and_then(|answer| local_game_repo(answer))
+    else {
+        return Ok(());
+    };
+    if !executor.dry_run() && !repo.exists() {
+        // Reported as a warning by the restore itself
+        println!("Warning: {} does not exist, skipping it", repo.display());
+        return Ok(());
+    }
+    let target = paths::chroot_path(&repo.to_string_lossy());
+    if is_mountpoint(&target) {
+        return Ok(());
+    }
+    if !executor.dry_run() {
+        std::fs::create_dir_all(&target)
+            .with_context(|| format!("Failed to create {}", target.display()))?;
+    }
+    let mut cmd = Command::new("mount");
+    cmd.arg("--bind").arg(repo).arg(&target);
+    executor.run(&mut cmd)
+}
+
+/// Release the game repository mounted by [`stage_into_chroot`]
+pub fn unmount_from_chroot(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    let Some(repo) = context
+        .get_answer(&QuestionId::RestoreGameRepo)
+        .and_then(|answer| local_game_repo(answer))
+    else {
+        return Ok(());
+    };
+    let target = paths::chroot_path(&repo.to_string_lossy());
+    if !is_mountpoint(&target) {
+        return Ok(());
+    }
+    executor.run(Command::new("umount").arg(&target))
+}
+
+/// Path of a restore source inside the chroot
+fn chroot_source(id: &QuestionId, answer: &str) -> String {
+    if local_source(id, answer).is_some() {
+        staged_path(id).unwrap_or_else(|| answer.to_string())
+    } else {
+        answer.to_string()
+    }
+}
+
+/// Run every configured restore as `username`, recording failures as warnings
+pub fn restore_user_data(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    checkpoints: &Checkpoints,
+    username: &str,
+) {
+    if let Some(answer) = context.get_answer(&QuestionId::RestoreDotfiles) {
+        let source = chroot_source(&QuestionId::RestoreDotfiles, answer);
+        if let Err(e) = restore_dotfiles(&source, username, executor) {
+            checkpoints.warn(&format!(
+                "Dotfiles from {} were not restored: {:#}",
+                answer, e
+            ));
+        }
+    }
+
+    if let Some(answer) = context.get_answer(&QuestionId::RestorePackageList) {
+        let source = chroot_source(&QuestionId::RestorePackageList, answer);
+        match restore_packages(&source, executor) {
+            Ok(unavailable) if !unavailable.is_empty() => checkpoints.warn(&format!(
+                "Packages from {} not found in the repositories: {}",
+                answer,
+                unavailable.join(", ")
+            )),
+            Ok(_) => {}
+            Err(e) => checkpoints.warn(&format!(
+                "Packages from {} were not installed: {:#}",
+                answer, e
+            )),
+        }
+    }
+
+    if let Some(repo) = context.get_answer(&QuestionId::RestoreGameRepo) {
+        let password = context.get_answer(&QuestionId::RestoreGamePassword);
+        match restore_games(repo, password.map(String::as_str), username, executor) {
+            Ok(true) => {}
+            Ok(false) => checkpoints.warn(&format!(
+                "Game saves from {} were not restored because the install ran without a terminal. Run `ins game setup` after logging in.",
+                repo
+            )),
+            Err(e) => checkpoints.warn(&format!(
+                "Game saves from {} were not restored: {:#}",
+                repo, e
+            )),
+        }
+    }
+}
+
+fn run_as_user(args: &[&str], username: &str, executor: &dyn CommandRunner) -> Result<()> {
+    let mut cmd = Command::new("su");
+    cmd.arg("-c").arg(shell_words::join(args)).arg(username);
+    executor.run(&mut cmd)
+}
+
+fn restore_dotfiles(source: &str, username: &str, executor: &dyn CommandRunner) -> Result<()> {
+    println!("Restoring dotfiles from {} for {}...", source, username);
+    // Cloning applies the repository as well
+    run_as_user(&["ins", "dot", "repo", "clone", source], username, executor)
+}
+
+/// Install the packages listed in `source`, returning those no repository provides
+fn restore_packages(source: &str, executor: &dyn CommandRunner) -> Result<Vec<String>> {
+    println!("Installing packages from {}...", source);
+    if executor.dry_run() && !Path::new(source).exists() {
+        println!("[DRY RUN] Read {}", source);
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))?;
+    let requested = parse_package_list(&content);
+
+    // An offline install never synced the online repositories
+    executor.run(Command::new("pacman").arg("-Sy"))?;
+    let mut cmd = Command::new("pacman");
+    cmd.arg("-Slq");
+    let available: Option<HashSet<String>> = executor.run_with_output(&mut cmd)?.map(|output| {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    });
+
+    let (installable, unavailable): (Vec<String>, Vec<String>) = match available {
+        Some(available) => requested
+            .into_iter()
+            .partition(|package| available.contains(package)),
+        None => (requested, Vec::new()),
+    };
+
+    let package_refs: Vec<&str> = installable.iter().map(String::as_str).collect();
+    super::pacman::install(&package_refs, executor)?;
+    Ok(unavailable)
+}
+
+/// Package names from a list such as `pacman -Qqe` output, ignoring `#` comments
+fn parse_package_list(content: &str) -> Vec<String> {
+    let mut packages: Vec<String> = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect();
+    super::packages::dedup_preserve(&mut packages);
+    packages
+}
+
+/// Connect the user to their game save repository and restore the saves.
+///
+/// Returns false when the restore was skipped because `ins game setup`, which
+/// asks where each game's saves go, has no terminal to run in.
+fn restore_games(
+    repo: &str,
+    password: Option<&str>,
+    username: &str,
+    executor: &dyn CommandRunner,
+) -> Result<bool> {
+    // `ins game init` would create an empty repository in place of a missing one
+    if !executor.dry_run()
+        && let Some(path) = local_game_repo(repo)
+        && !path.exists()
+    {
+        anyhow::bail!("{} is not available on the installer's machine", repo);
+    }
+
+    println!(
+        "Setting up game save repository {} for {}...",
+        repo, username
+    );
+    let mut init = Command::new("su");
+    init.arg("-c")
+        .arg(shell_words::join(["ins", "game", "init", "--repo", repo]))
+        .arg(username);
+    // Passed through the environment so it stays out of the install log
+    if let Some(password) = password {
+        init.env(crate::game::cli::GAME_PASSWORD_ENV, password);
+    }
+    executor.run(&mut init)?;
+
+    if !std::io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    println!("Restoring game saves for {}...", username);
+    let mut setup = Command::new("su");
+    setup
+        .arg("-c")
+        .arg(shell_words::join(["ins", "game", "setup"]))
+        .arg(username);
+    if executor.dry_run() {
+        executor.run(&mut setup)?;
+        return Ok(true);
+    }
+    // Interactive, so it keeps the terminal instead of the logged pipes of `executor.run`
+    executor.log("RUN: su -c 'ins game setup' (interactive)");
+    let status = setup.status().context("Failed to run ins game setup")?;
+    if !status.success() {
+        anyhow::bail!("ins game setup failed with {}", status);
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::execution::mock::MockRunner;
+
+    #[test]
+    fn parses_package_lists_with_comments() {
+        let content = "# my packages\nfirefox\n\nneovim  # editor\nmpv htop\nfirefox\n";
+        assert_eq!(
+            parse_package_list(content),
+            vec!["firefox", "neovim", "mpv", "htop"]
+        );
+    }
+
+    #[test]
+    fn package_lists_are_resolved_against_synced_repos() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("packages.txt");
+        std::fs::write(&list, "firefox\nneovim\n").unwrap();
+        let executor = MockRunner::new();
+
+        let unavailable = restore_packages(&list.to_string_lossy(), &executor).unwrap();
+
+        assert!(unavailable.is_empty());
+        assert_eq!(
+            executor.command_log(),
+            [
+                "pacman -Sy",
+                "pacman -Slq",
+                "pacman -S --noconfirm --needed firefox neovim"
+            ]
+        );
+    }
+
+    #[test]
+    fn stages_only_local_sources() {
+        let url = "https://github.com/me/dotfiles";
+        assert_eq!(local_source(&QuestionId::RestoreDotfiles, url), None);
+        assert_eq!(chroot_source(&QuestionId::RestoreDotfiles, url), url);
+        assert_eq!(
+            chroot_source(&QuestionId::RestoreDotfiles, "/media/usb/dotfiles.bundle"),
+            "/etc/instant/restore/dotfiles.bundle"
+        );
+        assert_eq!(
+            local_source(&QuestionId::RestoreGameRepo, "/media/usb/games"),
+            None
+        );
+    }
+
+    #[test]
+    fn tells_local_game_repos_from_restic_backends() {
+        assert_eq!(
+            local_path(&QuestionId::RestoreGameRepo, "/media/usb/games"),
+            Some(Path::new("/media/usb/games"))
+        );
+        assert_eq!(
+            local_path(&QuestionId::RestoreGameRepo, "sftp:nas:/backups/games"),
+            None
+        );
+        assert_eq!(
+            local_path(&QuestionId::RestoreGameRepo, "rclone:drive:games"),
+            None
+        );
+    }
+}
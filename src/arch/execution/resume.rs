@@ -0,0 +1,342 @@
+//! Resuming an install that failed partway: restoring the mounts the Disk step set
+//! up, reacting to an edited questions file and reporting what already changed.
+
+use super::CommandRunner;
+use super::checkpoint::SubStep;
+use super::state::{Checkpoint, InstallState, MountRecord, answers_hash};
+use super::step::InstallStep;
+use crate::arch::config::DiskLayout;
+use crate::arch::engine::{InstallContext, QuestionId};
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Answers that decide the disk layout. Changing one after the Disk step would
+/// leave the rest of the install working on a layout it did not ask for.
+pub const DISK: SubStep = SubStep {
+    name: "disk",
+    questions: &[
+        QuestionId::Disk,
+        QuestionId::PartitioningMethod,
+        QuestionId::DiskLayout,
+        QuestionId::SecondDisk,
+        QuestionId::UseEncryption,
+        QuestionId::EncryptionPassword,
+        QuestionId::RootFilesystem,
+        QuestionId::BtrfsCompression,
+        QuestionId::BtrfsSnapshots,
+        QuestionId::Bootloader,
+        QuestionId::RootPartition,
+        QuestionId::BootPartition,
+        QuestionId::SwapPartition,
+        QuestionId::SwapType,
+        QuestionId::HomePartition,
+        QuestionId::DualBootPartition,
+        QuestionId::DualBootSize,
+    ],
+    changes: "Partitioned and formatted the disk and mounted it at /mnt",
+};
+
+const STEPS: [InstallStep; 6] = [
+    InstallStep::Disk,
+    InstallStep::Base,
+    InstallStep::Fstab,
+    InstallStep::Config,
+    InstallStep::Bootloader,
+    InstallStep::Post,
+];
+
+pub fn record_disk_checkpoint(context: &InstallContext, state: &mut InstallState) {
+    state.record_checkpoint(Checkpoint {
+        step: InstallStep::Disk,
+        name: DISK.name.to_string(),
+        questions: DISK.questions.to_vec(),
+        inputs: answers_hash(context, DISK.questions),
+        changes: DISK.changes.to_string(),
+    });
+}
+
+/// Record what the Disk step opened and mounted, for [`restore_mounts`]
+pub fn record_mounts(context: &InstallContext, state: &mut InstallState) -> Result<()> {
+    let layout = DiskLayout::from_context(context);
+    let mapping = format!("/dev/mapper/{}", layout.luks_mapping());
+    state.luks_device = if !std::path::Path::new(&mapping).exists() {
+        None
+    } else if layout == DiskLayout::LuksOnLvm {
+        Some("/dev/instantOS/root".to_string())
+    } else {
+        let disk = context
+            .get_answer(&QuestionId::Disk)
+            .context("Disk not selected")?;
+        Some(super::disk::get_part_path(disk, 2))
+    };
+
+    let output = Command::new("findmnt")
+        .args(["-R", "-n", "-r", "-o", "SOURCE,TARGET,FSTYPE,OPTIONS"])
+        .arg(super::paths::CHROOT_MOUNT)
+        .output()
+        .context("Failed to run findmnt")?;
+    state.mounts = parse_findmnt(&String::from_utf8_lossy(&output.stdout));
+
+    let output = Command::new("swapon")
+        .args(["--show=NAME", "--noheadings", "--raw"])
+        .output()
+        .context("Failed to run swapon")?;
+    state.swaps = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    Ok(())
+}
+
+/// Parse `findmnt -R -n -r -o SOURCE,TARGET,FSTYPE,OPTIONS` output
+fn parse_findmnt(output: &str) -> Vec<MountRecord> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().map(unescape_findmnt);
+            let source = fields.next()?;
+            // btrfs subvolumes are shown as `/dev/sda3[/@]`; the subvolume is in the options
+            let source = match source.find('[') {
+                Some(index) => source[..index].to_string(),
+                None => source,
+            };
+            Some(MountRecord {
+                source,
+                target: fields.next()?,
+                fstype: fields.next()?,
+                options: fields.next()?,
+            })
+        })
+        .collect()
+}
+
+/// Raw findmnt output escapes whitespace as `\x20`
+fn unescape_findmnt(field: &str) -> String {
+    field
+        .replace("\\x20", " ")
+        .replace("\\x09", "\t")
+        .replace("\\x0a", "\n")
+}
+
+/// Reopen LUKS, remount /mnt and reactivate swap as the Disk step left them
+pub fn restore_mounts(
+    context: &InstallContext,
+    state: &InstallState,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    if state.mounts.is_empty() {
+        anyhow::bail!(
+            "The previous attempt did not record its mounts. Mount the target at {} and run the remaining steps individually.",
+            super::paths::CHROOT_MOUNT
+        );
+    }
+
+    let layout = DiskLayout::from_context(context);
+    let mapping = layout.luks_mapping();
+    if let Some(device) = &state.luks_device
+        && !std::path::Path::new(&format!("/dev/mapper/{mapping}")).exists()
+    {
+        println!("Reopening LUKS container {}...", device);
+        let password = context
+            .get_answer(&QuestionId::EncryptionPassword)
+            .context("Encryption password not set")?;
+        if layout == DiskLayout::LuksOnLvm {
+            // The LUKS containers live inside the logical volumes
+            executor.run(Command::new("vgchange").args(["-ay", "instantOS"]))?;
+            for (volume, name) in [
+                (device.as_str(), mapping),
+                ("/dev/instantOS/home", super::disk::CRYPT_HOME),
+            ] {
+                let mut cmd = Command::new("cryptsetup");
+                cmd.arg("open").arg(volume).arg(name).arg("-");
+                executor.run_with_input(&mut cmd, password)?;
+            }
+        } else {
+            let mut cmd = Command::new("cryptsetup");
+            cmd.arg("open").arg(device).arg(mapping).arg("-");
+            executor.run_with_input(&mut cmd, password)?;
+            executor.run(Command::new("vgchange").args(["-ay", "instantOS"]))?;
+        }
+    } else if state.luks_device.is_none() && layout == DiskLayout::Lvm {
+        executor.run(Command::new("vgchange").args(["-ay", "instantOS"]))?;
+    }
+
+    println!("Remounting {}...", super::paths::CHROOT_MOUNT);
+    for mount in &state.mounts {
+        executor.run(Command::new("mount").args([
+            "--mkdir",
+            "-t",
+            &mount.fstype,
+            "-o",
+            &mount.options,
+            &mount.source,
+            &mount.target,
+        ]))?;
+    }
+
+    let active_swaps = std::fs::read_to_string("/proc/swaps").unwrap_or_default();
+    for swap in &state.swaps {
+        if !active_swaps
+            .lines()
+            .any(|line| line.starts_with(swap.as_str()))
+        {
+            executor.run(Command::new("swapon").arg(swap))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// What the completed steps and sub-steps changed, with rollback hints
+pub fn report(state: &InstallState) -> String {
+    let mut lines = Vec::new();
+    for step in STEPS {
+        let checkpoints: Vec<_> = state
+            .checkpoints
+            .iter()
+            .filter(|checkpoint| checkpoint.step == step)
+            .collect();
+        if state.is_complete(step) {
+            lines.push(format!("[done] {:?}: {}", step, step.rollback_hint()));
+        } else if !checkpoints.is_empty() {
+            lines.push(format!("[partial] {:?}:", step));
+        } else {
+            continue;
+        }
+        for checkpoint in checkpoints {
+            lines.push(format!("    {}: {}", checkpoint.name, checkpoint.changes));
+        }
+    }
+    if lines.is_empty() {
+        lines.push("Nothing has been changed yet.".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Compare the questions file with the previous attempt. Steps whose answers changed
+/// run again; a changed disk layout cannot be applied on top of the existing one.
+pub fn reconcile_answers(context: &InstallContext, state: &mut InstallState) -> Result<()> {
+    let changed = state.changed_answers(context);
+    if !changed.is_empty() {
+        println!(
+            "The questions file changed since the previous attempt: {}",
+            changed.join(", ")
+        );
+    }
+
+    for step in state.stale_steps(context) {
+        if step == InstallStep::Disk {
+            anyhow::bail!(
+                "Disk layout answers changed after the disk was prepared. {}",
+                InstallStep::Disk.rollback_hint()
+            );
+        }
+        println!("{:?} depends on changed answers and will run again.", step);
+        state.invalidate(step);
+    }
+
+    state.record_answers(context);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::execution::mock::MockRunner;
+
+    #[test]
+    fn parses_findmnt_and_restores_mounts() {
+        let mounts = parse_findmnt(
+            "/dev/mapper/instantOS-root[/@] /mnt btrfs rw,noatime,subvol=/@\n\
+             /dev/nvme0n1p1 /mnt/boot vfat rw,relatime,fmask=0022\n\
+             /dev/sdb1 /mnt/home/My\\x20Files ext4 rw\n",
+        );
+        assert_eq!(mounts.len(), 3);
+        assert_eq!(mounts[0].source, "/dev/mapper/instantOS-root");
+        assert_eq!(mounts[0].options, "rw,noatime,subvol=/@");
+        assert_eq!(mounts[2].target, "/mnt/home/My Files");
+
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::EncryptionPassword, "secret".to_string());
+        let mut state = InstallState::new();
+        state.mounts = mounts;
+        state.luks_device = Some("/dev/nvme0n1p2".to_string());
+        state.swaps = vec!["/dev/mapper/instantOS-swap".to_string()];
+
+        let mock = MockRunner::new();
+        restore_mounts(&context, &state, &mock).unwrap();
+        let log = mock.command_log();
+        assert_eq!(
+            log[0],
+            "cryptsetup open /dev/nvme0n1p2 cryptlvm - <<< 'secret'"
+        );
+        assert_eq!(log[1], "vgchange -ay instantOS");
+        assert_eq!(
+            log[2],
+            "mount --mkdir -t btrfs -o rw,noatime,subvol=/@ /dev/mapper/instantOS-root /mnt"
+        );
+        assert_eq!(
+            log[3],
+            "mount --mkdir -t vfat -o rw,relatime,fmask=0022 /dev/nvme0n1p1 /mnt/boot"
+        );
+        assert_eq!(log.last().unwrap(), "swapon /dev/mapper/instantOS-swap");
+
+        assert!(restore_mounts(&context, &InstallState::new(), &mock).is_err());
+    }
+
+    #[test]
+    fn reopens_luks_on_lvm_volumes_after_activating_the_volume_group() {
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::EncryptionPassword, "secret".to_string());
+        context.set_answer(QuestionId::DiskLayout, "luks-on-lvm".to_string());
+        let mut state = InstallState::new();
+        state.mounts = parse_findmnt("/dev/mapper/cryptroot /mnt ext4 rw\n");
+        state.luks_device = Some("/dev/instantOS/root".to_string());
+
+        let mock = MockRunner::new();
+        restore_mounts(&context, &state, &mock).unwrap();
+        assert_eq!(
+            mock.command_log()[..3],
+            [
+                "vgchange -ay instantOS",
+                "cryptsetup open /dev/instantOS/root cryptroot - <<< 'secret'",
+                "cryptsetup open /dev/instantOS/home crypthome - <<< 'secret'",
+            ]
+        );
+    }
+
+    #[test]
+    fn reconcile_reruns_changed_steps_and_refuses_disk_changes() {
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::Disk, "/dev/sda".to_string());
+        context.set_answer(QuestionId::Hostname, "box".to_string());
+
+        let mut state = InstallState::new();
+        record_disk_checkpoint(&context, &mut state);
+        state.mark_complete(InstallStep::Disk);
+        state.mark_complete(InstallStep::Config);
+        state.record_checkpoint(Checkpoint {
+            step: InstallStep::Config,
+            name: "network".to_string(),
+            questions: vec![QuestionId::Hostname],
+            inputs: answers_hash(&context, &[QuestionId::Hostname]),
+            changes: "Wrote /etc/hostname".to_string(),
+        });
+        reconcile_answers(&context, &mut state).unwrap();
+
+        let summary = report(&state);
+        assert!(summary.starts_with("[done] Disk: "));
+        assert!(summary.contains("    network: Wrote /etc/hostname"));
+
+        context.set_answer(QuestionId::Hostname, "other".to_string());
+        reconcile_answers(&context, &mut state).unwrap();
+        assert!(!state.is_complete(InstallStep::Config));
+        assert!(state.is_complete(InstallStep::Disk));
+        assert!(report(&state).contains("[partial] Config:"));
+
+        context.set_answer(QuestionId::Disk, "/dev/sdb".to_string());
+        assert!(reconcile_answers(&context, &mut state).is_err());
+    }
+}
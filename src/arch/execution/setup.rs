@@ -86,6 +86,8 @@ pub async fn setup_instantos(
 pub async fn setup_instant_repo(executor: &dyn CommandRunner) -> Result<()> {
     println!("Setting up instantOS repository...");
     crate::common::pacman::setup_instant_repo(executor.dry_run()).await?;
+    // An offline install keeps [instant] disabled until the end of the Post step
+    crate::arch::offline::refresh(executor)?;
 
     // Update repositories to include [instant]
     println!("Updating repositories...");
@@ -237,6 +239,9 @@ fn enable_services(executor: &dyn CommandRunner, context: &InstallContext) -> Re
                     configure_lightdm_autologin(context, executor)?;
                 }
             }
+            crate::arch::config::DisplayManager::Sddm => {
+                configure_sddm(context, executor)?;
+            }
         }
     } else if other_dm_enabled {
         println!(
@@ -419,6 +424,68 @@ fn configure_lightdm_autologin(
     Ok(())
 }
 
+/// Write the instantOS SDDM drop-in with the greeter theme and optional autologin.
+///
+/// SDDM reads `/etc/sddm.conf.d/` in order, so a drop-in avoids editing a
+/// packaged config file.
+fn configure_sddm(context: &InstallContext, executor: &dyn CommandRunner) -> Result<()> {
+    let desktop = crate::arch::config::DesktopEnvironment::from_context(context);
+    let autologin = if context.get_answer_bool(QuestionId::Autologin) {
+        let username = context
+            .get_answer(&QuestionId::Username)
+            .context("Username not set for SDDM autologin")?;
+        desktop
+            .session_name()
+            .map(|session_name| (username.as_str(), session_name))
+    } else {
+        None
+    };
+
+    println!("Configuring SDDM...");
+
+    let config_path = "/etc/sddm.conf.d/10-instantos.conf";
+    if executor.dry_run() {
+        println!("[DRY RUN] Write {}", config_path);
+        if let Some((username, session_name)) = autologin {
+            println!(
+                "[DRY RUN] Enable SDDM autologin for user {} into {}",
+                username, session_name
+            );
+        }
+        return Ok(());
+    }
+
+    // The breeze greeter ships with Plasma; other desktops keep SDDM's default
+    let theme = (desktop == crate::arch::config::DesktopEnvironment::Plasma).then_some("breeze");
+    std::fs::create_dir_all("/etc/sddm.conf.d")?;
+    std::fs::write(config_path, sddm_conf(theme, autologin))?;
+    println!("Created {}", config_path);
+
+    Ok(())
+}
+
+fn sddm_conf(theme: Option<&str>, autologin: Option<(&str, &str)>) -> String {
+    let mut lines = Vec::new();
+
+    if let Some(theme) = theme {
+        lines.push("[Theme]".to_string());
+        lines.push(format!("Current={}", theme));
+    }
+
+    if let Some((username, session_name)) = autologin {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push("[Autologin]".to_string());
+        lines.push(format!("User={}", username));
+        lines.push(format!("Session={}", session_name));
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
 fn update_lightdm_conf_session(content: &str, session_name: &str) -> String {
     let mut new_lines = Vec::new();
 
@@ -720,6 +787,18 @@ autologin-session=niri
         assert_eq!(result.trim(), expected.trim());
     }
 
+    #[test]
+    fn test_sddm_conf() {
+        assert_eq!(
+            sddm_conf(Some("breeze"), Some(("testuser", "plasma"))),
+            "[Theme]\nCurrent=breeze\n\n[Autologin]\nUser=testuser\nSession=plasma\n"
+        );
+        assert_eq!(
+            sddm_conf(None, Some(("testuser", "sway"))),
+            "[Autologin]\nUser=testuser\nSession=sway\n"
+        );
+    }
+
     #[test]
     fn test_update_gdm_conf_autologin() {
         let input = r#"
@@ -1,9 +1,14 @@
 use super::step::InstallStep;
+use crate::arch::engine::{InstallContext, QuestionId};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 use std::path::Path;
+use std::sync::OnceLock;
 
 use super::paths;
 
@@ -11,6 +16,131 @@ use super::paths;
 pub struct InstallState {
     pub completed_steps: HashSet<InstallStep>,
     pub start_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// Hash of every answer in the questions file of the last attempt, keyed by question
+    #[serde(default)]
+    pub answer_hashes: BTreeMap<String, String>,
+    /// LUKS partition opened as `cryptlvm` by the Disk step
+    #[serde(default)]
+    pub luks_device: Option<String>,
+    /// Swap devices activated by the Disk step
+    #[serde(default)]
+    pub swaps: Vec<String>,
+    /// Filesystems mounted under /mnt by the Disk step, parents first
+    #[serde(default)]
+    pub mounts: Vec<MountRecord>,
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Non-fatal problems, shown on the finished screen
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// A completed sub-step of an install step
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Checkpoint {
+    pub step: InstallStep,
+    pub name: String,
+    /// Questions the sub-step reads; see [`answers_hash`]
+    pub questions: Vec<QuestionId>,
+    pub inputs: String,
+    /// What the sub-step changed, shown in the resume report
+    pub changes: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MountRecord {
+    pub source: String,
+    pub target: String,
+    pub fstype: String,
+    pub options: String,
+}
+
+/// Answers that are only hashed together with [`answer_key`]
+const SENSITIVE_ANSWERS: [QuestionId; 3] = [
+    QuestionId::Password,
+    QuestionId::EncryptionPassword,
+    QuestionId::RestoreGamePassword,
+];
+
+/// Hands the answer key to steps running inside the chroot
+pub const ANSWER_KEY_ENV: &str = "INSTANTOS_ANSWER_KEY";
+
+/// Random per-install secret mixed into the hashes of passwords.
+///
+/// The state file ends up on the target, so plain password hashes in it could be
+/// cracked offline. The key stays on the live system in [`paths::ANSWER_KEY_FILE`]
+/// and reaches chroot steps through [`ANSWER_KEY_ENV`].
+pub fn answer_key() -> &'static str {
+    static KEY: OnceLock<String> = OnceLock::new();
+    KEY.get_or_init(|| {
+        if let Ok(key) = std::env::var(ANSWER_KEY_ENV)
+            && !key.is_empty()
+        {
+            return key;
+        }
+        if let Ok(key) = fs::read_to_string(paths::ANSWER_KEY_FILE)
+            && !key.trim().is_empty()
+        {
+            return key.trim().to_string();
+        }
+        let key = hex::encode(rand::random::<[u8; 32]>());
+        // Inside the chroot the file would land on the target
+        if !super::is_chroot()
+            && let Err(e) = write_private(Path::new(paths::ANSWER_KEY_FILE), &key)
+        {
+            println!("Warning: Failed to save answer key: {}", e);
+        }
+        key
+    })
+}
+
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn hashed_answer(id: &QuestionId, answer: &str, key: impl FnOnce() -> &'static str) -> String {
+    if answer.is_empty() || !SENSITIVE_ANSWERS.contains(id) {
+        return answer.to_string();
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(key().as_bytes());
+    hasher.update(b"\0");
+    hasher.update(answer.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Hash of the answers to `questions`, so a checkpoint reruns when one of them changes
+pub fn answers_hash(context: &InstallContext, questions: &[QuestionId]) -> String {
+    let mut hasher = Sha256::new();
+    for id in questions {
+        let answer = context.get_answer(id).map(|s| s.as_str()).unwrap_or("");
+        let answer = hashed_answer(id, answer, answer_key);
+        hasher.update(format!("{id:?}={answer}\n").as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn answer_hashes(context: &InstallContext) -> BTreeMap<String, String> {
+    context
+        .answers
+        .keys()
+        .map(|id| {
+            (
+                format!("{id:?}"),
+                answers_hash(context, std::slice::from_ref(id)),
+            )
+        })
+        .collect()
 }
 
 impl InstallState {
@@ -25,8 +155,12 @@ impl InstallState {
     }
 
     pub fn load() -> Result<Self> {
-        if Path::new(paths::STATE_FILE).exists() {
-            let content = fs::read_to_string(paths::STATE_FILE)?;
+        Self::load_from(Path::new(paths::STATE_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let content = fs::read_to_string(path)?;
             let state: InstallState = toml::from_str(&content)?;
             Ok(state)
         } else {
@@ -35,13 +169,19 @@ impl InstallState {
     }
 
     pub fn save(&self) -> Result<()> {
+        self.save_to(Path::new(paths::STATE_FILE))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self)?;
-        if let Some(parent) = Path::new(paths::STATE_FILE).parent()
+        if let Some(parent) = path.parent()
             && !parent.exists()
         {
             fs::create_dir_all(parent)?;
         }
-        fs::write(paths::STATE_FILE, content)?;
+        fs::write(path, content)?;
+        // Readable by root only: it records which devices and answers were used
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
         Ok(())
     }
 
@@ -66,4 +206,163 @@ impl InstallState {
             Err(missing)
         }
     }
+
+    pub fn checkpoint(&self, step: InstallStep, name: &str) -> Option<&Checkpoint> {
+        self.checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.step == step && checkpoint.name == name)
+    }
+
+    pub fn record_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.checkpoints
+            .retain(|c| !(c.step == checkpoint.step && c.name == checkpoint.name));
+        self.checkpoints.push(checkpoint);
+    }
+
+    pub fn record_warning(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Take over checkpoints and warnings saved by a step that ran inside the chroot
+    pub fn merge_checkpoints(&mut self, other: &InstallState) {
+        for checkpoint in &other.checkpoints {
+            self.record_checkpoint(checkpoint.clone());
+        }
+        for warning in &other.warnings {
+            self.record_warning(warning.clone());
+        }
+    }
+
+    /// Questions whose answers differ from the previous attempt, sorted by name
+    pub fn changed_answers(&self, context: &InstallContext) -> Vec<String> {
+        if self.answer_hashes.is_empty() {
+            return Vec::new();
+        }
+        let current = answer_hashes(context);
+        let mut changed: Vec<String> = current
+            .iter()
+            .filter(|(id, hash)| self.answer_hashes.get(*id) != Some(*hash))
+            .map(|(id, _)| id.clone())
+            .chain(
+                self.answer_hashes
+                    .keys()
+                    .filter(|id| !current.contains_key(*id))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+        changed
+    }
+
+    pub fn record_answers(&mut self, context: &InstallContext) {
+        self.answer_hashes = answer_hashes(context);
+    }
+
+    /// Completed steps with a checkpoint whose answers changed since it ran
+    pub fn stale_steps(&self, context: &InstallContext) -> Vec<InstallStep> {
+        let mut stale = Vec::new();
+        for checkpoint in &self.checkpoints {
+            if self.is_complete(checkpoint.step)
+                && answers_hash(context, &checkpoint.questions) != checkpoint.inputs
+                && !stale.contains(&checkpoint.step)
+            {
+                stale.push(checkpoint.step);
+            }
+        }
+        stale
+    }
+
+    /// Forget a step so it runs again; its checkpoints decide which sub-steps are redone
+    pub fn invalidate(&mut self, step: InstallStep) {
+        self.completed_steps.remove(&step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(context: &InstallContext, step: InstallStep, name: &str) -> Checkpoint {
+        let questions = vec![QuestionId::Hostname];
+        Checkpoint {
+            step,
+            name: name.to_string(),
+            inputs: answers_hash(context, &questions),
+            questions,
+            changes: "Wrote /etc/hostname".to_string(),
+        }
+    }
+
+    #[test]
+    fn detects_changed_answers_and_stale_steps() {
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::Hostname, "old".to_string());
+        context.set_answer(QuestionId::Username, "alice".to_string());
+
+        let mut state = InstallState::new();
+        assert!(state.changed_answers(&context).is_empty());
+        state.record_answers(&context);
+        state.mark_complete(InstallStep::Config);
+        state.record_checkpoint(checkpoint(&context, InstallStep::Config, "network"));
+        state.record_checkpoint(checkpoint(&context, InstallStep::Config, "network"));
+        assert_eq!(state.checkpoints.len(), 1);
+        assert!(state.stale_steps(&context).is_empty());
+
+        context.set_answer(QuestionId::Hostname, "new".to_string());
+        context.answers.remove(&QuestionId::Username);
+        context.set_answer(QuestionId::Keymap, "de".to_string());
+        assert_eq!(
+            state.changed_answers(&context),
+            ["Hostname", "Keymap", "Username"]
+        );
+        assert_eq!(state.stale_steps(&context), [InstallStep::Config]);
+
+        // Round-trips through the state file format
+        let saved = toml::to_string_pretty(&state).unwrap();
+        let loaded: InstallState = toml::from_str(&saved).unwrap();
+        assert_eq!(loaded.checkpoints, state.checkpoints);
+        assert_eq!(loaded.answer_hashes, state.answer_hashes);
+
+        // State files written before checkpoints existed still load
+        let legacy: InstallState = toml::from_str("completed_steps = [\"disk\"]\n").unwrap();
+        assert!(legacy.is_complete(InstallStep::Disk));
+        assert!(legacy.checkpoints.is_empty());
+    }
+
+    #[test]
+    fn hashes_passwords_only_with_the_answer_key() {
+        let key_a = || "key-a";
+        let key_b = || "key-b";
+        let plain = hex::encode(Sha256::digest(b"hunter2"));
+
+        let hashed = hashed_answer(&QuestionId::Password, "hunter2", key_a);
+        assert_ne!(hashed, plain);
+        assert_ne!(hashed, "hunter2");
+        assert_eq!(
+            hashed,
+            hashed_answer(&QuestionId::Password, "hunter2", key_a)
+        );
+        assert_ne!(
+            hashed,
+            hashed_answer(&QuestionId::Password, "hunter2", key_b)
+        );
+        assert_eq!(
+            hashed_answer(&QuestionId::Hostname, "box", || unreachable!()),
+            "box"
+        );
+    }
+
+    #[test]
+    fn merges_warnings_from_the_chroot_once() {
+        let mut chroot = InstallState::new();
+        chroot.record_warning("Dotfiles were not restored".to_string());
+        chroot.record_warning("Dotfiles were not restored".to_string());
+
+        let mut host = InstallState::new();
+        host.merge_checkpoints(&chroot);
+        host.merge_checkpoints(&chroot);
+        assert_eq!(host.warnings, ["Dotfiles were not restored"]);
+    }
 }
@@ -39,4 +39,26 @@ impl InstallStep {
             InstallStep::Post => vec![InstallStep::Bootloader],
         }
     }
+
+    /// How to undo or redo the step by hand when an install is abandoned after it
+    pub fn rollback_hint(&self) -> &'static str {
+        match self {
+            InstallStep::Disk => {
+                "The selected disk was repartitioned and formatted. To start over, run `umount -R /mnt`, `swapoff -a` and `cryptsetup close cryptlvm` (encrypted installs), then delete the install state file."
+            }
+            InstallStep::Base => {
+                "The base system was installed into /mnt and the live system's mirrorlist was replaced. Rerunning pacstrap is safe."
+            }
+            InstallStep::Fstab => "/mnt/etc/fstab was generated. Rerunning the step rewrites it.",
+            InstallStep::Config => {
+                "Packages, locale, users and the initramfs were configured on the target. Each part is safe to rerun."
+            }
+            InstallStep::Bootloader => {
+                "A bootloader was installed and may have added a firmware boot entry (see `efibootmgr`)."
+            }
+            InstallStep::Post => {
+                "instantOS was set up for the user. Rerunning the step reapplies the setup."
+            }
+        }
+    }
 }
@@ -0,0 +1,294 @@
+//! Swap other than the swap partition (zram and swap files) and the kernel
+//! parameters for resuming from hibernation.
+
+use super::CommandRunner;
+use crate::arch::config::{DiskLayout, RootFilesystem, SwapType, hibernation_enabled};
+use crate::arch::engine::{InstallContext, QuestionId};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Where the `@swap` subvolume is mounted on btrfs installs
+pub const SWAP_MOUNT_POINT: &str = "/swap";
+
+/// zram device sized at half the RAM, capped at 8 GiB
+const ZRAM_GENERATOR_CONFIG: &str = "[zram0]\n\
+     zram-size = min(ram / 2, 8192)\n\
+     compression-algorithm = zstd\n";
+
+/// Swap file location in the installed system
+pub fn swapfile_path(context: &InstallContext) -> &'static str {
+    if RootFilesystem::from_context(context).is_btrfs() {
+        "/swap/swapfile"
+    } else {
+        "/swapfile"
+    }
+}
+
+/// Packages for the selected swap type
+pub fn swap_package_list(context: &InstallContext) -> Vec<String> {
+    match SwapType::from_context(context) {
+        SwapType::Zram => vec!["zram-generator".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Create and enable the swap file under `/mnt`, so genfstab picks it up.
+///
+/// On btrfs the file must not be copy-on-write; `btrfs filesystem mkswapfile`
+/// creates it that way inside the `@swap` subvolume, which is also marked
+/// no-copy-on-write.
+pub fn create_swapfile(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    size_gb: u64,
+) -> Result<()> {
+    let path = super::paths::chroot_path(swapfile_path(context));
+    let path = path.to_string_lossy();
+    println!("Creating {} GiB swap file {}...", size_gb, path);
+
+    if RootFilesystem::from_context(context).is_btrfs() {
+        let directory = super::paths::chroot_path(SWAP_MOUNT_POINT);
+        executor.run(Command::new("chattr").arg("+C").arg(&directory))?;
+        executor.run(Command::new("btrfs").args([
+            "filesystem",
+            "mkswapfile",
+            "--size",
+            &format!("{size_gb}g"),
+            &path,
+        ]))?;
+    } else {
+        executor.run(Command::new("fallocate").args(["-l", &format!("{size_gb}G"), &path]))?;
+        executor.run(Command::new("chmod").args(["600", &path]))?;
+        executor.run(Command::new("mkswap").arg(path.as_ref()))?;
+    }
+
+    executor.run(Command::new("swapon").arg(path.as_ref()))?;
+    Ok(())
+}
+
+/// Write the zram-generator config when zram was selected
+pub fn configure_zram(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    root: &Path,
+) -> Result<()> {
+    if SwapType::from_context(context) != SwapType::Zram {
+        return Ok(());
+    }
+
+    println!("Configuring zram swap...");
+    let config = root.join("etc/systemd/zram-generator.conf");
+    if executor.dry_run() {
+        println!("[DRY RUN] Writing {}", config.display());
+        return Ok(());
+    }
+
+    if let Some(parent) = config.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&config, ZRAM_GENERATOR_CONFIG)
+        .with_context(|| format!("Failed to write {}", config.display()))?;
+    executor.log(&format!("Wrote {}", config.display()));
+    Ok(())
+}
+
+/// `resume=` (and `resume_offset=` for swap files) kernel parameters, or `None`
+/// when hibernation is not set up. Runs inside the installed system, rooted at `root`.
+pub fn resume_params(
+    context: &InstallContext,
+    executor: &dyn CommandRunner,
+    root: &Path,
+) -> Result<Option<String>> {
+    if !hibernation_enabled(context) {
+        return Ok(None);
+    }
+
+    let layout = DiskLayout::from_context(context);
+    let encrypted = context.get_answer_bool(QuestionId::UseEncryption);
+
+    match SwapType::from_context(context) {
+        SwapType::Partition if layout.uses_lvm(encrypted) => {
+            Ok(Some("resume=/dev/mapper/instantOS-swap".to_string()))
+        }
+        SwapType::Partition => {
+            if executor.dry_run() {
+                return Ok(Some("resume=UUID=<swap-uuid>".to_string()));
+            }
+            let fstab = std::fs::read_to_string(root.join("etc/fstab"))
+                .context("Failed to read /etc/fstab")?;
+            let device = parse_fstab_swap(&fstab).context("No swap partition in /etc/fstab")?;
+            Ok(Some(format!("resume={device}")))
+        }
+        SwapType::Swapfile => {
+            let path = swapfile_path(context);
+            let offset = if RootFilesystem::from_context(context).is_btrfs() {
+                command_output(
+                    executor,
+                    Command::new("btrfs").args(["inspect-internal", "map-swapfile", "-r", path]),
+                )?
+                .map(|output| output.trim().to_string())
+            } else {
+                command_output(executor, Command::new("filefrag").args(["-v", path]))?
+                    .map(|output| {
+                        parse_filefrag_offset(&output)
+                            .map(|offset| offset.to_string())
+                            .context("Could not read the swap file offset from filefrag")
+                    })
+                    .transpose()?
+            };
+            let uuid = command_output(
+                executor,
+                Command::new("findmnt").args(["-n", "-o", "UUID", "/"]),
+            )?
+            .map(|output| output.trim().to_string());
+
+            Ok(Some(format!(
+                "resume=UUID={} resume_offset={}",
+                uuid.as_deref().unwrap_or("<root-uuid>"),
+                offset.as_deref().unwrap_or("<swapfile-offset>")
+            )))
+        }
+        SwapType::None | SwapType::Zram => Ok(None),
+    }
+}
+
+/// Standard output of `command`, or `None` in dry-run mode
+fn command_output(executor: &dyn CommandRunner, command: &mut Command) -> Result<Option<String>> {
+    let Some(output) = executor.run_with_output(command)? else {
+        return Ok(None);
+    };
+    if !output.status.success() {
+        anyhow::bail!(
+            "{:?} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+/// Device of the first swap entry in fstab (`UUID=...` as written by genfstab -U)
+fn parse_fstab_swap(fstab: &str) -> Option<String> {
+    fstab
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            (fields.len() >= 3 && fields[2] == "swap").then(|| fields[0].to_string())
+        })
+}
+
+/// Physical offset (in filesystem blocks) of the first extent in `filefrag -v` output
+fn parse_filefrag_offset(output: &str) -> Option<u64> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split(':').map(str::trim);
+        if fields.next()? != "0" {
+            return None;
+        }
+        let _logical = fields.next()?;
+        fields.next()?.split("..").next()?.trim().parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::execution::mock::MockRunner;
+
+    #[test]
+    fn parses_fstab_swap_and_filefrag_offset() {
+        let fstab = "# /dev/sda3\n\
+                     UUID=aaaa / ext4 rw,relatime 0 1\n\
+                     # UUID=old none swap defaults 0 0\n\
+                     UUID=bbbb none swap defaults 0 0\n";
+        assert_eq!(parse_fstab_swap(fstab).as_deref(), Some("UUID=bbbb"));
+        assert_eq!(parse_fstab_swap("UUID=aaaa / ext4 rw 0 1\n"), None);
+
+        let filefrag = "Filesystem type is: ef53\n\
+                        File size of /swapfile is 4294967296 (1048576 blocks of 4096 bytes)\n \
+                        ext:     logical_offset:        physical_offset: length:   expected: flags:\n   \
+                        0:        0..    2047:      34816..     36863:   2048:\n   \
+                        1:     2048..    4095:      38912..     40959:   2048:      36864:\n";
+        assert_eq!(parse_filefrag_offset(filefrag), Some(34816));
+    }
+
+    #[test]
+    fn creates_btrfs_swapfile_and_resume_params() {
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::RootFilesystem, "btrfs".to_string());
+        context.set_answer(QuestionId::SwapType, "swapfile".to_string());
+        context.set_answer(QuestionId::Hibernation, "yes".to_string());
+
+        let mock = MockRunner::new();
+        create_swapfile(&context, &mock, 8).unwrap();
+        assert_eq!(
+            mock.command_log(),
+            [
+                "chattr +C /mnt/swap",
+                "btrfs filesystem mkswapfile --size 8g /mnt/swap/swapfile",
+                "swapon /mnt/swap/swapfile",
+            ]
+        );
+
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(
+            resume_params(&context, &mock, root.path())
+                .unwrap()
+                .unwrap(),
+            "resume=UUID=<root-uuid> resume_offset=<swapfile-offset>"
+        );
+
+        context.set_answer(QuestionId::Hibernation, "no".to_string());
+        assert_eq!(resume_params(&context, &mock, root.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn resume_from_swap_partition_or_volume() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("etc")).unwrap();
+        std::fs::write(
+            root.path().join("etc/fstab"),
+            "UUID=cccc none swap defaults 0 0\n",
+        )
+        .unwrap();
+        let mut context = InstallContext::new();
+        context.set_answer(QuestionId::Hibernation, "yes".to_string());
+        let mock = MockRunner::new();
+
+        assert_eq!(
+            resume_params(&context, &mock, root.path())
+                .unwrap()
+                .unwrap(),
+            "resume=UUID=cccc"
+        );
+
+        context.set_answer(QuestionId::UseEncryption, "yes".to_string());
+        assert_eq!(
+            resume_params(&context, &mock, root.path())
+                .unwrap()
+                .unwrap(),
+            "resume=/dev/mapper/instantOS-swap"
+        );
+    }
+
+    #[test]
+    fn writes_zram_generator_config() {
+        let root = tempfile::tempdir().unwrap();
+        let mut context = InstallContext::new();
+        let mock = MockRunner::new();
+
+        configure_zram(&context, &mock, root.path()).unwrap();
+        let config = root.path().join("etc/systemd/zram-generator.conf");
+        assert!(!config.exists());
+
+        context.set_answer(QuestionId::SwapType, "zram".to_string());
+        configure_zram(&context, &mock, root.path()).unwrap();
+        assert!(
+            std::fs::read_to_string(config)
+                .unwrap()
+                .starts_with("[zram0]\nzram-size = min(ram / 2, 8192)\n")
+        );
+    }
+}
@@ -198,6 +198,16 @@ impl MkinitcpioConfig {
             }
         }
     }
+
+    /// Place storage hooks between `block` and `filesystems`, each after the
+    /// previous one, so the initramfs assembles the layers in `layers` order
+    pub fn ensure_storage_hooks(&mut self, layers: &[&str]) {
+        let mut after = "block";
+        for hook in layers {
+            self.ensure_hook_position(hook, &[after], &["filesystems"]);
+            after = hook;
+        }
+    }
 }
 
 impl std::fmt::Display for MkinitcpioConfig {
@@ -497,4 +507,43 @@ mod tests {
         assert!(idx_encrypt > idx_block);
         assert!(idx_encrypt < idx_fs);
     }
+
+    #[test]
+    fn test_ensure_storage_hooks_orders_layers() {
+        let mut config =
+            MkinitcpioConfig::parse("HOOKS=(base systemd block sd-encrypt lvm2 filesystems)")
+                .unwrap();
+        config.ensure_storage_hooks(&["lvm2", "sd-encrypt"]);
+        assert_eq!(
+            config.hooks,
+            [
+                "base",
+                "systemd",
+                "block",
+                "lvm2",
+                "sd-encrypt",
+                "filesystems"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ensure_storage_hooks_adds_missing_hooks() {
+        let mut config =
+            MkinitcpioConfig::parse("HOOKS=(base systemd block filesystems fsck)").unwrap();
+        config.ensure_storage_hooks(&["sd-encrypt", "lvm2", "resume"]);
+        assert_eq!(
+            config.hooks,
+            [
+                "base",
+                "systemd",
+                "block",
+                "sd-encrypt",
+                "lvm2",
+                "resume",
+                "filesystems",
+                "fsck"
+            ]
+        );
+    }
 }
@@ -0,0 +1,585 @@
+//! Local package repositories for installing without network access.
+//!
+//! `ins arch cache build` downloads every package an answer file needs into an
+//! `instantos-repo` directory (on the ISO or a USB stick). The installer puts that
+//! repository in front of the online ones, and when it holds every package the
+//! install needs, disables the online repositories so no step touches the network.
+
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::arch::engine::InstallContext;
+use crate::arch::execution::{CommandRunner, paths};
+
+/// Name of the pacman repository, and of its database file
+pub const REPO_NAME: &str = "instantos-offline";
+
+/// Directory name searched for on the ISO and removable media
+pub const REPO_DIR_NAME: &str = "instantos-repo";
+
+/// Members of the package groups the install names, as `group package` lines of
+/// `pacman -Sg`; the package database only knows package names
+const GROUPS_FILE: &str = "instantos-offline.groups";
+
+/// Where the repository is bind-mounted for the steps run inside the chroot
+pub const CHROOT_REPO_DIR: &str = "/var/cache/instantos-offline";
+
+/// Places a local repository is looked for on the live system
+const SEARCH_PATTERNS: &[&str] = &[
+    "/run/archiso/bootmnt/instantos-repo",
+    "/run/media/*/*/instantos-repo",
+    "/media/*/instantos-repo",
+    "/mnt/usb/instantos-repo",
+];
+
+const BEGIN_MARKER: &str = "# BEGIN instantOS offline repository";
+const END_MARKER: &str = "# END instantOS offline repository";
+const OFFLINE_ONLY_MARKER: &str = "# offline only";
+/// Prefix of lines in repository sections disabled for an offline install
+const DISABLED_PREFIX: &str = "#instantos-offline# ";
+
+/// A directory of packages with a `repo-add` database
+#[derive(Debug, Clone)]
+pub struct LocalRepo {
+    pub path: PathBuf,
+    packages: HashSet<String>,
+    groups: HashMap<String, Vec<String>>,
+}
+
+impl LocalRepo {
+    /// Open the repository in `path`, if it has a database
+    pub fn open(path: &Path) -> Option<Self> {
+        let has_db = ["db", "db.tar.gz", "db.tar.zst"]
+            .iter()
+            .any(|extension| path.join(format!("{REPO_NAME}.{extension}")).exists());
+        if !has_db {
+            return None;
+        }
+
+        let packages = std::fs::read_dir(path)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                package_name(&entry.file_name().to_string_lossy()).map(str::to_string)
+            })
+            .collect();
+        let groups = std::fs::read_to_string(path.join(GROUPS_FILE))
+            .map(|content| parse_groups(&content))
+            .unwrap_or_default();
+        Some(Self {
+            path: path.to_path_buf(),
+            packages,
+            groups,
+        })
+    }
+
+    /// Find a local repository: the bind mount inside the chroot, otherwise the
+    /// first one on the ISO or mounted media
+    pub fn detect() -> Option<Self> {
+        if crate::arch::execution::is_chroot() {
+            return Self::open(Path::new(CHROOT_REPO_DIR));
+        }
+
+        SEARCH_PATTERNS
+            .iter()
+            .filter_map(|pattern| glob::glob(pattern).ok())
+            .flatten()
+            .filter_map(|path| path.ok())
+            .find_map(|path| Self::open(&path))
+    }
+
+    /// Packages of `packages` the repository does not contain.
+    ///
+    /// Only the named packages are checked; a repository made by
+    /// `ins arch cache build` also holds all of their dependencies.
+    pub fn missing(&self, packages: &[String]) -> Vec<String> {
+        packages
+            .iter()
+            .filter(|package| !self.contains(package))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `name` is a package in the repository, or a group whose members all are
+    fn contains(&self, name: &str) -> bool {
+        self.packages.contains(name)
+            || self
+                .groups
+                .get(name)
+                .is_some_and(|members| members.iter().all(|member| self.packages.contains(member)))
+    }
+
+    /// Whether the install can run without any online repository
+    pub fn covers(&self, context: &InstallContext) -> bool {
+        self.missing(&required_packages(context)).is_empty()
+    }
+}
+
+/// Package name of a package file name, e.g. `linux-6.9.1.arch1-1-x86_64.pkg.tar.zst`
+fn package_name(file_name: &str) -> Option<&str> {
+    let (stem, _) = file_name.split_once(".pkg.tar")?;
+    if file_name.ends_with(".sig") {
+        return None;
+    }
+    // name-version-release-architecture; names may contain dashes
+    let mut parts = stem.rsplitn(4, '-');
+    let _architecture = parts.next()?;
+    let _release = parts.next()?;
+    let _version = parts.next()?;
+    parts.next()
+}
+
+/// Group members by group from `group package` lines
+fn parse_groups(content: &str) -> HashMap<String, Vec<String>> {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for (group, package) in content
+        .lines()
+        .filter_map(|line| line.trim().split_once(' '))
+    {
+        groups
+            .entry(group.to_string())
+            .or_default()
+            .push(package.trim().to_string());
+    }
+    groups
+}
+
+/// The `group package` lines of `pacman -Sg` output for groups in `names`
+fn requested_groups(sg_output: &str, names: &[String]) -> String {
+    sg_output
+        .lines()
+        .filter(|line| {
+            line.split_once(' ')
+                .is_some_and(|(group, _)| names.iter().any(|name| name == group))
+        })
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+/// Every package the install names: the pacstrap set, the standard set and the
+/// instantOS packages
+pub fn required_packages(context: &InstallContext) -> Vec<String> {
+    let mut packages = crate::arch::execution::base::pacstrap_package_list(context);
+    match crate::arch::execution::packages::build_standard_package_plan(context) {
+        Ok(standard) => packages.extend(standard),
+        Err(e) => println!("Warning: Failed to build the package plan: {}", e),
+    }
+    packages.extend(crate::arch::execution::packages::build_instant_package_plan(context));
+    crate::arch::execution::packages::dedup_preserve(&mut packages);
+    packages
+}
+
+/// Summary line describing where packages will come from
+pub fn describe(context: &InstallContext) -> String {
+    let Some(repo) = LocalRepo::detect() else {
+        return "Online (no local repository found)".to_string();
+    };
+    let missing = repo.missing(&required_packages(context));
+    if missing.is_empty() {
+        format!("Fully offline ({})", repo.path.display())
+    } else {
+        format!(
+            "Online, {} package(s) not in {}",
+            missing.len(),
+            repo.path.display()
+        )
+    }
+}
+
+/// Put the local repository in front of the online ones in `content`.
+///
+/// With `offline_only`, every other repository section is commented out. The
+/// change is marked so that [`without_local_repo`] can undo it.
+fn with_local_repo(content: &str, repo_dir: &Path, offline_only: bool) -> String {
+    let content = without_local_repo(content);
+    let mut block = vec![BEGIN_MARKER.to_string()];
+    if offline_only {
+        block.push(OFFLINE_ONLY_MARKER.to_string());
+    }
+    block.extend([
+        format!("[{REPO_NAME}]"),
+        "SigLevel = Optional TrustAll".to_string(),
+        format!("Server = file://{}", repo_dir.display()),
+        END_MARKER.to_string(),
+        String::new(),
+    ]);
+
+    let mut lines = Vec::new();
+    let mut inserted = false;
+    let mut in_repo_section = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_repo_section = trimmed != "[options]";
+            if in_repo_section && !inserted {
+                lines.append(&mut block);
+                inserted = true;
+            }
+        }
+
+        if offline_only && in_repo_section && !trimmed.is_empty() {
+            lines.push(format!("{DISABLED_PREFIX}{line}"));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    if !inserted {
+        lines.push(String::new());
+        lines.append(&mut block);
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
+
+/// Remove the local repository added by [`with_local_repo`] and re-enable the
+/// repositories it disabled
+fn without_local_repo(content: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_block = false;
+    let mut skip_blank = false;
+
+    for line in content.lines() {
+        if line == BEGIN_MARKER {
+            in_block = true;
+            continue;
+        }
+        if in_block {
+            if line == END_MARKER {
+                in_block = false;
+                skip_blank = true;
+            }
+            continue;
+        }
+        if skip_blank {
+            skip_blank = false;
+            if line.is_empty() {
+                continue;
+            }
+        }
+        lines.push(
+            line.strip_prefix(DISABLED_PREFIX)
+                .unwrap_or(line)
+                .to_string(),
+        );
+    }
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+/// The repository directory and mode recorded in a pacman.conf, if any
+fn local_repo_settings(content: &str) -> Option<(PathBuf, bool)> {
+    let start = content.lines().position(|line| line == BEGIN_MARKER)?;
+    let mut offline_only = false;
+    for line in content.lines().skip(start + 1) {
+        if line == END_MARKER {
+            break;
+        }
+        if line == OFFLINE_ONLY_MARKER {
+            offline_only = true;
+        }
+        if let Some(server) = line.strip_prefix("Server = file://") {
+            return Some((PathBuf::from(server), offline_only));
+        }
+    }
+    None
+}
+
+fn rewrite_pacman_conf(
+    executor: &dyn CommandRunner,
+    description: &str,
+    rewrite: impl FnOnce(&str) -> String,
+) -> Result<()> {
+    let config_path = "/etc/pacman.conf";
+    if executor.dry_run() {
+        println!("[DRY RUN] {} in {}", description, config_path);
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", config_path))?;
+    let new_content = rewrite(&content);
+    if new_content != content {
+        std::fs::write(config_path, new_content)
+            .with_context(|| format!("Failed to write {}", config_path))?;
+        println!("{} in {}", description, config_path);
+    }
+    Ok(())
+}
+
+/// Add the detected local repository to /etc/pacman.conf.
+///
+/// Returns whether the install runs fully offline.
+pub fn enable(context: &InstallContext, executor: &dyn CommandRunner) -> Result<bool> {
+    let Some(repo) = LocalRepo::detect() else {
+        return Ok(false);
+    };
+    let offline_only = repo.covers(context);
+    println!(
+        "Using local package repository {}{}",
+        repo.path.display(),
+        if offline_only {
+            " (offline install)"
+        } else {
+            ""
+        }
+    );
+
+    rewrite_pacman_conf(executor, "Added the local repository", |content| {
+        with_local_repo(content, &repo.path, offline_only)
+    })?;
+    Ok(offline_only)
+}
+
+/// Apply the recorded local repository mode again, e.g. to disable a repository
+/// section that was appended after [`enable`]
+pub fn refresh(executor: &dyn CommandRunner) -> Result<()> {
+    rewrite_pacman_conf(executor, "Refreshed the local repository", |content| {
+        match local_repo_settings(content) {
+            Some((repo_dir, offline_only)) => with_local_repo(content, &repo_dir, offline_only),
+            None => content.to_string(),
+        }
+    })
+}
+
+/// Remove the local repository from /etc/pacman.conf, so the installed system
+/// uses the online repositories
+pub fn disable(executor: &dyn CommandRunner) -> Result<()> {
+    rewrite_pacman_conf(executor, "Removed the local repository", without_local_repo)
+}
+
+/// Bind-mount the local repository into the target for the chroot steps
+pub fn mount_into_chroot(executor: &dyn CommandRunner) -> Result<()> {
+    let Some(repo) = LocalRepo::detect() else {
+        return Ok(());
+    };
+    let target = paths::chroot_path(CHROOT_REPO_DIR);
+
+    let mounted = Command::new("mountpoint")
+        .arg("-q")
+        .arg(&target)
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false);
+    if mounted {
+        return Ok(());
+    }
+
+    if !executor.dry_run() {
+        std::fs::create_dir_all(&target)
+            .with_context(|| format!("Failed to create {}", target.display()))?;
+    }
+    let mut cmd = Command::new("mount");
+    cmd.arg("--bind").arg(&repo.path).arg(&target);
+    executor.run(&mut cmd)
+}
+
+/// pacman.conf used to download the cache: the host's repositories plus multilib
+/// and [instant], which the installed system enables later
+fn cache_pacman_conf(host_conf: &str, instant_mirrorlist: &Path) -> String {
+    let content = without_local_repo(host_conf);
+    let mut content = crate::common::pacman::enable_multilib_in_string(&content).unwrap_or(content);
+    if !content.contains("[instant]") {
+        content.push_str(&format!(
+            "\n[instant]\nSigLevel = Optional TrustAll\nInclude = {}\n",
+            instant_mirrorlist.display()
+        ));
+    }
+    content
+}
+
+/// Download every package `context` needs, with all dependencies, into `output`
+/// and index it with `repo-add`
+pub fn build_cache(
+    context: &InstallContext,
+    output: &Path,
+    executor: &dyn CommandRunner,
+) -> Result<()> {
+    let packages = required_packages(context);
+    println!(
+        "Building local repository in {} for {} packages...",
+        output.display(),
+        packages.len()
+    );
+
+    // A separate, empty package database makes pacman download every dependency,
+    // not only what is missing on this machine
+    let work_dir = tempfile::tempdir().context("Failed to create a temporary directory")?;
+    let db_path = work_dir.path().join("db");
+    let config_path = work_dir.path().join("pacman.conf");
+    let instant_mirrorlist = work_dir.path().join("instantmirrorlist");
+
+    let host_conf =
+        std::fs::read_to_string("/etc/pacman.conf").context("Failed to read /etc/pacman.conf")?;
+    if !executor.dry_run() {
+        std::fs::create_dir_all(&db_path)?;
+        std::fs::create_dir_all(output)
+            .with_context(|| format!("Failed to create {}", output.display()))?;
+        std::fs::write(
+            &instant_mirrorlist,
+            crate::common::pacman::INSTANT_MIRRORLIST,
+        )?;
+        std::fs::write(
+            &config_path,
+            cache_pacman_conf(&host_conf, &instant_mirrorlist),
+        )?;
+    }
+
+    let mut sync = Command::new("pacman");
+    sync.arg("-Sy")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--dbpath")
+        .arg(&db_path);
+    executor.run(&mut sync)?;
+
+    // Groups such as xfce4 are not package names, so record what they resolve to
+    let mut list_groups = Command::new("pacman");
+    list_groups
+        .arg("-Sg")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--dbpath")
+        .arg(&db_path);
+    if let Some(groups) = executor.run_with_output(&mut list_groups)? {
+        let groups = requested_groups(&String::from_utf8_lossy(&groups.stdout), &packages);
+        std::fs::write(output.join(GROUPS_FILE), groups)
+            .with_context(|| format!("Failed to write {}", GROUPS_FILE))?;
+    }
+
+    let mut download = Command::new("pacman");
+    download
+        .args(["-Sw", "--noconfirm"])
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--dbpath")
+        .arg(&db_path)
+        .arg("--cachedir")
+        .arg(output)
+        .args(&packages);
+    executor.run(&mut download)?;
+
+    let package_files: Vec<PathBuf> = if executor.dry_run() {
+        Vec::new()
+    } else {
+        std::fs::read_dir(output)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(package_name)
+                    .is_some()
+            })
+            .collect()
+    };
+
+    let mut repo_add = Command::new("repo-add");
+    repo_add
+        .arg("--new")
+        .arg(output.join(format!("{REPO_NAME}.db.tar.gz")))
+        .args(&package_files);
+    executor.run(&mut repo_add)?;
+
+    println!(
+        "Local repository ready. Copy {} to the root of a USB stick (as {}) to install offline.",
+        output.display(),
+        REPO_DIR_NAME
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACMAN_CONF: &str = "[options]\nHoldPkg = pacman glibc\n\n[core]\nInclude = /etc/pacman.d/mirrorlist\n\n[extra]\nInclude = /etc/pacman.d/mirrorlist\n";
+
+    #[test]
+    fn package_names_are_parsed_from_file_names() {
+        assert_eq!(
+            package_name("linux-6.9.1.arch1-1-x86_64.pkg.tar.zst"),
+            Some("linux")
+        );
+        assert_eq!(
+            package_name("xorg-xwayland-24.1.0-1-x86_64.pkg.tar.zst"),
+            Some("xorg-xwayland")
+        );
+        assert_eq!(
+            package_name("linux-6.9.1.arch1-1-x86_64.pkg.tar.zst.sig"),
+            None
+        );
+        assert_eq!(package_name("instantos-offline.db.tar.gz"), None);
+    }
+
+    #[test]
+    fn groups_count_as_present_when_all_members_are() {
+        let sg_output = "xfce4 thunar\nxfce4 xfwm4\nxorg xorg-server\ngnome nautilus\n";
+        let names = [
+            "linux".to_string(),
+            "xfce4".to_string(),
+            "gnome".to_string(),
+        ];
+        let groups = requested_groups(sg_output, &names);
+        assert_eq!(groups, "xfce4 thunar\nxfce4 xfwm4\ngnome nautilus\n");
+
+        let repo = LocalRepo {
+            path: PathBuf::from("/repo"),
+            packages: ["linux", "thunar", "xfwm4"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            groups: parse_groups(&groups),
+        };
+        assert_eq!(
+            repo.missing(&[
+                "linux".to_string(),
+                "xfce4".to_string(),
+                "gnome".to_string(),
+                "xorg".to_string(),
+            ]),
+            vec!["gnome", "xorg"]
+        );
+    }
+
+    #[test]
+    fn local_repo_goes_before_online_repos() {
+        let content = with_local_repo(PACMAN_CONF, Path::new("/repo"), false);
+        let repo = content.find("[instantos-offline]").unwrap();
+        assert!(repo < content.find("[core]").unwrap());
+        assert!(content.contains("Server = file:///repo\n"));
+        assert!(content.contains("\n[extra]\n"));
+        assert_eq!(without_local_repo(&content), PACMAN_CONF);
+    }
+
+    #[test]
+    fn offline_only_disables_online_repos_reversibly() {
+        let content = with_local_repo(PACMAN_CONF, Path::new("/repo"), true);
+        assert!(content.contains("#instantos-offline# [core]\n"));
+        assert!(content.contains("[options]\nHoldPkg"));
+        assert_eq!(
+            local_repo_settings(&content),
+            Some((PathBuf::from("/repo"), true))
+        );
+
+        // Repositories appended later are disabled when applied again
+        let appended = format!("{content}\n[instant]\nInclude = /etc/pacman.d/instantmirrorlist\n");
+        let refreshed = with_local_repo(&appended, Path::new("/repo"), true);
+        assert!(refreshed.contains("#instantos-offline# [instant]\n"));
+        assert_eq!(refreshed.matches(BEGIN_MARKER).count(), 1);
+
+        assert_eq!(
+            without_local_repo(&refreshed),
+            format!("{PACMAN_CONF}\n[instant]\nInclude = /etc/pacman.d/instantmirrorlist\n")
+        );
+    }
+}
@@ -77,6 +77,18 @@ impl Question for BooleanQuestion {
         }
     }
 
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        // `InstallContext::get_answer_bool` also reads hand-written true/false
+        vec!["yes", "no", "true", "false"]
+    }
+
+    fn validate(&self, _context: &InstallContext, answer: &str) -> Result<(), String> {
+        if !self.allowed_answers().contains(&answer) {
+            return Err(format!("Expected yes or no, got '{}'.", answer));
+        }
+        Ok(())
+    }
+
     fn get_default(&self, context: &InstallContext) -> Option<String> {
         let effective_default = if let Some(dynamic_func) = &self.dynamic_default {
             dynamic_func(context)
@@ -0,0 +1,239 @@
+use crate::arch::config::Bootloader;
+use crate::arch::engine::{InstallContext, Question, QuestionId, QuestionResult};
+use crate::common::format::format_size;
+use crate::menu_utils::{FzfPreview, FzfSelectable, FzfWrapper};
+use crate::ui::catppuccin::colors;
+use crate::ui::nerd_font::NerdFont;
+use crate::ui::preview::PreviewBuilder;
+use anyhow::Result;
+
+#[derive(Clone)]
+struct BootloaderOption(Bootloader);
+
+impl BootloaderOption {
+    fn preview(&self) -> FzfPreview {
+        match self.0 {
+            Bootloader::Grub => PreviewBuilder::new()
+                .header(NerdFont::Gear, "GRUB (recommended)")
+                .subtext("The most widely used Linux bootloader.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets([
+                    "UEFI and legacy BIOS systems",
+                    "Dual boot (other systems are found by os-prober)",
+                    "instantOS boot menu theme",
+                ])
+                .build(),
+            Bootloader::SystemdBoot => PreviewBuilder::new()
+                .header(NerdFont::Gear, "systemd-boot")
+                .subtext("Minimal UEFI boot manager that ships with systemd.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets([
+                    "Fast, simple boots on UEFI systems",
+                    "Windows Boot Manager is listed automatically",
+                ])
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullets(["UEFI only", "Kernels are stored on the EFI partition"])
+                .build(),
+            Bootloader::Limine => PreviewBuilder::new()
+                .header(NerdFont::Gear, "Limine")
+                .subtext("Modern, lightweight bootloader for UEFI and BIOS.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets([
+                    "Small, readable configuration",
+                    "Dual boot with a generated Windows entry",
+                ])
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullets([
+                    "Kernels are stored on a FAT partition (EFI or /boot)",
+                    "BIOS installs get a separate 1 GiB /boot partition",
+                ])
+                .build(),
+        }
+    }
+}
+
+impl FzfSelectable for BootloaderOption {
+    fn fzf_display_text(&self) -> String {
+        self.0.label().to_string()
+    }
+
+    fn fzf_preview(&self) -> FzfPreview {
+        self.preview()
+    }
+
+    fn fzf_key(&self) -> String {
+        self.0.answer_value().to_string()
+    }
+}
+
+pub struct BootloaderQuestion;
+
+#[async_trait::async_trait]
+impl Question for BootloaderQuestion {
+    fn id(&self) -> QuestionId {
+        QuestionId::Bootloader
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Choose the bootloader (GRUB, systemd-boot or Limine)")
+    }
+
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        vec!["grub", "systemd-boot", "limine"]
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    fn get_default(&self, _context: &InstallContext) -> Option<String> {
+        Some(Bootloader::DEFAULT.answer_value().to_string())
+    }
+
+    async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
+        let boot_mode = &context.system_info.boot_mode;
+        let options: Vec<_> = [
+            Bootloader::Grub,
+            Bootloader::SystemdBoot,
+            Bootloader::Limine,
+        ]
+        .into_iter()
+        .filter(|bootloader| bootloader.supports(boot_mode))
+        .map(BootloaderOption)
+        .collect();
+
+        let result = FzfWrapper::builder()
+            .header(format!("{} Select Bootloader", NerdFont::Gear))
+            .select(options)?;
+
+        match result {
+            crate::menu_utils::FzfResult::Selected(option) => {
+                Ok(QuestionResult::Answer(option.0.answer_value().to_string()))
+            }
+            _ => Ok(QuestionResult::Cancelled),
+        }
+    }
+
+    fn validate(&self, context: &InstallContext, answer: &str) -> Result<(), String> {
+        match answer {
+            "grub" | "systemd-boot" | "limine" => {}
+            _ => return Err("You must select a bootloader.".to_string()),
+        }
+        let bootloader = Bootloader::from_answer(answer);
+        if !bootloader.supports(&context.system_info.boot_mode) {
+            return Err("systemd-boot requires a UEFI system.".to_string());
+        }
+        if bootloader.keeps_kernels_on_esp()
+            && let Some(esp) = dualboot_esp(context)
+        {
+            // Windows already uses part of the ESP, so its size alone is not enough.
+            // Falls back to the size when the partition cannot be mounted.
+            let free =
+                crate::arch::dualboot::detection::esp_free_space(&esp).unwrap_or(esp.size_bytes);
+            if free < Bootloader::MIN_KERNEL_ESP_FREE {
+                return Err(format!(
+                    "The EFI partition {} has {} free, too little to hold kernels for {}. Use GRUB for dual boot.",
+                    esp.device,
+                    format_size(free),
+                    bootloader.label()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The existing ESP a dual-boot install will reuse
+fn dualboot_esp(context: &InstallContext) -> Option<crate::arch::dualboot::PartitionInfo> {
+    let dualboot = context
+        .get_answer(&QuestionId::PartitioningMethod)
+        .is_some_and(|method| method.contains("Dual Boot"));
+    if !dualboot {
+        return None;
+    }
+    let disk = context.get_answer(&QuestionId::Disk)?;
+    let disks = context.get::<crate::arch::dualboot::DisksKey>()?;
+    disks
+        .iter()
+        .find(|info| info.device == *disk)?
+        .find_reusable_esp()
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::dualboot::{DiskInfo, DisksKey, PartitionInfo, PartitionTableType};
+    use crate::arch::engine::BootMode;
+
+    fn dualboot_context(esp_size: u64) -> InstallContext {
+        let mut context = InstallContext::new();
+        context.system_info.boot_mode = BootMode::UEFI64;
+        context.set_answer(QuestionId::Disk, "/dev/nvme0n1".to_string());
+        context.set_answer(
+            QuestionId::PartitioningMethod,
+            "Dual Boot (Install alongside existing OS)".to_string(),
+        );
+        context.set::<DisksKey>(vec![DiskInfo {
+            device: "/dev/nvme0n1".into(),
+            size_bytes: 512 * 1024 * 1024 * 1024,
+            partition_table: PartitionTableType::GPT,
+            partitions: vec![PartitionInfo {
+                device: "/dev/nvme0n1p1".into(),
+                size_bytes: esp_size,
+                filesystem: None,
+                detected_os: None,
+                resize_info: None,
+                mount_point: None,
+                is_efi: true,
+                partition_type: None,
+            }],
+            unpartitioned_space_bytes: 0,
+            max_contiguous_free_space_bytes: 0,
+        }]);
+        context
+    }
+
+    #[test]
+    fn small_windows_esp_only_allows_grub() {
+        let context = dualboot_context(260 * 1024 * 1024);
+        assert!(BootloaderQuestion.validate(&context, "grub").is_ok());
+        assert!(
+            BootloaderQuestion
+                .validate(&context, "systemd-boot")
+                .unwrap_err()
+                .contains("/dev/nvme0n1p1")
+        );
+        assert!(BootloaderQuestion.validate(&context, "limine").is_err());
+
+        let context = dualboot_context(1024 * 1024 * 1024);
+        assert!(
+            BootloaderQuestion
+                .validate(&context, "systemd-boot")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn mounted_esp_is_judged_by_its_free_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = dualboot_context(1024 * 1024 * 1024);
+        let mut disks = context.get::<DisksKey>().unwrap();
+        let esp = &mut disks[0].partitions[0];
+        esp.mount_point = Some(dir.path().to_string_lossy().into_owned());
+        let free = crate::arch::dualboot::detection::esp_free_space(esp).unwrap();
+        context.set::<DisksKey>(disks);
+
+        assert_eq!(
+            BootloaderQuestion
+                .validate(&context, "systemd-boot")
+                .is_ok(),
+            free >= Bootloader::MIN_KERNEL_ESP_FREE
+        );
+    }
+}
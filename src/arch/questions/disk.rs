@@ -357,6 +357,24 @@ impl Question for PartitioningMethodQuestion {
         Some("Choose how to partition the disk")
     }
 
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        [
+            PartitioningMethodOption::Automatic,
+            PartitioningMethodOption::DualBoot,
+            PartitioningMethodOption::Manual,
+        ]
+        .iter()
+        .map(|option| option.label())
+        .collect()
+    }
+
+    fn validate(&self, _context: &InstallContext, answer: &str) -> Result<(), String> {
+        if !self.allowed_answers().contains(&answer) {
+            return Err("You must select a partitioning method.".to_string());
+        }
+        Ok(())
+    }
+
     async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
         let mut options = vec![
             PartitioningMethodOption::Automatic,
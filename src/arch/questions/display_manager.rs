@@ -1,4 +1,4 @@
-use crate::arch::config::DisplayManager;
+use crate::arch::config::{DesktopEnvironment, DisplayManager};
 use crate::arch::engine::{InstallContext, Question, QuestionId, QuestionResult};
 use crate::menu_utils::{FzfPreview, FzfSelectable, FzfWrapper};
 use crate::ui::catppuccin::colors;
@@ -13,7 +13,7 @@ impl DisplayManagerOption {
     fn preview(&self) -> FzfPreview {
         match self.0 {
             DisplayManager::Gdm => PreviewBuilder::new()
-                .header(NerdFont::Desktop, "gdm (recommended)")
+                .header(NerdFont::Desktop, "gdm")
                 .subtext(
                     "The GNOME Display Manager. Highly reliable and supports Wayland natively.",
                 )
@@ -34,6 +34,16 @@ impl DisplayManagerOption {
                     "Custom greeters and resource-constrained environments",
                 ])
                 .build(),
+            DisplayManager::Sddm => PreviewBuilder::new()
+                .header(NerdFont::Desktop, "sddm")
+                .subtext("The Qt-based display manager used by KDE Plasma.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets([
+                    "KDE Plasma and other Qt-based setups",
+                    "Themable greeters that match the Plasma look",
+                ])
+                .build(),
         }
     }
 }
@@ -61,7 +71,14 @@ impl Question for DisplayManagerQuestion {
     }
 
     fn description(&self) -> Option<&str> {
-        Some("Choose the display manager (gdm or lightdm)")
+        Some("Choose the display manager (gdm, sddm or lightdm)")
+    }
+
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        DisplayManager::ALL
+            .iter()
+            .map(|display_manager| display_manager.answer_value())
+            .collect()
     }
 
     fn is_optional(&self) -> bool {
@@ -69,18 +86,31 @@ impl Question for DisplayManagerQuestion {
     }
 
     fn should_ask(&self, context: &InstallContext) -> bool {
-        crate::arch::config::DesktopEnvironment::from_context(context).requires_display_manager()
+        DesktopEnvironment::from_context(context).requires_display_manager()
     }
 
-    fn get_default(&self, _context: &InstallContext) -> Option<String> {
-        Some(DisplayManager::DEFAULT.answer_value().to_string())
+    fn get_default(&self, context: &InstallContext) -> Option<String> {
+        Some(
+            DesktopEnvironment::from_context(context)
+                .default_display_manager()
+                .answer_value()
+                .to_string(),
+        )
     }
 
-    async fn ask(&self, _context: &InstallContext) -> Result<QuestionResult> {
-        let options = vec![
-            DisplayManagerOption(DisplayManager::Gdm),
-            DisplayManagerOption(DisplayManager::Lightdm),
-        ];
+    async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
+        let desktop = DesktopEnvironment::from_context(context);
+        let default = desktop.default_display_manager();
+        // List the desktop's default first so it is preselected
+        let options: Vec<_> = std::iter::once(default)
+            .chain(
+                DisplayManager::ALL
+                    .into_iter()
+                    .filter(|display_manager| *display_manager != default),
+            )
+            .filter(|display_manager| desktop.supports_display_manager(*display_manager))
+            .map(DisplayManagerOption)
+            .collect();
 
         let result = FzfWrapper::builder()
             .header(format!("{} Select Display Manager", NerdFont::Desktop))
@@ -94,10 +124,23 @@ impl Question for DisplayManagerQuestion {
         }
     }
 
-    fn validate(&self, _context: &InstallContext, answer: &str) -> Result<(), String> {
-        match answer {
-            "gdm" | "lightdm" => Ok(()),
-            _ => Err("You must select a display manager.".to_string()),
+    fn validate(&self, context: &InstallContext, answer: &str) -> Result<(), String> {
+        let Some(display_manager) = DisplayManager::ALL
+            .into_iter()
+            .find(|display_manager| display_manager.answer_value() == answer)
+        else {
+            return Err("You must select a display manager.".to_string());
+        };
+
+        let desktop = DesktopEnvironment::from_context(context);
+        if desktop.supports_display_manager(display_manager) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} requires {} as its display manager.",
+                desktop.label(),
+                desktop.default_display_manager().answer_value()
+            ))
         }
     }
 }
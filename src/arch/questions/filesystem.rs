@@ -71,6 +71,10 @@ impl Question for RootFilesystemQuestion {
         Some("Choose the root filesystem (btrfs or ext4)")
     }
 
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        vec!["btrfs", "ext4"]
+    }
+
     fn is_optional(&self) -> bool {
         true
     }
@@ -164,6 +168,10 @@ impl Question for BtrfsCompressionQuestion {
         Some("Choose btrfs compression algorithm")
     }
 
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        vec!["none", "zstd", "lzo", "zlib"]
+    }
+
     fn is_optional(&self) -> bool {
         true
     }
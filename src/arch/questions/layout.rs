@@ -0,0 +1,231 @@
+use crate::arch::config::{DiskLayout, RootFilesystem};
+use crate::arch::engine::{DataKey, InstallContext, Question, QuestionId, QuestionResult};
+use crate::menu_utils::{FzfPreview, FzfSelectable, FzfWrapper};
+use crate::ui::catppuccin::colors;
+use crate::ui::nerd_font::NerdFont;
+use crate::ui::preview::PreviewBuilder;
+use anyhow::Result;
+
+use super::DiskQuestion;
+
+fn is_automatic(context: &InstallContext) -> bool {
+    context
+        .get_answer(&QuestionId::PartitioningMethod)
+        .map(|method| !method.contains("Manual") && !method.contains("Dual Boot"))
+        .unwrap_or(true)
+}
+
+#[derive(Clone)]
+struct DiskLayoutOption(DiskLayout);
+
+impl DiskLayoutOption {
+    fn preview(&self) -> FzfPreview {
+        match self.0 {
+            DiskLayout::Standard => PreviewBuilder::new()
+                .header(NerdFont::HardDrive, "Standard")
+                .subtext("Boot, swap and root partitions on the selected disk.")
+                .blank()
+                .line(colors::TEAL, None, "With encryption")
+                .bullet("LVM on LUKS with root and swap volumes")
+                .build(),
+            DiskLayout::Lvm => PreviewBuilder::new()
+                .header(NerdFont::HardDrive, "LVM")
+                .subtext("Root, home and swap as logical volumes in one volume group.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets([
+                    "Resizing volumes later",
+                    "Keeping /home separate from the system",
+                ])
+                .blank()
+                .line(colors::TEAL, None, "With encryption")
+                .bullet("The volume group sits inside one LUKS container")
+                .build(),
+            DiskLayout::LuksOnLvm => PreviewBuilder::new()
+                .header(NerdFont::Lock, "LUKS on LVM")
+                .subtext("Each logical volume is encrypted on its own.")
+                .blank()
+                .line(colors::TEAL, None, "Layout")
+                .bullets([
+                    "Root unlocked with your passphrase at boot",
+                    "Home unlocked with a key file stored on root",
+                    "Swap encrypted with a new random key every boot",
+                ])
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullet("No hibernation (swap contents do not survive a reboot)")
+                .build(),
+            DiskLayout::BtrfsRaid1 => PreviewBuilder::new()
+                .header(NerdFont::HardDrive, "btrfs RAID1")
+                .subtext("Root data and metadata mirrored across two disks.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullet("Surviving the failure of one disk")
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullets([
+                    "Usable space is the size of the smaller disk",
+                    "Boot and swap stay on the first disk",
+                ])
+                .build(),
+            DiskLayout::HomeDisk => PreviewBuilder::new()
+                .header(NerdFont::Home, "Separate /home disk")
+                .subtext("The system goes on the selected disk, /home on a second disk.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullet("A fast system disk with a large data disk")
+                .build(),
+        }
+    }
+}
+
+impl FzfSelectable for DiskLayoutOption {
+    fn fzf_display_text(&self) -> String {
+        self.0.label().to_string()
+    }
+
+    fn fzf_preview(&self) -> FzfPreview {
+        self.preview()
+    }
+
+    fn fzf_key(&self) -> String {
+        self.0.answer_value().to_string()
+    }
+}
+
+pub struct DiskLayoutQuestion;
+
+#[async_trait::async_trait]
+impl Question for DiskLayoutQuestion {
+    fn id(&self) -> QuestionId {
+        QuestionId::DiskLayout
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Choose the volume layout (LVM, LUKS on LVM, RAID1, separate /home disk)")
+    }
+
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        DiskLayout::ALL
+            .iter()
+            .map(|layout| layout.answer_value())
+            .collect()
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    fn should_ask(&self, context: &InstallContext) -> bool {
+        is_automatic(context)
+    }
+
+    fn required_data_keys(&self) -> Vec<String> {
+        vec![crate::arch::disks::DisksKey::KEY.to_string()]
+    }
+
+    fn get_default(&self, _context: &InstallContext) -> Option<String> {
+        Some(DiskLayout::DEFAULT.answer_value().to_string())
+    }
+
+    async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
+        let disk_count = context
+            .get::<crate::arch::disks::DisksKey>()
+            .map(|disks| disks.len())
+            .unwrap_or(0);
+        let options: Vec<DiskLayoutOption> = DiskLayout::ALL
+            .into_iter()
+            .filter(|layout| !layout.needs_second_disk() || disk_count > 1)
+            .filter(|layout| self.validate(context, layout.answer_value()).is_ok())
+            .map(DiskLayoutOption)
+            .collect();
+
+        let result = FzfWrapper::builder()
+            .header(format!("{} Select Disk Layout", NerdFont::HardDrive))
+            .select(options)?;
+
+        match result {
+            crate::menu_utils::FzfResult::Selected(option) => {
+                Ok(QuestionResult::Answer(option.0.answer_value().to_string()))
+            }
+            _ => Ok(QuestionResult::Cancelled),
+        }
+    }
+
+    fn validate(&self, context: &InstallContext, answer: &str) -> Result<(), String> {
+        if !self.allowed_answers().contains(&answer) {
+            return Err("You must select a disk layout.".to_string());
+        }
+        let layout = DiskLayout::from_answer(answer);
+        let encrypted = context.get_answer_bool(QuestionId::UseEncryption);
+        if !layout.supports_encryption(encrypted) {
+            return Err(if encrypted {
+                format!(
+                    "{} cannot be encrypted; choose Standard, LVM or LUKS on LVM.",
+                    layout.label()
+                )
+            } else {
+                "LUKS on LVM encrypts the disk; enable encryption to use it.".to_string()
+            });
+        }
+        if layout == DiskLayout::BtrfsRaid1 && !RootFilesystem::from_context(context).is_btrfs() {
+            return Err("btrfs RAID1 needs btrfs as the root filesystem.".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub struct SecondDiskQuestion;
+
+#[async_trait::async_trait]
+impl Question for SecondDiskQuestion {
+    fn id(&self) -> QuestionId {
+        QuestionId::SecondDisk
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Select the second disk for RAID1 or /home")
+    }
+
+    fn should_ask(&self, context: &InstallContext) -> bool {
+        DiskLayout::from_context(context).needs_second_disk()
+    }
+
+    fn required_data_keys(&self) -> Vec<String> {
+        vec![crate::arch::disks::DisksKey::KEY.to_string()]
+    }
+
+    async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
+        let first = context.get_answer(&QuestionId::Disk);
+        let disks: Vec<_> = context
+            .get::<crate::arch::disks::DisksKey>()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|disk| Some(&disk.path) != first)
+            .collect();
+
+        if disks.is_empty() {
+            FzfWrapper::message("No second disk was detected. Choose another disk layout.")?;
+            return Ok(QuestionResult::Cancelled);
+        }
+
+        let result = FzfWrapper::builder()
+            .header(format!(
+                "{} Select Second Disk (will be erased)",
+                NerdFont::HardDrive
+            ))
+            .select(disks)?;
+
+        match result {
+            crate::menu_utils::FzfResult::Selected(disk) => Ok(QuestionResult::Answer(disk.path)),
+            _ => Ok(QuestionResult::Cancelled),
+        }
+    }
+
+    fn validate(&self, context: &InstallContext, answer: &str) -> Result<(), String> {
+        if context.get_answer(&QuestionId::Disk).map(|s| s.as_str()) == Some(answer) {
+            return Err("The second disk must differ from the installation disk.".to_string());
+        }
+        DiskQuestion.validate(context, answer)
+    }
+}
@@ -1,21 +1,29 @@
 pub mod boolean;
+pub mod bootloader;
 pub mod disk;
 pub mod display_manager;
 pub mod dualboot;
 pub mod filesystem;
+pub mod layout;
 pub mod partition;
 pub mod resize_instructions;
+pub mod restore;
+pub mod swap;
 pub mod system;
 pub mod warnings;
 
 // Re-exports
 pub use boolean::BooleanQuestion;
+pub use bootloader::BootloaderQuestion;
 pub use disk::{DiskQuestion, PartitioningMethodQuestion, RunCfdiskQuestion};
 pub use display_manager::DisplayManagerQuestion;
 pub use dualboot::{DualBootPartitionQuestion, DualBootSizeQuestion};
 pub use filesystem::{BtrfsCompressionQuestion, RootFilesystemQuestion};
+pub use layout::{DiskLayoutQuestion, SecondDiskQuestion};
 pub use partition::{EspPartitionValidator, PartitionSelectorQuestion};
 pub use resize_instructions::ResizeInstructionsQuestion;
+pub use restore::{RestoreGamePasswordQuestion, RestoreQuestion};
+pub use swap::SwapTypeQuestion;
 pub use system::{
     DesktopEnvironmentQuestion, EncryptionPasswordQuestion, HostnameQuestion, KernelQuestion,
     KeymapQuestion, LocaleQuestion, MirrorRegionQuestion, PasswordQuestion, TimezoneQuestion,
@@ -0,0 +1,141 @@
+use crate::arch::engine::{InstallContext, Question, QuestionId, QuestionResult};
+use crate::menu_utils::{FzfResult, FzfWrapper};
+use crate::ui::nerd_font::NerdFont;
+use anyhow::Result;
+use std::path::Path;
+
+/// Optional source restored into the new user's home after installation.
+///
+/// Leaving the input empty skips the restore.
+pub struct RestoreQuestion {
+    id: QuestionId,
+    description: &'static str,
+    prompt: &'static str,
+    icon: NerdFont,
+}
+
+impl RestoreQuestion {
+    pub fn dotfiles() -> Self {
+        Self {
+            id: QuestionId::RestoreDotfiles,
+            description: "Dotfile repository URL or exported git bundle to clone for the user",
+            prompt: "Dotfile repository URL or bundle path (empty to skip)",
+            icon: NerdFont::Git,
+        }
+    }
+
+    pub fn package_list() -> Self {
+        Self {
+            id: QuestionId::RestorePackageList,
+            description: "File listing packages to install, one per line",
+            prompt: "Path to a package list (empty to skip)",
+            icon: NerdFont::Package,
+        }
+    }
+
+    pub fn game_repo() -> Self {
+        Self {
+            id: QuestionId::RestoreGameRepo,
+            description: "Restic repository or local path with game saves to restore for the user",
+            prompt: "Game save restic repository (empty to skip)",
+            icon: NerdFont::Gamepad,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Question for RestoreQuestion {
+    fn id(&self) -> QuestionId {
+        self.id.clone()
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some(self.description)
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    async fn ask(&self, _context: &InstallContext) -> Result<QuestionResult> {
+        let result = FzfWrapper::builder()
+            .prompt(format!("{} {}", self.icon, self.prompt))
+            .input()
+            .input_result()?;
+
+        match result {
+            FzfResult::Selected(s) if !s.trim().is_empty() => {
+                Ok(QuestionResult::Answer(s.trim().to_string()))
+            }
+            _ => Ok(QuestionResult::Cancelled),
+        }
+    }
+
+    fn validate(&self, _context: &InstallContext, answer: &str) -> Result<(), String> {
+        if answer.trim().is_empty() {
+            return Err("Leave this question unanswered to skip the restore.".to_string());
+        }
+        if self.id == QuestionId::RestorePackageList && !answer.starts_with('/') {
+            return Err("The package list must be an absolute path.".to_string());
+        }
+        if self.id == QuestionId::RestoreGameRepo {
+            if answer.starts_with('~') {
+                return Err(
+                    "Use an absolute path or a restic repository such as sftp:host:/path."
+                        .to_string(),
+                );
+            }
+            if let Some(hidden) = ["/run", "/tmp"]
+                .into_iter()
+                .find(|dir| Path::new(answer).starts_with(dir))
+            {
+                return Err(format!(
+                    "{} is replaced inside the installer's chroot; mount the drive elsewhere, e.g. under /media.",
+                    hidden
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Password of the game save repository, if it does not use the built-in one
+pub struct RestoreGamePasswordQuestion;
+
+#[async_trait::async_trait]
+impl Question for RestoreGamePasswordQuestion {
+    fn id(&self) -> QuestionId {
+        QuestionId::RestoreGamePassword
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Password of the game save restic repository")
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    fn is_sensitive(&self) -> bool {
+        true
+    }
+
+    fn should_ask(&self, context: &InstallContext) -> bool {
+        context.is_answered(QuestionId::RestoreGameRepo)
+    }
+
+    async fn ask(&self, _context: &InstallContext) -> Result<QuestionResult> {
+        let result = FzfWrapper::builder()
+            .prompt(format!(
+                "{} Game save repository password (empty for the built-in one)",
+                NerdFont::Lock
+            ))
+            .password()
+            .password_dialog()?;
+
+        match result {
+            FzfResult::Selected(p) if !p.is_empty() => Ok(QuestionResult::Answer(p)),
+            _ => Ok(QuestionResult::Cancelled),
+        }
+    }
+}
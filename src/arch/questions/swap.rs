@@ -0,0 +1,140 @@
+use crate::arch::config::{DiskLayout, RootFilesystem, SwapType};
+use crate::arch::engine::{InstallContext, Question, QuestionId, QuestionResult};
+use crate::menu_utils::{FzfPreview, FzfSelectable, FzfWrapper};
+use crate::ui::catppuccin::colors;
+use crate::ui::nerd_font::NerdFont;
+use crate::ui::preview::PreviewBuilder;
+use anyhow::Result;
+
+#[derive(Clone)]
+struct SwapTypeOption(SwapType);
+
+impl SwapTypeOption {
+    fn preview(&self) -> FzfPreview {
+        match self.0 {
+            SwapType::Partition => PreviewBuilder::new()
+                .header(NerdFont::HardDrive, "Swap partition")
+                .subtext("A dedicated swap partition sized from your RAM.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullet("Hibernation")
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullet("On LVM layouts this is a swap logical volume")
+                .build(),
+            SwapType::Swapfile => PreviewBuilder::new()
+                .header(NerdFont::File, "Swap file")
+                .subtext("A swap file on the root filesystem.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets(["Resizing or removing swap later", "Hibernation"])
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullet("On btrfs it lives in its own no-copy-on-write @swap subvolume")
+                .build(),
+            SwapType::Zram => PreviewBuilder::new()
+                .header(NerdFont::Memory, "zram")
+                .subtext("Compressed swap in RAM, configured by zram-generator.")
+                .blank()
+                .line(colors::TEAL, None, "Best for")
+                .bullets([
+                    "SSDs you want to spare writes",
+                    "Machines with little disk space",
+                ])
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullet("No hibernation")
+                .build(),
+            SwapType::None => PreviewBuilder::new()
+                .header(NerdFont::Cross, "No swap")
+                .subtext("The system runs without any swap.")
+                .blank()
+                .line(colors::YELLOW, None, "Notes")
+                .bullets(["Programs are killed when memory runs out", "No hibernation"])
+                .build(),
+        }
+    }
+}
+
+impl FzfSelectable for SwapTypeOption {
+    fn fzf_display_text(&self) -> String {
+        self.0.label().to_string()
+    }
+
+    fn fzf_preview(&self) -> FzfPreview {
+        self.preview()
+    }
+
+    fn fzf_key(&self) -> String {
+        self.0.answer_value().to_string()
+    }
+}
+
+pub struct SwapTypeQuestion;
+
+#[async_trait::async_trait]
+impl Question for SwapTypeQuestion {
+    fn id(&self) -> QuestionId {
+        QuestionId::SwapType
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Choose swap: partition, swap file, zram or none")
+    }
+
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        SwapType::ALL
+            .iter()
+            .map(|swap| swap.answer_value())
+            .collect()
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    fn get_default(&self, context: &InstallContext) -> Option<String> {
+        Some(SwapType::default_for(context).answer_value().to_string())
+    }
+
+    async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
+        let options: Vec<SwapTypeOption> = SwapType::ALL
+            .into_iter()
+            .filter(|swap| self.validate(context, swap.answer_value()).is_ok())
+            .map(SwapTypeOption)
+            .collect();
+
+        let result = FzfWrapper::builder()
+            .header(format!("{} Select Swap", NerdFont::Memory))
+            .select(options)?;
+
+        match result {
+            crate::menu_utils::FzfResult::Selected(option) => {
+                Ok(QuestionResult::Answer(option.0.answer_value().to_string()))
+            }
+            _ => Ok(QuestionResult::Cancelled),
+        }
+    }
+
+    fn validate(&self, context: &InstallContext, answer: &str) -> Result<(), String> {
+        if !self.allowed_answers().contains(&answer) {
+            return Err("You must select a swap type.".to_string());
+        }
+        let swap = SwapType::from_answer(answer);
+        let manual = context
+            .get_answer(&QuestionId::PartitioningMethod)
+            .is_some_and(|method| method.contains("Manual"));
+        if swap.is_partition() && manual && context.get_answer(&QuestionId::SwapPartition).is_none()
+        {
+            return Err("Select a swap partition first, or choose another swap type.".to_string());
+        }
+        // The kernel cannot swap to a file on a multi-device btrfs filesystem
+        if swap == SwapType::Swapfile
+            && DiskLayout::from_context(context) == DiskLayout::BtrfsRaid1
+            && RootFilesystem::from_context(context).is_btrfs()
+        {
+            return Err("Swap files are not supported on btrfs RAID1.".to_string());
+        }
+        Ok(())
+    }
+}
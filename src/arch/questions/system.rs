@@ -254,6 +254,39 @@ fn desktop_environment_preview(environment: DesktopEnvironment) -> FzfPreview {
                 "Users who want polished motion and eye candy",
                 "Flexible tiling with a more opinionated feel",
             ]),
+        DesktopEnvironment::Plasma => PreviewBuilder::new()
+            .header(NerdFont::Desktop, "KDE Plasma")
+            .subtext("Full-featured Qt desktop with a traditional panel and deep customisation.")
+            .blank()
+            .line(colors::TEAL, None, "Good fit for")
+            .bullets([
+                "Users coming from Windows",
+                "People who want everything configurable from settings dialogs",
+            ])
+            .blank()
+            .line(colors::YELLOW, None, "Note")
+            .bullet("Uses SDDM as its display manager by default."),
+        DesktopEnvironment::Gnome => PreviewBuilder::new()
+            .header(NerdFont::Desktop, "GNOME")
+            .subtext("Polished, workflow-focused GTK desktop with an activities overview.")
+            .blank()
+            .line(colors::TEAL, None, "Good fit for")
+            .bullets([
+                "Laptops and touchpads with gesture navigation",
+                "Users who want sensible defaults over configuration",
+            ])
+            .blank()
+            .line(colors::YELLOW, None, "Note")
+            .bullet("Requires gdm as its display manager."),
+        DesktopEnvironment::Xfce => PreviewBuilder::new()
+            .header(NerdFont::Desktop, "Xfce")
+            .subtext("Lightweight, traditional X11 desktop that stays out of the way.")
+            .blank()
+            .line(colors::TEAL, None, "Good fit for")
+            .bullets([
+                "Older or resource-constrained hardware",
+                "Users who prefer a classic desktop layout",
+            ]),
         DesktopEnvironment::Tty => PreviewBuilder::new()
             .header(NerdFont::Terminal, "None / TTY")
             .subtext("Install without a graphical desktop session as the default.")
@@ -298,14 +331,15 @@ impl Question for DesktopEnvironmentQuestion {
         Some("Choose your desktop environment")
     }
 
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        DesktopEnvironment::ALL
+            .iter()
+            .map(|environment| environment.answer_value())
+            .collect()
+    }
+
     async fn ask(&self, _context: &InstallContext) -> Result<QuestionResult> {
-        let options = vec![
-            DesktopEnvironment::Sway,
-            DesktopEnvironment::Niri,
-            DesktopEnvironment::InstantWM,
-            DesktopEnvironment::Hyprland,
-            DesktopEnvironment::Tty,
-        ];
+        let options = DesktopEnvironment::ALL.to_vec();
 
         let result = FzfWrapper::builder()
             .header(format!("{} Select Desktop Environment", NerdFont::Desktop))
@@ -321,9 +355,13 @@ impl Question for DesktopEnvironmentQuestion {
     }
 
     fn validate(&self, _context: &InstallContext, answer: &str) -> Result<(), String> {
-        match answer {
-            "sway" | "niri" | "instantwm" | "hyprland" | "none/tty" => Ok(()),
-            _ => Err("You must select a desktop environment.".to_string()),
+        if DesktopEnvironment::ALL
+            .iter()
+            .any(|environment| environment.answer_value() == answer)
+        {
+            Ok(())
+        } else {
+            Err("You must select a desktop environment.".to_string())
         }
     }
 }
@@ -653,6 +691,10 @@ impl Question for KernelQuestion {
         Some("Select the Linux kernel variant")
     }
 
+    fn allowed_answers(&self) -> Vec<&'static str> {
+        vec!["linux", "linux-lts", "linux-zen"]
+    }
+
     fn is_optional(&self) -> bool {
         true
     }
@@ -674,7 +716,7 @@ impl Question for KernelQuestion {
     }
 
     fn validate(&self, _context: &InstallContext, answer: &str) -> Result<(), String> {
-        if answer.is_empty() {
+        if !self.allowed_answers().contains(&answer) {
             return Err("You must select a kernel.".to_string());
         }
         Ok(())
@@ -1,3 +1,4 @@
+use crate::arch::config::SwapType;
 use crate::arch::engine::{BootMode, InstallContext, Question, QuestionId, QuestionResult};
 use crate::common::format::format_size;
 use crate::menu_utils::FzfWrapper;
@@ -13,7 +14,7 @@ impl Question for LowRamWarning {
     }
 
     fn description(&self) -> Option<&str> {
-        Some("Warning: system has less than 1 GB of RAM")
+        Some("Warning: system has less than 1 GB of RAM, or less than 4 GB and no swap")
     }
 
     fn is_info_only(&self) -> bool {
@@ -21,20 +22,37 @@ impl Question for LowRamWarning {
     }
 
     fn should_ask(&self, context: &InstallContext) -> bool {
-        // Show warning if RAM is detected and less than 1GB
-        context.system_info.total_ram_gb.is_some_and(|ram| ram < 1)
+        // Show warning if RAM is detected and less than 1GB, or less than 4GB
+        // with nothing to swap to
+        let no_swap = SwapType::from_context(context) == SwapType::None;
+        context
+            .system_info
+            .total_ram_gb
+            .is_some_and(|ram| ram < 1 || (no_swap && ram < 4))
     }
 
     async fn ask(&self, context: &InstallContext) -> Result<QuestionResult> {
         let ram_gb = context.system_info.total_ram_gb.unwrap_or(0);
-        FzfWrapper::message(&format!(
-            "{} Low Memory Warning\n\n\
-             System has {} GB of RAM (less than 1 GB).\n\
-             Installation may be slow or fail.\n\n\
-             Installation will proceed, but consider upgrading your RAM for a better experience.",
-            NerdFont::Warning,
-            ram_gb
-        ))?;
+        let message = if ram_gb < 1 {
+            format!(
+                "{} Low Memory Warning\n\n\
+                 System has {} GB of RAM (less than 1 GB).\n\
+                 Installation may be slow or fail.\n\n\
+                 Installation will proceed, but consider upgrading your RAM for a better experience.",
+                NerdFont::Warning,
+                ram_gb
+            )
+        } else {
+            format!(
+                "{} Low Memory Warning\n\n\
+                 System has {} GB of RAM and no swap.\n\
+                 Programs will be killed when memory runs out.\n\n\
+                 Consider choosing zram or a swap file instead.",
+                NerdFont::Warning,
+                ram_gb
+            )
+        };
+        FzfWrapper::message(&message)?;
         Ok(QuestionResult::Answer("acknowledged".to_string()))
     }
 }
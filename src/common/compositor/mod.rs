@@ -85,6 +85,8 @@ pub enum CompositorType {
     KWin,
     /// Gnome compositor
     Gnome,
+    /// Xfce desktop (xfwm4, X11 only)
+    Xfce,
     /// Other/unknown compositor
     Other(String),
 }
@@ -109,6 +111,7 @@ impl CompositorType {
                 "niri" => return CompositorType::Niri,
                 "kde" | "plasma" | "kwin" => return CompositorType::KWin,
                 s if s.contains("gnome") || s == "ubuntu" => return CompositorType::Gnome,
+                "xfce" => return CompositorType::Xfce,
                 _ => {}
             }
         }
@@ -123,6 +126,7 @@ impl CompositorType {
                 "niri" => return CompositorType::Niri,
                 "kde" | "plasma" | "kwin" => return CompositorType::KWin,
                 s if s.contains("gnome") || s == "ubuntu" => return CompositorType::Gnome,
+                "xfce" => return CompositorType::Xfce,
                 _ => {}
             }
         }
@@ -144,7 +148,7 @@ impl CompositorType {
             return CompositorType::Niri;
         }
 
-        // Check XDG_CURRENT_DESKTOP for KDE or GNOME
+        // Check XDG_CURRENT_DESKTOP for KDE, GNOME or Xfce
         if let Ok(current) = env::var("XDG_CURRENT_DESKTOP") {
             let lower = current.to_lowercase();
             if lower == "kde" {
@@ -153,6 +157,9 @@ impl CompositorType {
             if lower.contains("gnome") {
                 return CompositorType::Gnome;
             }
+            if lower == "xfce" {
+                return CompositorType::Xfce;
+            }
         }
 
         // Use display server detection to guide compositor detection
@@ -199,6 +206,9 @@ impl CompositorType {
                 if CompositorType::is_process_running("gnome-shell") {
                     return CompositorType::Gnome;
                 }
+                if CompositorType::is_process_running("xfwm4") {
+                    return CompositorType::Xfce;
+                }
                 CompositorType::Other("x11".to_string())
             }
             DisplayServer::Unknown => CompositorType::Other("unknown".to_string()),
@@ -216,6 +226,7 @@ impl CompositorType {
             CompositorType::Niri => Box::new(niri::Niri),
             CompositorType::KWin => Box::new(kwin::KWin),
             CompositorType::Gnome => Box::new(gnome::Gnome),
+            CompositorType::Xfce => Box::new(fallback::Fallback),
             CompositorType::Other(_) => Box::new(fallback::Fallback),
         }
     }
@@ -260,6 +271,7 @@ impl CompositorType {
             CompositorType::Niri => "niri".to_string(),
             CompositorType::KWin => "KWin".to_string(),
             CompositorType::Gnome => "Gnome".to_string(),
+            CompositorType::Xfce => "Xfce".to_string(),
             CompositorType::Other(name) => name.clone(),
         }
     }
@@ -283,6 +295,7 @@ impl CompositorType {
         match self {
             CompositorType::I3 => true,
             CompositorType::Dwm => true,
+            CompositorType::Xfce => true,
             CompositorType::InstantWM => DisplayServer::detect() == DisplayServer::X11,
             CompositorType::KWin => DisplayServer::detect() == DisplayServer::X11,
             CompositorType::Gnome => DisplayServer::detect() == DisplayServer::X11,
@@ -300,6 +313,7 @@ impl CompositorType {
             }
             CompositorType::I3 => DisplayServer::X11,
             CompositorType::Dwm => DisplayServer::X11,
+            CompositorType::Xfce => DisplayServer::X11,
             CompositorType::InstantWM => DisplayServer::detect(),
             CompositorType::KWin => DisplayServer::detect(),
             CompositorType::Gnome => DisplayServer::detect(),
@@ -337,6 +351,7 @@ mod tests {
             | CompositorType::Niri
             | CompositorType::KWin
             | CompositorType::Gnome
+            | CompositorType::Xfce
             | CompositorType::Dwm
             | CompositorType::InstantWM
             | CompositorType::Other(_) => {
@@ -352,6 +367,7 @@ mod tests {
         assert_eq!(CompositorType::Niri.name(), "niri");
         assert_eq!(CompositorType::KWin.name(), "KWin");
         assert_eq!(CompositorType::Gnome.name(), "Gnome");
+        assert_eq!(CompositorType::Xfce.name(), "Xfce");
         assert_eq!(CompositorType::Other("test".to_string()).name(), "test");
     }
 
@@ -369,6 +385,7 @@ mod tests {
         assert!(!CompositorType::Sway.is_x11());
         assert!(!CompositorType::Hyprland.is_x11());
         assert!(!CompositorType::Niri.is_x11());
+        assert!(CompositorType::Xfce.is_x11());
         assert!(CompositorType::Other("x11".to_string()).is_x11());
         assert!(!CompositorType::Other("wayland".to_string()).is_x11());
     }
@@ -63,7 +63,7 @@ pub async fn enable_multilib(dry_run: bool) -> Result<()> {
     Ok(())
 }
 
-fn enable_multilib_in_string(content: &str) -> Option<String> {
+pub(crate) fn enable_multilib_in_string(content: &str) -> Option<String> {
     // We look for #[multilib] and the following #Include
     // Pattern:
     // #[multilib]
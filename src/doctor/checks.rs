@@ -10,11 +10,13 @@
 //! - security.rs: Security-related checks (polkit agents)
 //! - nerdfont.rs: Nerd Font symbol rendering checks
 //! - completions.rs: Shell completion checks
+//! - custom.rs: User-defined checks loaded from TOML files
 
 use crate::doctor::{CheckStatus, DoctorCheck, PrivilegeLevel};
 
 pub mod audio;
 pub mod completions;
+pub mod custom;
 pub mod display;
 pub mod locale;
 pub mod nerdfont;
@@ -27,6 +29,7 @@ pub mod tools;
 // Re-export all check types for easy access
 pub use audio::PipewireSessionManagerCheck;
 pub use completions::{ShellCompletionCheck, ZshHealthCheck};
+pub use custom::load_custom_checks;
 pub use display::{SwayDisplayCheck, SwaySetupCheck};
 pub use locale::LocaleCheck;
 pub use nerdfont::NerdFontCheck;
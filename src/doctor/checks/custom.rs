@@ -0,0 +1,401 @@
+//! User-defined checks loaded from `~/.config/instant/doctor.d/*.toml`
+//!
+//! Each file declares one check:
+//!
+//! ```toml
+//! name = "VPN connection"
+//! command = "systemctl is-active wg-quick@wg0"
+//! expect_output = "^active$"
+//! severity = "warning"
+//! privilege = "any"
+//! fix_command = "sudo systemctl restart wg-quick@wg0"
+//! fix_message = "Restart the WireGuard tunnel"
+//! ```
+//!
+//! The check ID defaults to the file name without `.toml`.
+
+use super::{CheckStatus, DoctorCheck, PrivilegeLevel};
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+use std::io::Read;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command as TokioCommand;
+
+use crate::doctor::DetailedCheckStatus;
+
+/// Directory below the instant config directory holding the check files
+pub const CUSTOM_CHECKS_DIR: &str = "doctor.d";
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    #[default]
+    Fail,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomCheckFile {
+    id: Option<String>,
+    name: String,
+    /// Shell command whose result decides the check
+    command: String,
+    #[serde(default)]
+    expect_exit: i32,
+    /// Regex the command's stdout has to match
+    expect_output: Option<String>,
+    #[serde(default)]
+    severity: Severity,
+    #[serde(default)]
+    privilege: Privilege,
+    fix_command: Option<String>,
+    fix_message: Option<String>,
+    /// Defaults to `privilege`
+    fix_privilege: Option<Privilege>,
+    #[serde(default = "default_timeout")]
+    timeout_secs: u64,
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Privilege {
+    User,
+    Root,
+    #[default]
+    Any,
+}
+
+impl From<Privilege> for PrivilegeLevel {
+    fn from(privilege: Privilege) -> Self {
+        match privilege {
+            Privilege::User => PrivilegeLevel::User,
+            Privilege::Root => PrivilegeLevel::Root,
+            Privilege::Any => PrivilegeLevel::Any,
+        }
+    }
+}
+
+/// A check declared in a TOML file
+#[derive(Debug, Clone)]
+pub struct CustomCheck {
+    id: String,
+    name: String,
+    command: String,
+    expect_exit: i32,
+    expect_output: Option<Regex>,
+    severity: Severity,
+    privilege: PrivilegeLevel,
+    fix_command: Option<String>,
+    fix_message: Option<String>,
+    fix_privilege: PrivilegeLevel,
+    timeout: Duration,
+}
+
+impl CustomCheck {
+    fn parse(content: &str, default_id: &str) -> Result<Self> {
+        let file: CustomCheckFile = toml::from_str(content)?;
+        let id = file.id.unwrap_or_else(|| default_id.to_string());
+        if id.is_empty() || id.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(anyhow!(
+                "invalid check id '{}': it must not be empty or contain spaces or commas",
+                id
+            ));
+        }
+        let expect_output = file
+            .expect_output
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("invalid expect_output regex")?;
+
+        Ok(Self {
+            id,
+            name: file.name,
+            command: file.command,
+            expect_exit: file.expect_exit,
+            expect_output,
+            severity: file.severity,
+            privilege: file.privilege.into(),
+            fix_command: file.fix_command,
+            fix_message: file.fix_message,
+            fix_privilege: file.fix_privilege.unwrap_or(file.privilege).into(),
+            timeout: Duration::from_secs(file.timeout_secs.max(1)),
+        })
+    }
+
+    /// Load a check file. As root, only files that root owns and nobody else can
+    /// write are trusted, since their commands run as root.
+    pub fn load(path: &Path, as_root: bool) -> Result<Self> {
+        let mut file =
+            std::fs::File::open(path).with_context(|| format!("reading {}", path.display()))?;
+        // Checked on the open file, so it cannot be swapped after the check
+        let metadata = file
+            .metadata()
+            .with_context(|| format!("reading {}", path.display()))?;
+        if as_root && !trusted_by_root(metadata.uid(), metadata.mode()) {
+            return Err(anyhow!(
+                "{} is ignored when running as root: it must be owned by root and not writable by group or others",
+                path.display()
+            ));
+        }
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let default_id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self::parse(&content, &default_id).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    fn failure(&self, message: String) -> CheckStatus {
+        let fixable = self.fix_command.is_some();
+        match self.severity {
+            Severity::Warning => CheckStatus::Warning { message, fixable },
+            Severity::Fail => CheckStatus::Fail { message, fixable },
+        }
+    }
+
+    /// Compare the command's result against the expectations
+    fn evaluate(&self, exit_code: Option<i32>, stdout: &str) -> CheckStatus {
+        if exit_code != Some(self.expect_exit) {
+            let actual = exit_code.map_or_else(|| "a signal".to_string(), |c| c.to_string());
+            return self.failure(format!(
+                "`{}` exited with {} (expected {})",
+                self.command, actual, self.expect_exit
+            ));
+        }
+        if let Some(regex) = &self.expect_output
+            && !regex.is_match(stdout.trim_end())
+        {
+            return self.failure(format!(
+                "`{}` output does not match `{}`",
+                self.command,
+                regex.as_str()
+            ));
+        }
+        CheckStatus::Pass(format!("`{}` succeeded", self.command))
+    }
+}
+
+fn trusted_by_root(uid: u32, mode: u32) -> bool {
+    uid == 0 && mode & 0o022 == 0
+}
+
+/// Directories searched for check files.
+///
+/// Under sudo the invoking user's directory is searched too, so fixes that escalate
+/// with `--batch-ids` still find checks the user handed to root.
+fn custom_check_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(config_dir) = dirs::config_dir() {
+        dirs.push(config_dir.join("instant").join(CUSTOM_CHECKS_DIR));
+    }
+    if let Ok(sudo_user) = std::env::var("SUDO_USER")
+        && let Some(home) = home_of(&sudo_user)
+    {
+        let dir = home.join(".config/instant").join(CUSTOM_CHECKS_DIR);
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+fn home_of(user: &str) -> Option<PathBuf> {
+    let output = std::process::Command::new("getent")
+        .args(["passwd", user])
+        .output()
+        .ok()?;
+    let entry = String::from_utf8_lossy(&output.stdout);
+    entry.trim_end().split(':').nth(5).map(PathBuf::from)
+}
+
+/// Load every check file, returning the checks and a message for each file that failed
+pub fn load_custom_checks() -> (Vec<CustomCheck>, Vec<String>) {
+    let mut checks: Vec<CustomCheck> = Vec::new();
+    let mut errors = Vec::new();
+    let as_root = matches!(sudo::check(), sudo::RunningAs::Root);
+
+    for dir in custom_check_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match CustomCheck::load(&path, as_root) {
+                // The first file with an ID wins, e.g. root's own over the sudo user's
+                Ok(check) if checks.iter().any(|c| c.id == check.id) => {}
+                Ok(check) => checks.push(check),
+                Err(e) => errors.push(format!("{:#}", e)),
+            }
+        }
+    }
+
+    (checks, errors)
+}
+
+fn shell(command: &str) -> TokioCommand {
+    let mut cmd = TokioCommand::new("sh");
+    cmd.arg("-c").arg(command).kill_on_drop(true);
+    cmd
+}
+
+async fn with_timeout<T>(
+    command: &str,
+    timeout: Duration,
+    future: impl std::future::Future<Output = std::io::Result<T>>,
+) -> Result<T> {
+    tokio::time::timeout(timeout, future)
+        .await
+        .map_err(|_| anyhow!("`{}` timed out after {}s", command, timeout.as_secs()))?
+        .with_context(|| format!("running `{}`", command))
+}
+
+#[async_trait]
+impl DoctorCheck for CustomCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn check_privilege_level(&self) -> PrivilegeLevel {
+        self.privilege
+    }
+
+    fn fix_privilege_level(&self) -> PrivilegeLevel {
+        self.fix_privilege
+    }
+
+    async fn execute(&self) -> CheckStatus {
+        self.execute_detailed().await.status
+    }
+
+    async fn execute_detailed(&self) -> DetailedCheckStatus {
+        match with_timeout(&self.command, self.timeout, shell(&self.command).output()).await {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let details = [stdout.trim(), stderr.trim()]
+                    .into_iter()
+                    .filter(|text| !text.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                DetailedCheckStatus {
+                    status: self.evaluate(output.status.code(), &stdout),
+                    details: (!details.is_empty()).then_some(details),
+                }
+            }
+            Err(e) => self.failure(format!("{:#}", e)).into(),
+        }
+    }
+
+    fn fix_message(&self) -> Option<String> {
+        self.fix_command.as_ref().map(|command| {
+            self.fix_message
+                .clone()
+                .unwrap_or_else(|| format!("Run `{}`", command))
+        })
+    }
+
+    async fn fix(&self) -> Result<()> {
+        let Some(command) = &self.fix_command else {
+            return Ok(());
+        };
+        let status = with_timeout(command, self.timeout, shell(command).status()).await?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!("`{}` failed with {}", command, status))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_check_files_with_defaults() {
+        let check = CustomCheck::parse(
+            "name = \"NFS mount\"\ncommand = \"mountpoint -q /mnt/nfs\"\n",
+            "nfs",
+        )
+        .unwrap();
+        assert_eq!(check.id, "nfs");
+        assert_eq!(check.severity, Severity::Fail);
+        assert_eq!(check.privilege, PrivilegeLevel::Any);
+        assert!(check.fix_message().is_none());
+
+        assert!(
+            CustomCheck::parse(
+                "name = \"x\"\ncommand = \"true\"\nseverty = \"warning\"\n",
+                "x"
+            )
+            .is_err()
+        );
+        assert!(
+            CustomCheck::parse(
+                "name = \"x\"\ncommand = \"true\"\nexpect_output = \"(\"\n",
+                "x"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn root_trusts_only_root_owned_files_nobody_else_can_write() {
+        assert!(trusted_by_root(0, 0o100644));
+        assert!(trusted_by_root(0, 0o100600));
+        assert!(!trusted_by_root(1000, 0o100644));
+        assert!(!trusted_by_root(0, 0o100664));
+        assert!(!trusted_by_root(0, 0o100646));
+    }
+
+    #[test]
+    fn evaluates_exit_code_and_output() {
+        let check = CustomCheck::parse(
+            "name = \"VPN\"\n\
+             command = \"systemctl is-active vpn\"\n\
+             expect_output = \"^active$\"\n\
+             severity = \"warning\"\n\
+             privilege = \"root\"\n\
+             fix_command = \"systemctl restart vpn\"\n",
+            "vpn",
+        )
+        .unwrap();
+        assert_eq!(check.fix_privilege, PrivilegeLevel::Root);
+        assert_eq!(
+            check.fix_message().as_deref(),
+            Some("Run `systemctl restart vpn`")
+        );
+
+        assert!(check.evaluate(Some(0), "active\n").is_success());
+        assert!(matches!(
+            check.evaluate(Some(0), "inactive\n"),
+            CheckStatus::Warning { fixable: true, .. }
+        ));
+        assert!(matches!(
+            check.evaluate(Some(3), "active\n"),
+            CheckStatus::Warning { .. }
+        ));
+    }
+}
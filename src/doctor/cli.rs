@@ -1,12 +1,17 @@
 use super::DoctorCommands;
 use super::fix::{fix_all_checks, fix_batch_checks, fix_interactive, fix_single_check};
-use super::run::{list_available_checks, run_all_checks_cmd, run_single_check};
+use super::run::{
+    list_available_checks, report_custom_check_errors, run_all_checks_cmd, run_single_check,
+};
 use anyhow::{Result, bail};
 
 pub async fn handle_doctor_command(
     command: Option<DoctorCommands>,
     max_concurrency: usize,
 ) -> Result<()> {
+    // Every subcommand, including the escalated `--batch-ids` run, says why a
+    // doctor.d check it cannot find was skipped
+    report_custom_check_errors();
     match command {
         None => run_all_checks_cmd(max_concurrency).await,
         Some(DoctorCommands::List) => list_available_checks().await,
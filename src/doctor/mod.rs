@@ -180,8 +180,8 @@ impl Display for CheckResult {
 
 #[async_trait::async_trait]
 pub trait DoctorCheck: Send + Sync {
-    fn name(&self) -> &'static str;
-    fn id(&self) -> &'static str; // Machine-readable identifier
+    fn name(&self) -> &str;
+    fn id(&self) -> &str; // Machine-readable identifier
 
     async fn execute(&self) -> CheckStatus;
 
@@ -1,16 +1,19 @@
 use super::{DoctorCheck, checks::*};
 use std::collections::HashMap;
 
-pub type CheckFactory = fn() -> Box<dyn DoctorCheck + Send + Sync>;
+pub type CheckFactory = Box<dyn Fn() -> Box<dyn DoctorCheck + Send + Sync> + Send + Sync>;
 
 pub struct CheckRegistry {
-    checks: HashMap<&'static str, CheckFactory>,
+    checks: HashMap<String, CheckFactory>,
+    /// Problems with user-defined check files, reported by the doctor commands
+    custom_errors: Vec<String>,
 }
 
 impl CheckRegistry {
     pub fn new() -> Self {
         let mut registry = CheckRegistry {
             checks: HashMap::new(),
+            custom_errors: Vec::new(),
         };
 
         // Register all checks
@@ -39,11 +42,35 @@ impl CheckRegistry {
         registry.register::<PipewireSessionManagerCheck>("pipewire-session-manager");
         registry.register::<SshAuthSockCheck>("ssh-auth-sock");
 
+        registry.register_custom_checks();
+
         registry
     }
 
     fn register<T: DoctorCheck + Default + Send + Sync + 'static>(&mut self, id: &'static str) {
-        self.checks.insert(id, || Box::new(T::default()));
+        self.checks
+            .insert(id.to_string(), Box::new(|| Box::new(T::default())));
+    }
+
+    fn register_custom_checks(&mut self) {
+        let (checks, errors) = load_custom_checks();
+        self.custom_errors = errors;
+        for check in checks {
+            let id = check.id().to_string();
+            if self.checks.contains_key(&id) {
+                self.custom_errors.push(format!(
+                    "custom check '{}' is ignored because a built-in check has the same ID",
+                    id
+                ));
+                continue;
+            }
+            self.checks
+                .insert(id, Box::new(move || Box::new(check.clone())));
+        }
+    }
+
+    pub fn custom_errors(&self) -> &[String] {
+        &self.custom_errors
     }
 
     pub fn create_check(&self, id: &str) -> Option<Box<dyn DoctorCheck + Send + Sync>> {
@@ -2,8 +2,26 @@ use super::privileges::check_privilege_requirements;
 use super::registry::REGISTRY;
 use super::ui::show_available_fixes;
 use super::{CheckResult, DoctorCheck, run_all_checks};
+use crate::ui::nerd_font::NerdFont;
+use crate::ui::{Level, prelude::*};
 use anyhow::{Result, anyhow};
 
+/// Warn about check files in `doctor.d` that could not be loaded
+pub fn report_custom_check_errors() {
+    for error in REGISTRY.custom_errors() {
+        emit(
+            Level::Warn,
+            "doctor.custom_check.invalid",
+            &format!(
+                "{} Skipping custom check: {}",
+                char::from(NerdFont::Warning),
+                error
+            ),
+            None,
+        );
+    }
+}
+
 /// Run all health checks and display results
 pub async fn run_all_checks_cmd(max_concurrency: usize) -> Result<()> {
     let checks = REGISTRY.all_checks();
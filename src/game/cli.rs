@@ -3,6 +3,9 @@ use std::ffi::OsString;
 use clap::{Subcommand, ValueEnum};
 use clap_complete::engine::ArgValueCompleter;
 
+/// Repository password for `ins game init`, kept out of the process arguments
+pub const GAME_PASSWORD_ENV: &str = "INS_GAME_PASSWORD";
+
 /// Game save management commands
 #[derive(Subcommand, Debug, Clone)]
 pub enum GameCommands {
@@ -11,7 +14,7 @@ pub enum GameCommands {
         /// Restic repository path to use (non-interactive)
         #[arg(long)]
         repo: Option<String>,
-        /// Restic repository password (defaults to built-in password)
+        /// Restic repository password (defaults to $INS_GAME_PASSWORD, then the built-in password)
         #[arg(long)]
         password: Option<String>,
     },
@@ -208,6 +211,9 @@ pub enum DependencyCommands {
         dependency_id: Option<String>,
         /// Path to dependency source file or directory (optional, prompts if omitted)
         path: Option<String>,
+        /// Label for the first version (defaults to v1)
+        #[arg(long)]
+        version: Option<String>,
     },
     /// Install a dependency onto this device
     Install {
@@ -218,6 +224,37 @@ pub enum DependencyCommands {
         dependency_id: Option<String>,
         /// Destination path (optional, prompts if omitted)
         path: Option<String>,
+        /// Version to install (defaults to the latest version)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Snapshot the dependency source again and record it as a new version
+    Update {
+        /// Game name (optional, prompts if omitted)
+        #[arg(add = ArgValueCompleter::new(crate::completions::game_name_completion))]
+        game_name: Option<String>,
+        /// Dependency identifier (optional, prompts if omitted)
+        dependency_id: Option<String>,
+        /// Label for the new version (defaults to the next vN)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Show which installed dependencies are out of date on this device
+    Status {
+        /// Game name (optional, shows all games with dependencies if omitted)
+        #[arg(add = ArgValueCompleter::new(crate::completions::game_name_completion))]
+        game_name: Option<String>,
+    },
+    /// Restore an installed dependency to another recorded version
+    Rollback {
+        /// Game name (optional, prompts if omitted)
+        #[arg(add = ArgValueCompleter::new(crate::completions::game_name_completion))]
+        game_name: Option<String>,
+        /// Dependency identifier (optional, prompts if omitted)
+        dependency_id: Option<String>,
+        /// Version to restore (optional, prompts if omitted)
+        #[arg(long)]
+        version: Option<String>,
     },
     /// Remove dependency installation record from this device
     Uninstall {
@@ -5,8 +5,10 @@ use crate::common::package::{InstallResult, ensure_all};
 
 use super::cli::{DependencyCommands, GameCommands, GameDiscoverySourceArg};
 use super::deps::{
-    AddDependencyOptions, InstallDependencyOptions, UninstallDependencyOptions, add_dependency,
-    install_dependency, list_dependencies as list_game_dependencies, uninstall_dependency,
+    AddDependencyOptions, InstallDependencyOptions, RollbackDependencyOptions,
+    UninstallDependencyOptions, UpdateDependencyOptions, add_dependency, dependency_status,
+    install_dependency, list_dependencies as list_game_dependencies, rollback_dependency,
+    uninstall_dependency, update_dependency,
 };
 use super::games::AddGameOptions;
 use super::games::{GameManager, remove_game};
@@ -207,21 +209,32 @@ fn handle_scan_wine_prefix(prefix: Option<String>, list: bool) -> Result<()> {
         return Ok(());
     }
 
-    // List mode: print results
+    // List mode: print results (ranked best-first within each game)
     if list {
         println!("\n{} Discovered saves:\n", char::from(NerdFont::Check));
+        let mut previous_game: Option<&str> = None;
         for save in &results {
-            let tag_str = if !save.tags.is_empty() {
-                format!(" [{}]", save.tags.join(", "))
+            let is_best = previous_game != Some(save.game_name.as_str());
+            previous_game = Some(save.game_name.as_str());
+
+            let mut labels = save.tags.clone();
+            if save.is_registry() {
+                labels.push("registry".to_string());
+            }
+            if is_best {
+                labels.push("best match".to_string());
+            }
+            let tag_str = if !labels.is_empty() {
+                format!(" [{}]", labels.join(", "))
             } else {
                 String::new()
             };
-            println!(
-                "  {} {}{}",
-                char::from(NerdFont::File),
-                save.game_name,
-                tag_str
-            );
+            let icon = if save.is_registry() {
+                NerdFont::Key
+            } else {
+                NerdFont::File
+            };
+            println!("  {} {}{}", char::from(icon), save.game_name, tag_str);
             println!("    {}", save.save_path);
         }
         println!("\n{} total saves found.", results.len());
@@ -234,11 +247,14 @@ fn handle_scan_wine_prefix(prefix: Option<String>, list: bool) -> Result<()> {
         game_name: String,
         save_path: String,
         tags: Vec<String>,
+        is_registry: bool,
     }
 
     impl FzfSelectable for ScanResultItem {
         fn fzf_display_text(&self) -> String {
-            let icon = if self.tags.iter().any(|t| t == "save") {
+            let icon = if self.is_registry {
+                format_icon_colored(NerdFont::Key, colors::MAUVE)
+            } else if self.tags.iter().any(|t| t == "save") {
                 format_icon_colored(NerdFont::File, colors::GREEN)
             } else if self.tags.iter().any(|t| t == "config") {
                 format_icon_colored(NerdFont::Gear, colors::YELLOW)
@@ -258,6 +274,15 @@ fn handle_scan_wine_prefix(prefix: Option<String>, list: bool) -> Result<()> {
             } else {
                 self.tags.join(", ")
             };
+            if self.is_registry {
+                return PreviewBuilder::new()
+                    .header(NerdFont::Key, &self.game_name)
+                    .text(&format!("Registry key: {}", self.save_path))
+                    .text(&format!("Tags: {}", tag_str))
+                    .blank()
+                    .subtext("Press Enter to back up this key with the game's saves")
+                    .build();
+            }
             PreviewBuilder::new()
                 .header(NerdFont::File, &self.game_name)
                 .text(&format!("Save path: {}", self.save_path))
@@ -271,6 +296,7 @@ fn handle_scan_wine_prefix(prefix: Option<String>, list: bool) -> Result<()> {
     let items: Vec<ScanResultItem> = results
         .into_iter()
         .map(|r| ScanResultItem {
+            is_registry: r.is_registry(),
             game_name: r.game_name,
             save_path: r.save_path,
             tags: r.tags,
@@ -285,6 +311,9 @@ fn handle_scan_wine_prefix(prefix: Option<String>, list: bool) -> Result<()> {
         .select_padded(items)?;
 
     match result {
+        FzfResult::Selected(item) if item.is_registry => {
+            track_registry_key(&item.game_name, &prefix_path, &item.save_path)
+        }
         FzfResult::Selected(item) => {
             // Pre-fill add game with discovered details
             handle_add(AddGameOptions {
@@ -301,7 +330,104 @@ fn handle_scan_wine_prefix(prefix: Option<String>, list: bool) -> Result<()> {
     }
 }
 
+/// Attach a registry key to a game's installation, adding the game first if needed
+fn track_registry_key(game_name: &str, prefix: &std::path::Path, key: &str) -> Result<()> {
+    use crate::common::TildePath;
+    use crate::game::config::{InstallationsConfig, InstantGameConfig, RegistrySaveKey};
+    use crate::ui::nerd_font::NerdFont;
+    use anyhow::Context;
+
+    let is_installed = |installations: &InstallationsConfig| {
+        installations
+            .installations
+            .iter()
+            .any(|inst| inst.game_name.0 == game_name)
+    };
+
+    let mut installations = InstallationsConfig::load()?;
+    if !is_installed(&installations) {
+        println!(
+            "{} '{}' is not tracked yet. Add it with a save directory to store the registry export in.",
+            char::from(NerdFont::Info),
+            game_name
+        );
+        handle_add(AddGameOptions {
+            name: Some(game_name.to_string()),
+            description: None,
+            launch_command: None,
+            save_path: None,
+            create_save_path: false,
+            no_cache: false,
+        })?;
+        installations = InstallationsConfig::load()?;
+        if !is_installed(&installations) {
+            return Ok(());
+        }
+    }
+
+    let installation = installations
+        .installations
+        .iter_mut()
+        .find(|inst| inst.game_name.0 == game_name)
+        .ok_or_else(|| anyhow::anyhow!("Game '{}' is not configured on this device", game_name))?;
+
+    if !installation.save_path_type.is_directory() {
+        return Err(anyhow::anyhow!(
+            "Registry keys can only be tracked for games with a save directory"
+        ));
+    }
+
+    if installation
+        .registry_keys
+        .iter()
+        .any(|existing| existing.key.eq_ignore_ascii_case(key))
+    {
+        println!(
+            "{} Registry key {} is already tracked for '{}'.",
+            char::from(NerdFont::Info),
+            key,
+            game_name
+        );
+        return Ok(());
+    }
+
+    let tracked = RegistrySaveKey {
+        prefix: TildePath::new(prefix.to_path_buf()),
+        key: key.to_string(),
+    };
+    installation.registry_keys.push(tracked);
+    installations.save()?;
+
+    // Shared without the prefix, which `ins game setup` resolves on each device
+    let mut game_config = InstantGameConfig::load().context("Failed to load game configuration")?;
+    if let Some(game) = game_config
+        .games
+        .iter_mut()
+        .find(|game| game.name.0 == game_name)
+        && !game
+            .registry_keys
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(key))
+    {
+        game.registry_keys.push(key.to_string());
+        game_config.save()?;
+    }
+
+    println!(
+        "{} Tracking registry key {} for '{}'. It is exported into the save directory on each backup.",
+        char::from(NerdFont::Check),
+        key,
+        game_name
+    );
+    Ok(())
+}
+
 fn handle_init(debug: bool, repo: Option<String>, password: Option<String>) -> Result<()> {
+    let password = password.or_else(|| {
+        std::env::var(super::cli::GAME_PASSWORD_ENV)
+            .ok()
+            .filter(|password| !password.is_empty())
+    });
     GameRepositoryManager::initialize_game_manager(debug, InitOptions { repo, password })
 }
 
@@ -366,19 +492,42 @@ fn handle_dependency_command(command: DependencyCommands) -> Result<()> {
             game_name,
             dependency_id,
             path,
+            version,
         } => add_dependency(AddDependencyOptions {
             game_name,
             dependency_id,
             source_path: path,
+            version,
         }),
         DependencyCommands::Install {
             game_name,
             dependency_id,
             path,
+            version,
         } => install_dependency(InstallDependencyOptions {
             game_name,
             dependency_id,
             install_path: path,
+            version,
+        }),
+        DependencyCommands::Update {
+            game_name,
+            dependency_id,
+            version,
+        } => update_dependency(UpdateDependencyOptions {
+            game_name,
+            dependency_id,
+            version,
+        }),
+        DependencyCommands::Status { game_name } => dependency_status(game_name),
+        DependencyCommands::Rollback {
+            game_name,
+            dependency_id,
+            version,
+        } => rollback_dependency(RollbackDependencyOptions {
+            game_name,
+            dependency_id,
+            version,
         }),
         DependencyCommands::Uninstall {
             game_name,
@@ -133,6 +133,10 @@ pub struct Game {
     pub launch_command: Option<LaunchCommand>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub dependencies: Vec<GameDependency>,
+    /// Registry key paths saved with this game, e.g. `HKEY_CURRENT_USER\Software\Studio\Game`.
+    /// The Wine prefix differs per device, so it is resolved from each installation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registry_keys: Vec<String>,
 }
 
 impl Game {
@@ -142,6 +146,7 @@ impl Game {
             description: None,
             launch_command: None,
             dependencies: Vec::new(),
+            registry_keys: Vec::new(),
         }
     }
 }
@@ -153,6 +158,48 @@ pub struct GameDependency {
     pub source_path: String,
     #[serde(default)]
     pub source_type: PathContentKind,
+    /// Known versions of this dependency, oldest first. Each version maps to one snapshot.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<DependencyVersion>,
+}
+
+impl GameDependency {
+    /// The most recently recorded version, if the dependency is versioned
+    pub fn latest_version(&self) -> Option<&DependencyVersion> {
+        self.versions.last()
+    }
+
+    pub fn find_version(&self, version: &str) -> Option<&DependencyVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+
+    /// Label used when no explicit version is given (`v1`, `v2`, ...)
+    pub fn next_version_label(&self) -> String {
+        let mut index = self.versions.len() + 1;
+        loop {
+            let label = format!("v{index}");
+            if self.find_version(&label).is_none() {
+                return label;
+            }
+            index += 1;
+        }
+    }
+
+    pub fn push_version(&mut self, version: impl Into<String>, snapshot_id: impl Into<String>) {
+        self.versions.push(DependencyVersion {
+            version: version.into(),
+            snapshot_id: snapshot_id.into(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+}
+
+/// A single snapshotted version of a game dependency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyVersion {
+    pub version: String,
+    pub snapshot_id: String,
+    pub created_at: String,
 }
 
 /// Game installation - device-specific
@@ -169,6 +216,9 @@ pub struct GameInstallation {
     pub launch_command: Option<LaunchCommand>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub dependencies: Vec<InstalledDependency>,
+    /// Registry keys exported into the save directory on backup and merged back on restore
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registry_keys: Vec<RegistrySaveKey>,
 }
 
 impl GameInstallation {
@@ -185,6 +235,7 @@ impl GameInstallation {
             checkpoint_time: None,
             launch_command: None,
             dependencies: Vec::new(),
+            registry_keys: Vec::new(),
         }
     }
 
@@ -203,6 +254,15 @@ impl GameInstallation {
     }
 }
 
+/// Registry key tracked as part of a game's saves on this device, stored in installations.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistrySaveKey {
+    /// Wine prefix whose `user.reg`/`system.reg` holds the key
+    pub prefix: TildePath,
+    /// Full key path, e.g. `HKEY_CURRENT_USER\Software\Studio\Game`
+    pub key: String,
+}
+
 /// Installed dependency mapping stored in installations.toml
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledDependency {
@@ -210,6 +270,9 @@ pub struct InstalledDependency {
     pub install_path: TildePath,
     #[serde(default)]
     pub install_path_type: PathContentKind,
+    /// Version restored onto this device; `None` for installs made before versioning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_version: Option<String>,
 }
 
 /// Main game configuration
@@ -351,6 +414,11 @@ description = "Optional description"
 id = "example-save-data"
 source_path = "~/Games/Example Game/Saves"
 source_type = "directory"
+
+[[games.dependencies.versions]]
+version = "v1"
+snapshot_id = "0123abcd"
+created_at = "2025-01-01T12:00:00Z"
 "#,
     retention_policy,
     "Optional top-level backup retention policy; omit to use defaults (keep-daily, keep-weekly, etc.)",
@@ -461,6 +529,70 @@ mod tests {
         assert!(rules.contains(&("keep-yearly".to_string(), "13".to_string())));
     }
 
+    #[test]
+    fn test_dependency_versions_round_trip() {
+        let toml_content = r#"
+            [[games]]
+            name = "Game1"
+
+            [[games.dependencies]]
+            id = "legacy-mod"
+            source_path = "/mods/legacy"
+
+            [[games.dependencies]]
+            id = "texture-pack"
+            source_path = "/mods/textures"
+
+            [[games.dependencies.versions]]
+            version = "v1"
+            snapshot_id = "aaaa"
+            created_at = "2025-01-01T12:00:00Z"
+
+            [[games.dependencies.versions]]
+            version = "1.2"
+            snapshot_id = "bbbb"
+            created_at = "2025-02-01T12:00:00Z"
+        "#;
+
+        let config: InstantGameConfig = toml::from_str(toml_content).expect("Parsing failed");
+        let dependencies = &config.games[0].dependencies;
+
+        assert!(dependencies[0].versions.is_empty());
+        assert!(dependencies[0].latest_version().is_none());
+        assert_eq!(dependencies[0].next_version_label(), "v1");
+
+        let textures = &dependencies[1];
+        assert_eq!(textures.latest_version().unwrap().snapshot_id, "bbbb");
+        assert_eq!(textures.find_version("v1").unwrap().snapshot_id, "aaaa");
+        assert_eq!(textures.next_version_label(), "v3");
+
+        let mut colliding = textures.clone();
+        colliding.push_version("v3", "cccc");
+        assert_eq!(colliding.next_version_label(), "v4");
+    }
+
+    #[test]
+    fn test_registry_keys_are_shared_with_the_game() {
+        let toml_content = r#"
+            [[games]]
+            name = "Game1"
+            registry_keys = ['HKEY_CURRENT_USER\Software\Studio\Game']
+
+            [[games]]
+            name = "Game2"
+        "#;
+
+        let config: InstantGameConfig = toml::from_str(toml_content).expect("Parsing failed");
+        let keys = &config.games[0].registry_keys;
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0], "HKEY_CURRENT_USER\\Software\\Studio\\Game");
+        assert!(config.games[1].registry_keys.is_empty());
+
+        let serialized = toml::to_string(&config).expect("Serializing failed");
+        assert_eq!(serialized.matches("registry_keys").count(), 1);
+        assert!(!serialized.contains("prefix"));
+    }
+
     #[test]
     fn test_validate_duplicate_games() {
         let toml_content = r#"
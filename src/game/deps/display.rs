@@ -1,7 +1,7 @@
 use anyhow::Result;
 use serde_json::json;
 
-use crate::game::config::{GameDependency, GameInstallation};
+use crate::game::config::{GameDependency, GameInstallation, InstalledDependency};
 use crate::ui::nerd_font::NerdFont;
 use crate::ui::prelude::*;
 
@@ -36,22 +36,39 @@ pub fn show_dependency_list(
 
     let mut data = Vec::new();
     for dependency in dependencies {
-        let installed_path = installation
-            .and_then(|inst| {
-                inst.dependencies
-                    .iter()
-                    .find(|installed| installed.dependency_id == dependency.id)
-            })
-            .and_then(|installed| installed.install_path.to_tilde_string().ok());
+        let installed = installation.and_then(|inst| {
+            inst.dependencies
+                .iter()
+                .find(|installed| installed.dependency_id == dependency.id)
+        });
+        let installed_path =
+            installed.and_then(|installed| installed.install_path.to_tilde_string().ok());
+        let installed_version = installed.and_then(|installed| installed.installed_version.clone());
+        let latest_version = dependency.latest_version().map(|v| v.version.clone());
 
         let kind_label = if dependency.source_type.is_file() {
             "File"
         } else {
             "Directory"
         };
+        text.push_str(&format!(
+            "{} {}\n",
+            char::from(NerdFont::Package),
+            dependency.id
+        ));
         text.push_str(&format!("   • Kind: {kind_label}\n"));
+        if let Some(latest) = &latest_version {
+            text.push_str(&format!(
+                "   • Latest version: {} ({} total)\n",
+                latest,
+                dependency.versions.len()
+            ));
+        }
         if let Some(path) = &installed_path {
             text.push_str(&format!("   • Installed at: {}\n", path));
+            if let Some(version) = &installed_version {
+                text.push_str(&format!("   • Installed version: {}\n", version));
+            }
         } else {
             text.push_str("   • Installed at: <not installed>\n");
         }
@@ -62,6 +79,8 @@ pub fn show_dependency_list(
             "source_path": dependency.source_path,
             "kind": kind_label,
             "installed_path": installed_path,
+            "installed_version": installed_version,
+            "latest_version": latest_version,
         }));
     }
 
@@ -78,3 +97,203 @@ pub fn show_dependency_list(
 
     Ok(())
 }
+
+/// A dependency paired with its installation on this device, if any
+pub struct DependencyStatusEntry<'a> {
+    pub game_name: &'a str,
+    pub dependency: &'a GameDependency,
+    pub installed: Option<&'a InstalledDependency>,
+}
+
+/// How an installed dependency compares to the latest recorded version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyState {
+    UpToDate,
+    Outdated,
+    NotInstalled,
+    /// Installed, but either the dependency or the install predates versioning
+    Unknown,
+}
+
+impl DependencyState {
+    pub fn of(dependency: &GameDependency, installed: Option<&InstalledDependency>) -> Self {
+        let Some(installed) = installed else {
+            return DependencyState::NotInstalled;
+        };
+        match (dependency.latest_version(), &installed.installed_version) {
+            (Some(latest), Some(version)) if latest.version == *version => {
+                DependencyState::UpToDate
+            }
+            (Some(_), Some(_)) => DependencyState::Outdated,
+            _ => DependencyState::Unknown,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            DependencyState::UpToDate => "up to date",
+            DependencyState::Outdated => "out of date",
+            DependencyState::NotInstalled => "not installed",
+            DependencyState::Unknown => "unknown version",
+        }
+    }
+
+    fn icon(self) -> NerdFont {
+        match self {
+            DependencyState::UpToDate => NerdFont::Check,
+            DependencyState::Outdated => NerdFont::Warning,
+            DependencyState::NotInstalled => NerdFont::Info,
+            DependencyState::Unknown => NerdFont::Question,
+        }
+    }
+}
+
+pub fn show_dependency_status(entries: &[DependencyStatusEntry<'_>]) -> Result<()> {
+    if entries.is_empty() {
+        emit(
+            Level::Info,
+            "game.deps.status.empty",
+            &format!(
+                "{} No game dependencies are registered.",
+                char::from(NerdFont::Info)
+            ),
+            Some(json!({ "dependency_count": 0 })),
+        );
+        return Ok(());
+    }
+
+    let mut text = String::new();
+    text.push_str(&format!(
+        "{} Dependency status on this device\n\n",
+        char::from(NerdFont::Package)
+    ));
+
+    let mut data = Vec::new();
+    let mut outdated = 0;
+    for entry in entries {
+        let state = DependencyState::of(entry.dependency, entry.installed);
+        if state == DependencyState::Outdated {
+            outdated += 1;
+        }
+
+        let latest_version = entry.dependency.latest_version().map(|v| v.version.clone());
+        let installed_version = entry
+            .installed
+            .and_then(|installed| installed.installed_version.clone());
+        let install_path = entry.installed.map(|installed| {
+            installed
+                .install_path
+                .to_tilde_string()
+                .unwrap_or_else(|_| installed.install_path.as_path().display().to_string())
+        });
+
+        text.push_str(&format!(
+            "{} {} / {}: {}\n",
+            char::from(state.icon()),
+            entry.game_name,
+            entry.dependency.id,
+            state.label()
+        ));
+        text.push_str(&format!(
+            "   • Latest: {}\n",
+            latest_version.as_deref().unwrap_or("<unversioned>")
+        ));
+        if let Some(path) = &install_path {
+            text.push_str(&format!(
+                "   • Installed: {} at {}\n",
+                installed_version.as_deref().unwrap_or("<unknown>"),
+                path
+            ));
+        }
+        text.push('\n');
+
+        data.push(json!({
+            "game": entry.game_name,
+            "dependency": entry.dependency.id,
+            "state": state.label(),
+            "latest_version": latest_version,
+            "installed_version": installed_version,
+            "install_path": install_path,
+        }));
+    }
+
+    if outdated > 0 {
+        text.push_str(&format!(
+            "{} {} dependenc{} out of date. Run '{} game deps install --version <version>' or '{} game deps rollback' to switch versions.\n",
+            char::from(NerdFont::Warning),
+            outdated,
+            if outdated == 1 { "y is" } else { "ies are" },
+            env!("CARGO_BIN_NAME"),
+            env!("CARGO_BIN_NAME")
+        ));
+    }
+
+    emit(
+        Level::Info,
+        "game.deps.status",
+        &text,
+        Some(json!({
+            "dependency_count": entries.len(),
+            "outdated_count": outdated,
+            "dependencies": data
+        })),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TildePath;
+    use crate::game::config::PathContentKind;
+    use std::path::PathBuf;
+
+    fn dependency_with_versions(versions: &[&str]) -> GameDependency {
+        let mut dependency = GameDependency {
+            id: "mod".to_string(),
+            source_path: "/mods/mod".to_string(),
+            source_type: PathContentKind::Directory,
+            versions: Vec::new(),
+        };
+        for (index, version) in versions.iter().enumerate() {
+            dependency.push_version(*version, format!("snap{index}"));
+        }
+        dependency
+    }
+
+    fn installed(version: Option<&str>) -> InstalledDependency {
+        InstalledDependency {
+            dependency_id: "mod".to_string(),
+            install_path: TildePath::new(PathBuf::from("/games/mod")),
+            install_path_type: PathContentKind::Directory,
+            installed_version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_dependency_state() {
+        let dependency = dependency_with_versions(&["v1", "v2"]);
+
+        assert_eq!(
+            DependencyState::of(&dependency, None),
+            DependencyState::NotInstalled
+        );
+        assert_eq!(
+            DependencyState::of(&dependency, Some(&installed(Some("v2")))),
+            DependencyState::UpToDate
+        );
+        assert_eq!(
+            DependencyState::of(&dependency, Some(&installed(Some("v1")))),
+            DependencyState::Outdated
+        );
+        assert_eq!(
+            DependencyState::of(&dependency, Some(&installed(None))),
+            DependencyState::Unknown
+        );
+        assert_eq!(
+            DependencyState::of(&dependency_with_versions(&[]), Some(&installed(None))),
+            DependencyState::Unknown
+        );
+    }
+}
@@ -5,8 +5,8 @@ use anyhow::{Context, Result, anyhow};
 
 use crate::common::TildePath;
 use crate::game::config::{
-    Game, GameDependency, GameInstallation, InstallationsConfig, InstalledDependency,
-    InstantGameConfig, PathContentKind,
+    DependencyVersion, Game, GameDependency, GameInstallation, InstallationsConfig,
+    InstalledDependency, InstantGameConfig, PathContentKind,
 };
 use crate::game::deps::{display, selection};
 use crate::game::games::selection::select_game_interactive;
@@ -24,12 +24,26 @@ pub struct AddDependencyOptions {
     pub game_name: Option<String>,
     pub dependency_id: Option<String>,
     pub source_path: Option<String>,
+    pub version: Option<String>,
 }
 
 pub struct InstallDependencyOptions {
     pub game_name: Option<String>,
     pub dependency_id: Option<String>,
     pub install_path: Option<String>,
+    pub version: Option<String>,
+}
+
+pub struct UpdateDependencyOptions {
+    pub game_name: Option<String>,
+    pub dependency_id: Option<String>,
+    pub version: Option<String>,
+}
+
+pub struct RollbackDependencyOptions {
+    pub game_name: Option<String>,
+    pub dependency_id: Option<String>,
+    pub version: Option<String>,
 }
 
 pub struct UninstallDependencyOptions {
@@ -114,6 +128,7 @@ impl DependencyContext {
         installation: &mut GameInstallation,
         dependency_id: &str,
         install_path: TildePath,
+        installed_version: Option<String>,
     ) {
         let install_path_type = if let Ok(metadata) = fs::metadata(install_path.as_path()) {
             PathContentKind::from(metadata)
@@ -128,11 +143,13 @@ impl DependencyContext {
         {
             existing.install_path = install_path;
             existing.install_path_type = install_path_type;
+            existing.installed_version = installed_version;
         } else {
             installation.dependencies.push(InstalledDependency {
                 dependency_id: dependency_id.to_string(),
                 install_path,
                 install_path_type,
+                installed_version,
             });
         }
     }
@@ -192,6 +209,7 @@ pub fn add_dependency(options: AddDependencyOptions) -> Result<()> {
         game_name: game_name_arg,
         dependency_id: dependency_id_arg,
         source_path: source_path_arg,
+        version: version_arg,
     } = options;
 
     let game_name = resolve_game_name(game_name_arg, Some("Select game to add dependency"))?;
@@ -201,6 +219,14 @@ pub fn add_dependency(options: AddDependencyOptions) -> Result<()> {
     let dependency_id = resolve_dependency_id(dependency_id_arg, &game_name, game)?;
     let source = prepare_dependency_source(&game_name, source_path_arg)?;
 
+    let mut dependency = GameDependency {
+        id: dependency_id.clone(),
+        source_path: source.expanded.to_string_lossy().to_string(),
+        source_type: source.kind,
+        versions: Vec::new(),
+    };
+    let version = resolve_version_label(version_arg, &dependency)?;
+
     println!(
         "{} Creating snapshot for '{}' dependency. This may take a while...",
         char::from(NerdFont::Info),
@@ -214,12 +240,7 @@ pub fn add_dependency(options: AddDependencyOptions) -> Result<()> {
         context.game_config(),
     )?;
 
-    let dependency = GameDependency {
-        id: dependency_id.clone(),
-        source_path: source.expanded.to_string_lossy().to_string(),
-        source_type: source.kind,
-    };
-
+    dependency.push_version(version.clone(), backup.snapshot_id.clone());
     context.game_mut(&game_name)?.dependencies.push(dependency);
 
     let installation = context.installation_mut(&game_name)?;
@@ -227,6 +248,7 @@ pub fn add_dependency(options: AddDependencyOptions) -> Result<()> {
         installation,
         &dependency_id,
         source.tilde.clone(),
+        Some(version.clone()),
     );
 
     context.save_all()?;
@@ -235,9 +257,10 @@ pub fn add_dependency(options: AddDependencyOptions) -> Result<()> {
         Level::Success,
         "game.deps.add",
         &format!(
-            "{} Added dependency '{}' for '{}' (snapshot: {}). Installed at {}.",
+            "{} Added dependency '{}' {} for '{}' (snapshot: {}). Installed at {}.",
             char::from(NerdFont::Check),
             dependency_id,
+            version,
             game_name,
             backup.snapshot_id,
             source.display_path()
@@ -245,6 +268,7 @@ pub fn add_dependency(options: AddDependencyOptions) -> Result<()> {
         Some(serde_json::json!({
             "game": game_name,
             "dependency": dependency_id,
+            "version": version,
             "snapshot": backup.snapshot_id,
             "reused_existing": backup.reused_existing,
             "install_path": source.display_path()
@@ -261,6 +285,7 @@ pub fn install_dependency(options: InstallDependencyOptions) -> Result<()> {
         game_name: game_name_arg,
         dependency_id: dependency_id_arg,
         install_path: install_path_arg,
+        version: version_arg,
     } = options;
 
     let game_name = resolve_game_name(game_name_arg, Some("Select game to install dependency"))?;
@@ -275,7 +300,8 @@ pub fn install_dependency(options: InstallDependencyOptions) -> Result<()> {
         return Ok(());
     }
 
-    let selected_dependency = select_dependency_for_install(game, dependency_id_arg, &game_name)?;
+    let selected_dependency = select_existing_dependency(game, dependency_id_arg, &game_name)?;
+    let version = find_requested_version(&selected_dependency, version_arg)?;
 
     let dependency_id = selected_dependency.id.clone();
     let target_path_input = resolve_install_path(
@@ -302,15 +328,18 @@ pub fn install_dependency(options: InstallDependencyOptions) -> Result<()> {
     let restore_outcome = restore_dependency(
         &game_name,
         &selected_dependency,
+        version.as_ref().map(|v| v.snapshot_id.as_str()),
         context.game_config(),
         install_path_tilde.as_path(),
     )?;
 
+    let version_label = version.map(|v| v.version);
     let installation = context.installation_mut(&game_name)?;
     DependencyContext::upsert_installed_dependency(
         installation,
         &dependency_id,
         install_path_tilde.clone(),
+        version_label.clone(),
     );
     context.save_installations()?;
 
@@ -322,9 +351,13 @@ pub fn install_dependency(options: InstallDependencyOptions) -> Result<()> {
         Level::Success,
         "game.deps.install",
         &format!(
-            "{} Installed dependency '{}' for '{}' into {} (snapshot: {}).",
+            "{} Installed dependency '{}'{} for '{}' into {} (snapshot: {}).",
             char::from(NerdFont::Check),
             dependency_id,
+            version_label
+                .as_deref()
+                .map(|v| format!(" {v}"))
+                .unwrap_or_default(),
             game_name,
             install_display,
             restore_outcome.snapshot_id
@@ -332,6 +365,7 @@ pub fn install_dependency(options: InstallDependencyOptions) -> Result<()> {
         Some(serde_json::json!({
             "game": game_name,
             "dependency": dependency_id,
+            "version": version_label,
             "snapshot": restore_outcome.snapshot_id,
             "summary": restore_outcome.summary,
             "install_path": install_display
@@ -417,6 +451,283 @@ pub fn uninstall_dependency(options: UninstallDependencyOptions) -> Result<()> {
     Ok(())
 }
 
+pub fn update_dependency(options: UpdateDependencyOptions) -> Result<()> {
+    let mut context = DependencyContext::load_with_validation()?;
+
+    let UpdateDependencyOptions {
+        game_name: game_name_arg,
+        dependency_id: dependency_id_arg,
+        version: version_arg,
+    } = options;
+
+    let game_name = resolve_game_name(game_name_arg, Some("Select game to update dependency"))?;
+    let game = context.game(&game_name)?;
+
+    if game.dependencies.is_empty() {
+        println!(
+            "{} Game '{}' has no registered dependencies.",
+            char::from(NerdFont::Info),
+            game_name
+        );
+        return Ok(());
+    }
+
+    let dependency = select_existing_dependency(game, dependency_id_arg, &game_name)?;
+    let explicit_version = version_arg.is_some();
+    let version = resolve_version_label(version_arg, &dependency)?;
+
+    let source_path = PathBuf::from(&dependency.source_path);
+    ensure_safe_path(&source_path, PathUsage::DependencySource)?;
+    let metadata = fs::metadata(&source_path).with_context(|| {
+        format!(
+            "Dependency source path {} is not available on this device. Run the update on the device that holds the dependency source.",
+            format_path_for_display(&dependency.source_path)
+        )
+    })?;
+    if PathContentKind::from(metadata) != dependency.source_type {
+        return Err(anyhow!(
+            "Dependency source path {} no longer matches the recorded kind ({})",
+            format_path_for_display(&dependency.source_path),
+            if dependency.source_type.is_file() {
+                "file"
+            } else {
+                "directory"
+            }
+        ));
+    }
+
+    println!(
+        "{} Creating snapshot for '{}' dependency. This may take a while...",
+        char::from(NerdFont::Info),
+        dependency.id
+    );
+
+    let backup = backup_dependency(
+        &game_name,
+        &dependency.id,
+        &source_path,
+        context.game_config(),
+    )?;
+
+    if backup.reused_existing
+        && !explicit_version
+        && let Some(latest) = dependency.latest_version()
+    {
+        emit(
+            Level::Info,
+            "game.deps.update.unchanged",
+            &format!(
+                "{} Dependency '{}' is unchanged since {}.",
+                char::from(NerdFont::Info),
+                dependency.id,
+                latest.version
+            ),
+            Some(serde_json::json!({
+                "game": game_name,
+                "dependency": dependency.id,
+                "version": latest.version,
+            })),
+        );
+        return Ok(());
+    }
+
+    let source_tilde = TildePath::new(source_path.clone());
+    context
+        .game_mut(&game_name)?
+        .dependencies
+        .iter_mut()
+        .find(|dep| dep.id == dependency.id)
+        .ok_or_else(|| anyhow!("Dependency '{}' not found", dependency.id))?
+        .push_version(version.clone(), backup.snapshot_id.clone());
+
+    // The device that captured the new version already has it on disk
+    if let Ok(installation) = context.installation_mut(&game_name)
+        && let Some(installed) = installation
+            .dependencies
+            .iter_mut()
+            .find(|dep| dep.dependency_id == dependency.id)
+        && installed.install_path.as_path() == source_tilde.as_path()
+    {
+        installed.installed_version = Some(version.clone());
+    }
+
+    context.save_all()?;
+
+    emit(
+        Level::Success,
+        "game.deps.update",
+        &format!(
+            "{} Recorded dependency '{}' {} for '{}' (snapshot: {}).",
+            char::from(NerdFont::Check),
+            dependency.id,
+            version,
+            game_name,
+            backup.snapshot_id
+        ),
+        Some(serde_json::json!({
+            "game": game_name,
+            "dependency": dependency.id,
+            "version": version,
+            "snapshot": backup.snapshot_id,
+            "previous_version": dependency.latest_version().map(|v| v.version.clone()),
+        })),
+    );
+
+    Ok(())
+}
+
+pub fn rollback_dependency(options: RollbackDependencyOptions) -> Result<()> {
+    let mut context = DependencyContext::load_with_validation()?;
+
+    let RollbackDependencyOptions {
+        game_name: game_name_arg,
+        dependency_id: dependency_id_arg,
+        version: version_arg,
+    } = options;
+
+    let game_name = resolve_game_name(game_name_arg, Some("Select game to roll back dependency"))?;
+    let game = context.game(&game_name)?;
+
+    if game.dependencies.is_empty() {
+        println!(
+            "{} Game '{}' has no registered dependencies.",
+            char::from(NerdFont::Info),
+            game_name
+        );
+        return Ok(());
+    }
+
+    let dependency = select_existing_dependency(game, dependency_id_arg, &game_name)?;
+
+    if dependency.versions.is_empty() {
+        return Err(anyhow!(
+            "Dependency '{}' has no recorded versions. Run '{} game deps update' to create one.",
+            dependency.id,
+            env!("CARGO_BIN_NAME")
+        ));
+    }
+
+    let installed = context
+        .installation_mut(&game_name)?
+        .dependencies
+        .iter()
+        .find(|dep| dep.dependency_id == dependency.id)
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "Dependency '{}' is not installed for '{}' on this device. Use '{} game deps install' instead.",
+                dependency.id,
+                game_name,
+                env!("CARGO_BIN_NAME")
+            )
+        })?;
+
+    let version = match version_arg {
+        Some(requested) => find_requested_version(&dependency, Some(requested))?
+            .ok_or_else(|| anyhow!("Version selection cancelled"))?,
+        None => selection::select_dependency_version(
+            &dependency,
+            installed.installed_version.as_deref(),
+        )?
+        .cloned()
+        .ok_or_else(|| anyhow!("Version selection cancelled"))?,
+    };
+
+    if installed.installed_version.as_deref() == Some(version.version.as_str()) {
+        println!(
+            "{} Dependency '{}' {} is already installed.",
+            char::from(NerdFont::Info),
+            dependency.id,
+            version.version
+        );
+        return Ok(());
+    }
+
+    let restore_outcome = restore_dependency(
+        &game_name,
+        &dependency,
+        Some(&version.snapshot_id),
+        context.game_config(),
+        installed.install_path.as_path(),
+    )?;
+
+    let installation = context.installation_mut(&game_name)?;
+    DependencyContext::upsert_installed_dependency(
+        installation,
+        &dependency.id,
+        installed.install_path.clone(),
+        Some(version.version.clone()),
+    );
+    context.save_installations()?;
+
+    let install_display = installed
+        .install_path
+        .to_tilde_string()
+        .unwrap_or_else(|_| installed.install_path.as_path().display().to_string());
+
+    emit(
+        Level::Success,
+        "game.deps.rollback",
+        &format!(
+            "{} Switched dependency '{}' for '{}' to {} in {} (snapshot: {}).",
+            char::from(NerdFont::Check),
+            dependency.id,
+            game_name,
+            version.version,
+            install_display,
+            restore_outcome.snapshot_id
+        ),
+        Some(serde_json::json!({
+            "game": game_name,
+            "dependency": dependency.id,
+            "version": version.version,
+            "previous_version": installed.installed_version,
+            "snapshot": restore_outcome.snapshot_id,
+            "install_path": install_display
+        })),
+    );
+
+    Ok(())
+}
+
+pub fn dependency_status(game_name: Option<String>) -> Result<()> {
+    let context = DependencyContext::load()?;
+
+    let games: Vec<&Game> = match game_name {
+        Some(name) => vec![context.game(&name)?],
+        None => context
+            .game_config
+            .games
+            .iter()
+            .filter(|game| !game.dependencies.is_empty())
+            .collect(),
+    };
+
+    let entries: Vec<display::DependencyStatusEntry<'_>> = games
+        .into_iter()
+        .flat_map(|game| {
+            let installation = context
+                .installations
+                .installations
+                .iter()
+                .find(|inst| inst.game_name == game.name);
+            game.dependencies
+                .iter()
+                .map(move |dependency| display::DependencyStatusEntry {
+                    game_name: &game.name.0,
+                    dependency,
+                    installed: installation.and_then(|inst| {
+                        inst.dependencies
+                            .iter()
+                            .find(|installed| installed.dependency_id == dependency.id)
+                    }),
+                })
+        })
+        .collect();
+
+    display::show_dependency_status(&entries)
+}
+
 pub fn list_dependencies(game_name: Option<String>) -> Result<()> {
     let context = DependencyContext::load()?;
 
@@ -489,7 +800,7 @@ fn resolve_dependency_id(
     Ok(trimmed.to_string())
 }
 
-fn select_dependency_for_install(
+fn select_existing_dependency(
     game: &Game,
     dependency_id: Option<String>,
     game_name: &str,
@@ -515,6 +826,50 @@ fn select_dependency_for_install(
         .cloned()
 }
 
+fn resolve_version_label(version: Option<String>, dependency: &GameDependency) -> Result<String> {
+    let Some(version) = version else {
+        return Ok(dependency.next_version_label());
+    };
+
+    let trimmed = version.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("Dependency version cannot be empty"));
+    }
+    if dependency.find_version(trimmed).is_some() {
+        return Err(anyhow!(
+            "Version '{}' already exists for dependency '{}'",
+            trimmed,
+            dependency.id
+        ));
+    }
+
+    Ok(trimmed.to_string())
+}
+
+/// Look up an explicitly requested version, defaulting to the latest one
+fn find_requested_version(
+    dependency: &GameDependency,
+    version: Option<String>,
+) -> Result<Option<DependencyVersion>> {
+    match version {
+        Some(requested) => {
+            let trimmed = requested.trim();
+            dependency
+                .find_version(trimmed)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Version '{}' not found for dependency '{}'",
+                        trimmed,
+                        dependency.id
+                    )
+                })
+        }
+        None => Ok(dependency.latest_version().cloned()),
+    }
+}
+
 fn resolve_source_path(path: Option<String>, game_name: &str) -> Result<String> {
     if let Some(path) = path {
         let trimmed = path.trim();
@@ -3,6 +3,8 @@ pub mod manager;
 pub mod selection;
 
 pub use manager::{
-    AddDependencyOptions, InstallDependencyOptions, UninstallDependencyOptions, add_dependency,
-    install_dependency, list_dependencies, uninstall_dependency,
+    AddDependencyOptions, InstallDependencyOptions, RollbackDependencyOptions,
+    UninstallDependencyOptions, UpdateDependencyOptions, add_dependency, dependency_status,
+    install_dependency, list_dependencies, rollback_dependency, uninstall_dependency,
+    update_dependency,
 };
@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 
 use std::path::PathBuf;
 
-use crate::game::config::GameDependency;
+use crate::game::config::{DependencyVersion, GameDependency};
 use crate::menu::protocol::FzfPreview;
 use crate::menu_utils::{FzfSelectable, FzfWrapper};
 use crate::ui::nerd_font::NerdFont;
@@ -49,6 +49,10 @@ impl<'a> FzfSelectable for DependencyOption<'a> {
             "Directory"
         };
         preview.push_str(&format!("Kind: {kind}\n"));
+        if let Some(latest) = self.dependency.latest_version() {
+            preview.push_str(&format!("Latest version: {}\n", latest.version));
+            preview.push_str(&format!("Versions: {}\n", self.dependency.versions.len()));
+        }
 
         FzfPreview::Text(preview)
     }
@@ -78,6 +82,63 @@ pub fn select_dependency<'a>(
     Ok(selection.map(|option| option.dependency))
 }
 
+#[derive(Clone)]
+struct DependencyVersionOption<'a> {
+    version: &'a DependencyVersion,
+    is_latest: bool,
+    is_installed: bool,
+}
+
+impl<'a> FzfSelectable for DependencyVersionOption<'a> {
+    fn fzf_display_text(&self) -> String {
+        let mut label = format!("{} {}", char::from(NerdFont::Tag), self.version.version);
+        if self.is_latest {
+            label.push_str(" (latest)");
+        }
+        if self.is_installed {
+            label.push_str(" (installed)");
+        }
+        label
+    }
+
+    fn fzf_key(&self) -> String {
+        self.version.version.clone()
+    }
+
+    fn fzf_preview(&self) -> FzfPreview {
+        FzfPreview::Text(format!(
+            "{} VERSION DETAILS\n\nVersion: {}\nSnapshot: {}\nCreated: {}\n",
+            char::from(NerdFont::Tag),
+            self.version.version,
+            self.version.snapshot_id,
+            self.version.created_at
+        ))
+    }
+}
+
+/// Pick a recorded version of a dependency, newest first
+pub fn select_dependency_version<'a>(
+    dependency: &'a GameDependency,
+    installed_version: Option<&str>,
+) -> Result<Option<&'a DependencyVersion>> {
+    let latest = dependency.latest_version().map(|v| v.version.as_str());
+    let options: Vec<DependencyVersionOption<'_>> = dependency
+        .versions
+        .iter()
+        .rev()
+        .map(|version| DependencyVersionOption {
+            version,
+            is_latest: latest == Some(version.version.as_str()),
+            is_installed: installed_version == Some(version.version.as_str()),
+        })
+        .collect();
+
+    let selection =
+        FzfWrapper::select_one(options).context("Failed to select dependency version")?;
+
+    Ok(selection.map(|option| option.version))
+}
+
 fn format_path_for_display(path: &str) -> String {
     let path_buf = PathBuf::from(path);
     crate::common::TildePath::new(path_buf)
@@ -8,7 +8,7 @@
 //! both setups.
 
 use std::collections::{HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::process::Command;
 
 use anyhow::Result;
@@ -17,6 +17,7 @@ use serde::Deserialize;
 use super::DiscoveredGame;
 use crate::common::TildePath;
 use crate::game::platforms::ludusavi::{DiscoveredWineSave, collect_primary_wine_prefix_saves};
+use crate::game::platforms::wine_registry::find_wine_prefix;
 use crate::menu::protocol::FzfPreview;
 use crate::ui::nerd_font::NerdFont;
 use crate::ui::preview::PreviewBuilder;
@@ -222,21 +223,6 @@ fn run_legendary_list_installed() -> Option<String> {
     None
 }
 
-/// Find the wine prefix root for a given path inside a prefix.
-/// Walks up the directory tree looking for a `drive_c` directory.
-fn find_wine_prefix(path: &Path) -> Option<PathBuf> {
-    let mut current = path;
-    loop {
-        if current.join("drive_c").exists() {
-            return Some(current.to_path_buf());
-        }
-        match current.parent() {
-            Some(parent) => current = parent,
-            None => return None,
-        }
-    }
-}
-
 /// Check if a Ludusavi game name matches an Epic game title.
 /// Uses case-insensitive matching with normalization so compact launcher
 /// titles like `RollerCoasterTycoon3` still match manifest names.
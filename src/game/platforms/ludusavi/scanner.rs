@@ -1,6 +1,6 @@
 //! Wine prefix scanner: reverse-lookup saves from Ludusavi manifest
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
@@ -8,7 +8,13 @@ use anyhow::Result;
 use glob::glob;
 
 use super::manifest::load_manifest;
-use super::types::{DiscoveredWineSave, FileConstraint, LudusaviManifest, choose_primary_save};
+use super::types::{
+    DiscoveredWineSave, FileConstraint, LudusaviManifest, MatchConfidence, choose_primary_save,
+    semantic_rank,
+};
+use crate::game::platforms::wine_registry::{
+    RegistryRoot, ShellFolders, WinePrefixRegistry, split_registry_path,
+};
 
 static WINDOWS_MANIFEST: OnceLock<std::result::Result<Vec<WindowsGameEntry>, String>> =
     OnceLock::new();
@@ -42,6 +48,7 @@ struct WindowsGameEntry {
     game_name: String,
     install_dirs: Vec<String>,
     files: Vec<WindowsFileEntry>,
+    registry: Vec<WindowsRegistryEntry>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +59,13 @@ struct WindowsFileEntry {
     has_store_user_id: bool,
 }
 
+#[derive(Debug, Clone)]
+struct WindowsRegistryEntry {
+    root: RegistryRoot,
+    key: String,
+    tags: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 struct UserPaths {
     name: String,
@@ -73,6 +87,8 @@ struct WinePrefixContext {
     base_directories: Vec<BaseDirectory>,
     base_expansion_roots: Vec<PathBuf>,
     root_candidates: Vec<String>,
+    registry: WinePrefixRegistry,
+    store_user_ids: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -98,7 +114,7 @@ impl WinePrefixContext {
             .or_else(|| dirs::config_dir().map(|p| p.to_string_lossy().to_string()))
             .unwrap_or_else(|| format!("{home_dir}/.config"));
 
-        let users = if users_dir.is_dir() {
+        let mut users = if users_dir.is_dir() {
             std::fs::read_dir(&users_dir)
                 .ok()
                 .map(|entries| {
@@ -142,6 +158,13 @@ impl WinePrefixContext {
             Vec::new()
         };
 
+        let registry = WinePrefixRegistry::load(prefix);
+        apply_shell_folders(&mut users, &registry.shell_folders(), &users_dir);
+        let win_program_data = registry
+            .program_data()
+            .filter(|path| path.is_dir())
+            .unwrap_or_else(|| drive_c.join("ProgramData"));
+
         let base_expansion_roots = collect_base_expansion_roots(prefix, options.install_hint_root);
 
         Self {
@@ -149,11 +172,28 @@ impl WinePrefixContext {
             home_dir,
             xdg_data,
             xdg_config,
-            win_program_data: drive_c.join("ProgramData").to_string_lossy().to_string(),
+            win_program_data: win_program_data.to_string_lossy().to_string(),
             win_dir: drive_c.join("Windows").to_string_lossy().to_string(),
             base_directories: collect_base_directories(&base_expansion_roots),
             base_expansion_roots,
             root_candidates: collect_root_candidates(&drive_c),
+            store_user_ids: registry.store_user_ids(),
+            registry,
+        }
+    }
+
+    /// Rank a path matched through `<storeUserId>` by whether it is the registry's store user
+    fn store_user_confidence(&self, matched_path: &Path) -> MatchConfidence {
+        let is_known_user = matched_path.components().any(|component| {
+            self.store_user_ids
+                .iter()
+                .any(|id| component.as_os_str() == id.as_str())
+        });
+
+        if is_known_user {
+            MatchConfidence::Registry
+        } else {
+            MatchConfidence::Wildcard
         }
     }
 
@@ -205,6 +245,50 @@ impl WinePrefixContext {
     }
 }
 
+/// Point user folders at the locations recorded in the prefix's `Shell Folders` key
+///
+/// The folders belong to the user whose profile they live in; with a single user the
+/// registry is trusted even if it points outside `drive_c/users`.
+fn apply_shell_folders(users: &mut [UserPaths], folders: &ShellFolders, users_dir: &Path) {
+    let candidates = [
+        &folders.documents,
+        &folders.app_data,
+        &folders.local_app_data,
+        &folders.local_app_data_low,
+    ];
+    let owner = candidates.iter().copied().flatten().find_map(|path| {
+        path.strip_prefix(users_dir)
+            .ok()?
+            .components()
+            .next()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+    });
+    let single_user = users.len() == 1;
+
+    for user in users
+        .iter_mut()
+        .filter(|user| single_user || owner.as_deref() == Some(user.name.as_str()))
+    {
+        let existing = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .filter(|path| path.is_dir())
+                .map(|path| path.to_string_lossy().to_string())
+        };
+        if let Some(path) = existing(&folders.documents) {
+            user.win_documents = path;
+        }
+        if let Some(path) = existing(&folders.app_data) {
+            user.win_app_data = path;
+        }
+        if let Some(path) = existing(&folders.local_app_data) {
+            user.win_local_app_data = path;
+        }
+        if let Some(path) = existing(&folders.local_app_data_low) {
+            user.win_local_app_data_low = path;
+        }
+    }
+}
+
 fn collect_root_candidates(drive_c: &Path) -> Vec<String> {
     let candidates = [
         drive_c
@@ -293,7 +377,7 @@ fn build_windows_manifest(manifest: LudusaviManifest) -> Vec<WindowsGameEntry> {
     let mut entries = Vec::new();
 
     for (game_name, entry) in manifest {
-        if entry.alias.is_some() || entry.files.is_empty() {
+        if entry.alias.is_some() || (entry.files.is_empty() && entry.registry.is_empty()) {
             continue;
         }
 
@@ -309,7 +393,21 @@ fn build_windows_manifest(manifest: LudusaviManifest) -> Vec<WindowsGameEntry> {
             })
             .collect();
 
-        if !files.is_empty() {
+        let registry: Vec<WindowsRegistryEntry> = entry
+            .registry
+            .into_iter()
+            .filter(|(_, registry_entry)| is_windows_registry_constraint(&registry_entry.when))
+            .filter_map(|(key, registry_entry)| {
+                let (root, key) = split_registry_path(&key)?;
+                Some(WindowsRegistryEntry {
+                    root,
+                    key,
+                    tags: registry_entry.tags,
+                })
+            })
+            .collect();
+
+        if !files.is_empty() || !registry.is_empty() {
             let mut install_dirs = entry.install_dir.into_keys().collect::<Vec<_>>();
             install_dirs.sort();
             install_dirs.dedup();
@@ -318,6 +416,7 @@ fn build_windows_manifest(manifest: LudusaviManifest) -> Vec<WindowsGameEntry> {
                 game_name,
                 install_dirs,
                 files,
+                registry,
             });
         }
     }
@@ -516,6 +615,16 @@ fn is_windows_constraint(pattern: &str, constraints: &[FileConstraint]) -> bool
     saw_unspecified_os && looks_windows_pattern(pattern)
 }
 
+/// Registry entries are Windows-only; skip those explicitly limited to other systems
+fn is_windows_registry_constraint(constraints: &[FileConstraint]) -> bool {
+    constraints.is_empty()
+        || constraints.iter().any(|constraint| {
+            constraint.os.as_deref().is_none_or(|os| {
+                os.eq_ignore_ascii_case("windows") || os.eq_ignore_ascii_case("win")
+            })
+        })
+}
+
 fn looks_windows_pattern(pattern: &str) -> bool {
     let lower = pattern.to_ascii_lowercase();
 
@@ -689,28 +798,56 @@ where
         for file in &entry.files {
             for expanded_path in ctx.expand_paths(entry, file) {
                 for matched_path in path_cache.matching_paths(&expanded_path) {
-                    game_results.push(DiscoveredWineSave::new(
-                        entry.game_name.clone(),
-                        matched_path.to_string_lossy().to_string(),
-                        file.tags.clone(),
-                        file.has_store_user_id,
-                    ));
+                    let confidence = if file.has_store_user_id {
+                        ctx.store_user_confidence(&matched_path)
+                    } else {
+                        MatchConfidence::Pattern
+                    };
+                    game_results.push(
+                        DiscoveredWineSave::new(
+                            entry.game_name.clone(),
+                            matched_path.to_string_lossy().to_string(),
+                            file.tags.clone(),
+                            file.has_store_user_id,
+                        )
+                        .with_confidence(confidence),
+                    );
                 }
             }
         }
 
+        for registry in &entry.registry {
+            if ctx.registry.contains_key(registry.root, &registry.key) {
+                game_results.push(DiscoveredWineSave::registry(
+                    entry.game_name.clone(),
+                    format!("{}\\{}", registry.root.name(), registry.key),
+                    registry.tags.clone(),
+                ));
+            }
+        }
+
         if game_results.is_empty() {
             continue;
         }
 
-        game_results.sort_by(|a, b| a.save_path.cmp(&b.save_path));
-        game_results.dedup_by(|a, b| a.save_path == b.save_path);
-        on_game(game_results)?;
+        on_game(rank_candidates(game_results))?;
     }
 
     Ok(())
 }
 
+/// Order the candidates of one game best-first and drop duplicate locations.
+///
+/// Filesystem saves come before registry keys, then saves before configs, and
+/// registry-confirmed store users before wildcard matches (see `semantic_rank`).
+fn rank_candidates(mut candidates: Vec<DiscoveredWineSave>) -> Vec<DiscoveredWineSave> {
+    candidates.sort_by_cached_key(|save| (semantic_rank(save), save.save_path.clone()));
+
+    let mut seen = HashSet::new();
+    candidates.retain(|save| seen.insert(save.save_path.clone()));
+    candidates
+}
+
 /// Collect the primary save per game from a wine prefix into a Vec.
 pub fn collect_primary_wine_prefix_saves(prefix: &Path) -> Vec<DiscoveredWineSave> {
     let mut results = Vec::new();
@@ -723,7 +860,7 @@ pub fn collect_primary_wine_prefix_saves(prefix: &Path) -> Vec<DiscoveredWineSav
     results
 }
 
-/// Collect all saves from a wine prefix into a sorted, deduplicated Vec.
+/// Collect all saves from a wine prefix, grouped by game and ranked best-first per game.
 pub fn collect_wine_prefix_saves(prefix: &Path) -> Result<Vec<DiscoveredWineSave>> {
     let mut results = Vec::new();
     stream_wine_prefix_saves(WinePrefixScanOptions::new(prefix), |game_saves| {
@@ -731,12 +868,8 @@ pub fn collect_wine_prefix_saves(prefix: &Path) -> Result<Vec<DiscoveredWineSave
         Ok(())
     })?;
 
-    results.sort_by(|a, b| {
-        a.game_name
-            .cmp(&b.game_name)
-            .then_with(|| a.save_path.cmp(&b.save_path))
-    });
-    results.dedup_by(|a, b| a.game_name == b.game_name && a.save_path == b.save_path);
+    // Stable sort keeps the per-game ranking from `rank_candidates`
+    results.sort_by(|a, b| a.game_name.cmp(&b.game_name));
 
     Ok(results)
 }
@@ -755,6 +888,7 @@ mod tests {
             files,
             alias: None,
             install_dir: HashMap::new(),
+            registry: HashMap::new(),
         }
     }
 
@@ -768,6 +902,7 @@ mod tests {
                 needs_user: pattern_uses_user_placeholders(pattern),
                 has_store_user_id: pattern.contains(STORE_USER_ID_PLACEHOLDER),
             }],
+            registry: Vec::new(),
         }
     }
 
@@ -809,6 +944,7 @@ mod tests {
                 alias: Some("Other".to_string()),
                 files: HashMap::new(),
                 install_dir: HashMap::new(),
+                registry: HashMap::new(),
             },
         );
 
@@ -841,6 +977,107 @@ mod tests {
         assert_eq!(filtered[0].files.len(), 1);
     }
 
+    #[test]
+    fn build_windows_manifest_keeps_registry_only_entries() {
+        let manifest = HashMap::from([(
+            "Registry Game".to_string(),
+            GameEntry {
+                registry: HashMap::from([(
+                    "HKEY_CURRENT_USER/Software/Studio/Registry Game".to_string(),
+                    FileEntry {
+                        tags: vec!["save".to_string()],
+                        when: vec![],
+                    },
+                )]),
+                ..empty_game_entry(HashMap::new())
+            },
+        )]);
+
+        let filtered = build_windows_manifest(manifest);
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].files.is_empty());
+        assert_eq!(filtered[0].registry[0].root, RegistryRoot::CurrentUser);
+        assert_eq!(
+            filtered[0].registry[0].key,
+            "Software\\Studio\\Registry Game"
+        );
+    }
+
+    #[test]
+    fn shell_folders_from_user_hive_override_documents() {
+        let prefix = tempfile::tempdir().unwrap();
+        let user_root = prefix
+            .path()
+            .join("drive_c")
+            .join("users")
+            .join("steamuser");
+        std::fs::create_dir_all(user_root.join("My Documents")).unwrap();
+        std::fs::write(
+            prefix.path().join("user.reg"),
+            "WINE REGISTRY Version 2\n\n\
+             [Software\\\\Microsoft\\\\Windows\\\\CurrentVersion\\\\Explorer\\\\Shell Folders] 1\n\
+             \"Personal\"=\"C:\\\\users\\\\steamuser\\\\My Documents\"\n",
+        )
+        .unwrap();
+
+        let ctx = test_context(prefix.path());
+        let game = test_windows_game("<winDocuments>/Game", &[]);
+
+        assert_eq!(
+            ctx.expand_paths(&game, &game.files[0]),
+            vec![
+                user_root
+                    .join("My Documents")
+                    .join("Game")
+                    .display()
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn store_user_from_registry_ranks_above_wildcard_matches() {
+        let prefix = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(prefix.path().join("drive_c")).unwrap();
+        std::fs::write(
+            prefix.path().join("user.reg"),
+            "WINE REGISTRY Version 2\n\n\
+             [Software\\\\Valve\\\\Steam\\\\ActiveProcess] 1\n\
+             \"ActiveUser\"=dword:0000002a\n",
+        )
+        .unwrap();
+        let ctx = test_context(prefix.path());
+
+        let remote = prefix.path().join("remote");
+        let active = remote.join("42");
+        let other = remote.join("77");
+        std::fs::create_dir_all(&active).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        assert_eq!(
+            ctx.store_user_confidence(&active),
+            MatchConfidence::Registry
+        );
+        assert_eq!(ctx.store_user_confidence(&other), MatchConfidence::Wildcard);
+
+        let save = |path: &Path, confidence| {
+            DiscoveredWineSave::new(
+                "Game".to_string(),
+                path.display().to_string(),
+                vec!["save".to_string()],
+                true,
+            )
+            .with_confidence(confidence)
+        };
+        let ranked = rank_candidates(vec![
+            save(&other, MatchConfidence::Wildcard),
+            save(&active, MatchConfidence::Registry),
+            save(&other, MatchConfidence::Wildcard),
+        ]);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].save_path, active.display().to_string());
+    }
+
     #[test]
     fn context_expands_without_recomputing_global_paths() {
         let prefix = tempfile::tempdir().unwrap();
@@ -20,6 +20,9 @@ pub struct GameEntry {
     pub alias: Option<String>,
     #[serde(default, rename = "installDir")]
     pub install_dir: HashMap<String, Value>,
+    /// Registry keys (e.g. `HKEY_CURRENT_USER/Software/Game`) holding save data
+    #[serde(default)]
+    pub registry: HashMap<String, FileEntry>,
 }
 
 /// A file/directory entry with constraints and tags
@@ -41,6 +44,26 @@ pub struct FileConstraint {
     pub store: Option<String>,
 }
 
+/// Where a discovered save lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiscoveredSaveKind {
+    #[default]
+    Path,
+    /// `save_path` holds a registry key such as `HKEY_CURRENT_USER\Software\Game`
+    Registry,
+}
+
+/// How closely a discovered save is tied to the prefix configuration (best first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum MatchConfidence {
+    /// Confirmed by registry data, e.g. the active store user or an existing key
+    Registry,
+    #[default]
+    Pattern,
+    /// A placeholder could only be matched with a wildcard
+    Wildcard,
+}
+
 /// A discovered save from a wine prefix scan
 #[derive(Debug, Clone)]
 pub struct DiscoveredWineSave {
@@ -48,6 +71,8 @@ pub struct DiscoveredWineSave {
     pub save_path: String,
     pub tags: Vec<String>,
     pub from_store_user_id: bool,
+    pub kind: DiscoveredSaveKind,
+    pub confidence: MatchConfidence,
 }
 
 impl DiscoveredWineSave {
@@ -62,9 +87,28 @@ impl DiscoveredWineSave {
             save_path,
             tags,
             from_store_user_id,
+            kind: DiscoveredSaveKind::Path,
+            confidence: MatchConfidence::Pattern,
+        }
+    }
+
+    pub fn registry(game_name: String, key: String, tags: Vec<String>) -> Self {
+        Self {
+            kind: DiscoveredSaveKind::Registry,
+            confidence: MatchConfidence::Registry,
+            ..Self::new(game_name, key, tags, false)
         }
     }
 
+    pub fn with_confidence(mut self, confidence: MatchConfidence) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn is_registry(&self) -> bool {
+        self.kind == DiscoveredSaveKind::Registry
+    }
+
     /// Returns true if this entry has a 'save' tag
     #[allow(dead_code)]
     pub fn is_save(&self) -> bool {
@@ -117,15 +161,20 @@ impl DiscoveredWineSave {
     }
 }
 
-fn semantic_rank(save: &DiscoveredWineSave) -> (bool, bool, bool, u8, usize, usize) {
+/// Sort key for save candidates; lower is better
+pub(super) fn semantic_rank(
+    save: &DiscoveredWineSave,
+) -> (bool, bool, bool, bool, MatchConfidence, u8, usize, usize) {
     let path = Path::new(&save.save_path);
     let is_dir = path.is_dir();
     let depth = path.components().count();
 
     (
+        save.is_registry(),
         !is_dir,
         !save.is_save(),
         save.is_config(),
+        save.confidence,
         save.store_user_id_match_quality(),
         depth,
         save.save_path.len(),
@@ -156,7 +205,8 @@ fn entry_size(path: &Path) -> u64 {
 }
 
 pub fn choose_primary_save(mut saves: Vec<DiscoveredWineSave>) -> Option<DiscoveredWineSave> {
-    saves.retain(DiscoveredWineSave::is_save);
+    // Registry saves need a save directory to be exported into, so never pick them here
+    saves.retain(|save| save.is_save() && !save.is_registry());
     if saves.is_empty() {
         return None;
     }
@@ -257,4 +307,40 @@ mod tests {
 
         assert!(selected.is_none());
     }
+
+    #[test]
+    fn choose_primary_save_prefers_registry_confirmed_store_user() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_root = temp.path().join("remote");
+        let other_user = game_root.join("11111111");
+        let active_user = game_root.join("22222222");
+        std::fs::create_dir_all(&other_user).unwrap();
+        std::fs::create_dir_all(&active_user).unwrap();
+        std::fs::write(other_user.join("save.dat"), [0_u8; 64]).unwrap();
+
+        let selected = choose_primary_save(vec![
+            DiscoveredWineSave::new(
+                "Game".to_string(),
+                other_user.display().to_string(),
+                vec!["save".to_string()],
+                true,
+            )
+            .with_confidence(MatchConfidence::Wildcard),
+            DiscoveredWineSave::new(
+                "Game".to_string(),
+                active_user.display().to_string(),
+                vec!["save".to_string()],
+                true,
+            )
+            .with_confidence(MatchConfidence::Registry),
+            DiscoveredWineSave::registry(
+                "Game".to_string(),
+                "HKEY_CURRENT_USER\\Software\\Game".to_string(),
+                vec!["save".to_string()],
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(selected.save_path, active_user.display().to_string());
+    }
 }
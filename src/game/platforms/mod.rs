@@ -23,6 +23,7 @@ mod prompts;
 mod steam_launcher;
 mod umu;
 mod validation;
+pub(crate) mod wine_registry;
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
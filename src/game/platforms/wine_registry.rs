@@ -0,0 +1,688 @@
+//! Wine registry hive parsing
+//!
+//! Reads the `user.reg` and `system.reg` hives of a wine prefix so save discovery can
+//! resolve shell folders and store user IDs, and so registry-based saves can be exported
+//! to `.reg` files and merged back into the hive on restore.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+const REG_EXPORT_HEADER: &str = "Windows Registry Editor Version 5.00";
+const SHELL_FOLDERS_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\Shell Folders";
+const STEAM_ACTIVE_PROCESS_KEY: &str = r"Software\Valve\Steam\ActiveProcess";
+/// Known folder ID Windows uses for `AppData\LocalLow`
+const LOCAL_LOW_FOLDER_ID: &str = "{A520A1A4-1780-4FF6-BD18-167343C5AF16}";
+
+/// Top-level registry root backed by a wine hive file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryRoot {
+    CurrentUser,
+    LocalMachine,
+}
+
+impl RegistryRoot {
+    pub fn hive_file(self) -> &'static str {
+        match self {
+            RegistryRoot::CurrentUser => "user.reg",
+            RegistryRoot::LocalMachine => "system.reg",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RegistryRoot::CurrentUser => "HKEY_CURRENT_USER",
+            RegistryRoot::LocalMachine => "HKEY_LOCAL_MACHINE",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "HKEY_CURRENT_USER" | "HKCU" => Some(RegistryRoot::CurrentUser),
+            "HKEY_LOCAL_MACHINE" | "HKLM" => Some(RegistryRoot::LocalMachine),
+            _ => None,
+        }
+    }
+}
+
+/// Split a full registry path such as `HKEY_CURRENT_USER/Software/Game` into its root
+/// and the hive-relative key (`Software\Game`). Accepts `/` and `\` separators.
+pub fn split_registry_path(path: &str) -> Option<(RegistryRoot, String)> {
+    let mut parts = path
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty());
+    let root = RegistryRoot::parse(parts.next()?)?;
+    let key = parts.collect::<Vec<_>>().join("\\");
+    if key.is_empty() {
+        return None;
+    }
+    Some((root, key))
+}
+
+/// A single value line of a registry key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryValue {
+    /// Value name, `None` for the default (`@`) value
+    pub name: Option<String>,
+    /// Raw data as written after `=`, e.g. `"text"` or `dword:00000001`
+    pub data: String,
+}
+
+impl RegistryValue {
+    /// Decode string data (`"..."` or Wine's `str(2):"..."`)
+    pub fn as_string(&self) -> Option<String> {
+        let data = self.data.strip_prefix("str(2):").unwrap_or(&self.data);
+        let quoted = data.strip_prefix('"')?.strip_suffix('"')?;
+        Some(unescape_string(quoted))
+    }
+
+    pub fn as_dword(&self) -> Option<u32> {
+        let hex = self.data.strip_prefix("dword:")?;
+        u32::from_str_radix(hex.trim(), 16).ok()
+    }
+}
+
+/// A key block in a hive: header plus raw body lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryKey {
+    /// Key path relative to the hive root, using single backslashes
+    pub path: String,
+    /// Text after the closing bracket of the header (Wine writes a timestamp there)
+    header_suffix: String,
+    /// Body lines, including `#time=` metadata and value lines
+    lines: Vec<String>,
+}
+
+impl RegistryKey {
+    fn new(path: String, lines: Vec<String>) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self {
+            path,
+            header_suffix: format!(" {timestamp}"),
+            lines,
+        }
+    }
+
+    pub fn values(&self) -> Vec<RegistryValue> {
+        logical_lines(&self.lines)
+            .into_iter()
+            .filter_map(|line| parse_value_line(&line))
+            .collect()
+    }
+
+    pub fn value(&self, name: &str) -> Option<RegistryValue> {
+        self.values().into_iter().find(|value| {
+            value
+                .name
+                .as_deref()
+                .is_some_and(|value_name| value_name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Body lines without Wine-specific `#` metadata, as used in `.reg` exports
+    fn value_lines(&self) -> impl Iterator<Item = &String> {
+        self.lines.iter().filter(|line| !line.starts_with('#'))
+    }
+
+    fn is_within(&self, root: &str) -> bool {
+        let path = self.path.to_ascii_lowercase();
+        let root = root.to_ascii_lowercase();
+        path == root || path.starts_with(&format!("{root}\\"))
+    }
+}
+
+/// Parsed Wine registry hive file (`user.reg`, `system.reg`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryHive {
+    preamble: Vec<String>,
+    keys: Vec<RegistryKey>,
+}
+
+impl RegistryHive {
+    pub fn parse(content: &str) -> Self {
+        let mut hive = RegistryHive::default();
+
+        for line in content.lines() {
+            if let Some((path, suffix)) = parse_key_header(line) {
+                hive.keys.push(RegistryKey {
+                    path: unescape_key_path(path),
+                    header_suffix: suffix.to_string(),
+                    lines: Vec::new(),
+                });
+                continue;
+            }
+
+            match hive.keys.last_mut() {
+                Some(key) if !line.trim().is_empty() => key.lines.push(line.to_string()),
+                Some(_) => {}
+                None => hive.preamble.push(line.to_string()),
+            }
+        }
+
+        while hive
+            .preamble
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+        {
+            hive.preamble.pop();
+        }
+
+        hive
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read registry hive {}", path.display()))?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_hive_string())
+            .with_context(|| format!("Failed to write registry hive {}", path.display()))
+    }
+
+    pub fn to_hive_string(&self) -> String {
+        let mut output = String::new();
+        for line in &self.preamble {
+            output.push_str(line);
+            output.push('\n');
+        }
+        output.push('\n');
+
+        for key in &self.keys {
+            output.push_str(&format!(
+                "[{}]{}\n",
+                escape_key_path(&key.path),
+                key.header_suffix
+            ));
+            for line in &key.lines {
+                output.push_str(line);
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    pub fn key(&self, path: &str) -> Option<&RegistryKey> {
+        self.keys
+            .iter()
+            .find(|key| key.path.eq_ignore_ascii_case(path))
+    }
+
+    /// The key and all of its descendants, in hive order
+    pub fn subtree<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a RegistryKey> {
+        self.keys.iter().filter(move |key| key.is_within(path))
+    }
+
+    pub fn contains_subtree(&self, path: &str) -> bool {
+        self.subtree(path).next().is_some()
+    }
+
+    /// Export a key with its subkeys in `.reg` format
+    pub fn export_reg(&self, root: RegistryRoot, path: &str) -> Option<String> {
+        let mut keys: Vec<&RegistryKey> = self.subtree(path).collect();
+        if keys.is_empty() {
+            return None;
+        }
+        // Parents must precede children so the first block names the exported root
+        keys.sort_by_key(|key| key.path.matches('\\').count());
+
+        let mut output = format!("{REG_EXPORT_HEADER}\n\n");
+        for key in keys {
+            output.push_str(&format!("[{}\\{}]\n", root.name(), key.path));
+            for line in key.value_lines() {
+                output.push_str(line);
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+        Some(output)
+    }
+
+    /// Replace the subtree rooted at `path` with `keys`
+    pub fn replace_subtree(&mut self, path: &str, keys: Vec<RegistryKey>) {
+        let insert_at = self
+            .keys
+            .iter()
+            .position(|key| key.is_within(path))
+            .unwrap_or(self.keys.len());
+        self.keys.retain(|key| !key.is_within(path));
+        let insert_at = insert_at.min(self.keys.len());
+        self.keys.splice(insert_at..insert_at, keys);
+    }
+}
+
+/// A parsed `.reg` export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryExport {
+    pub root: RegistryRoot,
+    /// Hive-relative path of the exported key (the first block in the file)
+    pub path: String,
+    pub keys: Vec<RegistryKey>,
+}
+
+impl RegistryExport {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut root = None;
+        let mut keys: Vec<RegistryKey> = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim_end();
+            if trimmed.is_empty()
+                || trimmed.starts_with(';')
+                || trimmed == REG_EXPORT_HEADER
+                || trimmed == "REGEDIT4"
+            {
+                continue;
+            }
+
+            if let Some(full_path) = trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                let (key_root, path) = split_registry_path(full_path)
+                    .ok_or_else(|| anyhow!("Unsupported registry key in export: {full_path}"))?;
+                if *root.get_or_insert(key_root) != key_root {
+                    return Err(anyhow!("Registry export mixes multiple root keys"));
+                }
+                keys.push(RegistryKey::new(path, Vec::new()));
+                continue;
+            }
+
+            let key = keys
+                .last_mut()
+                .ok_or_else(|| anyhow!("Registry export has values before the first key"))?;
+            key.lines.push(trimmed.to_string());
+        }
+
+        let root = root.ok_or_else(|| anyhow!("Registry export contains no keys"))?;
+        let path = keys[0].path.clone();
+        Ok(Self { root, path, keys })
+    }
+}
+
+/// Shell folder locations resolved from a user hive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellFolders {
+    pub documents: Option<PathBuf>,
+    pub app_data: Option<PathBuf>,
+    pub local_app_data: Option<PathBuf>,
+    pub local_app_data_low: Option<PathBuf>,
+}
+
+/// The registry hives of one wine prefix
+#[derive(Debug, Clone, Default)]
+pub struct WinePrefixRegistry {
+    prefix: PathBuf,
+    user: Option<RegistryHive>,
+    system: Option<RegistryHive>,
+}
+
+impl WinePrefixRegistry {
+    /// Load whichever hives exist; missing or unreadable hives are treated as empty
+    pub fn load(prefix: &Path) -> Self {
+        let load_hive = |root: RegistryRoot| {
+            let path = prefix.join(root.hive_file());
+            path.is_file()
+                .then(|| RegistryHive::load(&path).ok())
+                .flatten()
+        };
+
+        Self {
+            prefix: prefix.to_path_buf(),
+            user: load_hive(RegistryRoot::CurrentUser),
+            system: load_hive(RegistryRoot::LocalMachine),
+        }
+    }
+
+    pub fn hive(&self, root: RegistryRoot) -> Option<&RegistryHive> {
+        match root {
+            RegistryRoot::CurrentUser => self.user.as_ref(),
+            RegistryRoot::LocalMachine => self.system.as_ref(),
+        }
+    }
+
+    pub fn contains_key(&self, root: RegistryRoot, path: &str) -> bool {
+        self.hive(root)
+            .is_some_and(|hive| hive.contains_subtree(path))
+    }
+
+    pub fn export_key(&self, root: RegistryRoot, path: &str) -> Option<String> {
+        self.hive(root)?.export_reg(root, path)
+    }
+
+    pub fn shell_folders(&self) -> ShellFolders {
+        let Some(key) = self
+            .user
+            .as_ref()
+            .and_then(|hive| hive.key(SHELL_FOLDERS_KEY))
+        else {
+            return ShellFolders::default();
+        };
+        let resolve = |name: &str| {
+            key.value(name)
+                .and_then(|value| value.as_string())
+                .and_then(|path| windows_path_to_prefix(&self.prefix, &path))
+        };
+
+        ShellFolders {
+            documents: resolve("Personal"),
+            app_data: resolve("AppData"),
+            local_app_data: resolve("Local AppData"),
+            local_app_data_low: resolve(LOCAL_LOW_FOLDER_ID),
+        }
+    }
+
+    /// `ProgramData` location from the machine hive
+    pub fn program_data(&self) -> Option<PathBuf> {
+        self.system
+            .as_ref()?
+            .key(SHELL_FOLDERS_KEY)?
+            .value("Common AppData")?
+            .as_string()
+            .and_then(|path| windows_path_to_prefix(&self.prefix, &path))
+    }
+
+    /// Store account IDs recorded in the prefix (currently the active Steam user)
+    pub fn store_user_ids(&self) -> Vec<String> {
+        self.user
+            .as_ref()
+            .and_then(|hive| hive.key(STEAM_ACTIVE_PROCESS_KEY))
+            .and_then(|key| key.value("ActiveUser"))
+            .and_then(|value| value.as_dword())
+            .filter(|id| *id != 0)
+            .map(|id| vec![id.to_string()])
+            .unwrap_or_default()
+    }
+}
+
+/// Merge a `.reg` export into the matching hive of a wine prefix, replacing the exported
+/// key and its subkeys. Wine must not be running for the prefix, otherwise wineserver
+/// overwrites the hive on exit.
+pub fn import_reg_export(prefix: &Path, content: &str) -> Result<RegistryExport> {
+    let export = RegistryExport::parse(content)?;
+    let hive_path = prefix.join(export.root.hive_file());
+    let mut hive = if hive_path.is_file() {
+        RegistryHive::load(&hive_path)?
+    } else {
+        return Err(anyhow!(
+            "Registry hive {} does not exist; run the game once to initialize the prefix",
+            hive_path.display()
+        ));
+    };
+
+    hive.replace_subtree(&export.path, export.keys.clone());
+    hive.save(&hive_path)?;
+    Ok(export)
+}
+
+/// Find the wine prefix root for a given path inside a prefix.
+/// Walks up the directory tree looking for a `drive_c` directory.
+pub fn find_wine_prefix(path: &Path) -> Option<PathBuf> {
+    let mut current = path;
+    loop {
+        if current.join("drive_c").exists() {
+            return Some(current.to_path_buf());
+        }
+        match current.parent() {
+            Some(parent) => current = parent,
+            None => return None,
+        }
+    }
+}
+
+/// Map a Windows path like `C:\users\steamuser\Documents` into the prefix filesystem
+pub fn windows_path_to_prefix(prefix: &Path, windows_path: &str) -> Option<PathBuf> {
+    let (drive, rest) = windows_path.split_once(':')?;
+    let mut drive_chars = drive.chars();
+    let letter = drive_chars.next()?.to_ascii_lowercase();
+    if drive_chars.next().is_some() || !letter.is_ascii_alphabetic() {
+        return None;
+    }
+
+    // `c:` always points at drive_c; other letters are only known through dosdevices
+    let mut path = if letter == 'c' {
+        prefix.join("drive_c")
+    } else {
+        let device = prefix.join("dosdevices").join(format!("{letter}:"));
+        if !device.exists() {
+            return None;
+        }
+        device
+    };
+
+    for component in rest.split(['\\', '/']).filter(|c| !c.is_empty()) {
+        path.push(component);
+    }
+    Some(path)
+}
+
+fn parse_key_header(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let end = rest.rfind(']')?;
+    Some((&rest[..end], &rest[end + 1..]))
+}
+
+/// Join value lines continued with a trailing backslash (used by `hex:` data)
+fn logical_lines(lines: &[String]) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        let trimmed = if current.is_empty() {
+            line.as_str()
+        } else {
+            line.trim_start()
+        };
+        if let Some(continued) = trimmed.strip_suffix('\\')
+            && !trimmed.ends_with("\\\\")
+        {
+            current.push_str(continued);
+            continue;
+        }
+        current.push_str(trimmed);
+        result.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+        result.push(current);
+    }
+    result
+}
+
+fn parse_value_line(line: &str) -> Option<RegistryValue> {
+    if let Some(data) = line.strip_prefix("@=") {
+        return Some(RegistryValue {
+            name: None,
+            data: data.to_string(),
+        });
+    }
+
+    let rest = line.strip_prefix('"')?;
+    let mut escaped = false;
+    for (index, ch) in rest.char_indices() {
+        match ch {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => {
+                let data = rest[index + 1..].strip_prefix('=')?;
+                return Some(RegistryValue {
+                    name: Some(unescape_string(&rest[..index])),
+                    data: data.to_string(),
+                });
+            }
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn unescape_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+fn unescape_key_path(path: &str) -> String {
+    path.replace("\\\\", "\\")
+}
+
+fn escape_key_path(path: &str) -> String {
+    path.replace('\\', "\\\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_HIVE: &str = r#"WINE REGISTRY Version 2
+;; All keys relative to \\User\\S-1-5-21-0-0-0-1000
+
+#arch=win64
+
+[Software\\Game Studio\\Cool Game] 1700000000
+#time=1da1b2c3d4e5f60
+"Slot1"="level 3"
+"Unlocked"=dword:00000001
+
+[Software\\Game Studio\\Cool Game\\Options] 1700000000
+"Volume"=dword:00000050
+
+[Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Shell Folders] 1700000000
+"AppData"="C:\\users\\steamuser\\AppData\\Roaming"
+"Local AppData"="C:\\users\\steamuser\\AppData\\Local"
+"Personal"="C:\\users\\steamuser\\My Documents"
+"{A520A1A4-1780-4FF6-BD18-167343C5AF16}"="C:\\users\\steamuser\\AppData\\LocalLow"
+
+[Software\\Valve\\Steam\\ActiveProcess] 1700000000
+"ActiveUser"=dword:0012d687
+"#;
+
+    #[test]
+    fn split_registry_path_accepts_both_separators() {
+        assert_eq!(
+            split_registry_path("HKEY_CURRENT_USER/Software/Game"),
+            Some((RegistryRoot::CurrentUser, r"Software\Game".to_string()))
+        );
+        assert_eq!(
+            split_registry_path(r"HKLM\Software\Game"),
+            Some((RegistryRoot::LocalMachine, r"Software\Game".to_string()))
+        );
+        assert_eq!(split_registry_path("HKEY_CLASSES_ROOT/Foo"), None);
+        assert_eq!(split_registry_path("HKEY_CURRENT_USER"), None);
+    }
+
+    #[test]
+    fn parses_keys_and_values() {
+        let hive = RegistryHive::parse(USER_HIVE);
+        let key = hive.key(r"software\game studio\cool game").unwrap();
+
+        assert_eq!(
+            key.value("Slot1").unwrap().as_string().as_deref(),
+            Some("level 3")
+        );
+        assert_eq!(key.value("unlocked").unwrap().as_dword(), Some(1));
+        assert_eq!(hive.subtree(r"Software\Game Studio\Cool Game").count(), 2);
+    }
+
+    #[test]
+    fn hive_round_trips_unchanged_content() {
+        let hive = RegistryHive::parse(USER_HIVE);
+        assert_eq!(RegistryHive::parse(&hive.to_hive_string()), hive);
+    }
+
+    #[test]
+    fn joins_continued_hex_values() {
+        let lines = vec![
+            "\"Blob\"=hex:00,01,\\".to_string(),
+            "  02,03".to_string(),
+            "\"Path\"=\"C:\\\\\"".to_string(),
+        ];
+        let joined = logical_lines(&lines);
+        assert_eq!(joined[0], "\"Blob\"=hex:00,01,02,03");
+        assert_eq!(joined[1], "\"Path\"=\"C:\\\\\"");
+    }
+
+    #[test]
+    fn export_and_import_replace_subtree() {
+        let prefix = tempfile::tempdir().unwrap();
+        let hive_path = prefix.path().join("user.reg");
+        fs::write(&hive_path, USER_HIVE).unwrap();
+
+        let registry = WinePrefixRegistry::load(prefix.path());
+        let export = registry
+            .export_key(RegistryRoot::CurrentUser, r"Software\Game Studio\Cool Game")
+            .unwrap();
+        assert!(export.starts_with(REG_EXPORT_HEADER));
+        assert!(export.contains(r"[HKEY_CURRENT_USER\Software\Game Studio\Cool Game\Options]"));
+        assert!(!export.contains("#time="));
+
+        let modified = USER_HIVE.replace("level 3", "level 9").replace(
+            "[Software\\\\Game Studio\\\\Cool Game\\\\Options] 1700000000\n\"Volume\"=dword:00000050\n",
+            "",
+        );
+        fs::write(&hive_path, modified).unwrap();
+
+        let imported = import_reg_export(prefix.path(), &export).unwrap();
+        assert_eq!(imported.path, r"Software\Game Studio\Cool Game");
+
+        let hive = RegistryHive::load(&hive_path).unwrap();
+        let key = hive.key(r"Software\Game Studio\Cool Game").unwrap();
+        assert_eq!(
+            key.value("Slot1").unwrap().as_string().as_deref(),
+            Some("level 3")
+        );
+        assert!(
+            hive.key(r"Software\Game Studio\Cool Game\Options")
+                .is_some()
+        );
+        assert!(hive.key(SHELL_FOLDERS_KEY).is_some());
+    }
+
+    #[test]
+    fn resolves_shell_folders_and_store_user() {
+        let prefix = tempfile::tempdir().unwrap();
+        fs::write(prefix.path().join("user.reg"), USER_HIVE).unwrap();
+
+        let registry = WinePrefixRegistry::load(prefix.path());
+        let folders = registry.shell_folders();
+        let user_root = prefix
+            .path()
+            .join("drive_c")
+            .join("users")
+            .join("steamuser");
+
+        assert_eq!(folders.documents, Some(user_root.join("My Documents")));
+        assert_eq!(
+            folders.local_app_data_low,
+            Some(user_root.join("AppData").join("LocalLow"))
+        );
+        assert_eq!(registry.store_user_ids(), vec!["1234567".to_string()]);
+    }
+
+    #[test]
+    fn windows_path_requires_known_drive() {
+        let prefix = Path::new("/prefix");
+        assert_eq!(
+            windows_path_to_prefix(prefix, r"C:\users\me"),
+            Some(PathBuf::from("/prefix/drive_c/users/me"))
+        );
+        assert_eq!(windows_path_to_prefix(prefix, r"D:\Games"), None);
+        assert_eq!(windows_path_to_prefix(prefix, "relative"), None);
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use std::{fs, path::Path};
 
-use crate::game::config::{GameInstallation, InstantGameConfig, PathContentKind};
-use crate::game::restic::{cache, single_file, tags};
+use crate::game::config::{GameInstallation, InstantGameConfig, PathContentKind, RegistrySaveKey};
+use crate::game::restic::{cache, registry, single_file, tags};
 use crate::restic::ResticWrapper;
+use crate::ui::nerd_font::NerdFont;
+use crate::ui::prelude::*;
 
 /// Request parameters for restoring a game backup
 pub struct RestoreRequest<'a> {
@@ -13,6 +15,8 @@ pub struct RestoreRequest<'a> {
     pub save_path_type: PathContentKind,
     /// Optional hint for the snapshot source path (from cached snapshot metadata)
     pub snapshot_source_path: Option<&'a str>,
+    /// Registry keys to merge back from the restored save directory
+    pub registry_keys: &'a [RegistrySaveKey],
 }
 
 /// Backup game saves to restic repository with proper tagging
@@ -50,6 +54,16 @@ impl GameBackup {
             ));
         }
 
+        if !game_installation.registry_keys.is_empty() {
+            if !game_installation.save_path_type.is_directory() {
+                return Err(anyhow::anyhow!(
+                    "Registry keys can only be tracked for games with a save directory"
+                ));
+            }
+            registry::export_registry_keys(save_path_buf, &game_installation.registry_keys)
+                .context("Failed to export registry keys")?;
+        }
+
         let restic = ResticWrapper::new(
             self.config.repo.as_path().to_string_lossy().to_string(),
             self.config.repo_password.clone(),
@@ -135,6 +149,27 @@ impl GameBackup {
                 // For directories, use the standard restore
                 let summary =
                     self.restore_game_backup(request.game_name, request.snapshot_id, request.path)?;
+                let registry = registry::import_registry_keys(request.path, request.registry_keys)
+                    .context("Failed to import registry keys")?;
+                if registry.skipped > 0 {
+                    emit(
+                        Level::Warn,
+                        "game.restore.registry_skipped",
+                        &format!(
+                            "{} Skipped {} registry key(s) for '{}' because the Wine prefix is not initialized on this device. Run the game once, then restore again.",
+                            char::from(NerdFont::Warning),
+                            registry.skipped,
+                            request.game_name
+                        ),
+                        None,
+                    );
+                }
+                if registry.imported > 0 {
+                    return Ok(format!(
+                        "{summary}, imported {} registry key(s)",
+                        registry.imported
+                    ));
+                }
                 Ok(summary)
             }
             PathContentKind::File => {
@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::game::config::{GameDependency, InstantGameConfig, PathContentKind};
@@ -123,14 +123,25 @@ pub fn backup_dependency(
     })
 }
 
-/// Restore dependency snapshot content into target path (directories only)
-pub fn restore_dependency(
+/// Resolve the snapshot to restore for a dependency
+///
+/// Uses the requested snapshot when given, otherwise the latest recorded version and
+/// finally the newest tagged snapshot for dependencies created before versioning.
+fn resolve_restore_snapshot(
     game_name: &str,
     dependency: &GameDependency,
+    snapshot_id: Option<&str>,
     config: &InstantGameConfig,
-    install_path: &Path,
-) -> Result<DependencyRestoreOutcome> {
-    let snapshot_id = list_dependency_snapshots(game_name, &dependency.id, config)?
+) -> Result<String> {
+    if let Some(id) = snapshot_id {
+        return Ok(id.to_string());
+    }
+
+    if let Some(version) = dependency.latest_version() {
+        return Ok(version.snapshot_id.clone());
+    }
+
+    list_dependency_snapshots(game_name, &dependency.id, config)?
         .first()
         .map(|snapshot| snapshot.id.clone())
         .ok_or_else(|| {
@@ -139,7 +150,20 @@ pub fn restore_dependency(
                 game_name,
                 dependency.id
             )
-        })?;
+        })
+}
+
+/// Restore dependency snapshot content into target path
+///
+/// `snapshot_id` selects a specific version; `None` restores the latest one.
+pub fn restore_dependency(
+    game_name: &str,
+    dependency: &GameDependency,
+    snapshot_id: Option<&str>,
+    config: &InstantGameConfig,
+    install_path: &Path,
+) -> Result<DependencyRestoreOutcome> {
+    let snapshot_id = resolve_restore_snapshot(game_name, dependency, snapshot_id, config)?;
 
     let restic = ResticWrapper::new(
         config.repo.as_path().to_string_lossy().to_string(),
@@ -151,27 +175,34 @@ pub fn restore_dependency(
 
     let summary = match source_kind {
         PathContentKind::Directory => {
-            fs::create_dir_all(install_path).with_context(|| {
+            // Restore next to the target and swap it in afterwards, so files from the
+            // version being replaced do not linger and a failed restore changes nothing
+            let staging = sibling_path(install_path, "restore");
+            let _ = remove_path(&staging);
+            fs::create_dir_all(&staging).with_context(|| {
                 format!(
-                    "Failed to create dependency install directory: {}",
-                    install_path.display()
+                    "Failed to create dependency restore directory: {}",
+                    staging.display()
                 )
             })?;
 
             let progress = restic
-                .restore_with_filter(
-                    &snapshot_id,
-                    Some(&dependency.source_path),
-                    install_path,
-                    None,
-                )
+                .restore_with_filter(&snapshot_id, Some(&dependency.source_path), &staging, None)
                 .with_context(|| {
                     format!(
                         "Failed to restore dependency '{}:{}' from restic",
                         game_name, dependency.id
                     )
-                })?;
+                });
+            let progress = match progress {
+                Ok(progress) => progress,
+                Err(e) => {
+                    let _ = remove_path(&staging);
+                    return Err(e);
+                }
+            };
 
+            replace_path(&staging, install_path)?;
             single_file::summarize_restore(&progress)
         }
         PathContentKind::File => {
@@ -235,3 +266,84 @@ pub fn restore_dependency(
         summary,
     })
 }
+
+/// Hidden path next to `path`, on the same filesystem so it can be renamed into place
+fn sibling_path(path: &Path, purpose: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.ins-{}", name, purpose))
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Move `staged` to `target`, replacing whatever was there before
+fn replace_path(staged: &Path, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    let previous = sibling_path(target, "previous");
+    let _ = remove_path(&previous);
+    let had_previous = fs::symlink_metadata(target).is_ok();
+    if had_previous {
+        fs::rename(target, &previous)
+            .with_context(|| format!("Failed to move aside {}", target.display()))?;
+    }
+
+    if let Err(e) = fs::rename(staged, target) {
+        if had_previous {
+            let _ = fs::rename(&previous, target);
+        }
+        let _ = remove_path(staged);
+        return Err(e).with_context(|| {
+            format!(
+                "Failed to move restored dependency from {} to {}",
+                staged.display(),
+                target.display()
+            )
+        });
+    }
+
+    if had_previous {
+        remove_path(&previous)
+            .with_context(|| format!("Failed to remove previous {}", target.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacing_a_dependency_drops_files_of_the_previous_version() {
+        let root = tempfile::tempdir().unwrap();
+        let install_path = root.path().join("mods");
+        fs::create_dir_all(install_path.join("old")).unwrap();
+        fs::write(install_path.join("old/stale.pak"), "v1").unwrap();
+        fs::write(install_path.join("shared.cfg"), "v1").unwrap();
+
+        let staging = sibling_path(&install_path, "restore");
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join("shared.cfg"), "v2").unwrap();
+
+        replace_path(&staging, &install_path).unwrap();
+
+        assert!(!install_path.join("old").exists());
+        assert_eq!(
+            fs::read_to_string(install_path.join("shared.cfg")).unwrap(),
+            "v2"
+        );
+        assert!(!staging.exists());
+        assert!(!sibling_path(&install_path, "previous").exists());
+    }
+}
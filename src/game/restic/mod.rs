@@ -4,6 +4,7 @@ pub mod commands;
 pub mod dependencies;
 pub mod helpers;
 pub mod prune;
+pub mod registry;
 pub mod security;
 pub mod single_file;
 pub mod snapshot_selection;
@@ -321,6 +322,7 @@ pub fn restore_game_saves(
         path: save_path,
         save_path_type: game_selection.installation.save_path_type,
         snapshot_source_path: snapshot.paths.first().map(|path| path.as_str()),
+        registry_keys: &game_selection.installation.registry_keys,
     }) {
         Ok(output) => {
             let output_clone = output.clone();
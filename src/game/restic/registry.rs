@@ -0,0 +1,223 @@
+use anyhow::{Context, Result, anyhow};
+use std::{fs, path::Path, path::PathBuf};
+
+use crate::common::TildePath;
+use crate::game::config::RegistrySaveKey;
+use crate::game::platforms::wine_registry::{
+    WinePrefixRegistry, find_wine_prefix, import_reg_export, split_registry_path,
+};
+
+/// Directory inside the save path that holds exported registry keys
+///
+/// Keeping the exports inside the save directory means they are captured by the regular
+/// snapshot and restored together with the rest of the saves.
+pub const REGISTRY_EXPORT_DIR: &str = ".ins-registry";
+
+/// File name for the `.reg` export of a key, e.g. `HKEY_CURRENT_USER_Software_Game.reg`
+pub fn export_file_name(key: &RegistrySaveKey) -> String {
+    let sanitized: String = key
+        .key
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    format!("{sanitized}.reg")
+}
+
+fn export_path(save_dir: &Path, key: &RegistrySaveKey) -> PathBuf {
+    save_dir
+        .join(REGISTRY_EXPORT_DIR)
+        .join(export_file_name(key))
+}
+
+/// Export tracked registry keys into the save directory ahead of a backup
+///
+/// Returns the number of keys written. Keys missing from the hive are skipped so a
+/// fresh prefix does not block backups of the remaining saves.
+pub fn export_registry_keys(save_dir: &Path, keys: &[RegistrySaveKey]) -> Result<usize> {
+    let mut exported = 0;
+    for key in keys {
+        let (root, key_path) = split_registry_path(&key.key)
+            .ok_or_else(|| anyhow!("Unsupported registry key: {}", key.key))?;
+        let registry = WinePrefixRegistry::load(key.prefix.as_path());
+        let Some(content) = registry.export_key(root, &key_path) else {
+            continue;
+        };
+
+        let path = export_path(save_dir, key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "Failed to create registry export directory: {}",
+                    parent.display()
+                )
+            })?;
+        }
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write registry export: {}", path.display()))?;
+        exported += 1;
+    }
+    Ok(exported)
+}
+
+/// Result of merging exported registry keys back into their prefixes
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RegistryImport {
+    pub imported: usize,
+    /// Keys whose prefix or hive does not exist on this device
+    pub skipped: usize,
+}
+
+/// Tie the key paths shared in games.toml to the Wine prefix holding `save_path` on this
+/// device. Returns no keys when the save path is not inside a prefix.
+pub fn keys_for_save_path(save_path: &Path, keys: &[String]) -> Vec<RegistrySaveKey> {
+    let Some(prefix) = find_wine_prefix(save_path) else {
+        return Vec::new();
+    };
+    keys.iter()
+        .map(|key| RegistrySaveKey {
+            prefix: TildePath::new(prefix.clone()),
+            key: key.clone(),
+        })
+        .collect()
+}
+
+/// Merge exported registry keys from a restored save directory back into their prefixes
+///
+/// Keys whose prefix has not been initialized yet are counted as skipped, since the save
+/// files themselves are already restored at this point.
+pub fn import_registry_keys(save_dir: &Path, keys: &[RegistrySaveKey]) -> Result<RegistryImport> {
+    let mut result = RegistryImport::default();
+    for key in keys {
+        let path = export_path(save_dir, key);
+        if !path.is_file() {
+            continue;
+        }
+        let (root, _) = split_registry_path(&key.key)
+            .ok_or_else(|| anyhow!("Unsupported registry key: {}", key.key))?;
+        if !key.prefix.as_path().join(root.hive_file()).is_file() {
+            result.skipped += 1;
+            continue;
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read registry export: {}", path.display()))?;
+        import_reg_export(key.prefix.as_path(), &content).with_context(|| {
+            format!(
+                "Failed to import registry key {} into {}",
+                key.key,
+                key.prefix.as_path().display()
+            )
+        })?;
+        result.imported += 1;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HIVE: &str =
+        "WINE REGISTRY Version 2\n\n[Software\\\\Studio\\\\Game] 1700000000\n\"Slot\"=\"1\"\n";
+
+    #[test]
+    fn registry_keys_round_trip_through_save_directory() {
+        let prefix = tempfile::tempdir().unwrap();
+        let save_dir = tempfile::tempdir().unwrap();
+        let hive_path = prefix.path().join("user.reg");
+        fs::write(&hive_path, HIVE).unwrap();
+
+        let keys = vec![
+            RegistrySaveKey {
+                prefix: TildePath::new(prefix.path().to_path_buf()),
+                key: "HKEY_CURRENT_USER\\Software\\Studio\\Game".to_string(),
+            },
+            RegistrySaveKey {
+                prefix: TildePath::new(prefix.path().to_path_buf()),
+                key: "HKEY_CURRENT_USER\\Software\\Missing".to_string(),
+            },
+        ];
+
+        assert_eq!(export_registry_keys(save_dir.path(), &keys).unwrap(), 1);
+        assert!(
+            save_dir
+                .path()
+                .join(REGISTRY_EXPORT_DIR)
+                .join("HKEY_CURRENT_USER_Software_Studio_Game.reg")
+                .is_file()
+        );
+
+        fs::write(&hive_path, HIVE.replace("\"1\"", "\"2\"")).unwrap();
+        assert_eq!(
+            import_registry_keys(save_dir.path(), &keys).unwrap(),
+            RegistryImport {
+                imported: 1,
+                skipped: 0
+            }
+        );
+        assert!(
+            fs::read_to_string(&hive_path)
+                .unwrap()
+                .contains("\"Slot\"=\"1\"")
+        );
+    }
+
+    #[test]
+    fn shared_keys_restore_into_the_prefix_of_another_device() {
+        let key = "HKEY_CURRENT_USER\\Software\\Studio\\Game".to_string();
+        let home = tempfile::tempdir().unwrap();
+
+        // Tracked on a device whose prefix is ~/.wine
+        let tracked_prefix = home.path().join(".wine");
+        let tracked_saves = tracked_prefix.join("drive_c/users/me/Saved Games/Game");
+        fs::create_dir_all(&tracked_saves).unwrap();
+        fs::write(tracked_prefix.join("user.reg"), HIVE).unwrap();
+        let tracked = keys_for_save_path(&tracked_saves, std::slice::from_ref(&key));
+        assert_eq!(tracked[0].prefix.as_path(), tracked_prefix);
+        assert_eq!(export_registry_keys(&tracked_saves, &tracked).unwrap(), 1);
+
+        // Restored on a device that keeps the game in its own prefix
+        let other_prefix = home.path().join("Games/game/prefix");
+        let other_saves = other_prefix.join("drive_c/users/you/Saved Games/Game");
+        fs::create_dir_all(other_saves.join(REGISTRY_EXPORT_DIR)).unwrap();
+        fs::copy(
+            tracked_saves
+                .join(REGISTRY_EXPORT_DIR)
+                .join(export_file_name(&tracked[0])),
+            other_saves
+                .join(REGISTRY_EXPORT_DIR)
+                .join(export_file_name(&tracked[0])),
+        )
+        .unwrap();
+        let restored = keys_for_save_path(&other_saves, std::slice::from_ref(&key));
+        assert_eq!(restored[0].prefix.as_path(), other_prefix);
+
+        // The game has not run there yet, so the hive is missing
+        assert_eq!(
+            import_registry_keys(&other_saves, &restored).unwrap(),
+            RegistryImport {
+                imported: 0,
+                skipped: 1
+            }
+        );
+
+        fs::write(
+            other_prefix.join("user.reg"),
+            HIVE.replace("\"1\"", "\"2\""),
+        )
+        .unwrap();
+        assert_eq!(
+            import_registry_keys(&other_saves, &restored).unwrap(),
+            RegistryImport {
+                imported: 1,
+                skipped: 0
+            }
+        );
+        assert!(
+            fs::read_to_string(other_prefix.join("user.reg"))
+                .unwrap()
+                .contains("\"Slot\"=\"1\"")
+        );
+        assert!(keys_for_save_path(home.path(), &[key]).is_empty());
+    }
+}
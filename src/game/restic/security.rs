@@ -51,6 +51,7 @@ pub fn get_game_installation(game_name: Option<String>) -> Result<GameSelectionR
                         checkpoint_time: None,
                         launch_command: None,
                         dependencies: Vec::new(),
+                        registry_keys: Vec::new(),
                     },
                 });
             }
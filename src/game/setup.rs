@@ -518,6 +518,7 @@ fn handle_candidate(
                 game_name: Some(game_name.clone()),
                 dependency_id: Some(id),
                 install_path: None,
+                version: None,
             })?;
             SetupStepOutcome::Completed
         }
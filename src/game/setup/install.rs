@@ -6,10 +6,10 @@ use std::path::Path;
 use crate::common::TildePath;
 use crate::game::checkpoint;
 use crate::game::config::{
-    GameInstallation, InstallationsConfig, InstantGameConfig, PathContentKind,
+    GameInstallation, InstallationsConfig, InstantGameConfig, PathContentKind, RegistrySaveKey,
 };
 use crate::game::restic::backup::{GameBackup, RestoreRequest};
-use crate::game::restic::cache;
+use crate::game::restic::{cache, registry};
 use crate::game::utils::safeguards::{PathUsage, ensure_safe_path};
 use crate::game::utils::save_files::{SaveDirectoryInfo, get_save_directory_info};
 use crate::menu::protocol;
@@ -280,6 +280,25 @@ fn finalize_game_setup(
 
     let mut installation =
         GameInstallation::with_kind(game_name, save_path.clone(), save_path_kind);
+    let shared_keys = game_config
+        .games
+        .iter()
+        .find(|game| game.name.0 == game_name)
+        .map(|game| game.registry_keys.as_slice())
+        .unwrap_or_default();
+    installation.registry_keys = registry::keys_for_save_path(save_path.as_path(), shared_keys);
+    if installation.registry_keys.len() < shared_keys.len() {
+        emit(
+            Level::Warn,
+            "game.setup.registry_skipped",
+            &format!(
+                "{} Skipped {} registry key(s) for '{game_name}' because {path_display} is not inside a Wine prefix. Track them with `ins game scan-wine-prefix` once the prefix exists.",
+                char::from(NerdFont::Warning),
+                shared_keys.len()
+            ),
+            None,
+        );
+    }
 
     let path_prep = match prepare_save_path(&save_path, save_path_kind, &path_display)? {
         PathPreparationOutcome::Ready(prep) => prep,
@@ -339,6 +358,7 @@ fn finalize_game_setup(
             game_config,
             installation.save_path_type,
             selected_path.snapshot_path.as_deref(),
+            &installation.registry_keys,
         )?;
         emit(
             Level::Success,
@@ -807,6 +827,7 @@ fn restore_latest_backup(
     game_config: &InstantGameConfig,
     save_path_type: PathContentKind,
     snapshot_source_path: Option<&str>,
+    registry_keys: &[RegistrySaveKey],
 ) -> Result<String> {
     let backup_handler = GameBackup::new(game_config.clone());
     let summary = backup_handler
@@ -816,6 +837,7 @@ fn restore_latest_backup(
             path: save_path.as_path(),
             save_path_type,
             snapshot_source_path,
+            registry_keys,
         })
         .context("Failed to restore latest backup")?;
 
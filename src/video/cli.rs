@@ -1,6 +1,8 @@
 use clap::{Args, Subcommand, ValueHint};
 use std::path::PathBuf;
 
+use crate::video::render::ExportFormat;
+use crate::video::transcriber::TranscriberType;
 use crate::video::transcript_language::TranscriptLanguage;
 
 #[derive(Subcommand, Debug, Clone)]
@@ -9,14 +11,27 @@ pub enum VideoCommands {
     Convert(ConvertArgs),
     /// Append another recording to a video markdown file
     Append(AppendArgs),
-    /// Generate a transcript for a video using WhisperX
+    /// Generate a transcript for a video using WhisperX or whisper.cpp
     Transcribe(TranscribeArgs),
+    /// Mark filler words, stutters and long pauses as suggested cuts in a markdown file
+    SuggestCuts(SuggestCutsArgs),
+    /// Align sources by their audio so segments can switch camera angles
+    Sync(SyncArgs),
     /// Render a video according to edits in a markdown file
     Render(RenderArgs),
+    /// Export the edited timeline for Kdenlive, DaVinci Resolve or Shotcut
+    Export(ExportArgs),
+    /// Inspect or prune the cache of rendered chunks
+    Cache {
+        #[command(subcommand)]
+        command: VideoCacheCommands,
+    },
     /// Preview the video with ffplay (allows scrubbing with arrow keys)
     Preview(PreviewArgs),
     /// Generate a slide image from a markdown file
     Slide(SlideArgs),
+    /// Render a title slide to a 1280x720 JPEG thumbnail using the project slide theme
+    Thumbnail(ThumbnailArgs),
     /// Validate and show statistics for a video markdown file
     Check(CheckArgs),
     /// Process audio with the configured preprocessor (local or auphonic)
@@ -44,7 +59,7 @@ pub struct ConvertArgs {
     #[arg(value_hint = ValueHint::FilePath)]
     pub video: PathBuf,
 
-    /// Timestamped transcript file (currently SRT)
+    /// Existing transcript (WhisperX or whisper.cpp JSON, WebVTT or SRT)
     #[arg(short = 't', long = "transcript", value_hint = ValueHint::FilePath)]
     pub transcript: Option<PathBuf>,
 
@@ -67,6 +82,10 @@ pub struct ConvertArgs {
     /// Transcription language for WhisperX (speech recognition and word alignment)
     #[arg(long, value_enum, default_value_t = TranscriptLanguage::En)]
     pub language: TranscriptLanguage,
+
+    /// Comment out filler words, stutters and long pauses as suggested cuts
+    #[arg(long)]
+    pub suggest_cuts: bool,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -79,7 +98,7 @@ pub struct AppendArgs {
     #[arg(value_hint = ValueHint::FilePath)]
     pub video: PathBuf,
 
-    /// Timestamped transcript file (WhisperX JSON)
+    /// Existing transcript (WhisperX or whisper.cpp JSON, WebVTT or SRT)
     #[arg(short = 't', long = "transcript", value_hint = ValueHint::FilePath)]
     pub transcript: Option<PathBuf>,
 
@@ -106,6 +125,10 @@ pub struct TranscribeArgs {
     #[arg(value_hint = ValueHint::FilePath)]
     pub video: PathBuf,
 
+    /// Speech-to-text backend; defaults to `transcriber` from the video config
+    #[arg(long, value_enum)]
+    pub backend: Option<TranscriberType>,
+
     /// WhisperX compute type (e.g. int8, float16)
     #[arg(long, default_value = "int8")]
     pub compute_type: String,
@@ -114,7 +137,7 @@ pub struct TranscribeArgs {
     #[arg(long, default_value = "cpu")]
     pub device: String,
 
-    /// Optional Whisper model override
+    /// Optional Whisper model override (a ggml model name or path for whisper.cpp)
     #[arg(long)]
     pub model: Option<String>,
 
@@ -131,6 +154,48 @@ pub struct TranscribeArgs {
     pub language: TranscriptLanguage,
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct SuggestCutsArgs {
+    /// Markdown file to annotate in place
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub markdown: PathBuf,
+
+    /// Minimum pause in seconds to suggest as a cut (defaults to `cut_pause_seconds`)
+    #[arg(long, value_name = "SECONDS")]
+    pub pause_threshold: Option<f64>,
+
+    /// Report suggested cuts without modifying the markdown
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SyncArgs {
+    /// Markdown file whose front matter sources should be aligned
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub markdown: PathBuf,
+
+    /// Source id the others are aligned to (defaults to `default_source`)
+    #[arg(long, value_name = "ID")]
+    pub reference: Option<String>,
+
+    /// Only sync these source ids (repeatable; defaults to all other sources)
+    #[arg(long = "source", value_name = "ID")]
+    pub sources: Vec<String>,
+
+    /// Largest offset in seconds to search in either direction
+    #[arg(long, value_name = "SECONDS", default_value_t = 120.0)]
+    pub max_offset: f64,
+
+    /// Also estimate clock drift from a second window near the end
+    #[arg(long)]
+    pub drift: bool,
+
+    /// Report offsets without modifying the markdown
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct RenderArgs {
     /// Markdown file describing the edited timeline
@@ -149,10 +214,85 @@ pub struct RenderArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Output preset from the video config (repeat to render several targets in one run)
+    #[arg(long = "preset", value_name = "NAME")]
+    pub presets: Vec<String>,
+
+    /// Embed chapter markers derived from the markdown headings
+    #[arg(long)]
+    pub chapters: bool,
+
+    /// Write SRT and WebVTT subtitle files next to the output
+    #[arg(long = "sidecar-subs")]
+    pub sidecar_subtitles: bool,
+
+    /// Mux the subtitles into the output as a selectable soft subtitle track
+    #[arg(long = "soft-subs")]
+    pub soft_subtitles: bool,
+
+    /// Write <output>.description.txt with chapter timestamps for YouTube/PeerTube
+    #[arg(long)]
+    pub description: bool,
+
+    /// Shorthand for --chapters --sidecar-subs --soft-subs --description
+    #[arg(long)]
+    pub publish: bool,
+
+    /// Render the whole video in one pass instead of reusing cached chunks
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Chunks rendered in parallel (default: half the CPU cores)
+    #[arg(short = 'j', long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Render each clip tagged with `<!-- clip: name -->` to its own file instead of the full video
+    #[arg(long)]
+    pub clips: bool,
+
     #[command(flatten)]
     pub common: VideoProcessArgs,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum VideoCacheCommands {
+    /// Show how many chunks are cached and how much space they use
+    Info,
+    /// Delete cached chunks that have not been used recently
+    Prune {
+        /// Remove chunks unused for this many days
+        #[arg(long, value_name = "DAYS", default_value_t = 30)]
+        older_than: u64,
+
+        /// Remove every cached chunk
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    /// Markdown file describing the edited timeline
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub markdown: PathBuf,
+
+    /// Interchange format to write
+    #[arg(long, value_enum, default_value_t = ExportFormat::Otio)]
+    pub format: ExportFormat,
+
+    /// Optional output path; defaults to <markdownname>.<format> next to the markdown
+    #[arg(short = 'o', long = "out-file", value_hint = ValueHint::FilePath)]
+    pub out_file: Option<PathBuf>,
+
+    /// Overwrite existing export files
+    #[arg(long)]
+    pub force: bool,
+
+    /// Export in Instagram Reels/TikTok format (9:16 vertical)
+    #[arg(long)]
+    pub reels: bool,
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct PreviewArgs {
     /// Markdown file describing the edited timeline
@@ -165,6 +305,10 @@ pub struct PreviewArgs {
     /// Seek to specific time in seconds before starting preview
     #[arg(long, value_name = "SECONDS")]
     pub seek: Option<f64>,
+
+    /// Keep mpv open and reload the preview at the edited segment whenever the markdown is saved
+    #[arg(long)]
+    pub watch: bool,
 }
 
 /// Common arguments for video processing commands (render, preview)
@@ -202,6 +346,21 @@ pub struct SlideArgs {
     pub reels: bool,
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct ThumbnailArgs {
+    /// Video markdown file whose front matter provides the slide theme
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub markdown: PathBuf,
+
+    /// Optional output path; defaults to <markdownfilename>.thumbnail.jpg
+    #[arg(short = 'o', long = "out-file", value_hint = ValueHint::FilePath)]
+    pub out_file: Option<PathBuf>,
+
+    /// Thumbnail title; defaults to the first heading, then the file name
+    #[arg(long)]
+    pub title: Option<String>,
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct CheckArgs {
     /// Markdown file describing the edited timeline
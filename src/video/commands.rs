@@ -3,7 +3,7 @@ use anyhow::Result;
 use super::audio;
 use super::cli::VideoCommands;
 use super::menu;
-use super::pipeline::{check, convert, setup, transcribe};
+use super::pipeline::{check, convert, setup, suggest, sync, transcribe};
 use super::render;
 use super::slides;
 
@@ -12,9 +12,14 @@ pub async fn handle_video_command(command: VideoCommands, _debug: bool) -> Resul
         VideoCommands::Convert(args) => convert::handle_convert(args).await,
         VideoCommands::Append(args) => convert::handle_append(args).await,
         VideoCommands::Transcribe(args) => transcribe::handle_transcribe(args),
+        VideoCommands::SuggestCuts(args) => suggest::handle_suggest_cuts(args),
+        VideoCommands::Sync(args) => sync::handle_sync(args),
         VideoCommands::Render(args) => render::handle_render(args).await.map(|_| ()),
+        VideoCommands::Export(args) => render::handle_export(args).await,
+        VideoCommands::Cache { command } => render::handle_cache(command),
         VideoCommands::Preview(args) => render::handle_preview(args).await.map(|_| ()),
         VideoCommands::Slide(args) => slides::cli::handle_slide(args),
+        VideoCommands::Thumbnail(args) => slides::cli::handle_thumbnail(args),
         VideoCommands::Check(args) => check::handle_check(args).await,
         VideoCommands::Preprocess(args) => audio::handle_preprocess(args).await,
         VideoCommands::Setup(args) => setup::handle_setup(args).await,
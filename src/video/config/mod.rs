@@ -8,9 +8,14 @@ use std::path::{Path, PathBuf};
 use crate::common::config::DocumentedConfig;
 use crate::common::paths;
 use crate::documented_config;
+use crate::video::document::cuts::{CutOptions, DEFAULT_PAUSE_THRESHOLD_SECONDS};
 use crate::video::transcript_language::TranscriptLanguage;
 
+mod presets;
+
+pub use self::presets::{DEFAULT_PRESET, EncodePass, OutputPreset, RateControl};
 pub use super::audio::PreprocessorType;
+use super::transcriber::TranscriberType;
 
 /// Data directories for video projects.
 ///
@@ -61,6 +66,16 @@ impl VideoDirectories {
         }
         .resolve()
     }
+
+    /// Content-addressed chunks reused by incremental renders
+    pub fn render_cache_dir(&self) -> PathBuf {
+        self.cache_root.join("chunks")
+    }
+
+    /// Directory searched for `ggml-<name>.bin` whisper.cpp models
+    pub fn whisper_cpp_models_dir(&self) -> PathBuf {
+        self.cache_root.join("whisper.cpp")
+    }
 }
 
 /// Paths for a single video project, keyed by video hash.
@@ -82,21 +97,58 @@ pub struct VideoConfig {
     /// Music volume for video processing (0.0-1.0)
     #[serde(default = "crate::video::config::VideoConfig::default_music_volume")]
     pub music_volume: f32,
+    /// How far music is ducked under dialogue, in dB (0 disables ducking)
+    #[serde(default = "crate::video::config::VideoConfig::default_music_duck_db")]
+    pub music_duck_db: f32,
+    /// Milliseconds the ducking takes to kick in once dialogue starts
+    #[serde(default = "crate::video::config::VideoConfig::default_music_duck_attack_ms")]
+    pub music_duck_attack_ms: f32,
+    /// Milliseconds the music takes to recover after dialogue stops
+    #[serde(default = "crate::video::config::VideoConfig::default_music_duck_release_ms")]
+    pub music_duck_release_ms: f32,
+    /// Seconds of crossfade when the music track changes
+    #[serde(default = "crate::video::config::VideoConfig::default_music_crossfade_seconds")]
+    pub music_crossfade_seconds: f64,
     /// Which audio preprocessor to use (local, auphonic, or none)
     pub preprocessor: PreprocessorType,
     /// Auphonic API key (only used when preprocessor = auphonic)
     pub auphonic_api_key: Option<String>,
     /// Auphonic preset UUID (only used when preprocessor = auphonic)
     pub auphonic_preset_uuid: Option<String>,
+    /// Speech-to-text backend (whisperx or whisper-cpp)
+    pub transcriber: TranscriberType,
+    /// whisper.cpp CLI binary; defaults to whichever of whisper-cli/whisper-cpp is installed
+    pub whisper_cpp_binary: Option<String>,
+    /// whisper.cpp model name (e.g. base.en) or path to a ggml model file
+    pub whisper_cpp_model: Option<String>,
+    /// Words suggested as filler cuts; empty uses the built-in English/German list
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filler_words: Vec<String>,
+    /// Pauses at least this many seconds long are suggested as cuts
+    #[serde(default = "crate::video::config::VideoConfig::default_cut_pause_seconds")]
+    pub cut_pause_seconds: f64,
+    /// Output presets for `ins video render --preset`, extending or overriding built-ins
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<OutputPreset>,
 }
 
 impl Default for VideoConfig {
     fn default() -> Self {
         Self {
             music_volume: Self::DEFAULT_MUSIC_VOLUME,
+            music_duck_db: Self::DEFAULT_MUSIC_DUCK_DB,
+            music_duck_attack_ms: Self::DEFAULT_MUSIC_DUCK_ATTACK_MS,
+            music_duck_release_ms: Self::DEFAULT_MUSIC_DUCK_RELEASE_MS,
+            music_crossfade_seconds: Self::DEFAULT_MUSIC_CROSSFADE_SECONDS,
             preprocessor: PreprocessorType::default(),
             auphonic_api_key: None,
             auphonic_preset_uuid: None,
+            transcriber: TranscriberType::default(),
+            whisper_cpp_binary: None,
+            whisper_cpp_model: None,
+            filler_words: Vec::new(),
+            cut_pause_seconds: DEFAULT_PAUSE_THRESHOLD_SECONDS,
+            presets: Vec::new(),
         }
     }
 }
@@ -107,6 +159,38 @@ impl VideoConfig {
     }
 
     pub const DEFAULT_MUSIC_VOLUME: f32 = 0.1;
+    pub const DEFAULT_MUSIC_DUCK_DB: f32 = 12.0;
+    pub const DEFAULT_MUSIC_DUCK_ATTACK_MS: f32 = 50.0;
+    pub const DEFAULT_MUSIC_DUCK_RELEASE_MS: f32 = 600.0;
+    pub const DEFAULT_MUSIC_CROSSFADE_SECONDS: f64 = 1.5;
+
+    fn default_music_duck_db() -> f32 {
+        Self::DEFAULT_MUSIC_DUCK_DB
+    }
+
+    fn default_music_duck_attack_ms() -> f32 {
+        Self::DEFAULT_MUSIC_DUCK_ATTACK_MS
+    }
+
+    fn default_music_duck_release_ms() -> f32 {
+        Self::DEFAULT_MUSIC_DUCK_RELEASE_MS
+    }
+
+    fn default_music_crossfade_seconds() -> f64 {
+        Self::DEFAULT_MUSIC_CROSSFADE_SECONDS
+    }
+
+    fn default_cut_pause_seconds() -> f64 {
+        DEFAULT_PAUSE_THRESHOLD_SECONDS
+    }
+
+    /// Options for `ins video suggest-cuts`, optionally overriding the pause threshold
+    pub fn cut_options(&self, pause_seconds: Option<f64>) -> CutOptions {
+        CutOptions::new(
+            &self.filler_words,
+            pause_seconds.unwrap_or(self.cut_pause_seconds),
+        )
+    }
 
     pub fn load() -> Result<Self> {
         <Self as DocumentedConfig>::load_from_path_documented(video_config_path()?)
@@ -123,6 +207,72 @@ impl VideoConfig {
             self.music_volume
         }
     }
+
+    /// Ducking applied to music under dialogue, or `None` when disabled
+    pub fn music_ducking(&self) -> Option<MusicDucking> {
+        let positive_or = |value: f32, fallback: f32| {
+            if value.is_finite() && value > 0.0 {
+                value
+            } else {
+                fallback
+            }
+        };
+        if !self.music_duck_db.is_finite() || self.music_duck_db <= 0.0 {
+            return None;
+        }
+        Some(MusicDucking {
+            depth_db: self.music_duck_db,
+            attack_ms: positive_or(
+                self.music_duck_attack_ms,
+                Self::DEFAULT_MUSIC_DUCK_ATTACK_MS,
+            ),
+            release_ms: positive_or(
+                self.music_duck_release_ms,
+                Self::DEFAULT_MUSIC_DUCK_RELEASE_MS,
+            ),
+        })
+    }
+
+    pub fn music_crossfade_seconds(&self) -> f64 {
+        if self.music_crossfade_seconds.is_finite() && self.music_crossfade_seconds >= 0.0 {
+            self.music_crossfade_seconds
+        } else {
+            Self::DEFAULT_MUSIC_CROSSFADE_SECONDS
+        }
+    }
+
+    /// Built-in presets followed by configured ones; configured presets win on name clashes
+    pub fn output_presets(&self) -> Vec<OutputPreset> {
+        let mut presets = OutputPreset::builtin();
+        for preset in &self.presets {
+            match presets.iter_mut().find(|p| p.name == preset.name) {
+                Some(existing) => *existing = preset.clone(),
+                None => presets.push(preset.clone()),
+            }
+        }
+        presets
+    }
+
+    pub fn output_preset(&self, name: &str) -> Result<OutputPreset> {
+        let presets = self.output_presets();
+        let Some(preset) = presets.iter().find(|preset| preset.name == name) else {
+            let available: Vec<&str> = presets.iter().map(|p| p.name.as_str()).collect();
+            anyhow::bail!(
+                "Unknown output preset `{name}`. Available presets: {}",
+                available.join(", ")
+            );
+        };
+        preset.validate()?;
+        Ok(preset.clone())
+    }
+}
+
+/// Sidechain compression of music keyed by the dialogue track
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MusicDucking {
+    pub depth_db: f32,
+    pub attack_ms: f32,
+    pub release_ms: f32,
 }
 
 // Implement DocumentedConfig trait for VideoConfig using the macro
@@ -130,6 +280,14 @@ documented_config!(
     VideoConfig,
     music_volume,
     "Music volume for video processing (0.0-1.0)",
+    music_duck_db,
+    "How many dB music is ducked while someone is speaking (0 disables ducking)",
+    music_duck_attack_ms,
+    "Milliseconds music takes to duck once dialogue starts",
+    music_duck_release_ms,
+    "Milliseconds music takes to come back up after dialogue stops",
+    music_crossfade_seconds,
+    "Seconds of crossfade between consecutive music tracks (0 cuts hard)",
     preprocessor,
     "Which audio preprocessor to use (local, auphonic, or none)",
     auphonic_api_key,
@@ -137,6 +295,38 @@ documented_config!(
     secret,
     auphonic_preset_uuid,
     "Auphonic preset UUID for consistent processing settings",
+    transcriber,
+    "Speech-to-text backend for transcription (whisperx or whisper-cpp)",
+    whisper_cpp_binary,
+    "whisper.cpp CLI binary (default: whisper-cli or whisper-cpp from PATH)",
+    whisper_cpp_model,
+    "whisper.cpp model name looked up as ggml-<name>.bin in ~/.cache/instant/video/whisper.cpp, or a model path",
+    filler_words,
+    "Words suggested as filler cuts by `ins video suggest-cuts` (default: um, uh, äh, ähm, ...)",
+    cut_pause_seconds,
+    "Pauses at least this many seconds long are suggested as cuts",
+    presets,
+    "Named output presets for `ins video render --preset` (built-ins: default, hevc, av1, webm, reels, square, portrait, youtube, podcast)",
+    example,
+    r#"
+[[presets]]
+name = "youtube-4k"
+video_codec = "libx265"
+crf = 20
+width = 3840
+fps = 30
+container = "mkv"
+
+[[presets]]
+name = "upload-limit"
+mode = "square"
+target_size_mb = 95
+audio_bitrate = "128k"
+
+[[presets]]
+name = "podcast-video"
+loudness_lufs = -16
+"#,
 );
 
 fn video_config_path() -> Result<PathBuf> {
@@ -0,0 +1,490 @@
+//! Named output presets for `ins video render --preset`.
+//!
+//! A preset bundles everything that happens after the filter graph: codec, rate control,
+//! audio, output scaling and container. Built-in presets cover the common targets and can
+//! be overridden or extended through `[[presets]]` tables in `video.toml`.
+
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::video::render::RenderMode;
+
+/// Name of the preset used when `--preset` is not given
+pub const DEFAULT_PRESET: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VideoCodec {
+    #[default]
+    #[serde(rename = "libx264")]
+    Libx264,
+    #[serde(rename = "libx265")]
+    Libx265,
+    #[serde(rename = "libsvtav1")]
+    Libsvtav1,
+    #[serde(rename = "libvpx-vp9")]
+    LibvpxVp9,
+}
+
+impl VideoCodec {
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            VideoCodec::Libx264 => "libx264",
+            VideoCodec::Libx265 => "libx265",
+            VideoCodec::Libsvtav1 => "libsvtav1",
+            VideoCodec::LibvpxVp9 => "libvpx-vp9",
+        }
+    }
+
+    /// Visually transparent-ish quality used when a preset sets no rate control
+    fn default_crf(self) -> u32 {
+        match self {
+            VideoCodec::Libx264 => 18,
+            VideoCodec::Libx265 => 22,
+            VideoCodec::Libsvtav1 => 30,
+            VideoCodec::LibvpxVp9 => 31,
+        }
+    }
+
+    fn default_speed(self) -> &'static str {
+        match self {
+            VideoCodec::Libx264 | VideoCodec::Libx265 => "medium",
+            VideoCodec::Libsvtav1 => "6",
+            VideoCodec::LibvpxVp9 => "2",
+        }
+    }
+
+    fn supports_two_pass(self) -> bool {
+        !matches!(self, VideoCodec::Libsvtav1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Container {
+    #[default]
+    Mp4,
+    Mkv,
+    Webm,
+    Mov,
+}
+
+impl Container {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::Mkv => "mkv",
+            Container::Webm => "webm",
+            Container::Mov => "mov",
+        }
+    }
+}
+
+/// How the encoder spends bits
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateControl {
+    Crf(u32),
+    /// Average bitrate in kbit/s, optionally encoded in two passes
+    Bitrate {
+        kbps: u64,
+        two_pass: bool,
+    },
+}
+
+/// Which pass of a two-pass encode the arguments are for
+#[derive(Debug, Clone, Copy)]
+pub enum EncodePass<'a> {
+    Single,
+    First(&'a Path),
+    Second(&'a Path),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputPreset {
+    pub name: String,
+    /// Frame layout; falls back to `--reels` / standard when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<RenderMode>,
+    pub video_codec: VideoCodec,
+    /// Encoder speed preset (`medium` for x264/x265, `0`-`13` for SVT-AV1, `0`-`5` for VP9)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crf: Option<u32>,
+    /// Average video bitrate such as `8M` or `2500k`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_bitrate: Option<String>,
+    /// Target output size in megabytes; implies two-pass bitrate encoding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_size_mb: Option<f64>,
+    pub two_pass: bool,
+    pub audio_codec: String,
+    pub audio_bitrate: String,
+    /// Output width; the other dimension keeps the aspect ratio when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<f64>,
+    pub container: Container,
+    /// Integrated loudness target in LUFS; enables two-pass EBU R128 normalisation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loudness_lufs: Option<f64>,
+}
+
+impl Default for OutputPreset {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PRESET.to_string(),
+            mode: None,
+            video_codec: VideoCodec::Libx264,
+            speed: None,
+            crf: None,
+            video_bitrate: None,
+            target_size_mb: None,
+            two_pass: false,
+            audio_codec: "aac".to_string(),
+            audio_bitrate: "192k".to_string(),
+            width: None,
+            height: None,
+            fps: None,
+            container: Container::Mp4,
+            loudness_lufs: None,
+        }
+    }
+}
+
+impl OutputPreset {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Presets available without any configuration
+    pub fn builtin() -> Vec<OutputPreset> {
+        vec![
+            Self::named(DEFAULT_PRESET),
+            Self {
+                video_codec: VideoCodec::Libx265,
+                ..Self::named("hevc")
+            },
+            Self {
+                video_codec: VideoCodec::Libsvtav1,
+                ..Self::named("av1")
+            },
+            Self {
+                video_codec: VideoCodec::LibvpxVp9,
+                audio_codec: "libopus".to_string(),
+                audio_bitrate: "128k".to_string(),
+                container: Container::Webm,
+                ..Self::named("webm")
+            },
+            Self {
+                mode: Some(RenderMode::Reels),
+                ..Self::named("reels")
+            },
+            Self {
+                mode: Some(RenderMode::Square),
+                ..Self::named("square")
+            },
+            Self {
+                mode: Some(RenderMode::Portrait),
+                ..Self::named("portrait")
+            },
+            Self {
+                loudness_lufs: Some(-14.0),
+                ..Self::named("youtube")
+            },
+            Self {
+                audio_bitrate: "128k".to_string(),
+                loudness_lufs: Some(-16.0),
+                ..Self::named("podcast")
+            },
+        ]
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("Output presets need a name");
+        }
+        if self.container == Container::Webm {
+            if !matches!(
+                self.video_codec,
+                VideoCodec::LibvpxVp9 | VideoCodec::Libsvtav1
+            ) {
+                bail!(
+                    "Preset `{}`: webm requires libvpx-vp9 or libsvtav1, not {}",
+                    self.name,
+                    self.video_codec.ffmpeg_name()
+                );
+            }
+            if !matches!(self.audio_codec.as_str(), "libopus" | "libvorbis") {
+                bail!(
+                    "Preset `{}`: webm requires libopus or libvorbis audio, not {}",
+                    self.name,
+                    self.audio_codec
+                );
+            }
+        }
+        if let Some(size) = self.target_size_mb
+            && (!size.is_finite() || size <= 0.0)
+        {
+            bail!("Preset `{}`: target_size_mb must be positive", self.name);
+        }
+        if let Some(bitrate) = &self.video_bitrate {
+            parse_bitrate_kbps(bitrate)?;
+        }
+        if let Some(lufs) = self.loudness_lufs
+            && !(-70.0..=-5.0).contains(&lufs)
+        {
+            bail!(
+                "Preset `{}`: loudness_lufs must be between -70 and -5, got {lufs}",
+                self.name
+            );
+        }
+        parse_bitrate_kbps(&self.audio_bitrate)?;
+        Ok(())
+    }
+
+    /// Resolve rate control for an output of `duration` seconds
+    pub fn rate_control(&self, duration: f64) -> Result<RateControl> {
+        if let Some(size_mb) = self.target_size_mb {
+            let audio_kbps = parse_bitrate_kbps(&self.audio_bitrate)? as f64;
+            let total_kbps = size_mb * 8.0 * 1024.0 / duration.max(1.0);
+            let video_kbps = total_kbps - audio_kbps;
+            if video_kbps < 100.0 {
+                bail!(
+                    "Preset `{}`: {size_mb} MB is too small for {duration:.0}s of video",
+                    self.name
+                );
+            }
+            return Ok(RateControl::Bitrate {
+                kbps: video_kbps as u64,
+                two_pass: self.video_codec.supports_two_pass(),
+            });
+        }
+
+        if let Some(bitrate) = &self.video_bitrate {
+            return Ok(RateControl::Bitrate {
+                kbps: parse_bitrate_kbps(bitrate)?,
+                two_pass: self.two_pass && self.video_codec.supports_two_pass(),
+            });
+        }
+
+        Ok(RateControl::Crf(
+            self.crf.unwrap_or_else(|| self.video_codec.default_crf()),
+        ))
+    }
+
+    /// Filters appended to the final video label for output scaling and frame rate
+    pub fn output_filters(&self) -> Vec<String> {
+        let mut filters = Vec::new();
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => filters.push(format!(
+                "scale={width}:{height}:force_original_aspect_ratio=decrease:force_divisible_by=2"
+            )),
+            (Some(width), None) => filters.push(format!("scale={width}:-2")),
+            (None, Some(height)) => filters.push(format!("scale=-2:{height}")),
+            (None, None) => {}
+        }
+        if let Some(fps) = self.fps {
+            filters.push(format!("fps={fps}"));
+        }
+        filters
+    }
+
+    /// Subtitle codec the container accepts for a muxed soft subtitle track
+    pub fn subtitle_codec(&self) -> &'static str {
+        match self.container {
+            Container::Mp4 | Container::Mov => "mov_text",
+            Container::Mkv => "srt",
+            Container::Webm => "webvtt",
+        }
+    }
+
+    /// Append codec, rate control and container arguments for one encoding pass
+    pub fn push_encoding_args(
+        &self,
+        args: &mut Vec<String>,
+        rate: &RateControl,
+        pass: EncodePass<'_>,
+    ) {
+        self.push_video_args(args, rate, pass);
+        self.push_audio_args(args);
+        if matches!(pass, EncodePass::First(_)) {
+            // The analysis pass only needs the encoder statistics
+            args.extend(["-f".to_string(), "null".to_string()]);
+            return;
+        }
+        self.push_container_args(args);
+    }
+
+    /// Video codec and rate control arguments for one encoding pass
+    pub fn push_video_args(
+        &self,
+        args: &mut Vec<String>,
+        rate: &RateControl,
+        pass: EncodePass<'_>,
+    ) {
+        let codec = self.video_codec;
+        let speed = self
+            .speed
+            .clone()
+            .unwrap_or_else(|| codec.default_speed().to_string());
+        let mut push = |values: &[&str]| args.extend(values.iter().map(|v| v.to_string()));
+
+        push(&["-c:v", codec.ffmpeg_name()]);
+        match codec {
+            VideoCodec::Libx264 | VideoCodec::Libx265 | VideoCodec::Libsvtav1 => {
+                push(&["-preset", &speed]);
+            }
+            VideoCodec::LibvpxVp9 => {
+                push(&["-deadline", "good", "-cpu-used", &speed, "-row-mt", "1"])
+            }
+        }
+
+        match rate {
+            RateControl::Crf(crf) => {
+                let crf = crf.to_string();
+                push(&["-crf", &crf]);
+                if codec == VideoCodec::LibvpxVp9 {
+                    // VP9 only runs in constant quality mode with a zero bitrate
+                    push(&["-b:v", "0"]);
+                }
+            }
+            RateControl::Bitrate { kbps, .. } => {
+                let bitrate = format!("{kbps}k");
+                push(&["-b:v", &bitrate]);
+            }
+        }
+
+        let passlog = match pass {
+            EncodePass::Single => None,
+            EncodePass::First(log) => Some(("1", log)),
+            EncodePass::Second(log) => Some(("2", log)),
+        };
+        if let Some((number, log)) = passlog {
+            let log = log.to_string_lossy();
+            if codec == VideoCodec::Libx265 {
+                let params = format!("pass={number}:stats={log}.x265.log");
+                push(&["-x265-params", &params]);
+            } else {
+                push(&["-pass", number, "-passlogfile", &log]);
+            }
+        }
+
+        push(&["-pix_fmt", "yuv420p"]);
+    }
+
+    pub fn push_audio_args(&self, args: &mut Vec<String>) {
+        args.extend([
+            "-c:a".to_string(),
+            self.audio_codec.clone(),
+            "-b:a".to_string(),
+            self.audio_bitrate.clone(),
+        ]);
+    }
+
+    /// Container flags, also used when the video stream is copied
+    pub fn push_container_args(&self, args: &mut Vec<String>) {
+        if self.video_codec == VideoCodec::Libx265 && self.container == Container::Mp4 {
+            // Lets Apple players recognise HEVC in mp4
+            args.extend(["-tag:v".to_string(), "hvc1".to_string()]);
+        }
+        if matches!(self.container, Container::Mp4 | Container::Mov) {
+            args.extend(["-movflags".to_string(), "+faststart".to_string()]);
+        }
+    }
+}
+
+/// Parse bitrates such as `192k`, `8M` or `2500` (kbit/s)
+pub fn parse_bitrate_kbps(value: &str) -> Result<u64> {
+    let trimmed = value.trim();
+    let (number, multiplier) = match trimmed.chars().last() {
+        Some('k' | 'K') => (&trimmed[..trimmed.len() - 1], 1.0),
+        Some('m' | 'M') => (&trimmed[..trimmed.len() - 1], 1000.0),
+        _ => (trimmed, 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(parsed) if parsed > 0.0 => Ok((parsed * multiplier).round() as u64),
+        _ => bail!("Invalid bitrate `{value}` (expected e.g. 192k or 8M)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_preset_encodes_h264_with_faststart() {
+        let preset = OutputPreset::default();
+        let mut args = Vec::new();
+        let rate = preset.rate_control(60.0).unwrap();
+        preset.push_encoding_args(&mut args, &rate, EncodePass::Single);
+        assert_eq!(
+            args.join(" "),
+            "-c:v libx264 -preset medium -crf 18 -pix_fmt yuv420p -c:a aac -b:a 192k -movflags +faststart"
+        );
+    }
+
+    #[test]
+    fn target_size_uses_two_pass_bitrate() {
+        let preset = OutputPreset {
+            target_size_mb: Some(100.0),
+            audio_bitrate: "128k".to_string(),
+            ..OutputPreset::named("small")
+        };
+        // 100 MB over 100 s = 8192 kbit/s total, minus audio
+        assert_eq!(
+            preset.rate_control(100.0).unwrap(),
+            RateControl::Bitrate {
+                kbps: 8064,
+                two_pass: true
+            }
+        );
+
+        let mut first = Vec::new();
+        let log = Path::new("/tmp/out-pass");
+        preset.push_encoding_args(&mut first, &RateControl::Crf(1), EncodePass::First(log));
+        assert!(first.ends_with(&["-f".to_string(), "null".to_string()]));
+        assert!(first.contains(&"-passlogfile".to_string()));
+    }
+
+    #[test]
+    fn webm_requires_matching_codecs() {
+        let webm = OutputPreset::builtin()
+            .into_iter()
+            .find(|preset| preset.name == "webm")
+            .unwrap();
+        assert!(webm.validate().is_ok());
+
+        let invalid = OutputPreset {
+            container: Container::Webm,
+            ..OutputPreset::named("bad")
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn output_filters_keep_aspect_ratio() {
+        let preset = OutputPreset {
+            width: Some(1280),
+            fps: Some(30.0),
+            ..OutputPreset::named("720p")
+        };
+        assert_eq!(preset.output_filters(), ["scale=1280:-2", "fps=30"]);
+    }
+
+    #[test]
+    fn parses_bitrates() {
+        assert_eq!(parse_bitrate_kbps("192k").unwrap(), 192);
+        assert_eq!(parse_bitrate_kbps("8M").unwrap(), 8000);
+        assert_eq!(parse_bitrate_kbps("2500").unwrap(), 2500);
+        assert!(parse_bitrate_kbps("fast").is_err());
+    }
+}
@@ -1,14 +1,17 @@
 use anyhow::{Context, Result, bail};
 use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
 
+use super::clips::{is_clip_comment, parse_clip_marker};
+use super::directives::{DirectiveBlock, DirectiveKind, parse_directive};
 use super::reference::{
     SegmentSourceConfig, looks_like_timestamp_reference, parse_segment_reference,
+    split_segment_angle,
 };
 use super::types::{
-    BrollBlock, DocumentBlock, HeadingBlock, MusicDirective, SegmentBlock, SegmentKind,
-    UnhandledBlock,
+    BrollBlock, BuildSlideBlock, DocumentBlock, HeadingBlock, MusicDirective, SegmentBlock,
+    SegmentKind, UnhandledBlock,
 };
-use super::util::{LineMap, heading_level_to_u32, is_music_code_block};
+use super::util::{LineMap, heading_level_to_u32, is_build_code_block, is_music_code_block};
 
 pub fn parse_body_blocks(
     body: &str,
@@ -82,6 +85,10 @@ impl<'a> BodyParserState<'a> {
                 self.flush_paragraph()?;
                 if is_music_code_block(&info) {
                     self.code_block = Some(CodeBlockState::music(line));
+                } else if is_build_code_block(&info) {
+                    self.code_block = Some(CodeBlockState::build(line));
+                } else if let Some(kind) = DirectiveKind::from_info(&info) {
+                    self.code_block = Some(CodeBlockState::directive(line, kind));
                 } else {
                     let lang = info.to_string();
                     self.code_block = Some(CodeBlockState::generic(line, lang));
@@ -126,6 +133,15 @@ impl<'a> BodyParserState<'a> {
             Event::HardBreak => {
                 self.handle_break(range.start, true);
             }
+            Event::Html(text) | Event::InlineHtml(text) if is_clip_comment(&text) => {
+                let line = self.base_line_offset + self.byte_to_line(range.start);
+                if self.paragraph.is_some() {
+                    bail!("Clip marker at line {} must be on its own line", line);
+                }
+                let marker = parse_clip_marker(&text)
+                    .with_context(|| format!("Invalid clip marker at line {}", line))?;
+                self.blocks.push(DocumentBlock::Clip(marker));
+            }
             Event::Html(text) => {
                 let line = self.byte_to_line(range.start);
                 if let Some(state) = self.paragraph.as_mut() {
@@ -169,6 +185,19 @@ impl<'a> BodyParserState<'a> {
                 let line = self.base_line_offset + state.start_line;
                 let directive = state.into_music_directive(line)?;
                 self.blocks.push(DocumentBlock::Music(directive));
+            } else if let CodeBlockKindState::Directive(kind) = state.kind {
+                let line = self.base_line_offset + state.start_line;
+                let directive = parse_directive(kind, &state.content, line)?;
+                self.blocks
+                    .push(DocumentBlock::Directive(DirectiveBlock { directive, line }));
+            } else if let CodeBlockKindState::Build = state.kind {
+                let markdown = state.content.trim().to_string();
+                if markdown.is_empty() {
+                    let line = self.base_line_offset + state.start_line;
+                    bail!("Build slide at line {} must not be empty", line);
+                }
+                self.blocks
+                    .push(DocumentBlock::Build(BuildSlideBlock { markdown }));
             } else {
                 let markdown = state.to_markdown();
                 if !markdown.trim().is_empty() {
@@ -334,15 +363,24 @@ impl ParagraphState {
                     } else {
                         SegmentKind::Dialogue
                     };
-                    let (source_id, range) = parse_segment_reference(&code, source_config, line)
-                        .with_context(|| {
-                            format!("Invalid timestamp `{}` at line {}", code, line)
-                        })?;
+                    let (reference, angle) = split_segment_angle(&code, source_config, line)?;
+                    let (source_id, range) =
+                        parse_segment_reference(reference, source_config, line).with_context(
+                            || format!("Invalid timestamp `{}` at line {}", code, line),
+                        )?;
+                    if angle.as_deref() == Some(source_id.as_str()) {
+                        bail!(
+                            "Camera angle `{}` at line {} repeats the segment source",
+                            source_id,
+                            line
+                        );
+                    }
                     blocks.push(DocumentBlock::Segment(SegmentBlock {
                         range,
                         text,
                         kind,
                         source_id,
+                        angle,
                     }));
                 }
                 InlineFragmentKind::Code(code) => {
@@ -538,6 +576,8 @@ struct CodeBlockState {
 
 enum CodeBlockKindState {
     Music,
+    Build,
+    Directive(DirectiveKind),
     Generic { lang: String },
 }
 
@@ -550,6 +590,22 @@ impl CodeBlockState {
         }
     }
 
+    fn build(start_line: usize) -> Self {
+        Self {
+            kind: CodeBlockKindState::Build,
+            start_line,
+            content: String::new(),
+        }
+    }
+
+    fn directive(start_line: usize, kind: DirectiveKind) -> Self {
+        Self {
+            kind: CodeBlockKindState::Directive(kind),
+            start_line,
+            content: String::new(),
+        }
+    }
+
     fn generic(start_line: usize, lang: String) -> Self {
         Self {
             kind: CodeBlockKindState::Generic { lang },
@@ -586,6 +642,10 @@ impl CodeBlockState {
     fn to_markdown(&self) -> String {
         match &self.kind {
             CodeBlockKindState::Music => format!("```music\n{}\n```", self.content.trim()),
+            CodeBlockKindState::Build => format!("```build\n{}\n```", self.content.trim()),
+            CodeBlockKindState::Directive(kind) => {
+                format!("```{}\n{}\n```", kind.name(), self.content.trim())
+            }
             CodeBlockKindState::Generic { lang } => {
                 format!("```{lang}\n{}\n```", self.content.trim())
             }
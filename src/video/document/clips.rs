@@ -0,0 +1,371 @@
+//! Named clips (shorts) cut from one video document.
+//!
+//! ```markdown
+//! <!-- clip: teaser reels subtitles intro="# Coming up" -->
+//! ## Why we rewrote the parser
+//! `a@01:02.0-01:09.5` ...
+//! <!-- /clip -->
+//! ```
+//!
+//! Bare words after the clip name pick a render mode (`reels`, `square`, `portrait`,
+//! `standard`), turn on burned-in `subtitles`, or name an output preset. `preset=`, `mode=`,
+//! `intro=` and `outro=` can be given explicitly; intro and outro are markdown shown as
+//! standalone slides before and after the clip.
+//!
+//! A clip ends at `<!-- /clip -->` (the innermost open clip) or `<!-- /clip: name -->`.
+//! Without an end marker, a clip tagged right before a heading covers that heading's
+//! section and any other clip runs to the end of the document.
+
+use anyhow::{Result, anyhow, bail};
+
+use super::types::{DocumentBlock, UnhandledBlock, VideoDocument};
+use crate::video::render::RenderMode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipMarker {
+    Start(ClipSpec),
+    /// End of the named clip, or of the innermost open clip
+    End(Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClipSpec {
+    pub name: String,
+    pub mode: Option<RenderMode>,
+    pub preset: Option<String>,
+    pub subtitles: bool,
+    pub intro: Option<String>,
+    pub outro: Option<String>,
+}
+
+/// A clip resolved to the document blocks it covers
+#[derive(Debug, Clone)]
+pub struct DocumentClip {
+    pub spec: ClipSpec,
+    pub blocks: std::ops::Range<usize>,
+}
+
+/// Whether an HTML comment is a clip marker the parser has to keep
+pub fn is_clip_comment(comment: &str) -> bool {
+    let inner = comment.trim().trim_start_matches("<!--").trim_start();
+    inner.starts_with("clip:") || inner.starts_with("/clip")
+}
+
+/// Parse a `<!-- clip: ... -->` or `<!-- /clip -->` comment
+pub fn parse_clip_marker(comment: &str) -> Result<ClipMarker> {
+    let inner = comment
+        .trim()
+        .strip_prefix("<!--")
+        .and_then(|rest| rest.strip_suffix("-->"))
+        .ok_or_else(|| anyhow!("Clip marker `{}` must fit on one line", comment.trim()))?
+        .trim();
+
+    if let Some(end) = inner.strip_prefix("/clip") {
+        let name = end.trim().trim_start_matches(':').trim();
+        if name.is_empty() {
+            return Ok(ClipMarker::End(None));
+        }
+        validate_clip_name(name)?;
+        return Ok(ClipMarker::End(Some(name.to_string())));
+    }
+
+    let rest = inner
+        .strip_prefix("clip:")
+        .ok_or_else(|| anyhow!("`{inner}` is not a clip marker"))?;
+    let mut tokens = split_tokens(rest)?.into_iter();
+    let name = tokens
+        .next()
+        .ok_or_else(|| anyhow!("Clip marker needs a name, e.g. `<!-- clip: teaser -->`"))?;
+    validate_clip_name(&name)?;
+
+    let mut spec = ClipSpec {
+        name,
+        ..ClipSpec::default()
+    };
+    for token in tokens {
+        match token.split_once('=') {
+            Some(("preset", value)) => spec.preset = Some(value.to_string()),
+            Some(("mode", value)) => {
+                spec.mode = Some(
+                    parse_mode(value)
+                        .ok_or_else(|| anyhow!("Unknown render mode `{value}` in clip"))?,
+                );
+            }
+            Some(("intro", value)) => spec.intro = Some(value.to_string()),
+            Some(("outro", value)) => spec.outro = Some(value.to_string()),
+            Some((key, _)) => {
+                bail!("Unknown clip option `{key}`; expected preset, mode, intro or outro")
+            }
+            None if token == "subtitles" || token == "subs" => spec.subtitles = true,
+            None => match parse_mode(&token) {
+                Some(mode) => spec.mode = Some(mode),
+                None => spec.preset = Some(token),
+            },
+        }
+    }
+    Ok(ClipMarker::Start(spec))
+}
+
+fn parse_mode(value: &str) -> Option<RenderMode> {
+    match value.to_ascii_lowercase().as_str() {
+        "standard" => Some(RenderMode::Standard),
+        "reels" => Some(RenderMode::Reels),
+        "square" => Some(RenderMode::Square),
+        "portrait" => Some(RenderMode::Portrait),
+        _ => None,
+    }
+}
+
+fn validate_clip_name(name: &str) -> Result<()> {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(())
+    } else {
+        bail!("Clip name `{name}` may only contain letters, digits, `-` and `_`")
+    }
+}
+
+/// Split on whitespace, keeping double-quoted values (`intro="# Hello there"`) together
+fn split_tokens(input: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        bail!("Unterminated quote in clip marker");
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+impl VideoDocument {
+    /// Clips tagged in the document, in the order they start
+    pub fn clips(&self) -> Result<Vec<DocumentClip>> {
+        let mut clips: Vec<DocumentClip> = Vec::new();
+        // Indices into `clips` of clips still waiting for their end
+        let mut open: Vec<usize> = Vec::new();
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            let DocumentBlock::Clip(marker) = block else {
+                continue;
+            };
+            match marker {
+                ClipMarker::Start(spec) => {
+                    if clips.iter().any(|clip| clip.spec.name == spec.name) {
+                        bail!("Clip `{}` is defined more than once", spec.name);
+                    }
+                    open.push(clips.len());
+                    clips.push(DocumentClip {
+                        spec: spec.clone(),
+                        blocks: index + 1..self.blocks.len(),
+                    });
+                }
+                ClipMarker::End(name) => {
+                    let position = match name {
+                        Some(name) => open
+                            .iter()
+                            .rposition(|&clip| &clips[clip].spec.name == name)
+                            .ok_or_else(|| anyhow!("`/clip: {name}` has no open clip `{name}`"))?,
+                        None => open
+                            .len()
+                            .checked_sub(1)
+                            .ok_or_else(|| anyhow!("`/clip` without an open clip"))?,
+                    };
+                    let clip = open.remove(position);
+                    clips[clip].blocks.end = index;
+                }
+            }
+        }
+
+        for clip in open {
+            let start = clips[clip].blocks.start;
+            if let Some(end) = self.section_end(start) {
+                clips[clip].blocks.end = end;
+            }
+        }
+        Ok(clips)
+    }
+
+    /// End of the heading section starting at `start`, if a heading is tagged
+    fn section_end(&self, start: usize) -> Option<usize> {
+        let DocumentBlock::Heading(heading) = self.blocks.get(start)? else {
+            return None;
+        };
+        let end = self.blocks[start + 1..]
+            .iter()
+            .position(|block| {
+                matches!(block, DocumentBlock::Heading(next) if next.level <= heading.level)
+            })
+            .map_or(self.blocks.len(), |offset| start + 1 + offset);
+        Some(end)
+    }
+
+    /// Document holding only the blocks of `clip`, framed by its intro and outro slides
+    ///
+    /// The music playing where the clip starts is carried over so the clip sounds the same
+    /// as that part of the full video.
+    pub fn clip_document(&self, clip: &DocumentClip) -> VideoDocument {
+        let mut blocks = Vec::new();
+        if let Some(music) = self.blocks[..clip.blocks.start]
+            .iter()
+            .rev()
+            .find(|block| matches!(block, DocumentBlock::Music(_)))
+        {
+            blocks.push(music.clone());
+        }
+        if let Some(intro) = &clip.spec.intro {
+            push_slide(&mut blocks, intro);
+        }
+        blocks.extend(
+            self.blocks[clip.blocks.clone()]
+                .iter()
+                .filter(|block| !matches!(block, DocumentBlock::Clip(_)))
+                .cloned(),
+        );
+        if let Some(outro) = &clip.spec.outro {
+            push_slide(&mut blocks, outro);
+        }
+
+        VideoDocument {
+            metadata: self.metadata.clone(),
+            blocks,
+        }
+    }
+}
+
+/// Content between two separators becomes a standalone slide
+fn push_slide(blocks: &mut Vec<DocumentBlock>, markdown: &str) {
+    blocks.push(DocumentBlock::Separator);
+    blocks.push(DocumentBlock::Unhandled(UnhandledBlock {
+        description: markdown.to_string(),
+    }));
+    blocks.push(DocumentBlock::Separator);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::document::parse_video_document;
+    use crate::video::planning::TimelinePlanItem;
+    use std::path::Path;
+
+    const FRONT_MATTER: &str = concat!(
+        "---\n",
+        "sources:\n",
+        "- id: a\n  source: video_a.mp4\n  transcript: a.json\n",
+        "default_source: a\n",
+        "---\n",
+    );
+
+    #[test]
+    fn parses_clip_options() {
+        let marker = parse_clip_marker(
+            r##"<!-- clip: teaser reels subtitles shorts intro="# Up next" -->"##,
+        )
+        .unwrap();
+        assert_eq!(
+            marker,
+            ClipMarker::Start(ClipSpec {
+                name: "teaser".to_string(),
+                mode: Some(RenderMode::Reels),
+                preset: Some("shorts".to_string()),
+                subtitles: true,
+                intro: Some("# Up next".to_string()),
+                outro: None,
+            })
+        );
+        assert_eq!(
+            parse_clip_marker("<!-- /clip: teaser -->").unwrap(),
+            ClipMarker::End(Some("teaser".to_string()))
+        );
+        assert!(parse_clip_marker("<!-- clip: bad/name -->").is_err());
+        assert!(parse_clip_marker("<!-- clip: a mode=sideways -->").is_err());
+    }
+
+    #[test]
+    fn resolves_ranges_and_heading_sections() {
+        let markdown = format!(
+            "{FRONT_MATTER}\
+             `a@00:00.0-00:01.0` intro line\n\n\
+             <!-- clip: first square -->\n\
+             `a@00:01.0-00:02.0` first clip\n\n\
+             <!-- /clip -->\n\n\
+             <!-- clip: section -->\n\
+             ## Section\n\n\
+             `a@00:02.0-00:03.0` inside section\n\n\
+             ### Nested\n\n\
+             `a@00:03.0-00:04.0` still inside\n\n\
+             ## Next\n\n\
+             `a@00:04.0-00:05.0` after section\n"
+        );
+        let document = parse_video_document(&markdown, Path::new("test.md")).unwrap();
+        let clips = document.clips().unwrap();
+        assert_eq!(clips.len(), 2);
+        assert_eq!(clips[0].spec.mode, Some(RenderMode::Square));
+
+        let texts = |clip: &DocumentClip| -> Vec<String> {
+            document
+                .clip_document(clip)
+                .blocks
+                .iter()
+                .filter_map(|block| match block {
+                    DocumentBlock::Segment(segment) => Some(segment.text.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(texts(&clips[0]), ["first clip"]);
+        assert_eq!(texts(&clips[1]), ["inside section", "still inside"]);
+
+        // Clip markers never reach the full render
+        let plan = document.plan_timeline().unwrap();
+        assert_eq!(plan.segment_count, 5);
+    }
+
+    #[test]
+    fn intro_and_outro_become_standalone_slides() {
+        let markdown = format!(
+            "{FRONT_MATTER}\
+             <!-- clip: teaser intro=\"# Watch this\" outro=\"Full video on the channel\" -->\n\
+             `a@00:01.0-00:02.0` the hook\n"
+        );
+        let document = parse_video_document(&markdown, Path::new("test.md")).unwrap();
+        let clips = document.clips().unwrap();
+        let plan = document.clip_document(&clips[0]).plan_timeline().unwrap();
+
+        let kinds: Vec<&str> = plan
+            .items
+            .iter()
+            .map(|item| match item {
+                TimelinePlanItem::Standalone(_) => "slide",
+                TimelinePlanItem::Clip(_) => "clip",
+                TimelinePlanItem::Music(_) => "music",
+            })
+            .collect();
+        assert_eq!(kinds, ["slide", "clip", "slide"]);
+    }
+
+    #[test]
+    fn rejects_unbalanced_markers() {
+        let markdown = format!("{FRONT_MATTER}<!-- /clip -->\n\n`a@00:01.0-00:02.0` line\n");
+        let document = parse_video_document(&markdown, Path::new("test.md")).unwrap();
+        assert!(document.clips().is_err());
+
+        let inline = format!("{FRONT_MATTER}`a@00:01.0-00:02.0` line <!-- clip: x -->\n");
+        assert!(parse_video_document(&inline, Path::new("test.md")).is_err());
+    }
+}
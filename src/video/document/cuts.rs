@@ -0,0 +1,637 @@
+//! Suggested cuts for filler words, stutters and long pauses.
+//!
+//! Suggestions are written back into the markdown as tagged HTML comments, e.g.
+//! ``<!-- cut:filler `a@00:01.2-00:01.5` um -->``. Comments are dropped by the parser, so a
+//! suggestion is applied by default and reverted by removing the comment markers.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::Result;
+use regex::Regex;
+
+use super::frontmatter::split_frontmatter;
+use super::markdown::format_timestamp;
+use super::time::parse_time_range;
+use super::types::TimeRange;
+use crate::video::support::transcript::{TranscriptCue, WordTiming};
+
+/// Common English and German hesitation sounds
+pub const DEFAULT_FILLER_WORDS: &[&str] = &[
+    "um", "uh", "uhm", "umm", "erm", "er", "ah", "hmm", "mm", "äh", "ähm", "öhm", "hm",
+];
+pub const DEFAULT_PAUSE_THRESHOLD_SECONDS: f64 = 1.5;
+
+/// Longest phrase (in words) detected as an immediate repetition
+const MAX_REPEATED_PHRASE: usize = 3;
+/// Markdown timestamps have tenth-second precision
+const BOUNDARY_TOLERANCE: Duration = Duration::from_millis(50);
+
+static SEGMENT_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^`(?:([A-Za-z][A-Za-z0-9_-]*)@)?([0-9:.]+-[0-9:.]+)`\s*(.*)$").unwrap()
+});
+static CUT_COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<!--\s*cut:([a-z]+)\s+`(?:[A-Za-z][A-Za-z0-9_-]*@)?([0-9:.]+-[0-9:.]+)`").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutReason {
+    Filler,
+    Stutter,
+    Pause,
+}
+
+impl CutReason {
+    const ALL: [CutReason; 3] = [CutReason::Filler, CutReason::Stutter, CutReason::Pause];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CutReason::Filler => "filler",
+            CutReason::Stutter => "stutter",
+            CutReason::Pause => "pause",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|reason| reason.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CutOptions {
+    /// Lower-case words treated as fillers
+    pub filler_words: HashSet<String>,
+    /// Pauses at least this long are cut
+    pub pause_threshold: Duration,
+}
+
+impl CutOptions {
+    pub fn new(filler_words: &[String], pause_threshold_seconds: f64) -> Self {
+        let filler_words = if filler_words.is_empty() {
+            DEFAULT_FILLER_WORDS.iter().map(|w| w.to_string()).collect()
+        } else {
+            filler_words.iter().map(|w| normalize_word(w)).collect()
+        };
+        Self {
+            filler_words,
+            pause_threshold: Duration::from_secs_f64(pause_threshold_seconds.max(0.1)),
+        }
+    }
+}
+
+impl Default for CutOptions {
+    fn default() -> Self {
+        Self::new(&[], DEFAULT_PAUSE_THRESHOLD_SECONDS)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CutStat {
+    pub reason: CutReason,
+    pub count: usize,
+    pub duration: Duration,
+}
+
+/// Suggested cuts per reason
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CutSummary {
+    pub stats: Vec<CutStat>,
+}
+
+impl CutSummary {
+    fn record(&mut self, reason: CutReason, duration: Duration) {
+        match self.stats.iter_mut().find(|stat| stat.reason == reason) {
+            Some(stat) => {
+                stat.count += 1;
+                stat.duration += duration;
+            }
+            None => self.stats.push(CutStat {
+                reason,
+                count: 1,
+                duration,
+            }),
+        }
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.stats.iter().map(|stat| stat.count).sum()
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.stats.iter().map(|stat| stat.duration).sum()
+    }
+
+    /// e.g. `filler 8 (4.2s), pause 2 (6.5s)`
+    pub fn describe(&self) -> String {
+        self.stats
+            .iter()
+            .map(|stat| {
+                format!(
+                    "{} {} ({:.1}s)",
+                    stat.reason.as_str(),
+                    stat.count,
+                    stat.duration.as_secs_f64()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Rewrite timestamp lines so suggested cuts become tagged comments
+pub fn suggest_cuts(
+    markdown: &str,
+    cues: &[TranscriptCue],
+    default_source: &str,
+    options: &CutOptions,
+) -> Result<(String, CutSummary)> {
+    let (_, body, body_offset) = split_frontmatter(markdown)?;
+    let mut summary = CutSummary::default();
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut in_comment = false;
+    let mut current_source = default_source.to_string();
+    for raw in body.lines() {
+        let was_in_comment = in_comment;
+        in_comment = update_comment_state(raw, in_comment);
+        if was_in_comment || raw.contains("<!--") {
+            lines.push(Line::Other(raw.to_string()));
+            continue;
+        }
+        match parse_segment_line(raw, &current_source) {
+            Some(segment) => {
+                current_source = segment.source.clone();
+                lines.push(Line::Segment(segment));
+            }
+            None => lines.push(Line::Other(raw.to_string())),
+        }
+    }
+
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+    let mut idx = 0;
+    while idx < lines.len() {
+        match &lines[idx] {
+            Line::Other(raw) => {
+                output.push(raw.clone());
+                idx += 1;
+            }
+            Line::Segment(segment) if segment.is_silence() => {
+                let run_end = silence_run_end(&lines, idx);
+                let run = &lines[idx..run_end];
+                let duration = segment_at(run, run.len() - 1).range.end - segment.range.start;
+                if duration >= options.pause_threshold {
+                    for line in run {
+                        if let Line::Segment(silence) = line {
+                            summary
+                                .record(CutReason::Pause, silence.range.end - silence.range.start);
+                            output.push(cut_line(CutReason::Pause, silence, &silence.text));
+                        }
+                    }
+                } else {
+                    output.extend(run.iter().map(Line::render));
+                }
+                idx = run_end;
+            }
+            Line::Segment(segment) => {
+                let words = words_in_range(cues, &segment.source, segment.range);
+                let words = with_line_text(segment, words);
+                match split_segment(segment, &words, options) {
+                    Some(pieces) => {
+                        for piece in pieces {
+                            output.push(piece.render(segment, &mut summary));
+                        }
+                    }
+                    None => output.push(segment.raw.clone()),
+                }
+                idx += 1;
+            }
+        }
+    }
+
+    let mut result = String::with_capacity(markdown.len());
+    result.push_str(&markdown[..body_offset]);
+    result.push_str(&output.join("\n"));
+    if body.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok((result, summary))
+}
+
+/// Tally the tagged cut comments in a markdown document
+pub fn cut_statistics(markdown: &str) -> CutSummary {
+    let mut summary = CutSummary::default();
+    for caps in CUT_COMMENT_RE.captures_iter(markdown) {
+        let (Some(reason), Ok(range)) = (CutReason::parse(&caps[1]), parse_time_range(&caps[2]))
+        else {
+            continue;
+        };
+        summary.record(reason, range.end - range.start);
+    }
+    summary
+}
+
+enum Line {
+    Other(String),
+    Segment(SegmentLine),
+}
+
+impl Line {
+    fn render(&self) -> String {
+        match self {
+            Line::Other(raw) => raw.clone(),
+            Line::Segment(segment) => segment.raw.clone(),
+        }
+    }
+}
+
+struct SegmentLine {
+    raw: String,
+    source: String,
+    range: TimeRange,
+    text: String,
+}
+
+impl SegmentLine {
+    fn is_silence(&self) -> bool {
+        self.text.eq_ignore_ascii_case("silence")
+    }
+}
+
+fn segment_at(lines: &[Line], idx: usize) -> &SegmentLine {
+    match &lines[idx] {
+        Line::Segment(segment) => segment,
+        Line::Other(_) => unreachable!("silence runs only contain segments"),
+    }
+}
+
+/// Index after the last contiguous SILENCE line starting at `start`
+fn silence_run_end(lines: &[Line], start: usize) -> usize {
+    let mut end = start + 1;
+    while end < lines.len() {
+        let previous = segment_at(lines, end - 1);
+        match &lines[end] {
+            Line::Segment(next)
+                if next.is_silence()
+                    && next.source == previous.source
+                    && next.range.start == previous.range.end =>
+            {
+                end += 1;
+            }
+            _ => break,
+        }
+    }
+    end
+}
+
+fn update_comment_state(line: &str, mut in_comment: bool) -> bool {
+    let mut rest = line;
+    loop {
+        let marker = if in_comment { "-->" } else { "<!--" };
+        match rest.find(marker) {
+            Some(pos) => {
+                in_comment = !in_comment;
+                rest = &rest[pos + marker.len()..];
+            }
+            None => return in_comment,
+        }
+    }
+}
+
+fn parse_segment_line(raw: &str, current_source: &str) -> Option<SegmentLine> {
+    let caps = SEGMENT_LINE_RE.captures(raw.trim())?;
+    let range = parse_time_range(&caps[2]).ok()?;
+    let source = caps
+        .get(1)
+        .map_or(current_source, |m| m.as_str())
+        .to_string();
+    Some(SegmentLine {
+        raw: raw.to_string(),
+        source,
+        range,
+        text: caps[3].trim().to_string(),
+    })
+}
+
+fn words_in_range(cues: &[TranscriptCue], source: &str, range: TimeRange) -> Vec<WordTiming> {
+    let start = range.start.saturating_sub(BOUNDARY_TOLERANCE);
+    let end = range.end + BOUNDARY_TOLERANCE;
+    let mut words: Vec<WordTiming> = cues
+        .iter()
+        .filter(|cue| cue.source_id.is_empty() || cue.source_id == source)
+        .flat_map(|cue| cue.words.iter())
+        .filter(|word| word.start >= start && word.end <= end)
+        .cloned()
+        .collect();
+    words.sort_by_key(|word| word.start);
+    words
+}
+
+struct Piece {
+    start: Duration,
+    end: Duration,
+    cut: Option<CutReason>,
+    words: Vec<String>,
+}
+
+impl Piece {
+    fn render(&self, segment: &SegmentLine, summary: &mut CutSummary) -> String {
+        let text = if self.words.is_empty() {
+            "SILENCE".to_string()
+        } else {
+            self.words.join(" ")
+        };
+        let line = SegmentLine {
+            raw: String::new(),
+            source: segment.source.clone(),
+            range: TimeRange {
+                start: self.start,
+                end: self.end,
+            },
+            text: String::new(),
+        };
+        match self.cut {
+            Some(reason) => {
+                summary.record(reason, self.end - self.start);
+                cut_line(reason, &line, &text)
+            }
+            None => format!("{} {}", reference(&line), text),
+        }
+    }
+}
+
+fn reference(segment: &SegmentLine) -> String {
+    format!(
+        "`{}@{}-{}`",
+        segment.source,
+        format_timestamp(segment.range.start),
+        format_timestamp(segment.range.end)
+    )
+}
+
+fn cut_line(reason: CutReason, segment: &SegmentLine, text: &str) -> String {
+    format!(
+        "<!-- cut:{} {} {} -->",
+        reason.as_str(),
+        reference(segment),
+        text
+    )
+}
+
+/// Transcript word timings carrying the line's own wording, so corrections made in the
+/// markdown survive a split. Lines whose words no longer line up with the transcript are
+/// left alone.
+fn with_line_text(segment: &SegmentLine, mut words: Vec<WordTiming>) -> Vec<WordTiming> {
+    let tokens: Vec<&str> = segment.text.split_whitespace().collect();
+    if tokens.len() != words.len() {
+        return Vec::new();
+    }
+    for (word, token) in words.iter_mut().zip(tokens) {
+        word.word = token.to_string();
+    }
+    words
+}
+
+/// Split a dialogue line into kept and cut pieces; `None` when nothing is cut
+fn split_segment(
+    segment: &SegmentLine,
+    words: &[WordTiming],
+    options: &CutOptions,
+) -> Option<Vec<Piece>> {
+    if words.is_empty() {
+        return None;
+    }
+    let marks = mark_words(words, options);
+
+    // Each word owns the time up to the next word, unless a long pause sits in between
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut push = |start: Duration, end: Duration, cut: Option<CutReason>, word: Option<&str>| {
+        if end <= start {
+            return;
+        }
+        match pieces.last_mut() {
+            Some(last) if last.cut == cut && cut != Some(CutReason::Pause) => {
+                last.end = end;
+                last.words.extend(word.map(str::to_string));
+            }
+            _ => pieces.push(Piece {
+                start,
+                end,
+                cut,
+                words: word.map(str::to_string).into_iter().collect(),
+            }),
+        }
+    };
+
+    let mut cursor = segment.range.start;
+    for (idx, word) in words.iter().enumerate() {
+        let word_end = match words.get(idx + 1) {
+            Some(next) if next.start.saturating_sub(word.end) >= options.pause_threshold => {
+                word.end
+            }
+            Some(next) => next.start,
+            None => segment.range.end,
+        };
+        push(
+            cursor,
+            word_end.max(cursor),
+            marks[idx],
+            Some(word.word.trim()),
+        );
+        cursor = word_end.max(cursor);
+        if let Some(next) = words.get(idx + 1)
+            && next.start > cursor
+        {
+            push(cursor, next.start, Some(CutReason::Pause), None);
+            cursor = next.start;
+        }
+    }
+
+    if pieces.iter().all(|piece| piece.cut.is_none()) {
+        return None;
+    }
+
+    // Drop pieces that vanish at tenth-second precision
+    pieces.retain(|piece| format_timestamp(piece.start) != format_timestamp(piece.end));
+    Some(pieces)
+}
+
+fn mark_words(words: &[WordTiming], options: &CutOptions) -> Vec<Option<CutReason>> {
+    let normalized: Vec<String> = words.iter().map(|w| normalize_word(&w.word)).collect();
+    let mut marks: Vec<Option<CutReason>> = normalized
+        .iter()
+        .zip(words)
+        .map(|(word, raw)| {
+            if options.filler_words.contains(word) {
+                Some(CutReason::Filler)
+            } else if raw.word.trim().ends_with('-') && !word.is_empty() {
+                // False start such as "th- the"
+                Some(CutReason::Stutter)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Immediate repetitions ("I I think", "we can we can"), ignoring fillers in between
+    let kept: Vec<usize> = (0..words.len()).filter(|&i| marks[i].is_none()).collect();
+    let mut pos = 0;
+    while pos < kept.len() {
+        let repeated = (1..=MAX_REPEATED_PHRASE).rev().find(|&n| {
+            pos + 2 * n <= kept.len()
+                && (0..n).all(|k| normalized[kept[pos + k]] == normalized[kept[pos + n + k]])
+                && (0..n).all(|k| !normalized[kept[pos + k]].is_empty())
+        });
+        match repeated {
+            Some(n) => {
+                for k in 0..n {
+                    marks[kept[pos + k]] = Some(CutReason::Stutter);
+                }
+                pos += n;
+            }
+            None => pos += 1,
+        }
+    }
+    marks
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim()
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::document::{DocumentBlock, parse_video_document};
+    use std::path::Path;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> WordTiming {
+        WordTiming {
+            word: text.to_string(),
+            start: Duration::from_millis(start_ms),
+            end: Duration::from_millis(end_ms),
+        }
+    }
+
+    fn cue(words: Vec<WordTiming>) -> TranscriptCue {
+        TranscriptCue {
+            start: words.first().unwrap().start,
+            end: words.last().unwrap().end,
+            text: String::new(),
+            words,
+            source_id: "a".to_string(),
+        }
+    }
+
+    const FRONT: &str =
+        "---\nsources:\n- id: a\n  source: video.mp4\n  transcript: video.json\n---\n";
+
+    #[test]
+    fn fillers_and_repetitions_become_cut_comments() {
+        let cues = vec![cue(vec![
+            word("So", 0, 300),
+            word("um,", 400, 800),
+            word("I", 900, 1000),
+            word("I", 1100, 1200),
+            word("think", 1300, 1700),
+        ])];
+        let markdown = format!("{FRONT}`a@00:00.0-00:01.8` So um, I I think\n");
+
+        let (output, summary) =
+            suggest_cuts(&markdown, &cues, "a", &CutOptions::default()).unwrap();
+
+        assert!(output.starts_with(FRONT));
+        assert_eq!(
+            &output[FRONT.len()..],
+            "`a@00:00.0-00:00.4` So\n\
+             <!-- cut:filler `a@00:00.4-00:00.9` um, -->\n\
+             <!-- cut:stutter `a@00:00.9-00:01.1` I -->\n\
+             `a@00:01.1-00:01.8` I think\n"
+        );
+        assert_eq!(summary.total_count(), 2);
+        assert_eq!(summary.total_duration(), Duration::from_millis(700));
+        assert_eq!(cut_statistics(&output), summary);
+
+        // Suggestions are idempotent
+        let (again, summary) = suggest_cuts(&output, &cues, "a", &CutOptions::default()).unwrap();
+        assert_eq!(again, output);
+        assert_eq!(summary.total_count(), 0);
+
+        let document = parse_video_document(&output, Path::new("video.md")).unwrap();
+        let segments = document
+            .blocks
+            .iter()
+            .filter(|block| matches!(block, DocumentBlock::Segment(_)))
+            .count();
+        assert_eq!(segments, 2);
+    }
+
+    #[test]
+    fn long_pauses_are_cut_inside_lines_and_silence_runs() {
+        let cues = vec![cue(vec![word("Hello", 0, 500), word("world", 2500, 3000)])];
+        let markdown = format!(
+            "{FRONT}`a@00:00.0-00:03.0` Hello world\n`a@00:03.0-00:05.0` SILENCE\n`a@00:05.0-00:05.5` SILENCE\n"
+        );
+
+        let (output, summary) =
+            suggest_cuts(&markdown, &cues, "a", &CutOptions::default()).unwrap();
+
+        assert_eq!(
+            &output[FRONT.len()..],
+            "`a@00:00.0-00:00.5` Hello\n\
+             <!-- cut:pause `a@00:00.5-00:02.5` SILENCE -->\n\
+             `a@00:02.5-00:03.0` world\n\
+             <!-- cut:pause `a@00:03.0-00:05.0` SILENCE -->\n\
+             <!-- cut:pause `a@00:05.0-00:05.5` SILENCE -->\n"
+        );
+        let pauses = summary.stats[0];
+        assert_eq!(pauses.reason, CutReason::Pause);
+        assert_eq!(pauses.count, 3);
+        assert_eq!(pauses.duration, Duration::from_millis(4500));
+        assert_eq!(cut_statistics(&output), summary);
+    }
+
+    #[test]
+    fn short_silence_and_clean_lines_are_untouched() {
+        let cues = vec![cue(vec![word("Fine", 0, 500)])];
+        let markdown =
+            format!("{FRONT}# Intro\n\n`a@00:00.0-00:00.5` Fine\n`00:00.5-00:01.5` SILENCE\n");
+
+        let (output, summary) =
+            suggest_cuts(&markdown, &cues, "a", &CutOptions::default()).unwrap();
+
+        assert_eq!(output, markdown);
+        assert_eq!(summary, CutSummary::default());
+    }
+
+    #[test]
+    fn split_lines_keep_corrected_wording() {
+        let cues = vec![cue(vec![
+            word("Their", 0, 300),
+            word("um", 400, 800),
+            word("it", 900, 1000),
+            word("is", 1100, 1500),
+        ])];
+        let markdown = format!("{FRONT}`a@00:00.0-00:01.5` There um it is.\n");
+
+        let (output, _) = suggest_cuts(&markdown, &cues, "a", &CutOptions::default()).unwrap();
+
+        assert_eq!(
+            &output[FRONT.len()..],
+            "`a@00:00.0-00:00.4` There\n\
+             <!-- cut:filler `a@00:00.4-00:00.9` um -->\n\
+             `a@00:00.9-00:01.5` it is.\n"
+        );
+
+        // Words added or removed by hand no longer line up with the timings
+        let rewritten = format!("{FRONT}`a@00:00.0-00:01.5` So there it is.\n");
+        let (output, summary) =
+            suggest_cuts(&rewritten, &cues, "a", &CutOptions::default()).unwrap();
+        assert_eq!(output, rewritten);
+        assert_eq!(summary, CutSummary::default());
+    }
+}
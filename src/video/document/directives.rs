@@ -0,0 +1,315 @@
+//! Fenced overlay directives in video markdown.
+//!
+//! ````markdown
+//! ```lower-third
+//! title: Jane Doe
+//! subtitle: Maintainer
+//! ```
+//!
+//! ```pip
+//! position: bottom-right
+//! scale: 0.3
+//! ```
+//!
+//! ```ken-burns
+//! image: assets/diagram.png
+//! zoom: [1.0, 1.3]
+//! pan: right
+//! ```
+//! ````
+//!
+//! Lower thirds and Ken Burns stills apply to the next segment; `pip` places the
+//! b-roll quotes that follow it over the speaker instead of covering the frame.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+pub const DEFAULT_LOWER_THIRD_SECONDS: f64 = 5.0;
+const DEFAULT_LOWER_THIRD_FADE: f64 = 0.3;
+const DEFAULT_PIP_SCALE: f64 = 0.35;
+const DEFAULT_KEN_BURNS_ZOOM: [f64; 2] = [1.0, 1.2];
+const DEFAULT_KEN_BURNS_FADE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    LowerThird,
+    PictureInPicture,
+    KenBurns,
+}
+
+impl DirectiveKind {
+    /// Match the info string of a fenced code block (e.g. `lower-third`)
+    pub fn from_info(info: &str) -> Option<Self> {
+        let lang = info.split_whitespace().next()?;
+        match lang.to_ascii_lowercase().as_str() {
+            "lower-third" | "lowerthird" => Some(Self::LowerThird),
+            "pip" | "picture-in-picture" => Some(Self::PictureInPicture),
+            "ken-burns" | "kenburns" => Some(Self::KenBurns),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::LowerThird => "lower-third",
+            Self::PictureInPicture => "pip",
+            Self::KenBurns => "ken-burns",
+        }
+    }
+}
+
+/// Where an overlay sits in the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// Direction the camera travels across a still image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PanDirection {
+    #[default]
+    None,
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LowerThird {
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+    #[serde(default = "default_lower_third_position")]
+    pub position: OverlayPosition,
+    #[serde(default = "default_lower_third_duration")]
+    pub duration: f64,
+    #[serde(default = "default_lower_third_fade")]
+    pub fade: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipLayout {
+    #[serde(default = "default_pip_position")]
+    pub position: OverlayPosition,
+    #[serde(default = "default_pip_scale")]
+    pub scale: f64,
+    #[serde(default)]
+    pub fade: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KenBurns {
+    /// Image path, relative to the markdown file
+    pub image: String,
+    /// Start and end zoom factors
+    #[serde(default = "default_ken_burns_zoom")]
+    pub zoom: [f64; 2],
+    #[serde(default)]
+    pub pan: PanDirection,
+    /// Seconds to show the image; defaults to the length of the next segment
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default = "default_ken_burns_fade")]
+    pub fade: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverlayDirective {
+    LowerThird(LowerThird),
+    PictureInPicture(PipLayout),
+    KenBurns(KenBurns),
+}
+
+impl OverlayDirective {
+    pub fn kind(&self) -> DirectiveKind {
+        match self {
+            Self::LowerThird(_) => DirectiveKind::LowerThird,
+            Self::PictureInPicture(_) => DirectiveKind::PictureInPicture,
+            Self::KenBurns(_) => DirectiveKind::KenBurns,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectiveBlock {
+    pub directive: OverlayDirective,
+    /// Line of the opening fence in the markdown file
+    pub line: usize,
+}
+
+pub fn parse_directive(
+    kind: DirectiveKind,
+    content: &str,
+    line: usize,
+) -> Result<OverlayDirective> {
+    let context = || format!("Invalid `{}` block at line {}", kind.name(), line);
+    // An empty `pip` block means "all defaults"
+    let content = if content.trim().is_empty() {
+        "{}"
+    } else {
+        content
+    };
+    let directive = match kind {
+        DirectiveKind::LowerThird => {
+            let lower_third: LowerThird = serde_yaml::from_str(content).with_context(context)?;
+            if lower_third.title.trim().is_empty() {
+                bail!("`lower-third` block at line {} needs a title", line);
+            }
+            ensure_positive("duration", lower_third.duration, kind, line)?;
+            OverlayDirective::LowerThird(lower_third)
+        }
+        DirectiveKind::PictureInPicture => {
+            let layout: PipLayout = serde_yaml::from_str(content).with_context(context)?;
+            if !(layout.scale > 0.0 && layout.scale < 1.0) {
+                bail!(
+                    "`pip` scale at line {} must be between 0 and 1, got {}",
+                    line,
+                    layout.scale
+                );
+            }
+            OverlayDirective::PictureInPicture(layout)
+        }
+        DirectiveKind::KenBurns => {
+            let ken_burns: KenBurns = serde_yaml::from_str(content).with_context(context)?;
+            if ken_burns.image.trim().is_empty() {
+                bail!("`ken-burns` block at line {} needs an image", line);
+            }
+            for zoom in ken_burns.zoom {
+                if zoom < 1.0 {
+                    bail!(
+                        "`ken-burns` zoom at line {} must be at least 1.0, got {}",
+                        line,
+                        zoom
+                    );
+                }
+            }
+            if let Some(duration) = ken_burns.duration {
+                ensure_positive("duration", duration, kind, line)?;
+            }
+            OverlayDirective::KenBurns(ken_burns)
+        }
+    };
+
+    let fade = match &directive {
+        OverlayDirective::LowerThird(lower_third) => lower_third.fade,
+        OverlayDirective::PictureInPicture(layout) => layout.fade,
+        OverlayDirective::KenBurns(ken_burns) => ken_burns.fade,
+    };
+    if fade < 0.0 {
+        bail!(
+            "`{}` fade at line {} must not be negative",
+            kind.name(),
+            line
+        );
+    }
+
+    Ok(directive)
+}
+
+fn ensure_positive(field: &str, value: f64, kind: DirectiveKind, line: usize) -> Result<()> {
+    if value <= 0.0 {
+        bail!(
+            "`{}` {} at line {} must be positive, got {}",
+            kind.name(),
+            field,
+            line,
+            value
+        );
+    }
+    Ok(())
+}
+
+fn default_lower_third_position() -> OverlayPosition {
+    OverlayPosition::BottomLeft
+}
+
+fn default_lower_third_duration() -> f64 {
+    DEFAULT_LOWER_THIRD_SECONDS
+}
+
+fn default_lower_third_fade() -> f64 {
+    DEFAULT_LOWER_THIRD_FADE
+}
+
+fn default_pip_position() -> OverlayPosition {
+    OverlayPosition::BottomRight
+}
+
+fn default_pip_scale() -> f64 {
+    DEFAULT_PIP_SCALE
+}
+
+fn default_ken_burns_zoom() -> [f64; 2] {
+    DEFAULT_KEN_BURNS_ZOOM
+}
+
+fn default_ken_burns_fade() -> f64 {
+    DEFAULT_KEN_BURNS_FADE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_directives_with_defaults() {
+        let lower_third = parse_directive(
+            DirectiveKind::LowerThird,
+            "title: Jane Doe\nsubtitle: Maintainer\n",
+            3,
+        )
+        .unwrap();
+        let OverlayDirective::LowerThird(lower_third) = lower_third else {
+            panic!("expected lower third");
+        };
+        assert_eq!(lower_third.subtitle.as_deref(), Some("Maintainer"));
+        assert_eq!(lower_third.position, OverlayPosition::BottomLeft);
+        assert_eq!(lower_third.duration, DEFAULT_LOWER_THIRD_SECONDS);
+
+        let ken_burns = parse_directive(
+            DirectiveKind::KenBurns,
+            "image: still.png\nzoom: [1.1, 1.4]\npan: up\n",
+            7,
+        )
+        .unwrap();
+        let OverlayDirective::KenBurns(ken_burns) = ken_burns else {
+            panic!("expected ken burns");
+        };
+        assert_eq!(ken_burns.zoom, [1.1, 1.4]);
+        assert_eq!(ken_burns.pan, PanDirection::Up);
+        assert_eq!(ken_burns.duration, None);
+    }
+
+    #[test]
+    fn rejects_invalid_directives() {
+        assert!(parse_directive(DirectiveKind::PictureInPicture, "scale: 1.5\n", 1).is_err());
+        assert!(parse_directive(DirectiveKind::PictureInPicture, "corner: top\n", 1).is_err());
+        assert!(parse_directive(DirectiveKind::LowerThird, "title: ''\n", 1).is_err());
+        assert!(
+            parse_directive(DirectiveKind::KenBurns, "image: a.png\nzoom: [0.5, 1]\n", 1).is_err()
+        );
+    }
+
+    #[test]
+    fn matches_fence_info_strings() {
+        assert_eq!(
+            DirectiveKind::from_info("lower-third"),
+            Some(DirectiveKind::LowerThird)
+        );
+        assert_eq!(
+            DirectiveKind::from_info("PIP extra"),
+            Some(DirectiveKind::PictureInPicture)
+        );
+        assert_eq!(DirectiveKind::from_info("rust"), None);
+    }
+}
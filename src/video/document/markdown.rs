@@ -1,6 +1,8 @@
+use crate::video::slides::SlideTheme;
 use crate::video::support::transcript::TranscriptCue;
 use crate::video::support::utils::duration_to_tenths;
 use chrono::Utc;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use super::VideoMetadata;
@@ -71,25 +73,64 @@ impl VideoMetadata {
             let video_source = yaml_quote(&source.source.to_string_lossy());
             let transcript_source = yaml_quote(&source.transcript.to_string_lossy());
             let video_hash = yaml_quote(source.hash.as_deref().unwrap_or(""));
-            source_lines.push(format!(
+            let mut entry = format!(
                 "- id: {source_id}\n  hash: {video_hash}\n  name: {name}\n  source: {video_source}\n  transcript: {transcript_source}",
                 name = yaml_quote(source.name.as_deref().unwrap_or("")),
-            ));
+            );
+            if let Some(sync) = &source.sync {
+                entry.push_str(&format!(
+                    "\n  sync:\n    reference: {}\n    offset: {:.4}\n    drift: {:.8}",
+                    yaml_quote(&sync.reference),
+                    sync.offset,
+                    sync.drift
+                ));
+            }
+            source_lines.push(entry);
         }
+        let theme = self
+            .slide_theme
+            .as_ref()
+            .map(render_slide_theme)
+            .unwrap_or_default();
         if source_lines.is_empty() {
             return format!(
-                "---\ndefault_source: {default_source}\nsources: []\ngenerated_at: '{timestamp}'\n---"
+                "---\ndefault_source: {default_source}\nsources: []\n{theme}generated_at: '{timestamp}'\n---"
             );
         }
 
         let sources_block = source_lines.join("\n");
         format!(
-            "---\ndefault_source: {default_source}\nsources:\n{sources}\ngenerated_at: '{timestamp}'\n---",
+            "---\ndefault_source: {default_source}\nsources:\n{sources}\n{theme}generated_at: '{timestamp}'\n---",
             sources = sources_block,
         )
     }
 }
 
+/// `slide_theme` block, so rewriting the front matter keeps the project theme
+fn render_slide_theme(theme: &SlideTheme) -> String {
+    let path = |path: &PathBuf| yaml_quote(&path.to_string_lossy());
+    let mut lines = vec!["slide_theme:".to_string()];
+    if let Some(css) = &theme.css {
+        lines.push(format!("  css: {}", path(css)));
+    }
+    if !theme.fonts.is_empty() {
+        lines.push("  fonts:".to_string());
+        for font in &theme.fonts {
+            lines.push(format!("  - {}", path(font)));
+        }
+    }
+    if let Some(logo) = &theme.logo {
+        lines.push(format!("  logo: {}", path(logo)));
+    }
+    if let Some(background) = &theme.background {
+        lines.push(format!("  background: {}", path(background)));
+    }
+    if let Some(style) = &theme.code_style {
+        lines.push(format!("  code_style: {}", yaml_quote(style)));
+    }
+    lines.join("\n") + "\n"
+}
+
 fn yaml_quote(value: &str) -> String {
     if value.is_empty() {
         "''".to_string()
@@ -139,8 +180,10 @@ mod tests {
                 transcript: PathBuf::from("/tmp/clip.srt"),
                 audio: PathBuf::new(),
                 hash: Some("hash".to_string()),
+                sync: None,
             }],
             default_source: Some("a".to_string()),
+            slide_theme: None,
         };
 
         let output = build_markdown(&cues, &metadata);
@@ -4,21 +4,16 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result, anyhow, bail};
 use serde::Deserialize;
 
-use super::types::{VideoMetadata, VideoSource};
+use super::types::{SourceSync, VideoMetadata, VideoSource};
+use crate::video::slides::SlideTheme;
 
 pub fn parse_metadata(front_matter: Option<&str>, source_path: &Path) -> Result<VideoMetadata> {
     let Some(fm) = front_matter else {
-        return Ok(VideoMetadata {
-            sources: Vec::new(),
-            default_source: None,
-        });
+        return Ok(VideoMetadata::default());
     };
 
     if fm.trim().is_empty() {
-        return Ok(VideoMetadata {
-            sources: Vec::new(),
-            default_source: None,
-        });
+        return Ok(VideoMetadata::default());
     }
 
     let parsed: FrontMatter = serde_yaml::from_str(fm).with_context(|| {
@@ -44,9 +39,17 @@ pub fn parse_metadata(front_matter: Option<&str>, source_path: &Path) -> Result<
             let source = entry
                 .source
                 .ok_or_else(|| anyhow!("Source `{}` is missing `source`", id))?;
-            let transcript = entry
-                .transcript
-                .ok_or_else(|| anyhow!("Source `{}` is missing `transcript`", id))?;
+            // Synced angle-only sources borrow the reference transcript
+            let transcript = match (entry.transcript, &entry.sync) {
+                (Some(transcript), _) => transcript,
+                (None, Some(_)) => String::new(),
+                (None, None) => bail!("Source `{}` is missing `transcript`", id),
+            };
+            let sync = entry.sync.map(|sync| SourceSync {
+                reference: sync.reference,
+                offset: sync.offset,
+                drift: sync.drift,
+            });
             sources.push(VideoSource {
                 id,
                 name: entry.name,
@@ -54,14 +57,15 @@ pub fn parse_metadata(front_matter: Option<&str>, source_path: &Path) -> Result<
                 transcript: PathBuf::from(transcript),
                 audio: PathBuf::new(),
                 hash: entry.hash,
+                sync,
             });
         }
     }
 
     if sources.is_empty() {
         return Ok(VideoMetadata {
-            sources: Vec::new(),
-            default_source: None,
+            slide_theme: parsed.slide_theme,
+            ..VideoMetadata::default()
         });
     }
 
@@ -86,9 +90,30 @@ pub fn parse_metadata(front_matter: Option<&str>, source_path: &Path) -> Result<
         }
     }
 
+    for source in &sources {
+        let Some(sync) = &source.sync else {
+            continue;
+        };
+        let Some(reference) = sources.iter().find(|s| s.id == sync.reference) else {
+            bail!(
+                "Source `{}` is synced to unknown source `{}`",
+                source.id,
+                sync.reference
+            );
+        };
+        if reference.sync.is_some() || reference.id == source.id {
+            bail!(
+                "Source `{}` must sync to an unsynced reference source, not `{}`",
+                source.id,
+                sync.reference
+            );
+        }
+    }
+
     Ok(VideoMetadata {
         sources,
         default_source,
+        slide_theme: parsed.slide_theme,
     })
 }
 
@@ -96,6 +121,7 @@ pub fn parse_metadata(front_matter: Option<&str>, source_path: &Path) -> Result<
 struct FrontMatter {
     sources: Option<Vec<FrontMatterSource>>,
     default_source: Option<String>,
+    slide_theme: Option<SlideTheme>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -105,4 +131,14 @@ struct FrontMatterSource {
     source: Option<String>,
     transcript: Option<String>,
     hash: Option<String>,
+    sync: Option<FrontMatterSync>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FrontMatterSync {
+    reference: String,
+    #[serde(default)]
+    offset: f64,
+    #[serde(default)]
+    drift: f64,
 }
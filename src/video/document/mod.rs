@@ -3,6 +3,8 @@
 //! Sub-modules:
 //! - `frontmatter` — splitting front matter from body
 //! - `markdown`    — building markdown from transcript cues (reverse direction)
+//! - `cuts`        — filler/stutter/pause cut suggestions and cut statistics
+//! - `clips`       — named clips (shorts) tagged with `<!-- clip: name -->` comments
 //! - `types`       — core document types (`VideoDocument`, `DocumentBlock`, etc.)
 //! - `metadata`    — YAML front matter parsing into `VideoMetadata`
 //! - `body`        — markdown body parsing (state machines for paragraphs, headings, etc.)
@@ -10,6 +12,9 @@
 //! - `reference`   — segment reference parsing (`source@time-range`)
 //! - `util`        — internal helpers (html comment stripping, line map, etc.)
 
+pub mod clips;
+pub mod cuts;
+pub mod directives;
 pub mod frontmatter;
 pub mod markdown;
 
@@ -22,8 +27,9 @@ pub(crate) mod util;
 
 // Re-export public types for external callers
 pub use types::{
-    BrollBlock, DocumentBlock, HeadingBlock, MusicDirective, SegmentBlock, SegmentKind,
-    UnhandledBlock, VideoDocument, VideoMetadata, VideoSource,
+    BrollBlock, BuildSlideBlock, DocumentBlock, HeadingBlock, MusicDirective, SegmentBlock,
+    SegmentKind, SourceSync, UnhandledBlock, VideoDocument, VideoMetadata, VideoSource,
+    map_source_time,
 };
 
 use std::path::Path;
@@ -89,6 +95,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_synced_sources_and_camera_angles() {
+        let markdown = concat!(
+            "---\n",
+            "sources:\n",
+            "- id: a\n  source: video_a.mp4\n  transcript: a.json\n",
+            "- id: b\n  source: video_b.mp4\n  sync:\n    reference: a\n    offset: 2.5\n    drift: 0.001\n",
+            "default_source: a\n",
+            "---\n",
+            "`a@00:10.0-00:15.0>b` seen from the second camera\n",
+        );
+        let document = parse_video_document(markdown, Path::new("test.md")).unwrap();
+
+        let sync = document.metadata.sources[1].sync.as_ref().unwrap();
+        assert_eq!(sync.reference, "a");
+        match &document.blocks[0] {
+            DocumentBlock::Segment(segment) => {
+                assert_eq!(segment.source_id, "a");
+                assert_eq!(segment.angle.as_deref(), Some("b"));
+            }
+            other => panic!("Expected Segment block, got {:?}", other),
+        }
+
+        let sources = &document.metadata.sources;
+        let in_b = map_source_time(sources, "a", "b", 10.0).unwrap();
+        assert!((in_b - 12.51).abs() < 1e-9);
+        let back = map_source_time(sources, "b", "a", in_b).unwrap();
+        assert!((back - 10.0).abs() < 1e-9);
+
+        let reparsed = parse_video_document(
+            &document.metadata.render_frontmatter(),
+            Path::new("test.md"),
+        )
+        .unwrap();
+        assert_eq!(reparsed.metadata.sources[1].sync, Some(sync.clone()));
+    }
+
+    #[test]
+    fn parses_build_slides_and_slide_theme() {
+        let markdown = concat!(
+            "---\n",
+            "sources:\n",
+            "- id: a\n  source: video_a.mp4\n  transcript: a.json\n",
+            "slide_theme:\n  css: theme/course.css\n  fonts: [theme/Inter.woff2]\n  code_style: tango\n",
+            "---\n",
+            "`a@00:00.0-00:02.0` intro\n\n",
+            "```build\n## Agenda\n\n- Ownership\n- Borrowing\n1. Lifetimes\n```\n",
+        );
+        let document = parse_video_document(markdown, Path::new("test.md")).unwrap();
+
+        let theme = document.metadata.slide_theme.as_ref().unwrap();
+        assert_eq!(
+            theme.fonts,
+            vec![std::path::PathBuf::from("theme/Inter.woff2")]
+        );
+        let reparsed = parse_video_document(
+            &document.metadata.render_frontmatter(),
+            Path::new("test.md"),
+        )
+        .unwrap();
+        assert_eq!(reparsed.metadata.slide_theme.as_ref(), Some(theme));
+
+        match &document.blocks[1] {
+            DocumentBlock::Build(build) => assert_eq!(build.steps(), 3),
+            other => panic!("Expected Build block, got {:?}", other),
+        }
+        let plan = document.plan_timeline().unwrap();
+        match &plan.items[1] {
+            crate::video::planning::TimelinePlanItem::Standalone(standalone) => {
+                assert_eq!(standalone.build_steps, 3);
+                assert!(standalone.markdown.starts_with("## Agenda"));
+            }
+            other => panic!("Expected Standalone item, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_angle_matching_segment_source() {
+        let markdown = concat!(
+            "---\n",
+            "sources:\n",
+            "- id: a\n  source: video_a.mp4\n  transcript: a.json\n",
+            "---\n",
+            "`a@00:10.0-00:15.0>a` same camera\n",
+        );
+        assert!(parse_video_document(markdown, Path::new("test.md")).is_err());
+    }
+
     #[test]
     fn preserves_unhandled_text_when_no_segments() {
         let markdown = "This is an intro paragraph without timestamps.";
@@ -107,6 +107,25 @@ pub fn parse_segment_reference(
     Ok((source_id.to_string(), range))
 }
 
+/// Splits a trailing `>angle` off a segment reference (`a@00:01.0-00:02.0>b`).
+pub fn split_segment_angle<'a>(
+    input: &'a str,
+    source_config: &SegmentSourceConfig,
+    line: usize,
+) -> Result<(&'a str, Option<String>)> {
+    let Some((reference, angle)) = input.rsplit_once('>') else {
+        return Ok((input, None));
+    };
+    let angle = angle.trim();
+    if !is_valid_source_id(angle) {
+        bail!("Invalid camera angle `{}` at line {}", angle, line);
+    }
+    if !source_config.known_sources.is_empty() && !source_config.known_sources.contains(angle) {
+        bail!("Unknown camera angle source `{}` at line {}", angle, line);
+    }
+    Ok((reference, Some(angle.to_string())))
+}
+
 pub fn is_valid_source_id(value: &str) -> bool {
     let mut chars = value.chars();
     let Some(first) = chars.next() else {
@@ -1,16 +1,24 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use anyhow::{Result, bail};
+
+use super::clips::ClipMarker;
+use super::directives::DirectiveBlock;
+use crate::video::slides::SlideTheme;
+
 #[derive(Debug)]
 pub struct VideoDocument {
     pub metadata: VideoMetadata,
     pub blocks: Vec<DocumentBlock>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct VideoMetadata {
     pub sources: Vec<VideoSource>,
     pub default_source: Option<String>,
+    /// Project slide theme (`slide_theme` in the front matter)
+    pub slide_theme: Option<SlideTheme>,
 }
 
 #[derive(Debug, Clone)]
@@ -22,9 +30,68 @@ pub struct VideoSource {
     /// Resolved at runtime during render; never serialized to frontmatter.
     pub audio: PathBuf,
     pub hash: Option<String>,
+    /// Clock alignment with another source, computed by `ins video sync`
+    pub sync: Option<SourceSync>,
 }
 
-#[derive(Debug)]
+/// Maps reference time to source time: `source = offset + reference * (1 + drift)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSync {
+    /// Source id whose timeline this source is aligned to
+    pub reference: String,
+    /// Seconds into this source at which the reference starts (negative if it starts later)
+    pub offset: f64,
+    /// Relative clock drift, e.g. `0.0001` when this source runs 0.01% fast
+    pub drift: f64,
+}
+
+impl SourceSync {
+    pub fn reference_time(&self, time: f64) -> f64 {
+        (time - self.offset) / (1.0 + self.drift)
+    }
+
+    pub fn source_time(&self, time: f64) -> f64 {
+        self.offset + time * (1.0 + self.drift)
+    }
+}
+
+/// Map a timestamp in source `from` to the same moment in source `to`
+pub fn map_source_time(sources: &[VideoSource], from: &str, to: &str, time: f64) -> Result<f64> {
+    if from == to {
+        return Ok(time);
+    }
+    let lookup = |id: &str| {
+        sources
+            .iter()
+            .find(|source| source.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown source `{}`", id))
+    };
+    let (from_source, to_source) = (lookup(from)?, lookup(to)?);
+
+    // Sources without sync data are their own reference
+    let (from_root, reference_time) = match &from_source.sync {
+        Some(sync) => (sync.reference.as_str(), sync.reference_time(time)),
+        None => (from, time),
+    };
+    let to_root = to_source
+        .sync
+        .as_ref()
+        .map_or(to, |sync| sync.reference.as_str());
+    if from_root != to_root {
+        bail!(
+            "Sources `{}` and `{}` are not synced; run `ins video sync` first",
+            from,
+            to
+        );
+    }
+
+    Ok(to_source
+        .sync
+        .as_ref()
+        .map_or(reference_time, |sync| sync.source_time(reference_time)))
+}
+
+#[derive(Debug, Clone)]
 pub enum DocumentBlock {
     Segment(SegmentBlock),
     Heading(HeadingBlock),
@@ -32,6 +99,9 @@ pub enum DocumentBlock {
     Unhandled(UnhandledBlock),
     Music(MusicDirective),
     Broll(BrollBlock),
+    Directive(DirectiveBlock),
+    Clip(ClipMarker),
+    Build(BuildSlideBlock),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -40,26 +110,55 @@ pub enum SegmentKind {
     Silence,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SegmentBlock {
     pub range: TimeRange,
     pub text: String,
     pub kind: SegmentKind,
     pub source_id: String,
+    /// Synced source whose picture is shown instead (`a@00:01.0-00:02.0>b`)
+    pub angle: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HeadingBlock {
     pub level: u32,
     pub text: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UnhandledBlock {
     pub description: String,
 }
 
-#[derive(Debug)]
+/// Slide from a fenced ```build block that reveals its list items one at a time
+#[derive(Debug, Clone)]
+pub struct BuildSlideBlock {
+    pub markdown: String,
+}
+
+impl BuildSlideBlock {
+    /// One step per list item
+    pub fn steps(&self) -> usize {
+        self.markdown
+            .lines()
+            .map(str::trim_start)
+            .filter(|line| {
+                let ordered = line.split_once(['.', ')']).is_some_and(|(number, rest)| {
+                    !number.is_empty()
+                        && number.chars().all(|c| c.is_ascii_digit())
+                        && rest.starts_with(' ')
+                });
+                ordered
+                    || ["- ", "* ", "+ "]
+                        .iter()
+                        .any(|marker| line.starts_with(marker))
+            })
+            .count()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct BrollBlock {
     pub range: TimeRange,
     pub text: String,
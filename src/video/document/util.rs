@@ -1,5 +1,7 @@
 use pulldown_cmark::HeadingLevel;
 
+use super::clips::is_clip_comment;
+
 pub fn heading_level_to_u32(level: HeadingLevel) -> u32 {
     match level {
         HeadingLevel::H1 => 1,
@@ -18,10 +20,19 @@ pub fn is_music_code_block(info: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Fenced ```build blocks hold a slide whose list items are revealed one by one
+pub fn is_build_code_block(info: &str) -> bool {
+    info.split(|c: char| c.is_whitespace())
+        .next()
+        .map(|lang| lang.eq_ignore_ascii_case("build"))
+        .unwrap_or(false)
+}
+
 pub fn count_newlines(text: &str) -> usize {
     text.bytes().filter(|b| *b == b'\n').count()
 }
 
+/// Remove HTML comments, keeping clip markers for the body parser
 pub fn strip_html_comments(input: &str) -> String {
     let mut output = String::with_capacity(input.len());
 
@@ -33,6 +44,9 @@ pub fn strip_html_comments(input: &str) -> String {
         let after_start = start + "<!--".len();
         if let Some(end_rel) = input[after_start..].find("-->") {
             let end = after_start + end_rel + "-->".len();
+            if is_clip_comment(&input[start..end]) {
+                output.push_str(&input[start..end]);
+            }
             cursor = end;
         } else {
             return output;
@@ -220,6 +220,7 @@ async fn create_multi_source_project(videos: Vec<PathBuf>) -> Result<()> {
         no_preprocess,
         preprocessor: preprocessor.clone(),
         language,
+        suggest_cuts: false,
     })
     .await?;
 
@@ -66,6 +66,7 @@ pub async fn run_transcribe() -> Result<()> {
 
     transcribe::handle_transcribe(TranscribeArgs {
         video: video_path,
+        backend: None,
         compute_type,
         device,
         model,
@@ -377,6 +377,15 @@ async fn run_render_for_project(markdown_path: &Path) -> Result<()> {
         out_file,
         force,
         dry_run: render_options.dry_run,
+        presets: Vec::new(),
+        chapters: false,
+        sidecar_subtitles: false,
+        soft_subtitles: false,
+        description: false,
+        publish: false,
+        no_cache: false,
+        jobs: None,
+        clips: false,
         common: crate::video::cli::VideoProcessArgs {
             precache_slides: render_options.precache_slides,
             reels,
@@ -414,6 +423,7 @@ async fn run_preview_for_project(markdown_path: &Path) -> Result<()> {
             verbose: false,
         },
         seek: None,
+        watch: false,
     })
     .await?;
 
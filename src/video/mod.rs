@@ -10,6 +10,7 @@ pub mod render;
 pub mod slides;
 pub mod subtitles;
 pub mod support;
+mod transcriber;
 pub mod transcript_language;
 
 pub use cli::VideoCommands;
@@ -5,7 +5,9 @@ use anyhow::{Result, bail};
 use crate::ui::prelude::Level;
 
 use crate::video::cli::CheckArgs;
-use crate::video::document::VideoSource;
+use crate::video::document::cuts::cut_statistics;
+use crate::video::document::directives::OverlayDirective;
+use crate::video::document::{DocumentBlock, VideoDocument, VideoSource};
 use crate::video::pipeline::report::{ReportLine, emit_report, format_report_lines};
 use crate::video::planning::TimelinePlanItem;
 use crate::video::render::{
@@ -108,6 +110,24 @@ async fn build_check_report(args: CheckArgs) -> Result<Vec<ReportLine>> {
         ),
     ));
 
+    let markdown = std::fs::read_to_string(&markdown_path)?;
+    let cuts = cut_statistics(&markdown);
+    if cuts.total_count() > 0 {
+        report.push(ReportLine::new(
+            Level::Info,
+            "video.check.cuts",
+            format!(
+                "Suggested cuts: {count} saving {saved} (~{seconds:.1}s) — {breakdown}",
+                count = cuts.total_count(),
+                saved = format_duration(cuts.total_duration().as_secs_f64()),
+                seconds = cuts.total_duration().as_secs_f64(),
+                breakdown = cuts.describe(),
+            ),
+        ));
+    }
+
+    emit_directive_status(&document, markdown_dir, &mut report);
+
     if unsupported_blocks == 0 {
         report.push(ReportLine::new(
             Level::Success,
@@ -128,14 +148,73 @@ async fn build_check_report(args: CheckArgs) -> Result<Vec<ReportLine>> {
 fn plan_duration_seconds(plan: &crate::video::planning::TimelinePlan) -> f64 {
     plan.items
         .iter()
-        .map(|item| match item {
-            TimelinePlanItem::Clip(clip) => clip.time_window.duration().max(0.0),
-            TimelinePlanItem::Standalone(standalone) => standalone.duration_seconds,
-            TimelinePlanItem::Music(_) => 0.0,
-        })
+        .map(TimelinePlanItem::duration_seconds)
         .sum::<f64>()
 }
 
+/// Summarize overlay directives and flag ones that will have no effect
+pub(crate) fn emit_directive_status(
+    document: &VideoDocument,
+    markdown_dir: &Path,
+    report: &mut Vec<ReportLine>,
+) {
+    let (mut lower_thirds, mut pips, mut stills) = (0, 0, 0);
+    for (idx, block) in document.blocks.iter().enumerate() {
+        let DocumentBlock::Directive(directive) = block else {
+            continue;
+        };
+        let following = &document.blocks[idx + 1..];
+        let has_segment = following
+            .iter()
+            .any(|block| matches!(block, DocumentBlock::Segment(_)));
+        let problem = match &directive.directive {
+            OverlayDirective::LowerThird(_) => {
+                lower_thirds += 1;
+                (!has_segment).then(|| "has no following segment to caption".to_string())
+            }
+            OverlayDirective::KenBurns(ken_burns) => {
+                stills += 1;
+                let image = markdown_dir.join(&ken_burns.image);
+                if !image.exists() {
+                    Some(format!("image {} not found", image.display()))
+                } else {
+                    (!has_segment).then(|| "has no following segment to cover".to_string())
+                }
+            }
+            OverlayDirective::PictureInPicture(_) => {
+                pips += 1;
+                let has_broll = following
+                    .iter()
+                    .take_while(|block| !matches!(block, DocumentBlock::Separator))
+                    .any(|block| matches!(block, DocumentBlock::Broll(_)));
+                (!has_broll).then(|| "is not followed by a b-roll quote".to_string())
+            }
+        };
+        if let Some(problem) = problem {
+            report.push(ReportLine::new(
+                Level::Warn,
+                "video.check.directive",
+                format!(
+                    "`{}` block at line {} {}",
+                    directive.directive.kind().name(),
+                    directive.line,
+                    problem
+                ),
+            ));
+        }
+    }
+
+    if lower_thirds + pips + stills > 0 {
+        report.push(ReportLine::new(
+            Level::Info,
+            "video.check.directives",
+            format!(
+                "Lower thirds: {lower_thirds}, Picture-in-picture: {pips}, Ken Burns stills: {stills}"
+            ),
+        ));
+    }
+}
+
 fn emit_source_status(source: &VideoSource, report: &mut Vec<ReportLine>) {
     let exists = source.source.exists();
     report.push(ReportLine::new(
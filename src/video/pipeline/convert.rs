@@ -9,10 +9,13 @@ use super::transcribe::handle_transcribe;
 use crate::video::audio::{PreprocessorType, create_preprocessor, parse_preprocessor_type};
 use crate::video::cli::{AppendArgs, ConvertArgs, TranscribeArgs};
 use crate::video::config::{VideoCachePaths, VideoConfig, VideoDirectories};
+use crate::video::document::cuts::{CutOptions, suggest_cuts};
 use crate::video::document::frontmatter::split_frontmatter;
 use crate::video::document::markdown::{build_markdown, format_timestamp};
 use crate::video::document::{VideoMetadata, VideoSource, parse_video_document};
-use crate::video::support::transcript::{TranscriptCue, parse_whisper_json};
+use crate::video::support::transcript::{
+    TranscriptCue, TranscriptFormat, normalize_transcript, parse_whisper_json, read_transcript,
+};
 use crate::video::support::utils::{canonicalize_existing, compute_file_hash};
 use crate::video::transcript_language::TranscriptLanguage;
 
@@ -50,7 +53,18 @@ pub async fn handle_convert(args: ConvertArgs) -> Result<()> {
     .await?;
 
     // Step 2: Generate markdown output
-    generate_markdown_output(&video_path, &video_hash, &transcript_path, &output_path)?;
+    let cut_options = if args.suggest_cuts {
+        Some(VideoConfig::load()?.cut_options(None))
+    } else {
+        None
+    };
+    generate_markdown_output(
+        &video_path,
+        &video_hash,
+        &transcript_path,
+        &output_path,
+        cut_options.as_ref(),
+    )?;
 
     emit(
         Level::Success,
@@ -160,6 +174,7 @@ async fn prepare_video_and_transcript(args: &AppendArgs) -> Result<VideoAppendIn
             no_preprocess: args.no_preprocess,
             preprocessor: args.preprocessor.clone(),
             language: args.language,
+            suggest_cuts: false,
         },
     )
     .await?;
@@ -215,6 +230,7 @@ fn add_new_source_to_metadata(
         transcript: relative_subtitle_path,
         audio: PathBuf::new(),
         hash: Some(video_hash.to_string()),
+        sync: None,
     });
 
     if metadata.default_source.is_none() {
@@ -260,7 +276,7 @@ async fn ensure_transcript(
     // If user provided a transcript, use it
     if let Some(provided) = provided_transcript {
         let provided_path = canonicalize_existing(provided)?;
-        copy_transcript(&provided_path, &cached_transcript_path)?;
+        import_transcript(&provided_path, &cached_transcript_path)?;
         return Ok(cached_transcript_path);
     }
 
@@ -281,6 +297,7 @@ async fn ensure_transcript(
 
     handle_transcribe(TranscribeArgs {
         video: audio_source.clone(),
+        backend: None,
         compute_type: "int8".to_string(),
         device: "cpu".to_string(),
         model: None,
@@ -399,6 +416,7 @@ fn generate_markdown_output(
     video_hash: &str,
     transcript_path: &Path,
     output_path: &Path,
+    cut_options: Option<&CutOptions>,
 ) -> Result<()> {
     let markdown_dir = output_path.parent().unwrap_or_else(|| Path::new("."));
     let subtitle_dir = markdown_dir.join("insvideodata");
@@ -437,11 +455,27 @@ fn generate_markdown_output(
             transcript: relative_subtitle_path,
             audio: PathBuf::new(),
             hash: Some(video_hash.to_string()),
+            sync: None,
         }],
         default_source: Some("a".to_string()),
+        slide_theme: None,
     };
 
-    let markdown = build_markdown(&cues, &metadata);
+    let mut markdown = build_markdown(&cues, &metadata);
+    if let Some(options) = cut_options {
+        let (annotated, summary) = suggest_cuts(&markdown, &cues, "a", options)?;
+        markdown = annotated;
+        emit(
+            Level::Info,
+            "video.convert.suggest_cuts",
+            &format!(
+                "Suggested {} cut(s) saving {:.1}s",
+                summary.total_count(),
+                summary.total_duration().as_secs_f64()
+            ),
+            None,
+        );
+    }
 
     if let Some(parent) = output_path.parent() {
         fs::create_dir_all(parent)
@@ -462,9 +496,7 @@ fn generate_markdown_output(
 }
 
 fn load_transcript_cues(path: &Path) -> Result<Vec<TranscriptCue>> {
-    let transcript_contents = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read transcript at {}", path.display()))?;
-    parse_whisper_json(&transcript_contents)
+    read_transcript(path)
 }
 
 fn next_source_id(metadata: &crate::video::document::VideoMetadata) -> Result<String> {
@@ -507,6 +539,32 @@ fn build_source_markdown(
     lines.join("\n")
 }
 
+/// Store a user-provided transcript in the cache as WhisperX-style JSON
+fn import_transcript(src: &Path, dest: &Path) -> Result<()> {
+    let contents = fs::read_to_string(src)
+        .with_context(|| format!("Failed to read transcript at {}", src.display()))?;
+    let format = TranscriptFormat::detect(src, &contents);
+    if format == TranscriptFormat::WhisperX {
+        return copy_transcript(src, dest);
+    }
+
+    emit(
+        Level::Info,
+        "video.convert.import_transcript",
+        &format!("Importing {:?} transcript {}", format, src.display()),
+        None,
+    );
+    let normalized = normalize_transcript(&contents, format)
+        .with_context(|| format!("Failed to import transcript {}", src.display()))?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create transcript directory {}", parent.display())
+        })?;
+    }
+    fs::write(dest, normalized)
+        .with_context(|| format!("Failed to write transcript to {}", dest.display()))
+}
+
 fn copy_transcript(src: &Path, dest: &Path) -> Result<()> {
     if src == dest {
         return Ok(());
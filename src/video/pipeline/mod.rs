@@ -2,4 +2,6 @@ pub mod check;
 pub mod convert;
 pub(crate) mod report;
 pub mod setup;
+pub mod suggest;
+pub mod sync;
 pub mod transcribe;
@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::ui::prelude::{Level, emit};
+
+use crate::video::cli::SuggestCutsArgs;
+use crate::video::config::VideoConfig;
+use crate::video::document::cuts::suggest_cuts;
+use crate::video::document::parse_video_document;
+use crate::video::render::load_transcript_cues;
+use crate::video::support::utils::canonicalize_existing;
+
+pub fn handle_suggest_cuts(args: SuggestCutsArgs) -> Result<()> {
+    let markdown_path = canonicalize_existing(&args.markdown)?;
+    let markdown_dir = markdown_path.parent().unwrap_or_else(|| Path::new("."));
+    let markdown = fs::read_to_string(&markdown_path)
+        .with_context(|| format!("Failed to read markdown file {}", markdown_path.display()))?;
+    let document = parse_video_document(&markdown, &markdown_path)?;
+
+    let cues = load_transcript_cues(&document.metadata.sources, markdown_dir)?;
+    let default_source = document
+        .metadata
+        .default_source
+        .clone()
+        .or_else(|| document.metadata.sources.first().map(|s| s.id.clone()))
+        .unwrap_or_else(|| "a".to_string());
+
+    let options = VideoConfig::load()?.cut_options(args.pause_threshold);
+    let (annotated, summary) = suggest_cuts(&markdown, &cues, &default_source, &options)?;
+
+    if summary.total_count() == 0 {
+        emit(
+            Level::Info,
+            "video.suggest_cuts.none",
+            "No filler words, stutters or long pauses found",
+            None,
+        );
+        return Ok(());
+    }
+
+    let message = format!(
+        "{} cut(s) saving {:.1}s: {}",
+        summary.total_count(),
+        summary.total_duration().as_secs_f64(),
+        summary.describe()
+    );
+    if args.dry_run {
+        emit(
+            Level::Info,
+            "video.suggest_cuts.dry_run",
+            &format!("Would suggest {message}"),
+            None,
+        );
+        return Ok(());
+    }
+
+    fs::write(&markdown_path, annotated)
+        .with_context(|| format!("Failed to write markdown file {}", markdown_path.display()))?;
+    emit(
+        Level::Success,
+        "video.suggest_cuts.success",
+        &format!(
+            "Suggested {message} in {} (remove the comment markers to keep a segment)",
+            markdown_path.display()
+        ),
+        None,
+    );
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result, anyhow, bail};
+use std::fs;
+use std::path::Path;
+
+use crate::ui::prelude::{Level, emit};
+
+use crate::video::cli::SyncArgs;
+use crate::video::document::frontmatter::split_frontmatter;
+use crate::video::document::{SourceSync, parse_video_document};
+use crate::video::support::audio_sync::{SyncOptions, estimate_sync};
+use crate::video::support::utils::canonicalize_existing;
+
+/// Peak correlations below this usually mean the sources don't share audio
+const LOW_CONFIDENCE: f64 = 0.3;
+
+pub fn handle_sync(args: SyncArgs) -> Result<()> {
+    let markdown_path = canonicalize_existing(&args.markdown)?;
+    let markdown_dir = markdown_path.parent().unwrap_or_else(|| Path::new("."));
+    let markdown = fs::read_to_string(&markdown_path)
+        .with_context(|| format!("Failed to read markdown file {}", markdown_path.display()))?;
+    let mut metadata = parse_video_document(&markdown, &markdown_path)?.metadata;
+
+    let reference_id = args
+        .reference
+        .clone()
+        .or_else(|| metadata.default_source.clone())
+        .or_else(|| metadata.sources.first().map(|source| source.id.clone()))
+        .ok_or_else(|| anyhow!("No sources declared in {}", markdown_path.display()))?;
+    let reference = metadata
+        .sources
+        .iter()
+        .find(|source| source.id == reference_id)
+        .ok_or_else(|| anyhow!("Unknown reference source `{}`", reference_id))?;
+    if reference.sync.is_some() && args.reference.is_some() {
+        bail!(
+            "Reference source `{}` is itself synced; pick an unsynced reference",
+            reference_id
+        );
+    }
+    let reference_path = canonicalize_existing(&markdown_dir.join(&reference.source))?;
+
+    let targets: Vec<String> = if args.sources.is_empty() {
+        metadata
+            .sources
+            .iter()
+            .map(|source| source.id.clone())
+            .filter(|id| id != &reference_id)
+            .collect()
+    } else {
+        args.sources.clone()
+    };
+    if targets.is_empty() {
+        bail!("Nothing to sync: only `{}` is declared", reference_id);
+    }
+
+    let options = SyncOptions {
+        max_offset: args.max_offset,
+        drift: args.drift,
+        ..SyncOptions::default()
+    };
+
+    for target_id in &targets {
+        if target_id == &reference_id {
+            bail!("Cannot sync `{}` to itself", target_id);
+        }
+        let target = metadata
+            .sources
+            .iter_mut()
+            .find(|source| &source.id == target_id)
+            .ok_or_else(|| anyhow!("Unknown source `{}`", target_id))?;
+        let target_path = canonicalize_existing(&markdown_dir.join(&target.source))?;
+
+        emit(
+            Level::Info,
+            "video.sync.analyze",
+            &format!(
+                "Correlating audio of `{}` against `{}`...",
+                target_id, reference_id
+            ),
+            None,
+        );
+        let estimate = estimate_sync(&reference_path, &target_path, options)
+            .with_context(|| format!("Failed to sync `{}` to `{}`", target_id, reference_id))?;
+
+        let mut message = format!(
+            "`{}` = `{}` {:+.3}s (confidence {:.2})",
+            target_id, reference_id, estimate.offset, estimate.confidence
+        );
+        if args.drift {
+            message.push_str(&format!(", drift {:+.1} ms/min", estimate.drift * 60_000.0));
+        }
+        emit(Level::Info, "video.sync.result", &message, None);
+        if estimate.confidence < LOW_CONFIDENCE {
+            emit(
+                Level::Warn,
+                "video.sync.low_confidence",
+                &format!(
+                    "Low confidence for `{}`; check that both recordings captured the same audio",
+                    target_id
+                ),
+                None,
+            );
+        }
+
+        target.sync = Some(SourceSync {
+            reference: reference_id.clone(),
+            offset: estimate.offset,
+            drift: estimate.drift,
+        });
+    }
+
+    if args.dry_run {
+        return Ok(());
+    }
+
+    let (_, body, _) = split_frontmatter(&markdown)?;
+    let contents = format!(
+        "{}\n{}",
+        metadata.render_frontmatter(),
+        body.trim_start_matches(['\r', '\n'])
+    );
+    fs::write(&markdown_path, contents)
+        .with_context(|| format!("Failed to write markdown file {}", markdown_path.display()))?;
+    emit(
+        Level::Success,
+        "video.sync.success",
+        &format!(
+            "Synced {} source(s) in {}; use `a@00:01.0-00:05.0>b` to cut to another angle",
+            targets.len(),
+            markdown_path.display()
+        ),
+        None,
+    );
+    Ok(())
+}
@@ -1,16 +1,16 @@
 use anyhow::{Context, Result};
-use duct::cmd;
 use std::fs;
 use std::path::Path;
 
 use crate::ui::prelude::{Level, emit};
 
 use crate::video::cli::TranscribeArgs;
-use crate::video::config::VideoDirectories;
-use crate::video::support::WHISPERX_UVX_ARGS;
+use crate::video::config::{VideoConfig, VideoDirectories};
+use crate::video::support::transcript::{TranscriptFormat, normalize_transcript};
 use crate::video::support::utils::{
     canonicalize_existing, compute_file_hash, extension_or_default,
 };
+use crate::video::transcriber::{TranscribeRequest, create_transcriber};
 
 pub fn handle_transcribe(args: TranscribeArgs) -> Result<()> {
     let video_path = canonicalize_existing(&args.video)?;
@@ -44,7 +44,25 @@ pub fn handle_transcribe(args: TranscribeArgs) -> Result<()> {
     let hashed_video_path = cache_paths.hashed_video_input(&extension);
     prepare_hashed_video_input(&video_path, &hashed_video_path)?;
 
-    let run_result = run_whisperx(&hashed_video_path, cache_paths.transcript_dir(), &args);
+    let config = VideoConfig::load()?;
+    let backend = args.backend.unwrap_or(config.transcriber);
+    let transcriber = create_transcriber(backend, &config, &directories);
+    if !transcriber.is_available() {
+        anyhow::bail!(
+            "Transcriber '{}' is not available. Check that required tools are installed.",
+            transcriber.name()
+        );
+    }
+
+    let request = TranscribeRequest {
+        language: args.language,
+        model: args.model.as_deref(),
+        compute_type: &args.compute_type,
+        device: &args.device,
+        vad_method: &args.vad_method,
+    };
+    let run_result =
+        transcriber.transcribe(&hashed_video_path, cache_paths.transcript_dir(), &request);
 
     // Clean up temporary copy regardless of success
     if let Err(err) = cleanup_hashed_video_input(&hashed_video_path) {
@@ -60,34 +78,8 @@ pub fn handle_transcribe(args: TranscribeArgs) -> Result<()> {
         );
     }
 
-    run_result?;
-
-    // WhisperX writes `{input_stem}.json`; German (and other non-default names) use a different cache filename.
-    let whisper_json = hashed_video_path.with_extension("json");
-    if whisper_json != transcript_path && whisper_json.exists() {
-        if transcript_path.exists() {
-            fs::remove_file(&transcript_path).with_context(|| {
-                format!(
-                    "Failed to remove stale transcript at {}",
-                    transcript_path.display()
-                )
-            })?;
-        }
-        fs::rename(&whisper_json, &transcript_path).with_context(|| {
-            format!(
-                "Failed to move WhisperX output {} to {}",
-                whisper_json.display(),
-                transcript_path.display()
-            )
-        })?;
-    }
-
-    if !transcript_path.exists() {
-        anyhow::bail!(
-            "WhisperX did not produce the expected transcript at {}",
-            transcript_path.display()
-        );
-    }
+    let raw_transcript = run_result?;
+    store_transcript(&raw_transcript, &transcript_path, transcriber.name())?;
 
     emit(
         Level::Success,
@@ -99,57 +91,32 @@ pub fn handle_transcribe(args: TranscribeArgs) -> Result<()> {
     Ok(())
 }
 
-fn run_whisperx(hashed_video: &Path, output_dir: &Path, args: &TranscribeArgs) -> Result<()> {
-    let hashed_video = hashed_video.to_string_lossy();
-    let output_dir = output_dir.to_string_lossy();
-
-    let language = args.language.whisper_code();
-    let align_model = args.language.align_model();
-
-    let mut whisper_args: Vec<&str> = vec![
-        "whisperx",
-        &hashed_video,
-        "--language",
-        language,
-        "--output_format",
-        "json",
-        "--output_dir",
-        &output_dir,
-        "--vad_method",
-        &args.vad_method,
-        "--compute_type",
-        &args.compute_type,
-        "--device",
-        &args.device,
-        "--align_model",
-        align_model,
-        "--batch_size",
-        "4",
-        "--segment_resolution",
-        "sentence",
-        "--beam_size",
-        "5",
-        "--patience",
-        "1.0",
-        "--max_line_width",
-        "42",
-        "--threads",
-        "8",
-    ];
-
-    if let Some(model) = &args.model {
-        whisper_args.push("--model");
-        whisper_args.push(model);
+/// Move backend output to the cache path, normalizing non-WhisperX formats.
+/// Backends name output after their input; German (and other non-default names) use a different cache filename.
+fn store_transcript(raw: &Path, transcript_path: &Path, backend: &str) -> Result<()> {
+    if !raw.exists() {
+        anyhow::bail!(
+            "{} did not produce the expected transcript at {}",
+            backend,
+            raw.display()
+        );
+    }
+    if raw == transcript_path {
+        return Ok(());
     }
 
-    // Combine uvx base args with whisperx-specific args
-    let mut full_args: Vec<&str> = WHISPERX_UVX_ARGS.to_vec();
-    full_args.extend(whisper_args);
-
-    cmd("uvx", &full_args)
-        .run()
-        .with_context(|| format!("Failed to run WhisperX for {}", hashed_video))?;
-
+    let contents = fs::read_to_string(raw)
+        .with_context(|| format!("Failed to read transcript at {}", raw.display()))?;
+    let format = TranscriptFormat::detect(raw, &contents);
+    let normalized = normalize_transcript(&contents, format)?;
+    fs::write(transcript_path, normalized).with_context(|| {
+        format!(
+            "Failed to write transcript to {}",
+            transcript_path.display()
+        )
+    })?;
+    fs::remove_file(raw)
+        .with_context(|| format!("Failed to remove {} output {}", backend, raw.display()))?;
     Ok(())
 }
 
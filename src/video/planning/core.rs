@@ -1,3 +1,4 @@
+use crate::video::document::directives::{KenBurns, LowerThird, OverlayDirective, PipLayout};
 use crate::video::document::{DocumentBlock, MusicDirective, SegmentKind, VideoDocument};
 use crate::video::render::timeline::TimeWindow;
 use anyhow::Result;
@@ -10,6 +11,8 @@ pub struct TimelinePlan {
     pub ignored_count: usize,
     pub heading_count: usize,
     pub segment_count: usize,
+    /// Headings in document order, used for chapter markers
+    pub chapters: Vec<ChapterPlan>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +22,17 @@ pub enum TimelinePlanItem {
     Music(MusicPlan),
 }
 
+impl TimelinePlanItem {
+    /// Time this item occupies in the output
+    pub fn duration_seconds(&self) -> f64 {
+        match self {
+            TimelinePlanItem::Clip(clip) => clip.time_window.duration().max(0.0),
+            TimelinePlanItem::Standalone(standalone) => standalone.duration_seconds,
+            TimelinePlanItem::Music(_) => 0.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ClipPlan {
     pub time_window: TimeWindow,
@@ -27,6 +41,12 @@ pub struct ClipPlan {
     pub overlay: Option<OverlayPlan>,
     pub broll: Option<BrollPlan>,
     pub source_id: String,
+    /// Synced source to show instead of `source_id`'s picture
+    pub angle: Option<String>,
+    /// Name caption shown from the start of this clip
+    pub lower_third: Option<Box<LowerThird>>,
+    /// Animated still covering this clip
+    pub still: Option<Box<KenBurns>>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,6 +58,17 @@ pub struct OverlayPlan {
 pub struct StandalonePlan {
     pub markdown: String,
     pub duration_seconds: f64,
+    /// List items revealed one by one; 0 for a plain slide
+    pub build_steps: usize,
+}
+
+/// A heading anchored to the plan item that follows it
+#[derive(Debug, Clone)]
+pub struct ChapterPlan {
+    pub title: String,
+    pub level: u32,
+    /// Index into `TimelinePlan::items` where the chapter starts
+    pub item_index: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +85,8 @@ pub struct BrollClip {
 #[derive(Debug, Clone)]
 pub struct BrollPlan {
     pub clips: Vec<BrollClip>,
+    /// Picture-in-picture placement; `None` covers the frame
+    pub layout: Option<PipLayout>,
 }
 
 impl VideoDocument {
@@ -67,6 +100,7 @@ impl VideoDocument {
 /// State machine for building a timeline plan from document blocks.
 struct TimelinePlanner {
     items: Vec<TimelinePlanItem>,
+    chapters: Vec<ChapterPlan>,
     stats: PlanStats,
     /// Current overlay to apply to upcoming segments.
     overlay_state: Option<OverlayPlan>,
@@ -80,6 +114,11 @@ struct TimelinePlanner {
     pending_content: Vec<String>,
     /// Pending B-roll clips being accumulated before applying to state.
     pending_broll: Vec<BrollClip>,
+    /// Layout for the next B-roll clips, set by a `pip` directive.
+    pending_layout: Option<PipLayout>,
+    /// One-shot directives for the next segment.
+    pending_lower_third: Option<Box<LowerThird>>,
+    pending_still: Option<Box<KenBurns>>,
 }
 
 #[derive(Default)]
@@ -95,6 +134,7 @@ impl TimelinePlanner {
     fn new() -> Self {
         Self {
             items: Vec::new(),
+            chapters: Vec::new(),
             stats: PlanStats::default(),
             overlay_state: None,
             broll_state: None,
@@ -102,6 +142,9 @@ impl TimelinePlanner {
             in_separator_region: false,
             pending_content: Vec::new(),
             pending_broll: Vec::new(),
+            pending_layout: None,
+            pending_lower_third: None,
+            pending_still: None,
         }
     }
 
@@ -114,6 +157,10 @@ impl TimelinePlanner {
                 DocumentBlock::Music(music) => self.handle_music(music),
                 DocumentBlock::Unhandled(unhandled) => self.handle_unhandled(unhandled),
                 DocumentBlock::Broll(broll) => self.handle_broll(broll),
+                DocumentBlock::Directive(directive) => self.handle_directive(&directive.directive),
+                // Clip boundaries only matter when rendering clips
+                DocumentBlock::Clip(_) => {}
+                DocumentBlock::Build(build) => self.handle_build(build),
             }
         }
         self.final_flush();
@@ -135,6 +182,9 @@ impl TimelinePlanner {
             overlay: self.overlay_state.clone(),
             broll: self.broll_state.clone(),
             source_id: segment.source_id.clone(),
+            angle: segment.angle.clone(),
+            lower_third: self.pending_lower_third.take(),
+            still: self.pending_still.take(),
         }));
         self.last_clip_idx = Some(self.items.len() - 1);
         self.stats.segment_count += 1;
@@ -148,6 +198,12 @@ impl TimelinePlanner {
         let markdown = format!("{} {}", hashes, heading.text.trim());
         self.pending_content.push(markdown);
         self.stats.heading_count += 1;
+        // Pause slides for this heading are pushed later, at this same index
+        self.chapters.push(ChapterPlan {
+            title: heading.text.trim().to_string(),
+            level: heading.level.max(1),
+            item_index: self.items.len(),
+        });
     }
 
     fn handle_separator(&mut self) {
@@ -183,6 +239,25 @@ impl TimelinePlanner {
         self.pending_content.push(unhandled.description.clone());
     }
 
+    fn handle_build(&mut self, build: &crate::video::document::BuildSlideBlock) {
+        self.flush_pending_broll_to_state();
+        if self.in_separator_region {
+            self.flush_pending_as_pause();
+        } else {
+            self.flush_pending_as_overlay();
+        }
+        let steps = build.steps();
+        let duration_seconds =
+            pause_duration_seconds(&build.markdown).max(steps as f64 * BUILD_STEP_MIN_SECONDS);
+        self.items
+            .push(TimelinePlanItem::Standalone(StandalonePlan {
+                markdown: build.markdown.clone(),
+                duration_seconds,
+                build_steps: steps,
+            }));
+        self.stats.standalone_count += 1;
+    }
+
     fn handle_broll(&mut self, broll: &crate::video::document::BrollBlock) {
         self.pending_broll.push(BrollClip {
             time_window: TimeWindow::new(broll.range.start_seconds(), broll.range.end_seconds()),
@@ -190,6 +265,20 @@ impl TimelinePlanner {
         });
     }
 
+    fn handle_directive(&mut self, directive: &OverlayDirective) {
+        match directive {
+            OverlayDirective::LowerThird(lower_third) => {
+                self.pending_lower_third = Some(Box::new(lower_third.clone()));
+            }
+            OverlayDirective::KenBurns(ken_burns) => {
+                self.pending_still = Some(Box::new(ken_burns.clone()));
+            }
+            OverlayDirective::PictureInPicture(layout) => {
+                self.pending_layout = Some(layout.clone());
+            }
+        }
+    }
+
     fn final_flush(&mut self) {
         // Flush pending B-roll to state
         self.flush_pending_broll_to_state();
@@ -203,7 +292,10 @@ impl TimelinePlanner {
             return;
         }
         let clips = std::mem::take(&mut self.pending_broll);
-        self.broll_state = Some(BrollPlan { clips });
+        self.broll_state = Some(BrollPlan {
+            clips,
+            layout: self.pending_layout.take(),
+        });
     }
 
     /// Merge pending content into an overlay and apply to the last clip.
@@ -230,6 +322,7 @@ impl TimelinePlanner {
             .push(TimelinePlanItem::Standalone(StandalonePlan {
                 markdown: merged.clone(),
                 duration_seconds: pause_duration_seconds(trimmed),
+                build_steps: 0,
             }));
         self.stats.standalone_count += 1;
         self.pending_content.clear();
@@ -262,6 +355,7 @@ impl TimelinePlanner {
             ignored_count: self.stats.ignored_count,
             heading_count: self.stats.heading_count,
             segment_count: self.stats.segment_count,
+            chapters: self.chapters,
         }
     }
 }
@@ -269,6 +363,8 @@ impl TimelinePlanner {
 pub const DEFAULT_PAUSE_MIN_SECONDS: f64 = 5.0;
 pub const DEFAULT_PAUSE_MAX_SECONDS: f64 = 20.0;
 pub const DEFAULT_PAUSE_READING_WPM: f64 = 180.0;
+/// Shortest time each bullet of a build slide stays on screen before the next appears
+pub const BUILD_STEP_MIN_SECONDS: f64 = 2.0;
 
 pub fn pause_duration_seconds(display_text: &str) -> f64 {
     let words = display_text.split_whitespace().count() as f64;
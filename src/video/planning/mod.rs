@@ -4,5 +4,5 @@ mod graph;
 
 pub use self::alignment::align_plan_with_subtitles;
 pub use self::core::{
-    BrollPlan, ClipPlan, MusicPlan, StandalonePlan, TimelinePlan, TimelinePlanItem,
+    BrollPlan, ChapterPlan, ClipPlan, MusicPlan, StandalonePlan, TimelinePlan, TimelinePlanItem,
 };
@@ -0,0 +1,149 @@
+//! On-disk store for encoded render chunks (`~/.cache/instant/video/chunks`).
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+
+use super::logging::log_event;
+use crate::ui::prelude::Level;
+use crate::video::cli::VideoCacheCommands;
+use crate::video::config::VideoDirectories;
+
+const CHUNK_EXTENSION: &str = "mkv";
+
+pub(super) struct ChunkCache {
+    dir: PathBuf,
+}
+
+impl ChunkCache {
+    pub(super) fn open() -> Result<Self> {
+        let dir = VideoDirectories::new()?.render_cache_dir();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create chunk cache at {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    pub(super) fn chunk_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.{CHUNK_EXTENSION}"))
+    }
+
+    /// Where a chunk is encoded before it is moved into place
+    pub(super) fn partial_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.partial.{CHUNK_EXTENSION}"))
+    }
+
+    /// Mark a cached chunk as used so age-based pruning keeps it
+    pub(super) fn touch(&self, key: &str) {
+        if let Ok(file) = fs::File::options().append(true).open(self.chunk_path(key)) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+    }
+
+    /// Chunk files with their size and last use
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read chunk cache {}", self.dir.display()))?
+        {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != CHUNK_EXTENSION) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((path, metadata.len(), modified));
+        }
+        Ok(entries)
+    }
+
+    /// Remove chunks unused for longer than `max_age` (all chunks when `None`)
+    fn prune(&self, max_age: Option<Duration>) -> Result<(usize, u64)> {
+        let now = SystemTime::now();
+        let (mut removed, mut bytes) = (0, 0);
+        for (path, size, modified) in self.entries()? {
+            let expired = match max_age {
+                Some(max_age) => now.duration_since(modified).unwrap_or_default() > max_age,
+                None => true,
+            };
+            if expired {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                removed += 1;
+                bytes += size;
+            }
+        }
+        Ok((removed, bytes))
+    }
+}
+
+pub fn handle_cache(command: VideoCacheCommands) -> Result<()> {
+    let cache = ChunkCache::open()?;
+    match command {
+        VideoCacheCommands::Info => {
+            let entries = cache.entries()?;
+            let bytes: u64 = entries.iter().map(|(_, size, _)| size).sum();
+            log_event(
+                Level::Info,
+                "video.cache.info",
+                format!(
+                    "{} cached chunk(s), {} in {}",
+                    entries.len(),
+                    format_size(bytes),
+                    cache.dir.display()
+                ),
+            );
+        }
+        VideoCacheCommands::Prune { older_than, all } => {
+            let max_age = (!all).then(|| Duration::from_secs(older_than * 24 * 60 * 60));
+            let (removed, bytes) = cache.prune(max_age)?;
+            log_event(
+                Level::Success,
+                "video.cache.pruned",
+                format!(
+                    "Removed {removed} cached chunk(s), freed {}",
+                    format_size(bytes)
+                ),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_removes_only_expired_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache {
+            dir: dir.path().to_path_buf(),
+        };
+        fs::write(cache.chunk_path("old"), b"old").unwrap();
+        fs::write(cache.chunk_path("new"), b"new").unwrap();
+        fs::write(dir.path().join("list.txt"), b"keep").unwrap();
+        let old = fs::File::options()
+            .append(true)
+            .open(cache.chunk_path("old"))
+            .unwrap();
+        old.set_modified(SystemTime::now() - Duration::from_secs(10 * 24 * 60 * 60))
+            .unwrap();
+
+        let (removed, bytes) = cache
+            .prune(Some(Duration::from_secs(24 * 60 * 60)))
+            .unwrap();
+        assert_eq!((removed, bytes), (1, 3));
+        assert!(!cache.chunk_path("old").exists());
+        assert!(cache.chunk_path("new").exists());
+
+        assert_eq!(cache.prune(None).unwrap().0, 1);
+        assert!(dir.path().join("list.txt").exists());
+    }
+}
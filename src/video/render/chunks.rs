@@ -0,0 +1,311 @@
+//! Content-addressed chunks for incremental rendering.
+//!
+//! The timeline is split at clean cuts between base-track segments into chunks of roughly
+//! 10-60 seconds. Cut points are chosen from the content around them rather than from
+//! elapsed time, so editing one sentence only moves the boundaries of the chunk it is in
+//! and every other chunk keeps its cache key.
+//!
+//! A chunk key hashes everything that ends up in the encoded picture: the sliced segments,
+//! the size and modification time of every file they read, the burned-in subtitle events,
+//! the output dimensions and the preset's video settings. Music and loudness are applied
+//! when chunks are joined and are deliberately left out.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use sha2::{Digest, Sha256};
+
+use super::ffmpeg::compiler::VideoDimensions;
+use super::mode::RenderMode;
+use super::timeline::{Segment, SegmentData, TimeWindow, Timeline};
+use crate::video::config::OutputPreset;
+
+/// Bump when the chunk encoding changes so stale chunks are not reused
+const CHUNK_FORMAT_VERSION: u32 = 1;
+const MIN_CHUNK_SECONDS: f64 = 10.0;
+const MAX_CHUNK_SECONDS: f64 = 60.0;
+/// On average one in this many clean cuts starts a new chunk
+const CUT_MODULUS: u64 = 6;
+/// Slack when comparing segment edges with a cut point
+const EDGE_TOLERANCE: f64 = 0.001;
+
+/// One chunk of the timeline, sliced so it starts at 0
+pub(super) struct TimelineChunk {
+    pub window: TimeWindow,
+    pub timeline: Timeline,
+    pub key: String,
+}
+
+/// Settings every chunk of one render shares
+pub(super) struct ChunkContext<'a> {
+    pub dimensions: VideoDimensions,
+    pub render_mode: RenderMode,
+    pub preset: &'a OutputPreset,
+    /// Contents of the ASS file burned into the picture
+    pub subtitles: Option<&'a str>,
+}
+
+/// Split the timeline into chunks and compute their cache keys
+pub(super) fn split_timeline(
+    timeline: &Timeline,
+    context: &ChunkContext<'_>,
+) -> Vec<TimelineChunk> {
+    plan_chunk_windows(timeline)
+        .into_iter()
+        .map(|window| {
+            let mut sliced = timeline.slice(window);
+            sliced
+                .segments
+                .retain(|segment| !matches!(segment.data, SegmentData::Music { .. }));
+            let key = chunk_key(&sliced, window, context);
+            TimelineChunk {
+                window,
+                timeline: sliced,
+                key,
+            }
+        })
+        .collect()
+}
+
+/// Chunk windows covering the whole timeline
+pub(super) fn plan_chunk_windows(timeline: &Timeline) -> Vec<TimeWindow> {
+    let total = timeline.total_duration();
+    let mut windows = Vec::new();
+    let mut chunk_start = 0.0;
+
+    for segment in timeline.video_segments() {
+        let cut = segment.start_time;
+        let length = cut - chunk_start;
+        if length < MIN_CHUNK_SECONDS || total - cut < MIN_CHUNK_SECONDS {
+            continue;
+        }
+        if !is_clean_cut(timeline, cut) {
+            continue;
+        }
+        if length >= MAX_CHUNK_SECONDS || boundary_hash(segment).is_multiple_of(CUT_MODULUS) {
+            windows.push(TimeWindow::new(chunk_start, cut));
+            chunk_start = cut;
+        }
+    }
+
+    if total > chunk_start {
+        windows.push(TimeWindow::new(chunk_start, total));
+    }
+    windows
+}
+
+/// A cut is clean when no overlay, b-roll or caption is on screen across it
+fn is_clean_cut(timeline: &Timeline, cut: f64) -> bool {
+    timeline.segments.iter().all(|segment| {
+        let spans_cut =
+            segment.start_time < cut - EDGE_TOLERANCE && segment.end_time() > cut + EDGE_TOLERANCE;
+        !spans_cut
+            || matches!(
+                segment.data,
+                SegmentData::VideoSubset { .. } | SegmentData::Music { .. }
+            )
+    })
+}
+
+/// Stable hash of what a base-track segment shows, independent of its timeline position
+fn boundary_hash(segment: &Segment) -> u64 {
+    let mut hasher = Sha256::new();
+    if let SegmentData::VideoSubset {
+        start_time, source, ..
+    } = &segment.data
+    {
+        hasher.update(source.id.as_bytes());
+        hasher.update(format!("{start_time:.3}:{:.3}", segment.duration).as_bytes());
+    }
+    let digest = hasher.finalize();
+    u64::from_le_bytes(digest[..8].try_into().expect("sha256 has 32 bytes"))
+}
+
+fn chunk_key(chunk: &Timeline, window: TimeWindow, context: &ChunkContext<'_>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(CHUNK_FORMAT_VERSION.to_le_bytes());
+    hasher.update(context.dimensions.width.to_le_bytes());
+    hasher.update(context.dimensions.height.to_le_bytes());
+    hasher.update(format!("{:?}", context.render_mode).as_bytes());
+
+    // Audio settings only matter once the chunks are joined
+    let video_preset = OutputPreset {
+        name: String::new(),
+        audio_codec: String::new(),
+        audio_bitrate: String::new(),
+        loudness_lufs: None,
+        ..context.preset.clone()
+    };
+    hasher.update(format!("{video_preset:?}").as_bytes());
+
+    let mut files: Vec<&PathBuf> = Vec::new();
+    for segment in &chunk.segments {
+        hasher.update(format!("{segment:?}").as_bytes());
+        files.extend(segment.data.source_path());
+        files.extend(segment.data.audio_source());
+    }
+    files.sort();
+    files.dedup();
+    for file in files {
+        hasher.update(file_fingerprint(file).as_bytes());
+    }
+
+    if let Some(subtitles) = context.subtitles {
+        hasher.update(subtitle_fingerprint(subtitles, window).as_bytes());
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+/// Size and modification time, so re-recorded or re-exported media invalidates chunks
+fn file_fingerprint(path: &Path) -> String {
+    let Ok(metadata) = fs::metadata(path) else {
+        return format!("{}:missing", path.display());
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos());
+    format!("{}:{}:{}", path.display(), metadata.len(), modified)
+}
+
+/// ASS styles plus the dialogue events on screen during `window`, relative to its start
+fn subtitle_fingerprint(ass: &str, window: TimeWindow) -> String {
+    let mut fingerprint = String::new();
+    for line in ass.lines() {
+        let Some(event) = line.strip_prefix("Dialogue:") else {
+            fingerprint.push_str(line);
+            fingerprint.push('\n');
+            continue;
+        };
+        let fields: Vec<&str> = event.splitn(4, ',').collect();
+        let [layer, start, end, rest] = fields.as_slice() else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (parse_ass_time(start), parse_ass_time(end)) else {
+            continue;
+        };
+        if TimeWindow::new(start, end).overlaps(window) {
+            fingerprint.push_str(&format!(
+                "{layer},{:.2},{:.2},{rest}\n",
+                start - window.start,
+                end - window.start
+            ));
+        }
+    }
+    fingerprint
+}
+
+/// Parse an ASS timestamp such as `0:01:02.50`
+fn parse_ass_time(value: &str) -> Option<f64> {
+    let mut parts = value.trim().split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::render::timeline::AvSourceRef;
+
+    fn sentence_timeline(count: usize, seconds: f64) -> Timeline {
+        let mut timeline = Timeline::new();
+        for index in 0..count {
+            let start = index as f64 * seconds;
+            timeline.add_segment(Segment::new_video_subset(
+                start,
+                seconds,
+                start + 1.0,
+                AvSourceRef {
+                    video: PathBuf::from("video.mp4"),
+                    audio: PathBuf::from("audio.mp4"),
+                    id: "a".to_string(),
+                },
+                None,
+                false,
+            ));
+        }
+        timeline
+    }
+
+    fn context(preset: &OutputPreset) -> ChunkContext<'_> {
+        ChunkContext {
+            dimensions: VideoDimensions::new(1920, 1080),
+            render_mode: RenderMode::Standard,
+            preset,
+            subtitles: None,
+        }
+    }
+
+    #[test]
+    fn chunks_cover_timeline_within_bounds() {
+        let timeline = sentence_timeline(100, 4.0);
+        let windows = plan_chunk_windows(&timeline);
+        assert!(windows.len() > 1);
+        assert_eq!(windows[0].start, 0.0);
+        assert_eq!(windows.last().unwrap().end, 400.0);
+        for pair in windows.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        for window in &windows {
+            assert!(window.duration() >= MIN_CHUNK_SECONDS);
+            assert!(window.duration() <= MAX_CHUNK_SECONDS + 4.0);
+        }
+    }
+
+    #[test]
+    fn overlays_keep_chunk_boundaries_out_of_their_span() {
+        let mut timeline = sentence_timeline(10, 4.0);
+        timeline.add_segment(Segment::new_image(
+            6.0,
+            30.0,
+            PathBuf::from("slide.png"),
+            None,
+        ));
+        assert!(!is_clean_cut(&timeline, 12.0));
+        assert!(is_clean_cut(&timeline, 36.0));
+    }
+
+    #[test]
+    fn editing_one_segment_keeps_other_chunk_keys() {
+        let preset = OutputPreset::default();
+        let timeline = sentence_timeline(60, 4.0);
+        let original = split_timeline(&timeline, &context(&preset));
+
+        // Drop one sentence from the middle and close the gap
+        let mut edited = Timeline::new();
+        for (index, segment) in timeline.segments.iter().enumerate() {
+            if index == 30 {
+                continue;
+            }
+            let mut segment = segment.clone();
+            if index > 30 {
+                segment.start_time -= 4.0;
+            }
+            edited.add_segment(segment);
+        }
+        let edited = split_timeline(&edited, &context(&preset));
+
+        let reused = edited
+            .iter()
+            .filter(|chunk| original.iter().any(|old| old.key == chunk.key))
+            .count();
+        assert!(reused >= edited.len().saturating_sub(2), "{reused} reused");
+        assert!(reused < edited.len());
+    }
+
+    #[test]
+    fn subtitle_fingerprint_is_relative_to_window() {
+        let ass = "[Events]\nDialogue: 0,0:00:41.00,0:00:43.50,Default,,0,0,0,,Hello\n";
+        let early = subtitle_fingerprint(ass, TimeWindow::new(40.0, 50.0));
+        let shifted = ass.replace("0:00:41.00,0:00:43.50", "0:00:31.00,0:00:33.50");
+        let late = subtitle_fingerprint(&shifted, TimeWindow::new(30.0, 40.0));
+        assert_eq!(early, late);
+        assert!(early.contains("1.00,3.50,Default"));
+        assert!(!subtitle_fingerprint(ass, TimeWindow::new(0.0, 10.0)).contains("Hello"));
+    }
+}
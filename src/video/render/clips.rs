@@ -0,0 +1,177 @@
+//! Rendering the clips tagged in a video document (`ins video render --clips`).
+//!
+//! Every clip is planned and aligned exactly like the full video, then rendered to
+//! `<video>_<clip>.<ext>` with its own mode, preset and subtitle setting.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use super::cache::ChunkCache;
+use super::output::prepare_output_destination;
+use super::publish::{PublishArtifacts, PublishOptions, write_publish_files};
+use super::{
+    RenderJob, RenderMode, RenderProject, build_render_timeline, build_timeline_plan,
+    burn_in_subtitles, default_render_jobs, execute_render, log_event, paths,
+};
+use crate::ui::prelude::Level;
+use crate::video::cli::RenderArgs;
+use crate::video::config::{DEFAULT_PRESET, OutputPreset};
+use crate::video::document::VideoDocument;
+use crate::video::document::clips::ClipSpec;
+use crate::video::planning::TimelinePlan;
+use crate::video::render::ffmpeg::services::FfmpegRunner;
+use crate::video::support::transcript::TranscriptCue;
+
+/// Plan every clip in the document with the same planning and alignment as the full video
+pub(super) fn plan_document_clips(
+    document: &VideoDocument,
+    cues: &[TranscriptCue],
+    markdown_path: &Path,
+) -> Result<Vec<(ClipSpec, TimelinePlan)>> {
+    let clips = document.clips()?;
+    if clips.is_empty() {
+        bail!(
+            "No clips tagged in {}. Mark one with `<!-- clip: name -->` before rendering with --clips.",
+            markdown_path.display()
+        );
+    }
+
+    clips
+        .into_iter()
+        .map(|clip| {
+            let plan = build_timeline_plan(&document.clip_document(&clip), cues, markdown_path)
+                .with_context(|| format!("Failed to plan clip `{}`", clip.spec.name))?;
+            Ok((clip.spec, plan))
+        })
+        .collect()
+}
+
+pub(super) fn render_clips(
+    args: &RenderArgs,
+    project: &RenderProject,
+    runner: &dyn FfmpegRunner,
+) -> Result<Option<PathBuf>> {
+    if args.presets.len() > 1 {
+        bail!("--clips takes at most one --preset, used for clips that do not name their own");
+    }
+    let fallback_mode = if args.common.reels {
+        RenderMode::Reels
+    } else {
+        RenderMode::Standard
+    };
+    let publish_options = PublishOptions::from_args(args);
+
+    let cache = if args.no_cache || args.common.precache_slides {
+        None
+    } else {
+        Some(ChunkCache::open()?)
+    };
+    let jobs = args.jobs.unwrap_or_else(default_render_jobs).max(1);
+
+    let mut rendered = None;
+    for (spec, plan) in &project.clips {
+        let preset_name = spec
+            .preset
+            .as_deref()
+            .or(args.presets.first().map(String::as_str))
+            .unwrap_or(DEFAULT_PRESET);
+        let preset = project.video_config.output_preset(preset_name)?;
+        let render_mode = spec.mode.or(preset.mode).unwrap_or(fallback_mode);
+        let output_path = clip_output_path(args, project, render_mode, spec, &preset)?;
+        if !args.common.precache_slides {
+            prepare_output_destination(&output_path, args, &project.default_source.source)?;
+        }
+
+        log_event(
+            Level::Info,
+            "video.render.clip",
+            format!(
+                "Rendering clip `{}` ({:?}, preset `{}`) to {}",
+                spec.name,
+                render_mode,
+                preset.name,
+                output_path.display()
+            ),
+        );
+        let (nle_timeline, target_dims) = build_render_timeline(project, plan, render_mode)?;
+        if args.common.precache_slides {
+            continue;
+        }
+
+        let subtitle_path = if spec.subtitles || args.common.subtitles {
+            Some(burn_in_subtitles(
+                &nle_timeline,
+                &project.cues,
+                &output_path,
+                target_dims,
+                render_mode,
+            )?)
+        } else {
+            None
+        };
+        let publish = if publish_options.any() && !args.dry_run {
+            write_publish_files(
+                &nle_timeline,
+                &project.cues,
+                &output_path,
+                &spec.name,
+                publish_options,
+            )?
+        } else {
+            PublishArtifacts::default()
+        };
+
+        rendered = execute_render(RenderJob {
+            timeline: nle_timeline,
+            output_path,
+            render_mode,
+            target_dims,
+            video_config: project.video_config.clone(),
+            audio_source: project.default_source.source.clone(),
+            subtitle_path,
+            preset,
+            publish,
+            dry_run: args.dry_run,
+            verbose: args.common.verbose,
+            runner,
+            chunks: cache.as_ref().map(|cache| (cache, jobs)),
+        })?
+        .or(rendered);
+    }
+
+    if args.common.precache_slides {
+        log_event(
+            Level::Success,
+            "video.render.precache_only",
+            "Prepared slides for every clip in cache; skipping final render",
+        );
+    }
+
+    Ok(rendered)
+}
+
+/// `<video><suffix>_<clip>.<ext>`, or `<out-file stem>_<clip>.<ext>` with `--out-file`
+fn clip_output_path(
+    args: &RenderArgs,
+    project: &RenderProject,
+    render_mode: RenderMode,
+    spec: &ClipSpec,
+    preset: &OutputPreset,
+) -> Result<PathBuf> {
+    let base = paths::resolve_output_path(
+        args.out_file.as_ref(),
+        &project.default_source.source,
+        &project.project_dir,
+        render_mode,
+    )?;
+    let stem = base
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(base.with_file_name(format!(
+        "{stem}_{}.{}",
+        spec.name,
+        preset.container.extension()
+    )))
+}
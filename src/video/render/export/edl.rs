@@ -0,0 +1,71 @@
+//! CMX3600 EDL writer.
+//!
+//! An EDL describes a single track, so the main track is written to the requested path
+//! and every other track to a sibling `<stem>.<track>.edl`. Editors link events to media
+//! through the `FROM CLIP NAME` / `SOURCE FILE` comments since reel names are limited to
+//! eight characters.
+
+use std::path::{Path, PathBuf};
+
+use super::{CutList, CutTrack, TrackKind};
+
+pub(super) fn write_edl_files(cut: &CutList, output: &Path) -> Vec<(PathBuf, String)> {
+    cut.tracks
+        .iter()
+        .enumerate()
+        .map(|(idx, track)| {
+            let path = if idx == 0 {
+                output.to_path_buf()
+            } else {
+                track_path(output, &track.name)
+            };
+            (path, write_edl(cut, track))
+        })
+        .collect()
+}
+
+pub(super) fn write_edl(cut: &CutList, track: &CutTrack) -> String {
+    let rate = cut.rate;
+    let channel = match track.kind {
+        TrackKind::Video => "V",
+        TrackKind::Audio => "A",
+    };
+
+    let mut out = format!(
+        "TITLE: {} - {}\nFCM: NON-DROP FRAME\n\n",
+        cut.name, track.name
+    );
+    for (idx, clip) in track.clips.iter().enumerate() {
+        out.push_str(&format!(
+            "{:03}  {:<8} {:<5} C        {} {} {} {}\n",
+            idx + 1,
+            "AX",
+            channel,
+            rate.timecode(clip.source_start),
+            rate.timecode(clip.source_start + clip.duration),
+            rate.timecode(clip.record_start),
+            rate.timecode(clip.record_end()),
+        ));
+        out.push_str(&format!("* FROM CLIP NAME: {}\n", clip.name));
+        out.push_str(&format!("* SOURCE FILE: {}\n\n", clip.media.display()));
+    }
+    out
+}
+
+fn track_path(output: &Path, track_name: &str) -> PathBuf {
+    let slug: String = track_name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let stem = output
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    output.with_file_name(format!("{stem}.{slug}.edl"))
+}
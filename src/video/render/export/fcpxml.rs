@@ -0,0 +1,258 @@
+//! FCPXML 1.8 writer.
+//!
+//! The main track becomes the primary storyline (spine). Every other track, and the
+//! subtitles as captions, is attached as connected clips on lanes of the spine element
+//! that is playing when the connected clip starts. Connected clip offsets are therefore
+//! expressed in the parent's local (source) time, as FCPXML requires.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{CutClip, CutList, FrameRate, MediaKind, TrackItem, TrackKind, file_url};
+
+const CAPTION_ROLE: &str = "SRT?captionFormat=SRT.en";
+
+pub(super) fn write_fcpxml(cut: &CutList) -> String {
+    let rate = cut.rate;
+    let assets = AssetTable::new(cut);
+
+    // Primary storyline elements: (record start, record end, local start, markup)
+    let mut spine: Vec<SpineElement> = Vec::new();
+    let mut cursor = 0;
+    for item in cut.main_track().items() {
+        match item {
+            TrackItem::Gap(duration) => {
+                spine.push(SpineElement {
+                    record_start: cursor,
+                    record_end: cursor + duration,
+                    local_start: 0,
+                    open: format!(
+                        "<gap name=\"Gap\" offset=\"{}\" start=\"0s\" duration=\"{}\"",
+                        time(rate, cursor),
+                        time(rate, duration)
+                    ),
+                    tag: "gap",
+                    children: Vec::new(),
+                });
+                cursor += duration;
+            }
+            TrackItem::Clip(clip) => {
+                let (tag, attrs) = clip_element(rate, &assets, clip, clip.record_start);
+                spine.push(SpineElement {
+                    record_start: clip.record_start,
+                    record_end: clip.record_end(),
+                    local_start: clip.source_start,
+                    open: format!("<{tag} {attrs}"),
+                    tag,
+                    children: Vec::new(),
+                });
+                cursor = clip.record_end();
+            }
+        }
+    }
+
+    let video_lanes = cut.connected_tracks(TrackKind::Video).count() as i32;
+    let lanes = cut.connected_tracks(TrackKind::Video).zip(1..).chain(
+        cut.connected_tracks(TrackKind::Audio)
+            .zip((1..).map(|lane: i32| -lane)),
+    );
+    for (track, lane) in lanes {
+        for clip in &track.clips {
+            let Some(parent) = parent_for(&mut spine, clip.record_start) else {
+                continue;
+            };
+            let offset = parent.local_offset(clip.record_start);
+            let (tag, attrs) = clip_element(rate, &assets, clip, offset);
+            parent
+                .children
+                .push(format!("<{tag} lane=\"{lane}\" {attrs}/>"));
+        }
+    }
+
+    for (idx, subtitle) in cut.subtitles.iter().enumerate() {
+        let Some(parent) = parent_for(&mut spine, subtitle.start) else {
+            continue;
+        };
+        let offset = parent.local_offset(subtitle.start);
+        let text = escape(&subtitle.text);
+        parent.children.push(format!(
+            "<caption lane=\"{lane}\" offset=\"{offset}\" name=\"{text}\" start=\"0s\" duration=\"{duration}\" role=\"{CAPTION_ROLE}\">\
+<text><text-style ref=\"ts{id}\">{text}</text-style></text>\
+<text-style-def id=\"ts{id}\"><text-style font=\"Helvetica\" fontSize=\"13\" fontColor=\"1 1 1 1\"/></text-style-def>\
+</caption>",
+            lane = video_lanes + 1,
+            offset = time(rate, offset),
+            duration = time(rate, subtitle.duration),
+            id = idx + 1,
+        ));
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE fcpxml>\n");
+    out.push_str("<fcpxml version=\"1.8\">\n  <resources>\n");
+    out.push_str(&format!(
+        "    <format id=\"r1\" name=\"FFVideoFormat{height}p\" frameDuration=\"{frame}\" width=\"{width}\" height=\"{height}\"/>\n",
+        frame = time(rate, 1),
+        width = cut.dimensions.width,
+        height = cut.dimensions.height,
+    ));
+    for asset in &assets.assets {
+        out.push_str(&format!("    {}\n", asset.markup(rate)));
+    }
+    out.push_str("  </resources>\n  <library>\n");
+    let name = escape(&cut.name);
+    out.push_str(&format!(
+        "    <event name=\"{name}\">\n      <project name=\"{name}\">\n        <sequence format=\"r1\" duration=\"{}\" tcStart=\"0s\" tcFormat=\"NDF\" audioLayout=\"stereo\" audioRate=\"48k\">\n          <spine>\n",
+        time(rate, cut.duration())
+    ));
+    for element in &spine {
+        if element.children.is_empty() {
+            out.push_str(&format!("            {}/>\n", element.open));
+            continue;
+        }
+        out.push_str(&format!("            {}>\n", element.open));
+        for child in &element.children {
+            out.push_str(&format!("              {child}\n"));
+        }
+        out.push_str(&format!("            </{}>\n", element.tag));
+    }
+    out.push_str(
+        "          </spine>\n        </sequence>\n      </project>\n    </event>\n  </library>\n</fcpxml>\n",
+    );
+    out
+}
+
+struct SpineElement {
+    record_start: i64,
+    record_end: i64,
+    local_start: i64,
+    open: String,
+    tag: &'static str,
+    children: Vec<String>,
+}
+
+impl SpineElement {
+    fn local_offset(&self, record: i64) -> i64 {
+        self.local_start + (record - self.record_start)
+    }
+}
+
+/// The spine element playing at `record`; anything past the end attaches to the last one
+fn parent_for(spine: &mut [SpineElement], record: i64) -> Option<&mut SpineElement> {
+    let idx = spine
+        .iter()
+        .position(|element| record >= element.record_start && record < element.record_end)
+        .or_else(|| spine.len().checked_sub(1))?;
+    spine.get_mut(idx)
+}
+
+fn clip_element(
+    rate: FrameRate,
+    assets: &AssetTable,
+    clip: &CutClip,
+    offset: i64,
+) -> (&'static str, String) {
+    let tag = match clip.media_kind {
+        MediaKind::Still => "video",
+        MediaKind::Video | MediaKind::Audio => "asset-clip",
+    };
+    // Sound comes from the Dialogue track, which already leaves out muted segments
+    let src_enable = match clip.media_kind {
+        MediaKind::Video => " srcEnable=\"video\"",
+        MediaKind::Still | MediaKind::Audio => "",
+    };
+    let attrs = format!(
+        "ref=\"{}\" offset=\"{}\" name=\"{}\" start=\"{}\" duration=\"{}\"{src_enable}",
+        assets.id(&clip.media),
+        time(rate, offset),
+        escape(&clip.name),
+        time(rate, clip.source_start),
+        time(rate, clip.duration),
+    );
+    (tag, attrs)
+}
+
+struct Asset {
+    id: String,
+    media: PathBuf,
+    kind: MediaKind,
+    /// Furthest source frame referenced, used as the asset duration
+    extent: i64,
+}
+
+impl Asset {
+    fn markup(&self, rate: FrameRate) -> String {
+        let name = self
+            .media
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (has_video, has_audio, duration) = match self.kind {
+            MediaKind::Video => (1, 1, time(rate, self.extent)),
+            MediaKind::Still => (1, 0, "0s".to_string()),
+            MediaKind::Audio => (0, 1, time(rate, self.extent)),
+        };
+        let format = if has_video == 1 { " format=\"r1\"" } else { "" };
+        format!(
+            "<asset id=\"{}\" name=\"{}\" src=\"{}\" start=\"0s\" duration=\"{duration}\" hasVideo=\"{has_video}\" hasAudio=\"{has_audio}\"{format}/>",
+            self.id,
+            escape(&name),
+            escape(&file_url(&self.media)),
+        )
+    }
+}
+
+struct AssetTable {
+    assets: Vec<Asset>,
+    ids: HashMap<PathBuf, usize>,
+}
+
+impl AssetTable {
+    fn new(cut: &CutList) -> Self {
+        let mut table = Self {
+            assets: Vec::new(),
+            ids: HashMap::new(),
+        };
+        for clip in cut.tracks.iter().flat_map(|track| &track.clips) {
+            let idx = *table.ids.entry(clip.media.clone()).or_insert_with(|| {
+                table.assets.push(Asset {
+                    // r1 is the sequence format
+                    id: format!("r{}", table.assets.len() + 2),
+                    media: clip.media.clone(),
+                    kind: clip.media_kind,
+                    extent: 0,
+                });
+                table.assets.len() - 1
+            });
+            let asset = &mut table.assets[idx];
+            asset.extent = asset.extent.max(clip.source_start + clip.duration);
+        }
+        table
+    }
+
+    fn id(&self, media: &Path) -> &str {
+        self.ids
+            .get(media)
+            .map(|&idx| self.assets[idx].id.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// FCPXML rational time in seconds, e.g. `1001/30000s`
+pub(super) fn time(rate: FrameRate, frames: i64) -> String {
+    if frames == 0 {
+        return "0s".to_string();
+    }
+    format!(
+        "{}/{}s",
+        frames * i64::from(rate.denominator),
+        rate.numerator
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
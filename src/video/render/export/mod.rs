@@ -0,0 +1,459 @@
+//! Interchange export of the render timeline.
+//!
+//! The ffmpeg compiler is the only consumer of [`Timeline`] that actually renders, but
+//! colour grading and fine manual tweaks belong in a full editor. This module flattens
+//! the timeline into a frame-accurate cut list with one entry per track and writes it
+//! as OpenTimelineIO, FCPXML or CMX3600 EDL so Kdenlive, DaVinci Resolve or Shotcut can
+//! open the project.
+
+mod edl;
+mod fcpxml;
+mod otio;
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+
+use crate::ui::prelude::Level;
+use crate::video::cli::ExportArgs;
+use crate::video::slides::SlideGenerator;
+use crate::video::subtitles::{RemappedSubtitle, generate_srt, remap_subtitles_to_timeline};
+use crate::video::support::ffmpeg::{probe_media_metadata, probe_video_dimensions};
+use crate::video::support::transcript::TranscriptCue;
+use crate::video::support::utils::canonicalize_existing;
+
+use super::ffmpeg::compiler::VideoDimensions;
+use super::load_render_project;
+use super::logging::log_event;
+use super::mode::RenderMode;
+use super::timeline::{SegmentData, Timeline};
+use super::timeline_builder::{SlideProvider, build_nle_timeline};
+
+/// Source id used by the timeline builder for generated title cards
+const SLIDE_SOURCE_ID: &str = "__slide";
+
+/// Fallback when the source frame rate cannot be probed
+const DEFAULT_FPS: f64 = 30.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// OpenTimelineIO JSON (Kdenlive, DaVinci Resolve, Shotcut via otio adapters)
+    Otio,
+    /// Final Cut Pro XML 1.8 (DaVinci Resolve, Final Cut Pro)
+    Fcpxml,
+    /// CMX3600 edit decision list, one file per track
+    Edl,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Otio => "otio",
+            ExportFormat::Fcpxml => "fcpxml",
+            ExportFormat::Edl => "edl",
+        }
+    }
+}
+
+/// Rational frame rate, e.g. 30000/1001 for NTSC 29.97
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct FrameRate {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl FrameRate {
+    pub fn from_fps(fps: f64) -> Self {
+        for nominal in [24_u32, 30, 60] {
+            let ntsc = f64::from(nominal) * 1000.0 / 1001.0;
+            if (fps - ntsc).abs() < 0.01 {
+                return Self {
+                    numerator: nominal * 1000,
+                    denominator: 1001,
+                };
+            }
+        }
+
+        Self {
+            numerator: (fps.round() as u32).max(1),
+            denominator: 1,
+        }
+    }
+
+    pub fn as_f64(self) -> f64 {
+        f64::from(self.numerator) / f64::from(self.denominator)
+    }
+
+    /// Whole frames per second used for non-drop timecode
+    pub fn nominal(self) -> u32 {
+        self.numerator.div_ceil(self.denominator)
+    }
+
+    pub fn frames(self, seconds: f64) -> i64 {
+        (seconds * self.as_f64()).round() as i64
+    }
+
+    /// Non-drop-frame `HH:MM:SS:FF` timecode for a frame count
+    pub fn timecode(self, frames: i64) -> String {
+        let fps = i64::from(self.nominal());
+        let frames = frames.max(0);
+        let ff = frames % fps;
+        let total_seconds = frames / fps;
+        format!(
+            "{:02}:{:02}:{:02}:{:02}",
+            total_seconds / 3600,
+            (total_seconds % 3600) / 60,
+            total_seconds % 60,
+            ff
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TrackKind {
+    Video,
+    Audio,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum MediaKind {
+    /// Moving picture with its own audio
+    Video,
+    /// Still image (slides and overlays)
+    Still,
+    Audio,
+}
+
+/// A clip placed on a track; all times are in frames of the cut list rate
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct CutClip {
+    pub name: String,
+    pub media: PathBuf,
+    pub media_kind: MediaKind,
+    pub record_start: i64,
+    pub source_start: i64,
+    pub duration: i64,
+}
+
+impl CutClip {
+    pub fn record_end(&self) -> i64 {
+        self.record_start + self.duration
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct CutTrack {
+    pub name: String,
+    pub kind: TrackKind,
+    pub clips: Vec<CutClip>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct CutSubtitle {
+    pub start: i64,
+    pub duration: i64,
+    pub text: String,
+}
+
+/// Frame-accurate, editor-agnostic view of a render timeline
+#[derive(Clone, Debug)]
+pub(super) struct CutList {
+    pub name: String,
+    pub rate: FrameRate,
+    pub dimensions: VideoDimensions,
+    pub tracks: Vec<CutTrack>,
+    pub subtitles: Vec<CutSubtitle>,
+}
+
+impl CutList {
+    pub fn from_timeline(
+        name: &str,
+        timeline: &Timeline,
+        cues: &[TranscriptCue],
+        rate: FrameRate,
+        dimensions: VideoDimensions,
+    ) -> Self {
+        let mut main = Vec::new();
+        let mut broll = Vec::new();
+        let mut overlays = Vec::new();
+        let mut dialogue = Vec::new();
+        let mut music = Vec::new();
+
+        for segment in &timeline.segments {
+            // Derive durations from rounded edges so adjacent clips never gap or overlap
+            let record_start = rate.frames(segment.start_time);
+            let duration = rate.frames(segment.end_time()) - record_start;
+            if duration <= 0 {
+                continue;
+            }
+            let clip = |media: &Path, media_kind: MediaKind, source_start: f64| CutClip {
+                name: clip_name(media),
+                media: media.to_path_buf(),
+                media_kind,
+                record_start,
+                source_start: rate.frames(source_start),
+                duration,
+            };
+
+            match &segment.data {
+                SegmentData::VideoSubset {
+                    start_time,
+                    source,
+                    mute_audio,
+                    ..
+                } => {
+                    if source.id == SLIDE_SOURCE_ID {
+                        main.push(clip(&source.video, MediaKind::Still, 0.0));
+                    } else {
+                        main.push(clip(&source.video, MediaKind::Video, *start_time));
+                    }
+                    if !*mute_audio {
+                        dialogue.push(clip(&source.audio, MediaKind::Audio, *start_time));
+                    }
+                }
+                SegmentData::Broll {
+                    start_time,
+                    source_video,
+                    ..
+                } => broll.push(clip(source_video, MediaKind::Video, *start_time)),
+                SegmentData::Image { source_image, .. } => {
+                    overlays.push(clip(source_image, MediaKind::Still, 0.0))
+                }
+                SegmentData::Music { audio_source } => {
+                    music.push(clip(audio_source, MediaKind::Audio, 0.0))
+                }
+                // Captions are generated at render time and have no media to link
+                SegmentData::LowerThird { .. } => {}
+            }
+        }
+
+        let mut tracks = vec![CutTrack::new("Main", TrackKind::Video, main)];
+        for (track_name, kind, clips) in [
+            ("B-roll", TrackKind::Video, broll),
+            ("Overlays", TrackKind::Video, overlays),
+            ("Dialogue", TrackKind::Audio, dialogue),
+            ("Music", TrackKind::Audio, music),
+        ] {
+            if !clips.is_empty() {
+                tracks.push(CutTrack::new(track_name, kind, clips));
+            }
+        }
+
+        let subtitles = remap_subtitles_to_timeline(timeline, cues)
+            .into_iter()
+            .filter_map(|subtitle| {
+                let start = rate.frames(subtitle.start.as_secs_f64());
+                let duration = rate.frames(subtitle.end.as_secs_f64()) - start;
+                (duration > 0).then(|| CutSubtitle {
+                    start,
+                    duration,
+                    text: subtitle.text.trim().to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            rate,
+            dimensions,
+            tracks,
+            subtitles,
+        }
+    }
+
+    /// Length of the cut list in frames
+    pub fn duration(&self) -> i64 {
+        self.tracks
+            .iter()
+            .flat_map(|track| track.clips.iter().map(CutClip::record_end))
+            .chain(self.subtitles.iter().map(|s| s.start + s.duration))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn main_track(&self) -> &CutTrack {
+        &self.tracks[0]
+    }
+
+    /// Secondary tracks of one kind, in stacking order
+    pub fn connected_tracks(&self, kind: TrackKind) -> impl Iterator<Item = &CutTrack> {
+        self.tracks
+            .iter()
+            .skip(1)
+            .filter(move |track| track.kind == kind)
+    }
+}
+
+/// A clip or the silence before it when walking a track in record order
+pub(super) enum TrackItem<'a> {
+    Gap(i64),
+    Clip(&'a CutClip),
+}
+
+impl CutTrack {
+    /// Clips interleaved with the gaps that separate them, starting at frame zero
+    pub fn items(&self) -> Vec<TrackItem<'_>> {
+        let mut items = Vec::new();
+        let mut cursor = 0;
+        for clip in &self.clips {
+            if clip.record_start > cursor {
+                items.push(TrackItem::Gap(clip.record_start - cursor));
+            }
+            items.push(TrackItem::Clip(clip));
+            cursor = clip.record_end();
+        }
+        items
+    }
+
+    fn new(name: &str, kind: TrackKind, mut clips: Vec<CutClip>) -> Self {
+        clips.sort_by_key(|clip| clip.record_start);
+        Self {
+            name: name.to_string(),
+            kind,
+            clips,
+        }
+    }
+}
+
+fn clip_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// `file://` URL for a media path, percent-encoding everything but unreserved characters
+pub(super) fn file_url(path: &Path) -> String {
+    let mut url = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            url.push(char::from(byte));
+        } else {
+            url.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    url
+}
+
+/// Subtitle track as SubRip, written next to every export so editors can import it
+pub(super) fn subtitles_to_srt(cut: &CutList) -> String {
+    let time = |frames: i64| {
+        let millis = (frames as f64 * 1000.0 / cut.rate.as_f64()).round();
+        Duration::from_millis(millis.max(0.0) as u64)
+    };
+    let subtitles: Vec<RemappedSubtitle> = cut
+        .subtitles
+        .iter()
+        .map(|subtitle| RemappedSubtitle {
+            start: time(subtitle.start),
+            end: time(subtitle.start + subtitle.duration),
+            text: subtitle.text.clone(),
+            words: Vec::new(),
+        })
+        .collect();
+    generate_srt(&subtitles)
+}
+
+/// Use the still image instead of the rendered card video so editors get real stills
+struct StillSlides<'a>(&'a SlideGenerator);
+
+impl SlideProvider for StillSlides<'_> {
+    fn overlay_slide_image(&self, markdown: &str) -> Result<PathBuf> {
+        Ok(self.0.markdown_slide(markdown)?.image_path)
+    }
+
+    fn standalone_slide_video(&self, markdown: &str, _duration: f64) -> Result<PathBuf> {
+        Ok(self.0.markdown_slide(markdown)?.image_path)
+    }
+
+    fn build_step_video(&self, markdown: &str, step: usize, _duration: f64) -> Result<PathBuf> {
+        Ok(self.0.build_slide_step(markdown, step)?.image_path)
+    }
+}
+
+pub async fn handle_export(args: ExportArgs) -> Result<()> {
+    let markdown_path = canonicalize_existing(&args.markdown)?;
+    let project = load_render_project(&markdown_path, false).await?;
+
+    let render_mode = if args.reels {
+        RenderMode::Reels
+    } else {
+        RenderMode::Standard
+    };
+    let source = &project.default_source.source;
+    let (video_width, video_height) = probe_video_dimensions(source)?;
+    let (width, height) = render_mode.target_dimensions(video_width, video_height);
+    let dimensions = VideoDimensions::new(width, height);
+    let rate = FrameRate::from_fps(
+        probe_media_metadata(source)
+            .framerate
+            .unwrap_or(DEFAULT_FPS),
+    );
+
+    let generator = SlideGenerator::new(width, height)?
+        .with_theme(project.slide_theme.as_ref(), &project.project_dir)?;
+    let (timeline, _stats) = build_nle_timeline(
+        project.plan.clone(),
+        &StillSlides(&generator),
+        &project.sources,
+        &project.project_dir,
+    )?;
+
+    let name = markdown_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "timeline".to_string());
+    let cut = CutList::from_timeline(&name, &timeline, &project.cues, rate, dimensions);
+
+    let output = match &args.out_file {
+        Some(path) if path.is_absolute() => path.clone(),
+        Some(path) => project.project_dir.join(path),
+        None => markdown_path.with_extension(args.format.extension()),
+    };
+
+    let mut files = match args.format {
+        ExportFormat::Otio => vec![(output.clone(), otio::write_otio(&cut)?)],
+        ExportFormat::Fcpxml => vec![(output.clone(), fcpxml::write_fcpxml(&cut))],
+        ExportFormat::Edl => edl::write_edl_files(&cut, &output),
+    };
+    if !cut.subtitles.is_empty() {
+        files.push((output.with_extension("srt"), subtitles_to_srt(&cut)));
+    }
+
+    if !args.force
+        && let Some((existing, _)) = files.iter().find(|(path, _)| path.exists())
+    {
+        bail!(
+            "Output file {} already exists. Use --force to overwrite.",
+            existing.display()
+        );
+    }
+
+    for (path, contents) in &files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create output directory {}", parent.display())
+            })?;
+        }
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write export file {}", path.display()))?;
+    }
+
+    let clip_count: usize = cut.tracks.iter().map(|track| track.clips.len()).sum();
+    log_event(
+        Level::Success,
+        "video.export.success",
+        format!(
+            "Exported {clip_count} clip(s) on {} track(s) and {} subtitle(s) to {}",
+            cut.tracks.len(),
+            cut.subtitles.len(),
+            output.display()
+        ),
+    );
+
+    Ok(())
+}
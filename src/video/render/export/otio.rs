@@ -0,0 +1,133 @@
+//! OpenTimelineIO JSON writer.
+//!
+//! Produces a `Timeline.1` with one `Track.1` per cut list track. Subtitles become
+//! markers on the main track because OTIO has no dedicated caption track kind.
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use super::{CutClip, CutList, FrameRate, TrackItem, TrackKind, file_url};
+
+pub(super) fn write_otio(cut: &CutList) -> Result<String> {
+    let tracks: Vec<Value> = cut
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(idx, track)| {
+            let children: Vec<Value> = track
+                .items()
+                .into_iter()
+                .map(|item| match item {
+                    TrackItem::Gap(duration) => gap(cut.rate, duration),
+                    TrackItem::Clip(clip) => clip_json(cut.rate, clip),
+                })
+                .collect();
+            let markers: Vec<Value> = if idx == 0 {
+                cut.subtitles
+                    .iter()
+                    .map(|subtitle| {
+                        json!({
+                            "OTIO_SCHEMA": "Marker.2",
+                            "name": subtitle.text,
+                            "comment": subtitle.text,
+                            "color": "YELLOW",
+                            "marked_range": time_range(cut.rate, subtitle.start, subtitle.duration),
+                            "metadata": { "ins": { "kind": "subtitle" } },
+                        })
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            json!({
+                "OTIO_SCHEMA": "Track.1",
+                "name": track.name,
+                "kind": match track.kind {
+                    TrackKind::Video => "Video",
+                    TrackKind::Audio => "Audio",
+                },
+                "children": children,
+                "effects": [],
+                "markers": markers,
+                "enabled": true,
+                "metadata": {},
+                "source_range": null,
+            })
+        })
+        .collect();
+
+    let timeline = json!({
+        "OTIO_SCHEMA": "Timeline.1",
+        "name": cut.name,
+        "global_start_time": rational_time(cut.rate, 0),
+        "metadata": {
+            "ins": {
+                "width": cut.dimensions.width,
+                "height": cut.dimensions.height,
+            }
+        },
+        "tracks": {
+            "OTIO_SCHEMA": "Stack.1",
+            "name": "tracks",
+            "children": tracks,
+            "effects": [],
+            "markers": [],
+            "enabled": true,
+            "metadata": {},
+            "source_range": null,
+        },
+    });
+
+    Ok(serde_json::to_string_pretty(&timeline)?)
+}
+
+fn clip_json(rate: FrameRate, clip: &CutClip) -> Value {
+    json!({
+        "OTIO_SCHEMA": "Clip.2",
+        "name": clip.name,
+        "source_range": time_range(rate, clip.source_start, clip.duration),
+        "media_references": {
+            "DEFAULT_MEDIA": {
+                "OTIO_SCHEMA": "ExternalReference.1",
+                "name": clip.name,
+                "target_url": file_url(&clip.media),
+                "available_range": null,
+                "metadata": {},
+            }
+        },
+        "active_media_reference_key": "DEFAULT_MEDIA",
+        "effects": [],
+        "markers": [],
+        "enabled": true,
+        "metadata": {},
+    })
+}
+
+fn gap(rate: FrameRate, duration: i64) -> Value {
+    json!({
+        "OTIO_SCHEMA": "Gap.1",
+        "name": "",
+        "source_range": time_range(rate, 0, duration),
+        "effects": [],
+        "markers": [],
+        "enabled": true,
+        "metadata": {},
+    })
+}
+
+fn time_range(rate: FrameRate, start: i64, duration: i64) -> Value {
+    json!({
+        "OTIO_SCHEMA": "TimeRange.1",
+        "start_time": rational_time(rate, start),
+        "duration": rational_time(rate, duration),
+    })
+}
+
+fn rational_time(rate: FrameRate, frames: i64) -> Value {
+    json!({
+        "OTIO_SCHEMA": "RationalTime.1",
+        "rate": rate.as_f64(),
+        "value": frames as f64,
+    })
+}
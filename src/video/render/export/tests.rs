@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use regex::Regex;
+use serde_json::Value;
+
+use super::edl::{write_edl, write_edl_files};
+use super::fcpxml::{time, write_fcpxml};
+use super::otio::write_otio;
+use super::{CutList, FrameRate, MediaKind, TrackKind, file_url, subtitles_to_srt};
+use crate::video::render::ffmpeg::compiler::VideoDimensions;
+use crate::video::render::timeline::{AvSourceRef, Segment, Timeline};
+use crate::video::support::transcript::TranscriptCue;
+
+/// (media url, record start, source start, duration) for every clip of a track
+type Placement = (String, i64, i64, i64);
+
+fn source(video: &str, audio: &str, id: &str) -> AvSourceRef {
+    AvSourceRef {
+        video: PathBuf::from(video),
+        audio: PathBuf::from(audio),
+        id: id.to_string(),
+    }
+}
+
+fn sample_cut() -> CutList {
+    let mut timeline = Timeline::new();
+    timeline.add_segment(Segment::new_video_subset(
+        0.0,
+        12.0,
+        30.0,
+        source("/media/talk.mp4", "/media/talk.wav", "a"),
+        None,
+        false,
+    ));
+    timeline.add_segment(Segment::new_broll(
+        2.0,
+        4.0,
+        10.0,
+        PathBuf::from("/media/b roll.mp4"),
+        "b".to_string(),
+        None,
+    ));
+    timeline.add_segment(Segment::new_image(
+        0.0,
+        12.0,
+        PathBuf::from("/cache/overlay.jpg"),
+        None,
+    ));
+    timeline.add_segment(Segment::new_video_subset(
+        12.0,
+        2.0,
+        0.0,
+        source("/cache/card.jpg", "/cache/card.jpg", "__slide"),
+        None,
+        true,
+    ));
+    timeline.add_segment(Segment::new_video_subset(
+        14.0,
+        6.0,
+        50.0,
+        source("/media/talk.mp4", "/media/talk.wav", "a"),
+        None,
+        false,
+    ));
+    timeline.add_segment(Segment::new_music(
+        0.0,
+        20.0,
+        PathBuf::from("/media/music.mp3"),
+    ));
+    timeline.has_overlays = true;
+
+    let cues = vec![TranscriptCue {
+        start: Duration::from_secs(31),
+        end: Duration::from_secs(33),
+        text: "Hello & <welcome>".to_string(),
+        words: Vec::new(),
+        source_id: "a".to_string(),
+    }];
+
+    CutList::from_timeline(
+        "demo",
+        &timeline,
+        &cues,
+        FrameRate::from_fps(25.0),
+        VideoDimensions::new(1920, 1080),
+    )
+}
+
+fn expected(cut: &CutList, track_name: &str) -> Vec<Placement> {
+    cut.tracks
+        .iter()
+        .find(|track| track.name == track_name)
+        .unwrap()
+        .clips
+        .iter()
+        .map(|clip| {
+            (
+                file_url(&clip.media),
+                clip.record_start,
+                clip.source_start,
+                clip.duration,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn cut_list_splits_timeline_into_tracks() {
+    let cut = sample_cut();
+
+    let names: Vec<_> = cut.tracks.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["Main", "B-roll", "Overlays", "Dialogue", "Music"]);
+    assert_eq!(cut.tracks[3].kind, TrackKind::Audio);
+
+    let main = &cut.main_track().clips;
+    assert_eq!(main.len(), 3);
+    assert_eq!(main[0].media_kind, MediaKind::Video);
+    assert_eq!((main[0].source_start, main[0].duration), (750, 300));
+    assert_eq!(main[1].media_kind, MediaKind::Still);
+    assert_eq!((main[1].record_start, main[1].duration), (300, 50));
+    assert_eq!(main[2].record_start, 350);
+
+    // The muted title card contributes no dialogue
+    assert_eq!(cut.tracks[3].clips.len(), 2);
+    assert_eq!(
+        cut.tracks[3].clips[0].media,
+        PathBuf::from("/media/talk.wav")
+    );
+
+    assert_eq!(cut.subtitles.len(), 1);
+    assert_eq!(
+        (cut.subtitles[0].start, cut.subtitles[0].duration),
+        (25, 50)
+    );
+    assert_eq!(cut.duration(), 500);
+}
+
+#[test]
+fn frame_rate_handles_ntsc_rates() {
+    let ntsc = FrameRate::from_fps(29.97);
+    assert_eq!((ntsc.numerator, ntsc.denominator), (30000, 1001));
+    assert_eq!(ntsc.nominal(), 30);
+    assert_eq!(ntsc.timecode(30 * 61 + 5), "00:01:01:05");
+    assert_eq!(time(ntsc, 1), "1001/30000s");
+    assert_eq!(time(ntsc, 0), "0s");
+
+    let pal = FrameRate::from_fps(25.0);
+    assert_eq!((pal.numerator, pal.denominator), (25, 1));
+    assert_eq!(pal.timecode(25 * 3600), "01:00:00:00");
+}
+
+#[test]
+fn file_url_percent_encodes_paths() {
+    assert_eq!(
+        file_url(Path::new("/media/b roll&1.mp4")),
+        "file:///media/b%20roll%261.mp4"
+    );
+}
+
+fn rational_frames(value: &Value) -> i64 {
+    value["value"].as_f64().unwrap().round() as i64
+}
+
+#[test]
+fn otio_round_trip_preserves_cut_list() {
+    let cut = sample_cut();
+    let parsed: Value = serde_json::from_str(&write_otio(&cut).unwrap()).unwrap();
+
+    assert_eq!(parsed["OTIO_SCHEMA"], "Timeline.1");
+    let tracks = parsed["tracks"]["children"].as_array().unwrap();
+    assert_eq!(tracks.len(), cut.tracks.len());
+
+    for (track, original) in tracks.iter().zip(&cut.tracks) {
+        assert_eq!(track["name"], original.name.as_str());
+        assert_eq!(
+            track["kind"],
+            match original.kind {
+                TrackKind::Video => "Video",
+                TrackKind::Audio => "Audio",
+            }
+        );
+
+        let mut cursor = 0;
+        let mut placements = Vec::new();
+        for child in track["children"].as_array().unwrap() {
+            let range = &child["source_range"];
+            assert_eq!(range["duration"]["rate"], 25.0);
+            let duration = rational_frames(&range["duration"]);
+            if child["OTIO_SCHEMA"] == "Clip.2" {
+                let url = child["media_references"]["DEFAULT_MEDIA"]["target_url"]
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+                placements.push((url, cursor, rational_frames(&range["start_time"]), duration));
+            } else {
+                assert_eq!(child["OTIO_SCHEMA"], "Gap.1");
+            }
+            cursor += duration;
+        }
+        assert_eq!(placements, expected(&cut, &original.name));
+    }
+
+    let markers = tracks[0]["markers"].as_array().unwrap();
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0]["name"], "Hello & <welcome>");
+    assert_eq!(
+        rational_frames(&markers[0]["marked_range"]["start_time"]),
+        25
+    );
+    assert_eq!(rational_frames(&markers[0]["marked_range"]["duration"]), 50);
+}
+
+fn parse_timecode(rate: FrameRate, timecode: &str) -> i64 {
+    let parts: Vec<i64> = timecode.split(':').map(|p| p.parse().unwrap()).collect();
+    let fps = i64::from(rate.nominal());
+    ((parts[0] * 60 + parts[1]) * 60 + parts[2]) * fps + parts[3]
+}
+
+#[test]
+fn edl_round_trip_preserves_each_track() {
+    let cut = sample_cut();
+    let event = Regex::new(r"^(\d{3})  (\S+)\s+(V|A)\s+C\s+(\S+) (\S+) (\S+) (\S+)$").unwrap();
+
+    for track in &cut.tracks {
+        let edl = write_edl(&cut, track);
+        assert!(edl.starts_with(&format!("TITLE: demo - {}\n", track.name)));
+
+        let mut placements = Vec::new();
+        let mut lines = edl.lines();
+        while let Some(line) = lines.next() {
+            let Some(caps) = event.captures(line) else {
+                continue;
+            };
+            let source_in = parse_timecode(cut.rate, &caps[4]);
+            let source_out = parse_timecode(cut.rate, &caps[5]);
+            let record_in = parse_timecode(cut.rate, &caps[6]);
+            let record_out = parse_timecode(cut.rate, &caps[7]);
+            assert_eq!(source_out - source_in, record_out - record_in);
+
+            let file = lines
+                .by_ref()
+                .find_map(|line| line.strip_prefix("* SOURCE FILE: "))
+                .unwrap();
+            placements.push((
+                file_url(Path::new(file)),
+                record_in,
+                source_in,
+                record_out - record_in,
+            ));
+        }
+        assert_eq!(placements, expected(&cut, &track.name));
+    }
+}
+
+#[test]
+fn edl_writes_secondary_tracks_next_to_main_file() {
+    let cut = sample_cut();
+    let files = write_edl_files(&cut, Path::new("/out/demo.edl"));
+    let paths: Vec<_> = files.iter().map(|(path, _)| path.clone()).collect();
+    assert_eq!(
+        paths,
+        [
+            PathBuf::from("/out/demo.edl"),
+            PathBuf::from("/out/demo.b-roll.edl"),
+            PathBuf::from("/out/demo.overlays.edl"),
+            PathBuf::from("/out/demo.dialogue.edl"),
+            PathBuf::from("/out/demo.music.edl"),
+        ]
+    );
+}
+
+fn parse_fcpxml_time(rate: FrameRate, value: &str) -> i64 {
+    let value = value.trim_end_matches('s');
+    match value.split_once('/') {
+        Some((num, den)) => {
+            let seconds = num.parse::<f64>().unwrap() / den.parse::<f64>().unwrap();
+            rate.frames(seconds)
+        }
+        None => rate.frames(value.parse::<f64>().unwrap()),
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn fcpxml_round_trip_preserves_storyline_and_lanes() {
+    let cut = sample_cut();
+    let xml = write_fcpxml(&cut);
+    let rate = cut.rate;
+
+    let element = Regex::new(r"<(asset-clip|video|gap|caption|asset|format)\s([^>]*?)/?>").unwrap();
+    let attribute = Regex::new(r#"(\w+)="([^"]*)""#).unwrap();
+
+    let mut assets = HashMap::new();
+    let mut lanes: HashMap<i32, Vec<Placement>> = HashMap::new();
+    let mut captions = Vec::new();
+    // (record offset, local start) of the storyline element connected clips attach to
+    let mut parent = (0, 0);
+
+    for caps in element.captures_iter(&xml) {
+        let attrs: HashMap<&str, String> = attribute
+            .captures_iter(caps.get(2).unwrap().as_str())
+            .map(|a| (a.get(1).unwrap().as_str(), unescape(&a[2])))
+            .collect();
+        let frames = |key: &str| parse_fcpxml_time(rate, &attrs[key]);
+
+        match &caps[1] {
+            "format" => assert_eq!(attrs["frameDuration"], "1/25s"),
+            "asset" => {
+                assets.insert(attrs["id"].clone(), attrs["src"].clone());
+            }
+            tag => {
+                let lane: i32 = attrs.get("lane").map_or(0, |lane| lane.parse().unwrap());
+                let record = if lane == 0 {
+                    parent = (frames("offset"), frames("start"));
+                    frames("offset")
+                } else {
+                    parent.0 + frames("offset") - parent.1
+                };
+                if tag == "asset-clip" && lane >= 0 {
+                    // Picture only; the Dialogue lane carries the sound
+                    assert_eq!(attrs.get("srcEnable").map(String::as_str), Some("video"));
+                }
+                match tag {
+                    "gap" => {}
+                    "caption" => captions.push((record, frames("duration"), attrs["name"].clone())),
+                    _ => lanes.entry(lane).or_default().push((
+                        assets[&attrs["ref"]].clone(),
+                        record,
+                        frames("start"),
+                        frames("duration"),
+                    )),
+                }
+            }
+        }
+    }
+
+    assert_eq!(lanes[&0], expected(&cut, "Main"));
+    assert_eq!(lanes[&1], expected(&cut, "B-roll"));
+    assert_eq!(lanes[&2], expected(&cut, "Overlays"));
+    assert_eq!(lanes[&-1], expected(&cut, "Dialogue"));
+    assert_eq!(lanes[&-2], expected(&cut, "Music"));
+    assert_eq!(captions, [(25, 50, "Hello & <welcome>".to_string())]);
+    assert!(xml.contains("<sequence format=\"r1\" duration=\"500/25s\""));
+}
+
+#[test]
+fn srt_sidecar_lists_subtitles() {
+    let cut = sample_cut();
+    assert_eq!(
+        subtitles_to_srt(&cut),
+        "1\n00:00:01,000 --> 00:00:03,000\nHello & <welcome>\n\n"
+    );
+}
@@ -1,11 +1,20 @@
+use std::path::PathBuf;
+
 use anyhow::{Result, bail};
 
-use super::FfmpegCompiler;
 use super::FilterChain;
 use super::inputs::SourceMap;
 use super::util::format_time;
+use super::{FfmpegCompiler, GraphMode};
+use crate::video::config::MusicDucking;
+use crate::video::render::ffmpeg::loudness::{loudnorm_analysis_filter, loudnorm_filter};
 use crate::video::render::timeline::{Segment, SegmentData};
 
+/// Dialogue level (linear) above which the music starts ducking
+const DUCK_THRESHOLD: f64 = 0.02;
+/// Gaps shorter than this still count as the music track changing in place
+const ADJACENT_TOLERANCE: f64 = 0.01;
+
 impl FfmpegCompiler {
     pub(super) fn build_audio_mix_filters(
         &self,
@@ -14,6 +23,7 @@ impl FfmpegCompiler {
         source_map: &SourceMap,
         has_base_track: bool,
         total_duration: f64,
+        mode: GraphMode,
     ) -> Result<()> {
         let mut audio_label: Option<String> = None;
 
@@ -23,9 +33,12 @@ impl FfmpegCompiler {
         }
 
         if !music_segments.is_empty() {
-            let music_label = self.build_music_filters(filters, music_segments, source_map)?;
+            let mut music_label = self.build_music_filters(filters, music_segments, source_map)?;
             audio_label = Some(match audio_label {
-                Some(base) => {
+                Some(mut base) => {
+                    if let Some(ducking) = self.config.music_ducking() {
+                        (base, music_label) = duck_music(filters, &base, &music_label, ducking);
+                    }
                     let mixed = "a_mix".to_string();
                     filters.push(format!(
                         "[{base}][{music}]amix=inputs=2:normalize=0:dropout_transition=0[{mixed}]",
@@ -49,7 +62,12 @@ impl FfmpegCompiler {
             "a_silence".to_string()
         };
 
-        filters.push(format!("[{label}]anull[outa]", label = final_audio));
+        let finish = match (self.preset.loudness_lufs, mode) {
+            (Some(target), GraphMode::LoudnessAnalysis) => loudnorm_analysis_filter(target),
+            (Some(target), GraphMode::Full) => loudnorm_filter(target, self.loudness.as_ref()),
+            _ => "anull".to_string(),
+        };
+        filters.push(format!("[{label}]{finish}[outa]", label = final_audio));
         Ok(())
     }
 
@@ -60,36 +78,86 @@ impl FfmpegCompiler {
         source_map: &SourceMap,
     ) -> Result<String> {
         let music_volume = f64::from(self.config.music_volume());
-        let labels =
-            collect_music_segment_labels(filters, music_segments, source_map, music_volume)?;
+        let crossfade = self.config.music_crossfade_seconds();
+        let labels = collect_music_segment_labels(
+            filters,
+            music_segments,
+            source_map,
+            music_volume,
+            crossfade,
+        )?;
         mix_music_labels(filters, labels)
     }
 }
 
+/// Compress music with the dialogue as sidechain key
+///
+/// The compressor runs at a steep ratio and is blended back with the dry signal so the
+/// music never drops more than `depth_db` below its normal level.
+fn duck_music(
+    filters: &mut FilterChain,
+    base_label: &str,
+    music_label: &str,
+    ducking: MusicDucking,
+) -> (String, String) {
+    let mix = 1.0 - 10f64.powf(-f64::from(ducking.depth_db) / 20.0);
+    filters.push(format!("[{base_label}]asplit=2[a_voice][a_duck_key]"));
+    filters.push(format!(
+        "[{music_label}][a_duck_key]sidechaincompress=threshold={DUCK_THRESHOLD}:ratio=20:attack={attack:.2}:release={release:.2}:mix={mix:.4}[music_ducked]",
+        attack = f64::from(ducking.attack_ms).clamp(0.01, 2000.0),
+        release = f64::from(ducking.release_ms).clamp(0.01, 9000.0),
+    ));
+    ("a_voice".to_string(), "music_ducked".to_string())
+}
+
 fn collect_music_segment_labels(
     filters: &mut FilterChain,
     music_segments: &[&Segment],
     source_map: &SourceMap,
     music_volume: f64,
+    crossfade: f64,
 ) -> Result<Vec<String>> {
-    let mut labels = Vec::new();
-
-    for (idx, segment) in music_segments.iter().enumerate() {
-        if segment.duration <= 0.0 {
-            continue;
-        }
-
-        let SegmentData::Music { audio_source } = &segment.data else {
-            continue;
-        };
+    let tracks: Vec<(usize, &Segment, &PathBuf)> = music_segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| segment.duration > 0.0)
+        .filter_map(|(idx, segment)| match &segment.data {
+            SegmentData::Music { audio_source } => Some((idx, *segment, audio_source)),
+            _ => None,
+        })
+        .collect();
+
+    // Crossfade length at the boundary after each track; zero where the music stops
+    let boundaries: Vec<f64> = tracks
+        .windows(2)
+        .map(|pair| {
+            let (current, next) = (pair[0].1, pair[1].1);
+            let gap = next.start_time - (current.start_time + current.duration);
+            if gap.abs() < ADJACENT_TOLERANCE {
+                crossfade.min(current.duration).min(next.duration)
+            } else {
+                0.0
+            }
+        })
+        .chain(std::iter::once(0.0))
+        .collect();
 
+    let mut labels = Vec::new();
+    for (position, (idx, segment, audio_source)) in tracks.iter().enumerate() {
         let input_index = source_map.index(audio_source)?;
+        let fade = MusicFade {
+            fade_in: position
+                .checked_sub(1)
+                .map_or(0.0, |previous| boundaries[previous]),
+            fade_out: boundaries[position],
+        };
 
         let label = format!("music_{idx}");
         filters.push(build_single_music_filter(
             segment,
             input_index,
             music_volume,
+            fade,
             &label,
         ));
         labels.push(label);
@@ -98,19 +166,41 @@ fn collect_music_segment_labels(
     Ok(labels)
 }
 
+/// Crossfades with the neighbouring music tracks, in seconds
+#[derive(Debug, Clone, Copy, Default)]
+struct MusicFade {
+    fade_in: f64,
+    /// The track keeps playing this long past its end while fading out
+    fade_out: f64,
+}
+
 fn build_single_music_filter(
     segment: &Segment,
     input_index: usize,
     music_volume: f64,
+    fade: MusicFade,
     label: &str,
 ) -> String {
-    let duration_str = format_time(segment.duration);
+    let duration_str = format_time(segment.duration + fade.fade_out);
     let delay_ms = ((segment.start_time * 1000.0).round()).max(0.0) as u64;
 
+    let mut fades = String::new();
+    if fade.fade_in > 0.0 {
+        fades.push_str(&format!(",afade=t=in:st=0:d={}", format_time(fade.fade_in)));
+    }
+    if fade.fade_out > 0.0 {
+        fades.push_str(&format!(
+            ",afade=t=out:st={}:d={}",
+            format_time(segment.duration),
+            format_time(fade.fade_out)
+        ));
+    }
+
     format!(
-        "[{input}:a]atrim=start=0:end={duration},asetpts=PTS-STARTPTS,apad=pad_dur={duration},atrim=duration={duration},aresample=async=1:first_pts=0,adelay={delay}|{delay},volume={volume:.6}[{label}]",
+        "[{input}:a]atrim=start=0:end={duration},asetpts=PTS-STARTPTS,apad=pad_dur={duration},atrim=duration={duration}{fades},aresample=async=1:first_pts=0,adelay={delay}|{delay},volume={volume:.6}[{label}]",
         input = input_index,
         duration = duration_str,
+        fades = fades,
         delay = delay_ms,
         volume = music_volume,
         label = label,
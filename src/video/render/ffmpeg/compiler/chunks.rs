@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::inputs::SourceMap;
+use super::{FfmpegCompileOutput, FfmpegCompiler, FilterChain, GraphMode};
+use crate::video::config::{EncodePass, RateControl};
+use crate::video::render::timeline::Timeline;
+
+impl FfmpegCompiler {
+    /// Whether the preset can be encoded chunk by chunk (two-pass encodes need the whole video)
+    pub fn supports_chunks(&self, total_duration: f64) -> Result<bool> {
+        let rate = self.preset.rate_control(total_duration)?;
+        Ok(!matches!(rate, RateControl::Bitrate { two_pass: true, .. }))
+    }
+
+    /// Encode one chunk of the timeline: the finished picture plus uncompressed dialogue
+    ///
+    /// `chunk` is the slice of the timeline starting `offset` seconds into the video and
+    /// `total_duration` the length of the whole video, which bitrate targets are based on.
+    pub fn compile_chunk(
+        &self,
+        output: &Path,
+        chunk: &Timeline,
+        offset: f64,
+        total_duration: f64,
+        audio_source: PathBuf,
+    ) -> Result<Vec<String>> {
+        let mut args = Vec::new();
+
+        let source_map = SourceMap::build(chunk, &audio_source, false);
+        args.extend(source_map.input_args());
+
+        let filter_complex = self.build_filter_complex(
+            chunk,
+            &source_map,
+            chunk.total_duration(),
+            GraphMode::Chunk { offset },
+        )?;
+        args.push("-filter_complex".to_string());
+        args.push(filter_complex);
+        args.push("-map".to_string());
+        args.push("[outv]".to_string());
+        args.push("-map".to_string());
+        args.push("[outa]".to_string());
+
+        let rate = self.preset.rate_control(total_duration)?;
+        self.preset
+            .push_video_args(&mut args, &rate, EncodePass::Single);
+        for arg in ["-c:a", "pcm_s16le", "-f", "matroska", "-y"] {
+            args.push(arg.to_string());
+        }
+        args.push(output.to_string_lossy().into_owned());
+        Ok(args)
+    }
+
+    /// Join encoded chunks listed in a concat demuxer file into the final output
+    ///
+    /// The video stream is copied; music, ducking and loudness normalisation are applied to
+    /// the joined dialogue track, which is cheap compared to re-encoding the picture.
+    pub fn compile_assembly(
+        &self,
+        output: PathBuf,
+        concat_list: &Path,
+        timeline: &Timeline,
+    ) -> Result<FfmpegCompileOutput> {
+        let (mut args, filter_complex, list_index, next_input) =
+            self.assembly_graph(concat_list, timeline, GraphMode::Full)?;
+        let mux_args = self.push_mux_inputs(&mut args, next_input);
+
+        args.push("-filter_complex".to_string());
+        args.push(filter_complex);
+        args.push("-map".to_string());
+        args.push(format!("{list_index}:v"));
+        args.push("-map".to_string());
+        args.push("[outa]".to_string());
+        args.extend(mux_args);
+        args.push("-c:v".to_string());
+        args.push("copy".to_string());
+        self.preset.push_audio_args(&mut args);
+        self.preset.push_container_args(&mut args);
+        args.push(output.to_string_lossy().into_owned());
+
+        Ok(FfmpegCompileOutput {
+            args,
+            first_pass: None,
+            passlog: None,
+        })
+    }
+
+    /// Loudness analysis of the joined chunks, when the preset has a loudness target
+    pub fn compile_assembly_loudness_analysis(
+        &self,
+        concat_list: &Path,
+        timeline: &Timeline,
+    ) -> Result<Option<Vec<String>>> {
+        if self.preset.loudness_lufs.is_none() {
+            return Ok(None);
+        }
+
+        let (mut args, filter_complex, _, _) =
+            self.assembly_graph(concat_list, timeline, GraphMode::LoudnessAnalysis)?;
+        args.push("-filter_complex".to_string());
+        args.push(filter_complex);
+        for arg in ["-map", "[outa]", "-f", "null", "-"] {
+            args.push(arg.to_string());
+        }
+        Ok(Some(args))
+    }
+
+    /// Inputs and audio graph for joining chunks: the concat list plus every music track
+    fn assembly_graph(
+        &self,
+        concat_list: &Path,
+        timeline: &Timeline,
+        mode: GraphMode,
+    ) -> Result<(Vec<String>, String, usize, usize)> {
+        let mut music = Timeline::new();
+        for segment in timeline.music_segments() {
+            music.add_segment(segment.clone());
+        }
+        let source_map = SourceMap::build(&music, concat_list, false);
+
+        let mut args = Vec::new();
+        for path in source_map.paths() {
+            if path == concat_list {
+                for arg in ["-f", "concat", "-safe", "0"] {
+                    args.push(arg.to_string());
+                }
+            }
+            args.push("-i".to_string());
+            args.push(path.to_string_lossy().into_owned());
+        }
+        let list_index = source_map.index(concat_list)?;
+
+        let mut filters = FilterChain::new();
+        filters.push(format!("[{list_index}:a]anull[concat_a]"));
+        self.build_audio_mix_filters(
+            &mut filters,
+            &music.music_segments(),
+            &source_map,
+            true,
+            timeline.total_duration(),
+            mode,
+        )?;
+
+        Ok((args, filters.join(), list_index, source_map.paths().count()))
+    }
+}
@@ -1,4 +1,5 @@
 mod audio;
+mod chunks;
 mod inputs;
 mod overlays;
 mod util;
@@ -14,15 +15,30 @@ use anyhow::Result;
 use self::inputs::SourceMap;
 
 use super::super::mode::RenderMode;
-use crate::video::config::VideoConfig;
+use crate::video::config::{EncodePass, OutputPreset, RateControl, VideoConfig};
+use crate::video::render::ffmpeg::loudness::LoudnessMeasurement;
 use crate::video::render::timeline::Timeline;
-use crate::video::support::ffmpeg::PROFILE_H264_AAC_QUALITY_FASTSTART;
 
 use self::util::escape_ffmpeg_path;
 
+/// What a filter graph is built for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GraphMode {
+    /// Final picture and mixed, normalised audio
+    Full,
+    /// Mixed audio with a loudness measurement; the picture is discarded
+    LoudnessAnalysis,
+    /// Cached chunk starting `offset` seconds into the timeline: picture and dialogue only
+    Chunk { offset: f64 },
+}
+
 #[derive(Debug, Clone)]
 pub struct FfmpegCompileOutput {
     pub args: Vec<String>,
+    /// Analysis pass that must run before `args` for two-pass encodes
+    pub first_pass: Option<Vec<String>>,
+    /// Prefix of the statistics files written by a two-pass encode
+    pub passlog: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -67,6 +83,13 @@ pub struct RenderConfig {
     pub render_mode: RenderMode,
     pub config: VideoConfig,
     pub subtitle_path: Option<PathBuf>,
+    pub preset: OutputPreset,
+    /// FFmpeg metadata file whose chapters are embedded in the output
+    pub chapters_path: Option<PathBuf>,
+    /// SRT file muxed as a soft subtitle track
+    pub soft_subtitle_path: Option<PathBuf>,
+    /// Result of the loudness analysis pass, applied when the preset sets a target
+    pub loudness: Option<LoudnessMeasurement>,
 }
 
 impl RenderConfig {
@@ -79,8 +102,32 @@ impl RenderConfig {
             render_mode,
             config,
             subtitle_path,
+            preset: OutputPreset::default(),
+            chapters_path: None,
+            soft_subtitle_path: None,
+            loudness: None,
         }
     }
+
+    pub fn with_preset(mut self, preset: OutputPreset) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    pub fn with_mux_inputs(
+        mut self,
+        chapters_path: Option<PathBuf>,
+        soft_subtitle_path: Option<PathBuf>,
+    ) -> Self {
+        self.chapters_path = chapters_path;
+        self.soft_subtitle_path = soft_subtitle_path;
+        self
+    }
+
+    pub fn with_loudness(mut self, loudness: LoudnessMeasurement) -> Self {
+        self.loudness = Some(loudness);
+        self
+    }
 }
 
 pub struct FfmpegCompiler {
@@ -89,6 +136,10 @@ pub struct FfmpegCompiler {
     render_mode: RenderMode,
     config: VideoConfig,
     subtitle_path: Option<PathBuf>,
+    preset: OutputPreset,
+    chapters_path: Option<PathBuf>,
+    soft_subtitle_path: Option<PathBuf>,
+    loudness: Option<LoudnessMeasurement>,
 }
 
 impl FfmpegCompiler {
@@ -99,6 +150,10 @@ impl FfmpegCompiler {
             render_mode: render_config.render_mode,
             config: render_config.config,
             subtitle_path: render_config.subtitle_path,
+            preset: render_config.preset,
+            chapters_path: render_config.chapters_path,
+            soft_subtitle_path: render_config.soft_subtitle_path,
+            loudness: render_config.loudness,
         }
     }
 
@@ -113,9 +168,12 @@ impl FfmpegCompiler {
         let source_map = SourceMap::build(timeline, &audio_source, false);
         args.extend(source_map.input_args());
 
+        let mux_args = self.push_mux_inputs(&mut args, source_map.paths().count());
+
         let total_duration = timeline.total_duration();
 
-        let filter_complex = self.build_filter_complex(timeline, &source_map, total_duration)?;
+        let filter_complex =
+            self.build_filter_complex(timeline, &source_map, total_duration, GraphMode::Full)?;
         args.push("-filter_complex".to_string());
         args.push(filter_complex);
 
@@ -124,10 +182,57 @@ impl FfmpegCompiler {
         args.push("-map".to_string());
         args.push("[outa]".to_string());
 
-        PROFILE_H264_AAC_QUALITY_FASTSTART.push_to(&mut args);
+        let rate = self.preset.rate_control(total_duration)?;
+        if !matches!(rate, RateControl::Bitrate { two_pass: true, .. }) {
+            args.extend(mux_args);
+            self.preset
+                .push_encoding_args(&mut args, &rate, EncodePass::Single);
+            args.push(output.to_string_lossy().into_owned());
+            return Ok(FfmpegCompileOutput {
+                args,
+                first_pass: None,
+                passlog: None,
+            });
+        }
+
+        let passlog = output.with_extension("passlog");
+        let mut first_pass = args.clone();
+        self.preset
+            .push_encoding_args(&mut first_pass, &rate, EncodePass::First(&passlog));
+        first_pass.push("-y".to_string());
+        first_pass.push("-".to_string());
+
+        args.extend(mux_args);
+        self.preset
+            .push_encoding_args(&mut args, &rate, EncodePass::Second(&passlog));
         args.push(output.to_string_lossy().into_owned());
 
-        Ok(FfmpegCompileOutput { args })
+        Ok(FfmpegCompileOutput {
+            args,
+            first_pass: Some(first_pass),
+            passlog: Some(passlog),
+        })
+    }
+
+    /// Add chapter and subtitle inputs after the media inputs, returning their mapping args
+    fn push_mux_inputs(&self, args: &mut Vec<String>, mut next_input: usize) -> Vec<String> {
+        let mut mux_args = Vec::new();
+        if let Some(chapters) = &self.chapters_path {
+            args.push("-i".to_string());
+            args.push(chapters.to_string_lossy().into_owned());
+            mux_args.push("-map_chapters".to_string());
+            mux_args.push(next_input.to_string());
+            next_input += 1;
+        }
+        if let Some(subtitles) = &self.soft_subtitle_path {
+            args.push("-i".to_string());
+            args.push(subtitles.to_string_lossy().into_owned());
+            mux_args.push("-map".to_string());
+            mux_args.push(format!("{next_input}:s"));
+            mux_args.push("-c:s".to_string());
+            mux_args.push(self.preset.subtitle_codec().to_string());
+        }
+        mux_args
     }
 
     pub fn compile_preview(
@@ -142,7 +247,8 @@ impl FfmpegCompiler {
 
         let total_duration = timeline.total_duration();
 
-        let filter_complex = self.build_filter_complex(timeline, &source_map, total_duration)?;
+        let filter_complex =
+            self.build_filter_complex(timeline, &source_map, total_duration, GraphMode::Full)?;
         args.push("-filter_complex".to_string());
         args.push(filter_complex);
 
@@ -167,7 +273,42 @@ impl FfmpegCompiler {
 
         // Output format and destination are set by the runner (MpvPreviewRunner)
 
-        Ok(FfmpegCompileOutput { args })
+        Ok(FfmpegCompileOutput {
+            args,
+            first_pass: None,
+            passlog: None,
+        })
+    }
+
+    /// Audio-only pass that prints the loudness of the final mix, when the preset has a target
+    pub fn compile_loudness_analysis(
+        &self,
+        timeline: &Timeline,
+        audio_source: PathBuf,
+    ) -> Result<Option<Vec<String>>> {
+        if self.preset.loudness_lufs.is_none() {
+            return Ok(None);
+        }
+
+        let mut args = Vec::new();
+        let source_map = SourceMap::build(timeline, &audio_source, false);
+        args.extend(source_map.input_args());
+
+        let filter_complex = self.build_filter_complex(
+            timeline,
+            &source_map,
+            timeline.total_duration(),
+            GraphMode::LoudnessAnalysis,
+        )?;
+        args.push("-filter_complex".to_string());
+        args.push(filter_complex);
+        args.push("-map".to_string());
+        args.push("[outa]".to_string());
+        args.push("-f".to_string());
+        args.push("null".to_string());
+        args.push("-".to_string());
+
+        Ok(Some(args))
     }
 
     fn build_filter_complex(
@@ -175,13 +316,19 @@ impl FfmpegCompiler {
         timeline: &Timeline,
         source_map: &SourceMap,
         total_duration: f64,
+        mode: GraphMode,
     ) -> Result<String> {
         let mut filters = FilterChain::new();
 
         let video_segments = timeline.video_segments();
         let overlay_segments = timeline.overlay_segments();
-        let music_segments = timeline.music_segments();
+        // Chunks carry dialogue only; music is mixed in when they are joined
+        let music_segments = match mode {
+            GraphMode::Chunk { .. } => Vec::new(),
+            _ => timeline.music_segments(),
+        };
         let broll_segments = timeline.broll_segments();
+        let lower_third_segments = timeline.lower_third_segments();
 
         let has_base_track =
             self.build_base_track_filters(&mut filters, &video_segments, source_map)?;
@@ -206,11 +353,24 @@ impl FfmpegCompiler {
             )?;
         }
 
+        if !lower_third_segments.is_empty() {
+            current_video_label =
+                self.apply_lower_thirds(&mut filters, &lower_third_segments, &current_video_label);
+        }
+
         if let Some(ass_path) = &self.subtitle_path {
             let escaped_path = escape_ffmpeg_path(ass_path);
             let next_label = "subtitled_v";
+            // Subtitle times are absolute, so chunks shift their frames into place and back
+            let (shift, unshift) = match mode {
+                GraphMode::Chunk { offset } if offset > 0.0 => (
+                    format!("setpts=PTS+{offset:.6}/TB,"),
+                    ",setpts=PTS-STARTPTS".to_string(),
+                ),
+                _ => (String::new(), String::new()),
+            };
             filters.push(format!(
-                "[{input}]ass='{path}'[{output}]",
+                "[{input}]{shift}ass='{path}'{unshift}[{output}]",
                 input = current_video_label,
                 path = escaped_path,
                 output = next_label
@@ -218,7 +378,18 @@ impl FfmpegCompiler {
             current_video_label = next_label.to_string();
         }
 
-        filters.push(format!("[{}]copy[outv]", current_video_label));
+        let output_filters = self.preset.output_filters();
+        if mode == GraphMode::LoudnessAnalysis {
+            filters.push(format!("[{}]nullsink", current_video_label));
+        } else if output_filters.is_empty() {
+            filters.push(format!("[{}]copy[outv]", current_video_label));
+        } else {
+            filters.push(format!(
+                "[{}]{}[outv]",
+                current_video_label,
+                output_filters.join(",")
+            ));
+        }
 
         self.build_audio_mix_filters(
             &mut filters,
@@ -226,6 +397,7 @@ impl FfmpegCompiler {
             source_map,
             has_base_track,
             total_duration,
+            mode,
         )?;
 
         Ok(filters.join())
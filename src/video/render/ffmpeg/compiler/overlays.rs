@@ -3,12 +3,26 @@ use anyhow::Result;
 use super::FfmpegCompiler;
 use super::FilterChain;
 use super::inputs::SourceMap;
-use crate::video::render::timeline::{Segment, SegmentData, TimeWindow, Transform};
+use crate::video::document::directives::{OverlayPosition, PanDirection};
+use crate::video::render::timeline::{Motion, Segment, SegmentData, TimeWindow, Transform};
 
 const OVERLAY_FRAME_SCALE: f64 = 0.9;
 const OVERLAY_FRAME_BORDER_WIDTH: u32 = 4;
 const OVERLAY_FRAME_BORDER_COLOR: &str = "0x89B4FA";
 const OVERLAY_FRAME_BACKGROUND_COLOR: &str = "0x1E1E2E";
+/// Distance of anchored overlays from the frame edge, relative to the frame size
+const OVERLAY_EDGE_MARGIN: f64 = 0.04;
+/// Frame rate of generated pan/zoom and caption streams without a preset `fps`
+const GENERATED_FPS: f64 = 30.0;
+
+// Lower thirds follow the Catppuccin Mocha subtitle styles
+const LOWER_THIRD_FONT: &str = "Inter";
+const LOWER_THIRD_BACKGROUND: &str = "0x1E1E2E@0.85";
+const LOWER_THIRD_ACCENT: &str = "0xCBA6F7";
+const LOWER_THIRD_TITLE_COLOR: &str = "0xCDD6F4";
+const LOWER_THIRD_SUBTITLE_COLOR: &str = "0xA6ADC8";
+/// Lower thirds sit above the burned-in subtitle band
+const LOWER_THIRD_BOTTOM_MARGIN: f64 = 0.2;
 
 struct OverlayPrep {
     filters: Vec<String>,
@@ -22,6 +36,17 @@ impl FfmpegCompiler {
         output_label: &str,
         scale: f64,
     ) -> String {
+        // Full-size overlays cover the frame without a border
+        if scale >= 1.0 {
+            return format!(
+                "[{input}]scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1[{out}]",
+                input = input_label,
+                w = self.target_width,
+                h = self.target_height,
+                out = output_label,
+            );
+        }
+
         let outer_width = (self.target_width as f64 * scale) as u32;
         let outer_height = (self.target_height as f64 * scale) as u32;
         let inner_width = outer_width - (OVERLAY_FRAME_BORDER_WIDTH * 2);
@@ -46,11 +71,17 @@ impl FfmpegCompiler {
         overlay_width: u32,
         overlay_height: u32,
     ) -> (i32, i32) {
-        let base_x = (self.target_width as i32 - overlay_width as i32) / 2;
-        let base_y = if self.render_mode.requires_padding() {
-            ((self.target_height as i32 - overlay_height as i32) as f64 * 0.3) as i32
-        } else {
-            (self.target_height as i32 - overlay_height as i32) / 2
+        let (base_x, base_y) = match transform.and_then(|t| t.position) {
+            Some(position) => self.anchored_position(position, overlay_width, overlay_height),
+            None => {
+                let base_x = (self.target_width as i32 - overlay_width as i32) / 2;
+                let base_y = if self.render_mode.requires_padding() {
+                    ((self.target_height as i32 - overlay_height as i32) as f64 * 0.3) as i32
+                } else {
+                    (self.target_height as i32 - overlay_height as i32) / 2
+                };
+                (base_x, base_y)
+            }
         };
 
         if let Some(t) = transform
@@ -62,6 +93,32 @@ impl FfmpegCompiler {
         }
     }
 
+    fn anchored_position(
+        &self,
+        position: OverlayPosition,
+        overlay_width: u32,
+        overlay_height: u32,
+    ) -> (i32, i32) {
+        let margin_x = (self.target_width as f64 * OVERLAY_EDGE_MARGIN) as i32;
+        let margin_y = (self.target_height as f64 * OVERLAY_EDGE_MARGIN) as i32;
+        let right = self.target_width as i32 - overlay_width as i32 - margin_x;
+        let bottom = self.target_height as i32 - overlay_height as i32 - margin_y;
+        match position {
+            OverlayPosition::TopLeft => (margin_x, margin_y),
+            OverlayPosition::TopRight => (right, margin_y),
+            OverlayPosition::BottomLeft => (margin_x, bottom),
+            OverlayPosition::BottomRight => (right, bottom),
+            OverlayPosition::Center => (
+                (self.target_width as i32 - overlay_width as i32) / 2,
+                (self.target_height as i32 - overlay_height as i32) / 2,
+            ),
+        }
+    }
+
+    fn generated_fps(&self) -> f64 {
+        self.preset.fps.unwrap_or(GENERATED_FPS)
+    }
+
     fn build_overlay_filter(
         &self,
         base_label: &str,
@@ -149,6 +206,14 @@ impl FfmpegCompiler {
             &scaled_label,
             scale_factor,
         ));
+        let scaled_label = match transform.and_then(|t| t.fade) {
+            Some(fade) => {
+                let faded_label = format!("{prefix}_fade_{idx}");
+                filters.push(fade_filter(&scaled_label, &faded_label, fade, time_window));
+                faded_label
+            }
+            None => scaled_label,
+        };
         filters.push(self.build_overlay_filter(
             current_video_label,
             &scaled_label,
@@ -226,6 +291,18 @@ impl FfmpegCompiler {
             };
 
             let input_index = source_map.index(source_image)?;
+            if let Some(motion) = transform.as_ref().and_then(|t| t.motion) {
+                current_video_label = self.apply_ken_burns(
+                    filters,
+                    input_index,
+                    motion,
+                    transform.as_ref().and_then(|t| t.fade),
+                    segment.time_window(),
+                    &current_video_label,
+                    idx,
+                );
+                continue;
+            }
             let prep = self.build_image_prep(input_index, idx);
 
             current_video_label = self.apply_overlay_segment(
@@ -241,4 +318,193 @@ impl FfmpegCompiler {
 
         Ok(current_video_label)
     }
+
+    /// Animate a still across the full frame with `zoompan`
+    #[allow(clippy::too_many_arguments)]
+    fn apply_ken_burns(
+        &self,
+        filters: &mut FilterChain,
+        input_index: usize,
+        motion: Motion,
+        fade: Option<f32>,
+        time_window: TimeWindow,
+        current_video_label: &str,
+        idx: usize,
+    ) -> String {
+        let fps = self.generated_fps();
+        let frames = ((time_window.duration() * fps).ceil() as u64).max(1);
+        let (width, height) = (self.target_width, self.target_height);
+        let progress = format!("on/{frames}");
+        let zoom = format!(
+            "{from}+({delta})*{progress}",
+            from = motion.zoom_from,
+            delta = motion.zoom_to - motion.zoom_from,
+        );
+        let centered_x = "iw/2-(iw/zoom/2)".to_string();
+        let centered_y = "ih/2-(ih/zoom/2)".to_string();
+        let (x, y) = match motion.pan {
+            PanDirection::None => (centered_x, centered_y),
+            PanDirection::Left => (format!("(iw-iw/zoom)*(1-{progress})"), centered_y),
+            PanDirection::Right => (format!("(iw-iw/zoom)*{progress}"), centered_y),
+            PanDirection::Up => (centered_x, format!("(ih-ih/zoom)*(1-{progress})")),
+            PanDirection::Down => (centered_x, format!("(ih-ih/zoom)*{progress}")),
+        };
+
+        let motion_label = format!("kenburns_{idx}");
+        // Upscale first so sub-pixel pan steps don't jitter
+        filters.push(format!(
+            "[{input}:v]scale={w2}:{h2}:force_original_aspect_ratio=increase,crop={w2}:{h2},zoompan=z='{zoom}':x='{x}':y='{y}':d={frames}:s={w}x{h}:fps={fps},setsar=1,format=rgba,setpts=PTS-STARTPTS+{offset}/TB[{out}]",
+            input = input_index,
+            w2 = width * 2,
+            h2 = height * 2,
+            w = width,
+            h = height,
+            offset = time_window.start,
+            out = motion_label,
+        ));
+
+        let overlay_label = match fade {
+            Some(fade) => {
+                let faded_label = format!("kenburns_fade_{idx}");
+                filters.push(fade_filter(&motion_label, &faded_label, fade, time_window));
+                faded_label
+            }
+            None => motion_label,
+        };
+
+        let output_label = format!("kenburns_out_{idx}");
+        filters.push(format!(
+            "[{video}][{overlay}]overlay=x=0:y=0:enable='between(t,{start},{end})'[{output}]",
+            video = current_video_label,
+            overlay = overlay_label,
+            start = time_window.start,
+            end = time_window.end,
+            output = output_label,
+        ));
+        output_label
+    }
+
+    fn lower_third_size(&self) -> (u32, u32) {
+        let width_ratio = if self.target_width >= self.target_height {
+            0.42
+        } else {
+            0.8
+        };
+        let width = (self.target_width as f64 * width_ratio) as u32;
+        let height = (self.target_height.min(self.target_width) as f64 * 0.13) as u32;
+        (width - width % 2, height - height % 2)
+    }
+
+    pub(super) fn apply_lower_thirds(
+        &self,
+        filters: &mut FilterChain,
+        lower_third_segments: &[&Segment],
+        input_label: &str,
+    ) -> String {
+        let mut current_video_label = input_label.to_string();
+        let (width, height) = self.lower_third_size();
+        let accent_width = (height / 16).max(4);
+        let padding = height / 5 + accent_width;
+
+        for (idx, segment) in lower_third_segments.iter().enumerate() {
+            let SegmentData::LowerThird {
+                title,
+                subtitle,
+                transform,
+            } = &segment.data
+            else {
+                continue;
+            };
+            let time_window = segment.time_window();
+
+            let mut caption = vec![
+                format!(
+                    "color=c={LOWER_THIRD_BACKGROUND}:s={width}x{height}:d={duration}:r={fps}",
+                    duration = segment.duration,
+                    fps = self.generated_fps(),
+                ),
+                "format=rgba".to_string(),
+                format!("drawbox=x=0:y=0:w={accent_width}:h=ih:color={LOWER_THIRD_ACCENT}:t=fill"),
+            ];
+            let title_size = if subtitle.is_some() {
+                height * 2 / 5
+            } else {
+                height / 2
+            };
+            let title_y = if subtitle.is_some() {
+                "h*0.14".to_string()
+            } else {
+                "(h-text_h)/2".to_string()
+            };
+            caption.push(format!(
+                "drawtext=font='{LOWER_THIRD_FONT}':text='{text}':fontcolor={LOWER_THIRD_TITLE_COLOR}:fontsize={title_size}:x={padding}:y={title_y}",
+                text = escape_drawtext(title),
+            ));
+            if let Some(subtitle) = subtitle {
+                caption.push(format!(
+                    "drawtext=font='{LOWER_THIRD_FONT}':text='{text}':fontcolor={LOWER_THIRD_SUBTITLE_COLOR}:fontsize={size}:x={padding}:y=h*0.62",
+                    text = escape_drawtext(subtitle),
+                    size = height / 4,
+                ));
+            }
+            caption.push(format!("setpts=PTS-STARTPTS+{}/TB", time_window.start));
+
+            let caption_label = format!("lower_third_{idx}");
+            filters.push(format!("{}[{caption_label}]", caption.join(",")));
+            let caption_label = match transform.as_ref().and_then(|t| t.fade) {
+                Some(fade) => {
+                    let faded_label = format!("lower_third_fade_{idx}");
+                    filters.push(fade_filter(&caption_label, &faded_label, fade, time_window));
+                    faded_label
+                }
+                None => caption_label,
+            };
+
+            let position = transform
+                .as_ref()
+                .and_then(|t| t.position)
+                .unwrap_or(OverlayPosition::BottomLeft);
+            let (x, mut y) = self.anchored_position(position, width, height);
+            if matches!(
+                position,
+                OverlayPosition::BottomLeft | OverlayPosition::BottomRight
+            ) {
+                y = self.target_height as i32
+                    - height as i32
+                    - (self.target_height as f64 * LOWER_THIRD_BOTTOM_MARGIN) as i32;
+            }
+
+            let output_label = format!("lower_third_out_{idx}");
+            filters.push(format!(
+                "[{video}][{caption}]overlay=x={x}:y={y}:enable='between(t,{start},{end})'[{output}]",
+                video = current_video_label,
+                caption = caption_label,
+                start = time_window.start,
+                end = time_window.end,
+                output = output_label,
+            ));
+            current_video_label = output_label;
+        }
+
+        current_video_label
+    }
+}
+
+/// Fade an RGBA overlay stream in and out within its timeline window
+fn fade_filter(input_label: &str, output_label: &str, fade: f32, window: TimeWindow) -> String {
+    let fade = (fade as f64).min(window.duration() / 2.0);
+    format!(
+        "[{input}]format=rgba,fade=t=in:st={start}:d={fade}:alpha=1,fade=t=out:st={out_start}:d={fade}:alpha=1[{output}]",
+        input = input_label,
+        start = window.start,
+        out_start = window.end - fade,
+        output = output_label,
+    )
+}
+
+/// Escape text for a single-quoted `drawtext` value
+fn escape_drawtext(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('\'', "\u{2019}")
 }
@@ -2,10 +2,12 @@ use std::path::PathBuf;
 
 use super::inputs::SourceMap;
 use super::util::escape_ffmpeg_path;
-use super::{FfmpegCompiler, RenderConfig, VideoDimensions};
-use crate::video::config::VideoConfig;
+use super::{FfmpegCompiler, GraphMode, RenderConfig, VideoDimensions};
+use crate::video::config::{OutputPreset, VideoConfig};
+use crate::video::document::directives::{OverlayPosition, PanDirection};
+use crate::video::render::ffmpeg::loudness::LoudnessMeasurement;
 use crate::video::render::mode::RenderMode;
-use crate::video::render::timeline::{AvSourceRef, Segment, Timeline};
+use crate::video::render::timeline::{AvSourceRef, Motion, Segment, Timeline, Transform};
 
 #[test]
 fn compiler_includes_output_path_in_args() {
@@ -160,7 +162,7 @@ fn test_filter_complex_includes_subtitles() {
 
     let source_map = SourceMap::build(&timeline, PathBuf::from("audio.mp4").as_path(), false);
     let filter_complex = compiler
-        .build_filter_complex(&timeline, &source_map, 5.0)
+        .build_filter_complex(&timeline, &source_map, 5.0, GraphMode::Full)
         .unwrap();
 
     assert!(filter_complex.contains("ass='/tmp/subs.ass'"));
@@ -191,3 +193,337 @@ fn test_escape_ffmpeg_path() {
         "/path/with'\\''quote/file.ass"
     );
 }
+
+#[test]
+fn target_size_preset_compiles_two_passes() {
+    let dimensions = VideoDimensions::new(1080, 1080);
+    let preset = OutputPreset {
+        name: "small".to_string(),
+        mode: Some(RenderMode::Square),
+        target_size_mb: Some(50.0),
+        width: Some(720),
+        ..OutputPreset::default()
+    };
+    let render_config =
+        RenderConfig::new(RenderMode::Square, VideoConfig::default(), None).with_preset(preset);
+    let compiler = FfmpegCompiler::new(dimensions, render_config);
+
+    let mut timeline = Timeline::new();
+    timeline.add_segment(Segment::new_video_subset(
+        0.0,
+        60.0,
+        0.0,
+        AvSourceRef {
+            video: PathBuf::from("video.mp4"),
+            audio: PathBuf::from("audio.mp4"),
+            id: "a".to_string(),
+        },
+        None,
+        false,
+    ));
+
+    let output = compiler
+        .compile(
+            PathBuf::from("out_small.mp4"),
+            &timeline,
+            PathBuf::from("audio.mp4"),
+        )
+        .unwrap();
+
+    let first_pass = output.first_pass.expect("two-pass encode");
+    assert_eq!(output.passlog, Some(PathBuf::from("out_small.passlog")));
+    assert!(first_pass.windows(2).any(|w| w == ["-pass", "1"]));
+    assert_eq!(first_pass.last().unwrap(), "-");
+    assert!(output.args.windows(2).any(|w| w == ["-pass", "2"]));
+    assert_eq!(output.args.last().unwrap(), "out_small.mp4");
+
+    let filter_complex_idx = output
+        .args
+        .iter()
+        .position(|arg| arg == "-filter_complex")
+        .unwrap();
+    assert!(output.args[filter_complex_idx + 1].contains("scale=720:-2[outv]"));
+}
+
+#[test]
+fn chapters_and_soft_subtitles_are_muxed_after_media_inputs() {
+    let dimensions = VideoDimensions::new(1920, 1080);
+    let render_config = RenderConfig::new(RenderMode::Standard, VideoConfig::default(), None)
+        .with_mux_inputs(
+            Some(PathBuf::from("out.ffmeta")),
+            Some(PathBuf::from("out.srt")),
+        );
+    let compiler = FfmpegCompiler::new(dimensions, render_config);
+
+    let mut timeline = Timeline::new();
+    timeline.add_segment(Segment::new_video_subset(
+        0.0,
+        10.0,
+        0.0,
+        AvSourceRef {
+            video: PathBuf::from("video.mp4"),
+            audio: PathBuf::from("video.mp4"),
+            id: "a".to_string(),
+        },
+        None,
+        false,
+    ));
+
+    let output = compiler
+        .compile(
+            PathBuf::from("out.mp4"),
+            &timeline,
+            PathBuf::from("video.mp4"),
+        )
+        .unwrap();
+
+    let args = &output.args;
+    assert!(args.windows(2).any(|w| w == ["-i", "out.ffmeta"]));
+    assert!(args.windows(2).any(|w| w == ["-i", "out.srt"]));
+    assert!(args.windows(2).any(|w| w == ["-map_chapters", "1"]));
+    assert!(args.windows(2).any(|w| w == ["-map", "2:s"]));
+    assert!(args.windows(2).any(|w| w == ["-c:s", "mov_text"]));
+}
+
+#[test]
+fn overlay_directives_compile_to_positioned_faded_overlays() {
+    let dimensions = VideoDimensions::new(1920, 1080);
+    let render_config = RenderConfig::new(RenderMode::Standard, VideoConfig::default(), None);
+    let compiler = FfmpegCompiler::new(dimensions, render_config);
+
+    let mut timeline = Timeline::new();
+    timeline.add_segment(Segment::new_video_subset(
+        0.0,
+        10.0,
+        0.0,
+        AvSourceRef {
+            video: PathBuf::from("video.mp4"),
+            audio: PathBuf::from("video.mp4"),
+            id: "a".to_string(),
+        },
+        None,
+        false,
+    ));
+    timeline.add_segment(Segment::new_broll(
+        0.0,
+        4.0,
+        30.0,
+        PathBuf::from("broll.mp4"),
+        "b".to_string(),
+        Some(Transform {
+            scale: Some(0.25),
+            position: Some(OverlayPosition::BottomRight),
+            fade: Some(0.5),
+            ..Transform::new()
+        }),
+    ));
+    timeline.add_segment(Segment::new_image(
+        4.0,
+        6.0,
+        PathBuf::from("still.png"),
+        Some(Transform {
+            motion: Some(Motion {
+                zoom_from: 1.0,
+                zoom_to: 1.5,
+                pan: PanDirection::Right,
+            }),
+            ..Transform::new()
+        }),
+    ));
+    timeline.add_segment(Segment::new_lower_third(
+        0.0,
+        5.0,
+        "Jane's 100%".to_string(),
+        Some("Maintainer".to_string()),
+        None,
+    ));
+
+    let source_map = SourceMap::build(&timeline, PathBuf::from("video.mp4").as_path(), false);
+    let filter_complex = compiler
+        .build_filter_complex(&timeline, &source_map, 10.0, GraphMode::Full)
+        .unwrap();
+
+    // 25% picture-in-picture anchored bottom-right with a 4% margin
+    assert!(filter_complex.contains("overlay=x=1364:y=767:enable='between(t,0,4)'"));
+    assert!(filter_complex.contains("fade=t=in:st=0:d=0.5:alpha=1"));
+    assert!(filter_complex.contains("fade=t=out:st=3.5:d=0.5:alpha=1"));
+    assert!(filter_complex.contains("zoompan=z='1+(0.5)*on/180'"));
+    assert!(filter_complex.contains("x='(iw-iw/zoom)*on/180'"));
+    assert!(filter_complex.contains("text='Jane\u{2019}s 100\\%'"));
+    assert!(filter_complex.contains("text='Maintainer'"));
+}
+
+fn dialogue_with_music_timeline() -> Timeline {
+    let mut timeline = Timeline::new();
+    timeline.add_segment(Segment::new_video_subset(
+        0.0,
+        20.0,
+        0.0,
+        AvSourceRef {
+            video: PathBuf::from("video.mp4"),
+            audio: PathBuf::from("audio.mp4"),
+            id: "a".to_string(),
+        },
+        None,
+        false,
+    ));
+    timeline.add_segment(Segment::new_music(0.0, 10.0, PathBuf::from("intro.mp3")));
+    timeline.add_segment(Segment::new_music(10.0, 10.0, PathBuf::from("outro.mp3")));
+    timeline
+}
+
+fn filter_complex(args: &[String]) -> &str {
+    let idx = args
+        .iter()
+        .position(|arg| arg == "-filter_complex")
+        .unwrap();
+    &args[idx + 1]
+}
+
+#[test]
+fn music_is_ducked_under_dialogue_and_crossfaded() {
+    let dimensions = VideoDimensions::new(1920, 1080);
+    let render_config = RenderConfig::new(RenderMode::Standard, VideoConfig::default(), None);
+    let compiler = FfmpegCompiler::new(dimensions, render_config);
+
+    let output = compiler
+        .compile(
+            PathBuf::from("out.mp4"),
+            &dialogue_with_music_timeline(),
+            PathBuf::from("audio.mp4"),
+        )
+        .unwrap();
+    let filters = filter_complex(&output.args);
+
+    // The first track plays on while fading out under the second one
+    assert!(filters.contains("atrim=duration=11.500000,afade=t=out:st=10.000000:d=1.500000"));
+    assert!(filters.contains("atrim=duration=10.000000,afade=t=in:st=0:d=1.500000"));
+    assert!(filters.contains("[a_base]asplit=2[a_voice][a_duck_key]"));
+    assert!(filters.contains(
+        "[music_mix][a_duck_key]sidechaincompress=threshold=0.02:ratio=20:attack=50.00:release=600.00:mix=0.7488[music_ducked]"
+    ));
+    assert!(filters.contains("[a_voice][music_ducked]amix=inputs=2"));
+    assert!(filters.ends_with("[a_mix]anull[outa]"));
+
+    let config = VideoConfig {
+        music_duck_db: 0.0,
+        music_crossfade_seconds: 0.0,
+        ..VideoConfig::default()
+    };
+    let compiler = FfmpegCompiler::new(
+        dimensions,
+        RenderConfig::new(RenderMode::Standard, config, None),
+    );
+    let output = compiler
+        .compile(
+            PathBuf::from("out.mp4"),
+            &dialogue_with_music_timeline(),
+            PathBuf::from("audio.mp4"),
+        )
+        .unwrap();
+    let filters = filter_complex(&output.args);
+    assert!(!filters.contains("afade"));
+    assert!(!filters.contains("sidechaincompress"));
+}
+
+#[test]
+fn loudness_target_measures_then_normalises_linearly() {
+    let dimensions = VideoDimensions::new(1920, 1080);
+    let preset = OutputPreset {
+        loudness_lufs: Some(-16.0),
+        ..OutputPreset::default()
+    };
+    let render_config =
+        RenderConfig::new(RenderMode::Standard, VideoConfig::default(), None).with_preset(preset);
+    let timeline = dialogue_with_music_timeline();
+
+    let compiler = FfmpegCompiler::new(dimensions, render_config.clone());
+    let analysis = compiler
+        .compile_loudness_analysis(&timeline, PathBuf::from("audio.mp4"))
+        .unwrap()
+        .expect("loudness analysis pass");
+    let filters = filter_complex(&analysis);
+    assert!(filters.contains("nullsink"));
+    assert!(!filters.contains("[outv]"));
+    assert!(filters.ends_with("loudnorm=I=-16:TP=-1.5:LRA=11:print_format=json[outa]"));
+    assert!(analysis.ends_with(&["-f".to_string(), "null".to_string(), "-".to_string()]));
+
+    let measured = LoudnessMeasurement {
+        integrated: -22.4,
+        true_peak: -3.1,
+        range: 5.2,
+        threshold: -32.9,
+        offset: 0.3,
+    };
+    let compiler = FfmpegCompiler::new(dimensions, render_config.with_loudness(measured));
+    let output = compiler
+        .compile(
+            PathBuf::from("out.mp4"),
+            &timeline,
+            PathBuf::from("audio.mp4"),
+        )
+        .unwrap();
+    assert!(filter_complex(&output.args).ends_with(
+        "loudnorm=I=-16:TP=-1.5:LRA=11:measured_I=-22.40:measured_TP=-3.10:measured_LRA=5.20:measured_thresh=-32.90:offset=0.30:linear=true,aresample=48000[outa]"
+    ));
+
+    let plain = FfmpegCompiler::new(
+        dimensions,
+        RenderConfig::new(RenderMode::Standard, VideoConfig::default(), None),
+    );
+    assert!(
+        plain
+            .compile_loudness_analysis(&timeline, PathBuf::from("audio.mp4"))
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn chunks_carry_dialogue_and_assembly_copies_video() {
+    let dimensions = VideoDimensions::new(1920, 1080);
+    let render_config = RenderConfig::new(
+        RenderMode::Standard,
+        VideoConfig::default(),
+        Some(PathBuf::from("subs.ass")),
+    );
+    let compiler = FfmpegCompiler::new(dimensions, render_config);
+    let timeline = dialogue_with_music_timeline();
+
+    let chunk = compiler
+        .compile_chunk(
+            &PathBuf::from("chunk.mkv"),
+            &timeline,
+            30.0,
+            120.0,
+            PathBuf::from("audio.mp4"),
+        )
+        .unwrap();
+    let filters = filter_complex(&chunk);
+    assert!(!filters.contains("music"));
+    assert!(filters.contains("setpts=PTS+30.000000/TB,ass='subs.ass',setpts=PTS-STARTPTS"));
+    assert!(chunk.windows(2).any(|w| w == ["-c:a", "pcm_s16le"]));
+    assert_eq!(chunk.last().unwrap(), "chunk.mkv");
+
+    let assembly = compiler
+        .compile_assembly(
+            PathBuf::from("out.mp4"),
+            &PathBuf::from("out.chunks.txt"),
+            &timeline,
+        )
+        .unwrap();
+    let list = assembly
+        .args
+        .iter()
+        .position(|arg| arg == "out.chunks.txt")
+        .unwrap();
+    assert_eq!(
+        assembly.args[list - 4..list],
+        ["concat", "-safe", "0", "-i"]
+    );
+    let filters = filter_complex(&assembly.args);
+    assert!(filters.starts_with("[2:a]anull[concat_a]"));
+    assert!(filters.contains("sidechaincompress"));
+    assert!(assembly.args.windows(2).any(|w| w == ["-map", "2:v"]));
+    assert!(assembly.args.windows(2).any(|w| w == ["-c:v", "copy"]));
+}
@@ -0,0 +1,138 @@
+//! EBU R128 loudness normalisation with ffmpeg's `loudnorm` filter.
+//!
+//! Normalisation runs in two passes: an analysis pass prints the measured loudness of the
+//! mixed audio as JSON, and the render pass feeds those values back into `loudnorm` so it
+//! can apply a single linear gain instead of dynamic compression.
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+
+/// Maximum true peak after normalisation, in dBTP
+pub const TRUE_PEAK_DB: f64 = -1.5;
+/// Target loudness range, in LU
+pub const LOUDNESS_RANGE: f64 = 11.0;
+
+/// Loudness of the mixed audio as measured by the analysis pass
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessMeasurement {
+    /// Integrated loudness in LUFS
+    pub integrated: f64,
+    /// True peak in dBTP
+    pub true_peak: f64,
+    /// Loudness range in LU
+    pub range: f64,
+    /// Gating threshold in LUFS
+    pub threshold: f64,
+    /// Gain offset suggested by `loudnorm` for the second pass
+    pub offset: f64,
+}
+
+#[derive(Deserialize)]
+struct LoudnormJson {
+    input_i: String,
+    input_tp: String,
+    input_lra: String,
+    input_thresh: String,
+    target_offset: String,
+}
+
+/// Extract the JSON summary `loudnorm=print_format=json` writes at the end of stderr
+pub fn parse_loudnorm_output(stderr: &str) -> Result<LoudnessMeasurement> {
+    let start = stderr
+        .rfind('{')
+        .ok_or_else(|| anyhow!("ffmpeg printed no loudnorm measurement"))?;
+    let end = stderr[start..]
+        .find('}')
+        .map(|end| start + end + 1)
+        .ok_or_else(|| anyhow!("ffmpeg printed an incomplete loudnorm measurement"))?;
+    let json: LoudnormJson = serde_json::from_str(&stderr[start..end])
+        .context("Failed to parse loudnorm measurement")?;
+
+    let value = |name: &str, raw: &str| -> Result<f64> {
+        let value: f64 = raw
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid loudnorm {name} `{raw}`"))?;
+        if !value.is_finite() {
+            // Silent input measures as -inf, which loudnorm cannot normalise
+            return Err(anyhow!(
+                "Audio is silent; loudnorm measured {name} as {raw}"
+            ));
+        }
+        Ok(value)
+    };
+
+    Ok(LoudnessMeasurement {
+        integrated: value("input_i", &json.input_i)?,
+        true_peak: value("input_tp", &json.input_tp)?,
+        range: value("input_lra", &json.input_lra)?,
+        threshold: value("input_thresh", &json.input_thresh)?,
+        offset: value("target_offset", &json.target_offset)?,
+    })
+}
+
+/// `loudnorm` filter for the analysis pass, or for the render pass once measured
+///
+/// Without a measurement the filter normalises in a single dynamic pass, which is what
+/// previews and dry runs use.
+pub fn loudnorm_filter(target: f64, measured: Option<&LoudnessMeasurement>) -> String {
+    let base = format!("loudnorm=I={target}:TP={TRUE_PEAK_DB}:LRA={LOUDNESS_RANGE}");
+    match measured {
+        Some(measured) => format!(
+            "{base}:measured_I={:.2}:measured_TP={:.2}:measured_LRA={:.2}:measured_thresh={:.2}:offset={:.2}:linear=true,aresample=48000",
+            measured.integrated,
+            measured.true_peak,
+            measured.range,
+            measured.threshold,
+            measured.offset,
+        ),
+        // loudnorm upsamples to 192 kHz internally
+        None => format!("{base},aresample=48000"),
+    }
+}
+
+/// `loudnorm` filter that only measures and prints the result as JSON
+pub fn loudnorm_analysis_filter(target: f64) -> String {
+    format!("loudnorm=I={target}:TP={TRUE_PEAK_DB}:LRA={LOUDNESS_RANGE}:print_format=json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"[Parsed_loudnorm_12 @ 0x5581]
+{
+	"input_i" : "-23.51",
+	"input_tp" : "-4.02",
+	"input_lra" : "6.30",
+	"input_thresh" : "-33.89",
+	"output_i" : "-14.01",
+	"output_tp" : "-1.50",
+	"output_lra" : "5.10",
+	"output_thresh" : "-24.36",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.01"
+}
+"#;
+
+    #[test]
+    fn parses_loudnorm_json_summary() {
+        let measured = parse_loudnorm_output(&format!("size=N/A time=00:01:00\n{SAMPLE}")).unwrap();
+        assert_eq!(measured.integrated, -23.51);
+        assert_eq!(measured.true_peak, -4.02);
+        assert_eq!(measured.range, 6.3);
+        assert_eq!(measured.threshold, -33.89);
+        assert_eq!(measured.offset, 0.01);
+
+        let filter = loudnorm_filter(-14.0, Some(&measured));
+        assert!(filter.starts_with("loudnorm=I=-14:TP=-1.5:LRA=11:measured_I=-23.51:"));
+        assert!(filter.contains(":linear=true,aresample=48000"));
+    }
+
+    #[test]
+    fn rejects_missing_or_silent_measurements() {
+        assert!(parse_loudnorm_output("Error opening input").is_err());
+        let silent = SAMPLE.replace("\"-23.51\"", "\"-inf\"");
+        assert!(parse_loudnorm_output(&silent).is_err());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod compiler;
+pub mod loudness;
+pub mod mpv;
 pub mod services;
@@ -0,0 +1,145 @@
+//! Long-running mpv window controlled over its JSON IPC socket.
+//!
+//! Each stream is an ffmpeg process writing matroska into a fresh FIFO; switching streams
+//! tells the running mpv to `loadfile` the new FIFO, so the window, its size and the
+//! volume survive a reload.
+
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use nix::sys::stat::Mode;
+use nix::unistd::mkfifo;
+use serde_json::json;
+use tempfile::TempDir;
+
+/// How long to wait for mpv to open its IPC socket
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(10);
+const SOCKET_POLL: Duration = Duration::from_millis(100);
+
+pub struct MpvSession {
+    mpv: Child,
+    stream: Option<Child>,
+    dir: TempDir,
+    socket: PathBuf,
+    generation: usize,
+}
+
+impl MpvSession {
+    /// Open an idle mpv window
+    pub fn start() -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("ins-preview-")
+            .tempdir()
+            .context("Failed to create preview directory")?;
+        let socket = dir.path().join("mpv.sock");
+
+        let mpv = Command::new("mpv")
+            .arg("--idle=yes")
+            .arg("--force-window=yes")
+            .arg("--keep-open=yes")
+            .arg("--no-terminal")
+            .arg(format!("--input-ipc-server={}", socket.display()))
+            .stdin(Stdio::null())
+            .spawn()
+            .context("Failed to spawn mpv. Install mpv for real-time preview.")?;
+
+        let mut session = Self {
+            mpv,
+            stream: None,
+            dir,
+            socket,
+            generation: 0,
+        };
+        session.wait_for_socket()?;
+        Ok(session)
+    }
+
+    fn wait_for_socket(&mut self) -> Result<()> {
+        let mut waited = Duration::ZERO;
+        while UnixStream::connect(&self.socket).is_err() {
+            if !self.is_running() {
+                bail!("mpv exited before opening its IPC socket");
+            }
+            if waited >= SOCKET_TIMEOUT {
+                bail!(
+                    "mpv did not open its IPC socket at {}",
+                    self.socket.display()
+                );
+            }
+            thread::sleep(SOCKET_POLL);
+            waited += SOCKET_POLL;
+        }
+        Ok(())
+    }
+
+    /// Replace the current stream with ffmpeg `args` (inputs, filters and codecs)
+    ///
+    /// `offset` is where the stream starts in the full timeline; timestamps are shifted by
+    /// it so mpv shows positions of the finished video.
+    pub fn play(&mut self, args: &[String], offset: f64) -> Result<()> {
+        self.stop_stream();
+
+        self.generation += 1;
+        let fifo = self
+            .dir
+            .path()
+            .join(format!("stream-{}.mkv", self.generation));
+        mkfifo(&fifo, Mode::S_IRUSR | Mode::S_IWUSR)
+            .with_context(|| format!("Failed to create FIFO {}", fifo.display()))?;
+
+        let stream = Command::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error"])
+            .args(args)
+            .arg("-output_ts_offset")
+            .arg(format!("{offset:.3}"))
+            .args(["-f", "matroska", "-y"])
+            .arg(&fifo)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+            .context("Failed to spawn ffmpeg for preview")?;
+        self.stream = Some(stream);
+
+        self.command(json!(["loadfile", fifo.to_string_lossy(), "replace"]))
+    }
+
+    /// Show a message on top of the video for a few seconds
+    pub fn show_text(&self, text: &str) -> Result<()> {
+        self.command(json!(["show-text", text, 4000]))
+    }
+
+    /// Whether the mpv window is still open
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.mpv.try_wait(), Ok(None))
+    }
+
+    fn command(&self, command: serde_json::Value) -> Result<()> {
+        let mut socket = UnixStream::connect(&self.socket)
+            .with_context(|| format!("Failed to connect to mpv at {}", self.socket.display()))?;
+        let mut line = json!({ "command": command }).to_string();
+        line.push('\n');
+        socket
+            .write_all(line.as_bytes())
+            .context("Failed to send command to mpv")
+    }
+
+    fn stop_stream(&mut self) {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.kill();
+            let _ = stream.wait();
+        }
+    }
+}
+
+impl Drop for MpvSession {
+    fn drop(&mut self) {
+        self.stop_stream();
+        let _ = self.mpv.kill();
+        let _ = self.mpv.wait();
+    }
+}
@@ -8,8 +8,12 @@ use indicatif::{ProgressBar, ProgressStyle};
 use crate::video::document::MusicDirective;
 use crate::video::support::music::MusicResolver;
 
-pub trait FfmpegRunner {
+/// Runs ffmpeg; shared across worker threads when chunks render in parallel
+pub trait FfmpegRunner: Sync {
     fn run(&self, args: &[String], options: FfmpegRunOptions) -> Result<()>;
+
+    /// Run ffmpeg and return everything it wrote to stderr (used for analysis filters)
+    fn run_capture(&self, args: &[String], options: FfmpegRunOptions) -> Result<String>;
 }
 
 pub trait PreviewPlayer {
@@ -36,61 +40,76 @@ impl FfmpegRunOptions {
 
 impl FfmpegRunner for SystemFfmpegRunner {
     fn run(&self, args: &[String], options: FfmpegRunOptions) -> Result<()> {
-        let mut child = Command::new("ffmpeg")
-            .args(args)
-            .stderr(Stdio::piped())
-            .spawn()
-            .with_context(|| "Failed to spawn ffmpeg")?;
+        run_ffmpeg(args, options, None)
+    }
+
+    fn run_capture(&self, args: &[String], options: FfmpegRunOptions) -> Result<String> {
+        let mut captured = String::new();
+        run_ffmpeg(args, options, Some(&mut captured))?;
+        Ok(captured)
+    }
+}
+
+fn run_ffmpeg(
+    args: &[String],
+    options: FfmpegRunOptions,
+    captured: Option<&mut String>,
+) -> Result<()> {
+    let mut child = Command::new("ffmpeg")
+        .args(args)
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| "Failed to spawn ffmpeg")?;
 
-        let stderr = child.stderr.take().expect("stderr was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
 
-        let pb = if let Some(duration) = options.total_duration {
-            let pb = ProgressBar::new((duration * 1000.0) as u64);
-            pb.set_style(
+    let pb = if let Some(duration) = options.total_duration {
+        let pb = ProgressBar::new((duration * 1000.0) as u64);
+        pb.set_style(
                 ProgressStyle::default_bar()
                     .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos:>8}/{len:8} ({eta}) {msg}")
                     .unwrap()
                     .progress_chars("█▉▊▋▌▍▎▏ "),
             );
-            pb.enable_steady_tick(std::time::Duration::from_millis(100));
-            pb.set_message("rendering".to_string());
-            Some(pb)
+        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        pb.set_message("rendering".to_string());
+        Some(pb)
+    } else {
+        None
+    };
+
+    let mut last_line = String::new();
+    let mut error_lines: Vec<String> = Vec::new();
+    let result = read_ffmpeg_stderr(
+        stderr,
+        options.verbose,
+        &pb,
+        &mut last_line,
+        &mut error_lines,
+        captured,
+    );
+
+    let status = child.wait().context("Failed to wait for ffmpeg")?;
+    result?;
+
+    if let Some(pb) = pb {
+        pb.finish_with_message("done");
+    }
+
+    if !status.success() {
+        let error_msg = if !error_lines.is_empty() {
+            error_lines.join("\n")
         } else {
-            None
+            last_line
         };
-
-        let mut last_line = String::new();
-        let mut error_lines: Vec<String> = Vec::new();
-        let result = read_ffmpeg_stderr(
-            stderr,
-            options.verbose,
-            &pb,
-            &mut last_line,
-            &mut error_lines,
+        bail!(
+            "ffmpeg exited with status {:?}: {}",
+            status.code(),
+            error_msg.trim()
         );
-
-        let status = child.wait().context("Failed to wait for ffmpeg")?;
-        result?;
-
-        if let Some(pb) = pb {
-            pb.finish_with_message("done");
-        }
-
-        if !status.success() {
-            let error_msg = if !error_lines.is_empty() {
-                error_lines.join("\n")
-            } else {
-                last_line
-            };
-            bail!(
-                "ffmpeg exited with status {:?}: {}",
-                status.code(),
-                error_msg.trim()
-            );
-        }
-
-        Ok(())
     }
+
+    Ok(())
 }
 
 fn read_ffmpeg_stderr<R: Read>(
@@ -99,6 +118,7 @@ fn read_ffmpeg_stderr<R: Read>(
     pb: &Option<ProgressBar>,
     last_line: &mut String,
     error_lines: &mut Vec<String>,
+    mut captured: Option<&mut String>,
 ) -> Result<()> {
     let mut buffer = [0u8; 4096];
     let mut accumulated = String::new();
@@ -124,6 +144,11 @@ fn read_ffmpeg_stderr<R: Read>(
 
             *last_line = line.clone();
 
+            if let Some(captured) = captured.as_deref_mut() {
+                captured.push_str(&line);
+                captured.push('\n');
+            }
+
             if verbose {
                 eprintln!("{}", line);
             }
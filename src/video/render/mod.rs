@@ -1,4 +1,8 @@
+mod cache;
+mod chunks;
+mod clips;
 mod document;
+mod export;
 mod ffmpeg;
 mod logging;
 mod mode;
@@ -6,11 +10,13 @@ mod output;
 pub mod paths;
 mod pipeline;
 mod plan;
+mod publish;
 mod sources;
 mod subtitles;
 pub mod timeline;
 mod timeline_builder;
 mod transcripts;
+mod watch;
 
 use std::path::{Path, PathBuf};
 
@@ -18,26 +24,35 @@ use anyhow::{Result, bail};
 
 use crate::ui::prelude::Level;
 
+use self::cache::ChunkCache;
+pub use self::cache::handle_cache;
+use self::clips::{plan_document_clips, render_clips};
 pub(crate) use self::document::load_video_document;
+pub use self::export::{ExportFormat, handle_export};
 use self::ffmpeg::compiler::{RenderConfig, VideoDimensions};
 use self::ffmpeg::services::{FfmpegRunner, SystemFfmpegRunner};
 use self::logging::log_event;
 pub use self::mode::RenderMode;
 use self::output::prepare_output_destination;
 use self::pipeline::RenderPipeline;
+use self::publish::{PublishArtifacts, PublishOptions, write_publish_files};
 
 struct RenderJob<'a> {
     timeline: timeline::Timeline,
-    cues: &'a [crate::video::support::transcript::TranscriptCue],
     output_path: PathBuf,
     render_mode: RenderMode,
     target_dims: VideoDimensions,
     video_config: VideoConfig,
     audio_source: PathBuf,
-    burn_subtitles: bool,
+    /// ASS file to burn in, shared by every preset rendered in the same mode
+    subtitle_path: Option<PathBuf>,
+    preset: OutputPreset,
+    publish: PublishArtifacts,
     dry_run: bool,
     verbose: bool,
     runner: &'a dyn FfmpegRunner,
+    /// Chunk cache and worker count for incremental renders; `None` renders in one pass
+    chunks: Option<(&'a ChunkCache, usize)>,
 }
 pub(crate) use self::plan::build_timeline_plan;
 pub(crate) use self::sources::resolve_video_sources;
@@ -45,11 +60,13 @@ use self::sources::{find_default_source, validate_timeline_sources};
 use self::subtitles::generate_subtitle_file;
 use self::timeline_builder::{SlideProvider, TimelineStats, build_nle_timeline};
 pub(crate) use self::transcripts::load_transcript_cues;
+use self::watch::{WatchSettings, watch_preview};
 use super::cli::{PreviewArgs, RenderArgs};
-use super::config::VideoConfig;
+use super::config::{DEFAULT_PRESET, OutputPreset, VideoConfig};
+use super::document::clips::ClipSpec;
 use super::support::ffmpeg::probe_video_dimensions;
 
-use super::slides::SlideGenerator;
+use super::slides::{SlideGenerator, SlideTheme};
 use super::support::utils::canonicalize_existing;
 
 impl SlideProvider for SlideGenerator {
@@ -61,6 +78,11 @@ impl SlideProvider for SlideGenerator {
         let asset = self.markdown_slide(markdown)?;
         self.ensure_video_for_duration(&asset, duration)
     }
+
+    fn build_step_video(&self, markdown: &str, step: usize, duration: f64) -> Result<PathBuf> {
+        let asset = self.build_slide_step(markdown, step)?;
+        self.ensure_video_for_duration(&asset, duration)
+    }
 }
 
 pub async fn handle_render(args: RenderArgs) -> Result<Option<PathBuf>> {
@@ -80,16 +102,19 @@ struct RenderProject {
     default_source: crate::video::document::VideoSource,
     video_config: VideoConfig,
     project_dir: PathBuf,
+    slide_theme: Option<SlideTheme>,
+    /// Plans of the clips tagged in the document, when rendering with `--clips`
+    clips: Vec<(ClipSpec, crate::video::planning::TimelinePlan)>,
 }
 
-async fn load_render_project(args: &RenderArgs) -> Result<RenderProject> {
+async fn load_render_project(markdown: &Path, with_clips: bool) -> Result<RenderProject> {
     log_event(
         Level::Info,
         "video.render.start",
         "Preparing render (reading markdown, transcript, and assets)",
     );
 
-    let markdown_path = canonicalize_existing(&args.markdown)?;
+    let markdown_path = canonicalize_existing(markdown)?;
     let project_dir = markdown_path
         .parent()
         .unwrap_or_else(|| Path::new("."))
@@ -105,6 +130,11 @@ async fn load_render_project(args: &RenderArgs) -> Result<RenderProject> {
     validate_timeline_sources(&document, &sources, &cues)?;
     let plan = build_timeline_plan(&document, &cues, &markdown_path)?;
     let default_source = find_default_source(&document.metadata, &sources)?.clone();
+    let clips = if with_clips {
+        plan_document_clips(&document, &cues, &markdown_path)?
+    } else {
+        Vec::new()
+    };
 
     Ok(RenderProject {
         sources,
@@ -113,11 +143,14 @@ async fn load_render_project(args: &RenderArgs) -> Result<RenderProject> {
         default_source,
         video_config,
         project_dir,
+        slide_theme: document.metadata.slide_theme.clone(),
+        clips,
     })
 }
 
 fn build_render_timeline(
     project: &RenderProject,
+    plan: &crate::video::planning::TimelinePlan,
     render_mode: RenderMode,
 ) -> Result<(timeline::Timeline, VideoDimensions)> {
     log_event(
@@ -129,7 +162,8 @@ fn build_render_timeline(
     let (target_width, target_height) = render_mode.target_dimensions(video_width, video_height);
     let target_dims = VideoDimensions::new(target_width, target_height);
 
-    let generator = SlideGenerator::new(target_dims.width, target_dims.height)?;
+    let generator = SlideGenerator::new(target_dims.width, target_dims.height)?
+        .with_theme(project.slide_theme.as_ref(), &project.project_dir)?;
 
     log_event(
         Level::Info,
@@ -137,7 +171,7 @@ fn build_render_timeline(
         "Building render timeline (may generate slides)",
     );
     let (nle_timeline, stats) = build_nle_timeline(
-        project.plan.clone(),
+        plan.clone(),
         &generator,
         &project.sources,
         &project.project_dir,
@@ -148,25 +182,29 @@ fn build_render_timeline(
     Ok((nle_timeline, target_dims))
 }
 
+fn burn_in_subtitles(
+    timeline: &timeline::Timeline,
+    cues: &[crate::video::support::transcript::TranscriptCue],
+    output_path: &Path,
+    target_dims: VideoDimensions,
+    render_mode: RenderMode,
+) -> Result<PathBuf> {
+    log_event(
+        Level::Info,
+        "video.render.subtitles",
+        format!("Generating ASS subtitles for {:?} mode", render_mode),
+    );
+    generate_subtitle_file(timeline, cues, output_path, target_dims, render_mode)
+}
+
 fn execute_render(job: RenderJob<'_>) -> Result<Option<PathBuf>> {
-    let subtitle_path = if job.burn_subtitles {
-        log_event(
-            Level::Info,
-            "video.render.subtitles",
-            format!("Generating ASS subtitles for {:?} mode", job.render_mode),
+    let loudness_target = job.preset.loudness_lufs;
+    let render_config = RenderConfig::new(job.render_mode, job.video_config, job.subtitle_path)
+        .with_preset(job.preset)
+        .with_mux_inputs(
+            job.publish.mux_chapters.clone(),
+            job.publish.mux_subtitles.clone(),
         );
-        Some(generate_subtitle_file(
-            &job.timeline,
-            job.cues,
-            &job.output_path,
-            job.target_dims,
-            job.render_mode,
-        )?)
-    } else {
-        None
-    };
-
-    let render_config = RenderConfig::new(job.render_mode, job.video_config, subtitle_path);
 
     let pipeline = RenderPipeline {
         output: job.output_path.clone(),
@@ -185,7 +223,10 @@ fn execute_render(job: RenderJob<'_>) -> Result<Option<PathBuf>> {
     );
 
     if job.dry_run {
-        pipeline.print_command()?;
+        match job.chunks {
+            Some((cache, _)) => pipeline.print_chunked_command(cache)?,
+            None => pipeline.print_command()?,
+        }
         log_event(
             Level::Info,
             "video.render.dry_run",
@@ -199,7 +240,25 @@ fn execute_render(job: RenderJob<'_>) -> Result<Option<PathBuf>> {
         "video.render.execute",
         "Starting ffmpeg render",
     );
-    pipeline.execute()?;
+    let result = match job.chunks {
+        Some((cache, jobs)) => pipeline.execute_chunked(cache, jobs),
+        None => pipeline.execute(),
+    };
+    for path in &job.publish.temporary {
+        let _ = std::fs::remove_file(path);
+    }
+    if let Some(loudness) = result?
+        && let Some(target) = loudness_target
+    {
+        log_event(
+            Level::Info,
+            "video.render.loudness",
+            format!(
+                "Measured {:.1} LUFS integrated, {:.1} dBTP true peak, {:.1} LU range; normalised to {target} LUFS",
+                loudness.integrated, loudness.true_peak, loudness.range
+            ),
+        );
+    }
 
     log_event(
         Level::Success,
@@ -214,57 +273,185 @@ async fn handle_render_with_services(
     args: RenderArgs,
     runner: &dyn FfmpegRunner,
 ) -> Result<Option<PathBuf>> {
-    let project = load_render_project(&args).await?;
+    let project = load_render_project(&args.markdown, args.clips).await?;
+    if args.clips {
+        return render_clips(&args, &project, runner);
+    }
 
-    let render_mode = if args.common.reels {
+    let fallback_mode = if args.common.reels {
         RenderMode::Reels
     } else {
         RenderMode::Standard
     };
+    let presets = resolve_render_presets(&args, &project.video_config)?;
+    let publish_options = PublishOptions::from_args(&args);
+    let publish_title = args
+        .markdown
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // Presets sharing a mode share the timeline, slides and subtitles
+    let mut modes: Vec<RenderMode> = Vec::new();
+    for preset in &presets {
+        let mode = preset.mode.unwrap_or(fallback_mode);
+        if !modes.contains(&mode) {
+            modes.push(mode);
+        }
+    }
+
+    let mut outputs = Vec::new();
+    if !args.common.precache_slides {
+        for preset in &presets {
+            let render_mode = preset.mode.unwrap_or(fallback_mode);
+            let output_path = preset_output_path(&args, &project, render_mode, preset)?;
+            if outputs.iter().any(|(path, _)| path == &output_path) {
+                bail!(
+                    "Presets render to the same file {}; use distinct preset names",
+                    output_path.display()
+                );
+            }
+            prepare_output_destination(&output_path, &args, &project.default_source.source)?;
+            outputs.push((output_path, preset.clone()));
+        }
+    }
 
-    let output_path = if args.common.precache_slides {
+    let cache = if args.no_cache || args.common.precache_slides {
         None
     } else {
-        Some(paths::resolve_output_path(
-            args.out_file.as_ref(),
-            &project.default_source.source,
-            &project.project_dir,
-            render_mode,
-        )?)
+        Some(ChunkCache::open()?)
     };
+    let jobs = args.jobs.unwrap_or_else(default_render_jobs).max(1);
+
+    let mut rendered = None;
+    for render_mode in modes {
+        let (nle_timeline, target_dims) =
+            build_render_timeline(&project, &project.plan, render_mode)?;
+        if args.common.precache_slides {
+            continue;
+        }
 
-    if let Some(output_path) = &output_path {
-        prepare_output_destination(output_path, &args, &project.default_source.source)?;
+        let mut subtitle_path = None;
+        for (output_path, preset) in outputs
+            .iter()
+            .filter(|(_, preset)| preset.mode.unwrap_or(fallback_mode) == render_mode)
+        {
+            if args.common.subtitles && subtitle_path.is_none() {
+                subtitle_path = Some(burn_in_subtitles(
+                    &nle_timeline,
+                    &project.cues,
+                    output_path,
+                    target_dims,
+                    render_mode,
+                )?);
+            }
+
+            if presets.len() > 1 {
+                log_event(
+                    Level::Info,
+                    "video.render.preset",
+                    format!(
+                        "Rendering preset `{}` to {}",
+                        preset.name,
+                        output_path.display()
+                    ),
+                );
+            }
+
+            let publish = if publish_options.any() && !args.dry_run {
+                write_publish_files(
+                    &nle_timeline,
+                    &project.cues,
+                    output_path,
+                    &publish_title,
+                    publish_options,
+                )?
+            } else {
+                PublishArtifacts::default()
+            };
+
+            rendered = execute_render(RenderJob {
+                timeline: nle_timeline.clone(),
+                output_path: output_path.clone(),
+                render_mode,
+                target_dims,
+                video_config: project.video_config.clone(),
+                audio_source: project.default_source.source.clone(),
+                subtitle_path: subtitle_path.clone(),
+                preset: preset.clone(),
+                publish,
+                dry_run: args.dry_run,
+                verbose: args.common.verbose,
+                runner,
+                chunks: cache.as_ref().map(|cache| (cache, jobs)),
+            })?
+            .or(rendered);
+        }
     }
 
-    let (nle_timeline, target_dims) = build_render_timeline(&project, render_mode)?;
-
     if args.common.precache_slides {
         log_event(
             Level::Success,
             "video.render.precache_only",
             "Prepared slides in cache; skipping final render",
         );
-        return Ok(None);
     }
 
-    let Some(output_path) = output_path else {
-        bail!("Output path is required when not pre-caching");
-    };
+    Ok(rendered)
+}
 
-    execute_render(RenderJob {
-        timeline: nle_timeline,
-        cues: &project.cues,
-        output_path,
+/// Each chunk's ffmpeg already uses several threads, so leave it half the cores
+fn default_render_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|cores| cores.get() / 2)
+        .unwrap_or(1)
+}
+
+/// Presets requested with `--preset`, or the default preset
+fn resolve_render_presets(args: &RenderArgs, config: &VideoConfig) -> Result<Vec<OutputPreset>> {
+    if args.presets.is_empty() {
+        return Ok(vec![config.output_preset(DEFAULT_PRESET)?]);
+    }
+
+    let mut presets: Vec<OutputPreset> = Vec::new();
+    for name in &args.presets {
+        if presets.iter().any(|preset| &preset.name == name) {
+            continue;
+        }
+        presets.push(config.output_preset(name)?);
+    }
+    Ok(presets)
+}
+
+/// Output path for a preset
+///
+/// Without `--preset` the historical `<video><suffix>.mp4` name is kept. Named presets add
+/// `_<preset>` and the container extension unless a single preset renders to `--out-file`.
+fn preset_output_path(
+    args: &RenderArgs,
+    project: &RenderProject,
+    render_mode: RenderMode,
+    preset: &OutputPreset,
+) -> Result<PathBuf> {
+    let base = paths::resolve_output_path(
+        args.out_file.as_ref(),
+        &project.default_source.source,
+        &project.project_dir,
         render_mode,
-        target_dims,
-        video_config: project.video_config,
-        audio_source: project.default_source.source.clone(),
-        burn_subtitles: args.common.subtitles,
-        dry_run: args.dry_run,
-        verbose: args.common.verbose,
-        runner,
-    })
+    )?;
+    if args.presets.is_empty() || (args.presets.len() == 1 && args.out_file.is_some()) {
+        return Ok(base);
+    }
+
+    let stem = base
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(base.with_file_name(format!(
+        "{stem}_{}.{}",
+        preset.name,
+        preset.container.extension()
+    )))
 }
 
 async fn handle_preview_with_services(
@@ -279,7 +466,18 @@ async fn handle_preview_with_services(
         RenderMode::Standard
     };
 
-    let (nle_timeline, target_dims) = build_render_timeline(&project, render_mode)?;
+    if args.watch && !args.common.precache_slides {
+        let markdown_path = canonicalize_existing(&args.markdown)?;
+        let settings = WatchSettings {
+            render_mode,
+            subtitles: args.common.subtitles,
+            seek: args.seek.unwrap_or(0.0),
+        };
+        watch_preview(&markdown_path, project, settings)?;
+        return Ok(None);
+    }
+
+    let (nle_timeline, target_dims) = build_render_timeline(&project, &project.plan, render_mode)?;
 
     // Truncate timeline at seek point so ffmpeg only processes
     // segments from that point forward (much faster startup)
@@ -302,19 +500,32 @@ async fn handle_preview_with_services(
 
     // Use temporary output for preview (will be piped to mpv)
     let output_path = project.project_dir.join("preview_temp.mkv");
+    let subtitle_path = if args.common.subtitles {
+        Some(burn_in_subtitles(
+            &nle_timeline,
+            &project.cues,
+            &output_path,
+            target_dims,
+            render_mode,
+        )?)
+    } else {
+        None
+    };
 
     execute_preview_render(RenderJob {
         timeline: nle_timeline,
-        cues: &project.cues,
         output_path,
         render_mode,
         target_dims,
         video_config: project.video_config,
         audio_source,
-        burn_subtitles: args.common.subtitles,
+        subtitle_path,
+        preset: OutputPreset::default(),
+        publish: PublishArtifacts::default(),
         dry_run: false, // Always execute for preview
         verbose: args.common.verbose,
         runner,
+        chunks: None,
     })?;
 
     Ok(None)
@@ -322,21 +533,7 @@ async fn handle_preview_with_services(
 
 /// Execute a preview render job with real-time mpv playback
 fn execute_preview_render(job: RenderJob<'_>) -> Result<Option<PathBuf>> {
-    // Generate subtitles if needed (same as render)
-    let subtitle_path = if job.burn_subtitles {
-        let temp_output = job.output_path.with_extension("ass");
-        Some(generate_subtitle_file(
-            &job.timeline,
-            job.cues,
-            &temp_output,
-            job.target_dims,
-            job.render_mode,
-        )?)
-    } else {
-        None
-    };
-
-    let render_config = RenderConfig::new(job.render_mode, job.video_config, subtitle_path);
+    let render_config = RenderConfig::new(job.render_mode, job.video_config, job.subtitle_path);
 
     let pipeline = RenderPipeline {
         output: job.output_path.clone(),
@@ -398,6 +595,8 @@ async fn load_preview_project(args: &PreviewArgs) -> Result<RenderProject> {
         default_source,
         video_config,
         project_dir,
+        slide_theme: document.metadata.slide_theme.clone(),
+        clips: Vec::new(),
     })
 }
 
@@ -472,10 +671,14 @@ mod tests {
                     overlay: None,
                     broll: None,
                     source_id: "a".to_string(),
+                    angle: None,
+                    lower_third: None,
+                    still: None,
                 }),
                 TimelinePlanItem::Standalone(StandalonePlan {
                     markdown: "# title card".to_string(),
                     duration_seconds: 2.0,
+                    build_steps: 0,
                 }),
                 TimelinePlanItem::Clip(ClipPlan {
                     time_window: TimeWindow::new(12.0, 20.0),
@@ -484,6 +687,9 @@ mod tests {
                     overlay: None,
                     broll: None,
                     source_id: "a".to_string(),
+                    angle: None,
+                    lower_third: None,
+                    still: None,
                 }),
             ],
             standalone_count: 1,
@@ -491,6 +697,7 @@ mod tests {
             ignored_count: 0,
             heading_count: 1,
             segment_count: 2,
+            chapters: Vec::new(),
         };
 
         let sources = vec![VideoSource {
@@ -500,6 +707,7 @@ mod tests {
             transcript: PathBuf::from("source.json"),
             audio: PathBuf::from("source_audio.wav"),
             hash: None,
+            sync: None,
         }];
 
         let (timeline, _stats) =
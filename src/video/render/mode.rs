@@ -1,11 +1,18 @@
+use serde::{Deserialize, Serialize};
+
 /// Rendering mode for the output video
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RenderMode {
     /// Standard rendering (same dimensions as source)
     #[default]
     Standard,
     /// Instagram Reels/TikTok (9:16 vertical, 1080x1920)
     Reels,
+    /// Square feed posts (1:1, 1080x1080)
+    Square,
+    /// Portrait feed posts (4:5, 1080x1350)
+    Portrait,
 }
 
 impl RenderMode {
@@ -14,6 +21,8 @@ impl RenderMode {
         match self {
             RenderMode::Standard => (source_width, source_height),
             RenderMode::Reels => (1080, 1920),
+            RenderMode::Square => (1080, 1080),
+            RenderMode::Portrait => (1080, 1350),
         }
     }
 
@@ -22,19 +31,22 @@ impl RenderMode {
         match self {
             RenderMode::Standard => "_edit",
             RenderMode::Reels => "_reels",
+            RenderMode::Square => "_square",
+            RenderMode::Portrait => "_portrait",
         }
     }
 
     /// Whether this mode requires letterboxing/pillboxing
     pub fn requires_padding(&self) -> bool {
-        matches!(self, RenderMode::Reels)
+        !matches!(self, RenderMode::Standard)
     }
 
     /// Get vertical position offset as percentage (0.0 = top, 0.5 = center)
     pub fn vertical_offset_pct(&self) -> f64 {
         match self {
-            RenderMode::Standard => 0.5,
+            RenderMode::Standard | RenderMode::Square => 0.5,
             RenderMode::Reels => 0.1, // 10% from top
+            RenderMode::Portrait => 0.3,
         }
     }
 }
@@ -1,8 +1,18 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::video::render::ffmpeg::compiler::{FfmpegCompiler, RenderConfig, VideoDimensions};
+use super::cache::ChunkCache;
+use super::chunks::{ChunkContext, TimelineChunk, split_timeline};
+use super::logging::log_event;
+use crate::ui::prelude::Level;
+use crate::video::render::ffmpeg::compiler::{
+    FfmpegCompileOutput, FfmpegCompiler, RenderConfig, VideoDimensions,
+};
+use crate::video::render::ffmpeg::loudness::{LoudnessMeasurement, parse_loudnorm_output};
 use crate::video::render::ffmpeg::services::{FfmpegRunOptions, FfmpegRunner, PreviewPlayer};
 use crate::video::render::timeline::Timeline;
 
@@ -18,16 +28,270 @@ pub(super) struct RenderPipeline<'a> {
 
 impl<'a> RenderPipeline<'a> {
     pub(super) fn print_command(&self) -> Result<()> {
-        let args = self.build_args()?;
+        if let Some(analysis) = self.loudness_analysis_args()? {
+            println!("ffmpeg loudness analysis that would be executed first:");
+            println!("ffmpeg {}", analysis.join(" "));
+        }
+        let compiled = self.compile()?;
+        if let Some(first_pass) = &compiled.first_pass {
+            println!("ffmpeg analysis pass that would be executed first:");
+            println!("ffmpeg {}", first_pass.join(" "));
+        }
         println!("ffmpeg command that would be executed:");
-        println!("ffmpeg {}", args.join(" "));
+        println!("ffmpeg {}", compiled.args.join(" "));
         Ok(())
     }
 
-    pub(super) fn execute(&self) -> Result<()> {
-        let args = self.build_args()?;
+    /// Render the output, returning the loudness measured before normalisation
+    pub(super) fn execute(&self) -> Result<Option<LoudnessMeasurement>> {
         let options = FfmpegRunOptions::new(Some(self.timeline.total_duration()), self.verbose);
-        self.runner.run(&args, options)
+        let loudness = match self.loudness_analysis_args()? {
+            Some(analysis) => {
+                let stderr = self.runner.run_capture(&analysis, options.clone())?;
+                Some(parse_loudnorm_output(&stderr)?)
+            }
+            None => None,
+        };
+
+        let compiled = self.compile_with(loudness)?;
+        if let Some(first_pass) = &compiled.first_pass {
+            self.runner.run(first_pass, options.clone())?;
+        }
+        let result = self.runner.run(&compiled.args, options);
+        if let Some(passlog) = &compiled.passlog {
+            remove_passlog_files(passlog);
+        }
+        result.map(|()| loudness)
+    }
+
+    /// Render through the chunk cache, re-encoding only chunks whose content changed
+    pub(super) fn execute_chunked(
+        &self,
+        cache: &ChunkCache,
+        jobs: usize,
+    ) -> Result<Option<LoudnessMeasurement>> {
+        let Some(chunks) = self.prepare_chunks()? else {
+            return self.execute();
+        };
+
+        let pending: Vec<&TimelineChunk> = chunks
+            .iter()
+            .filter(|chunk| !cache.chunk_path(&chunk.key).exists())
+            .collect();
+        let jobs = jobs.clamp(1, pending.len().max(1));
+        log_event(
+            Level::Info,
+            "video.render.chunks",
+            format!(
+                "Reusing {} of {} chunk(s); rendering {} on {} worker(s)",
+                chunks.len() - pending.len(),
+                chunks.len(),
+                pending.len(),
+                jobs
+            ),
+        );
+        self.render_chunks(cache, &pending, jobs)?;
+        for chunk in &chunks {
+            cache.touch(&chunk.key);
+        }
+
+        let concat_list = self.write_concat_list(cache, &chunks)?;
+        let result = self.assemble(&concat_list);
+        let _ = fs::remove_file(&concat_list);
+        result
+    }
+
+    pub(super) fn print_chunked_command(&self, cache: &ChunkCache) -> Result<()> {
+        let Some(chunks) = self.prepare_chunks()? else {
+            return self.print_command();
+        };
+
+        let compiler = self.compiler(None);
+        let total_duration = self.timeline.total_duration();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let path = cache.chunk_path(&chunk.key);
+            if path.exists() {
+                println!(
+                    "chunk {index} ({:.1}s-{:.1}s) is cached as {}",
+                    chunk.window.start,
+                    chunk.window.end,
+                    path.display()
+                );
+                continue;
+            }
+            let args = compiler.compile_chunk(
+                &path,
+                &chunk.timeline,
+                chunk.window.start,
+                total_duration,
+                self.audio_source.clone(),
+            )?;
+            println!(
+                "chunk {index} ({:.1}s-{:.1}s) would be rendered with:",
+                chunk.window.start, chunk.window.end
+            );
+            println!("ffmpeg {}", args.join(" "));
+        }
+
+        let concat_list = self.concat_list_path();
+        if let Some(analysis) =
+            compiler.compile_assembly_loudness_analysis(&concat_list, &self.timeline)?
+        {
+            println!("ffmpeg loudness analysis that would be executed before joining:");
+            println!("ffmpeg {}", analysis.join(" "));
+        }
+        let assembly =
+            compiler.compile_assembly(self.output.clone(), &concat_list, &self.timeline)?;
+        println!("ffmpeg command that would join the chunks:");
+        println!("ffmpeg {}", assembly.args.join(" "));
+        Ok(())
+    }
+
+    /// Chunks of the timeline, or `None` when it has to be rendered in one piece
+    fn prepare_chunks(&self) -> Result<Option<Vec<TimelineChunk>>> {
+        if self.timeline.video_segments().is_empty() {
+            return Ok(None);
+        }
+        if !self
+            .compiler(None)
+            .supports_chunks(self.timeline.total_duration())?
+        {
+            log_event(
+                Level::Info,
+                "video.render.chunks.skip",
+                format!(
+                    "Preset `{}` encodes in two passes; rendering without the chunk cache",
+                    self.render_config.preset.name
+                ),
+            );
+            return Ok(None);
+        }
+
+        let subtitles = match &self.render_config.subtitle_path {
+            Some(path) => Some(
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read subtitles from {}", path.display()))?,
+            ),
+            None => None,
+        };
+        let context = ChunkContext {
+            dimensions: self.dimensions,
+            render_mode: self.render_config.render_mode,
+            preset: &self.render_config.preset,
+            subtitles: subtitles.as_deref(),
+        };
+        Ok(Some(split_timeline(&self.timeline, &context)))
+    }
+
+    fn render_chunks(
+        &self,
+        cache: &ChunkCache,
+        pending: &[&TimelineChunk],
+        jobs: usize,
+    ) -> Result<()> {
+        let compiler = self.compiler(None);
+        let total_duration = self.timeline.total_duration();
+        let next = AtomicUsize::new(0);
+        let finished = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+
+        let render_next = || -> Result<()> {
+            while !failed.load(Ordering::SeqCst) {
+                let Some(chunk) = pending.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                    break;
+                };
+                let partial = cache.partial_path(&chunk.key);
+                let result = compiler
+                    .compile_chunk(
+                        &partial,
+                        &chunk.timeline,
+                        chunk.window.start,
+                        total_duration,
+                        self.audio_source.clone(),
+                    )
+                    .and_then(|args| {
+                        self.runner
+                            .run(&args, FfmpegRunOptions::new(None, self.verbose))
+                    })
+                    .and_then(|()| {
+                        fs::rename(&partial, cache.chunk_path(&chunk.key))
+                            .context("Failed to store rendered chunk")
+                    })
+                    .with_context(|| {
+                        format!(
+                            "Failed to render chunk {:.1}s-{:.1}s",
+                            chunk.window.start, chunk.window.end
+                        )
+                    });
+                if let Err(err) = result {
+                    failed.store(true, Ordering::SeqCst);
+                    let _ = fs::remove_file(&partial);
+                    return Err(err);
+                }
+
+                log_event(
+                    Level::Info,
+                    "video.render.chunk",
+                    format!(
+                        "Rendered chunk {}/{} ({:.1}s-{:.1}s)",
+                        finished.fetch_add(1, Ordering::SeqCst) + 1,
+                        pending.len(),
+                        chunk.window.start,
+                        chunk.window.end
+                    ),
+                );
+            }
+            Ok(())
+        };
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..jobs).map(|_| scope.spawn(render_next)).collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("chunk worker panicked"))
+                .collect::<Result<Vec<()>>>()
+        })?;
+        Ok(())
+    }
+
+    fn concat_list_path(&self) -> PathBuf {
+        self.output.with_extension("chunks.txt")
+    }
+
+    /// Concat demuxer list naming the cached chunks in timeline order
+    fn write_concat_list(&self, cache: &ChunkCache, chunks: &[TimelineChunk]) -> Result<PathBuf> {
+        let mut list = String::from("ffconcat version 1.0\n");
+        for chunk in chunks {
+            let path = cache.chunk_path(&chunk.key);
+            let escaped = path.to_string_lossy().replace('\'', "'\\''");
+            list.push_str(&format!("file '{escaped}'\n"));
+        }
+        let path = self.concat_list_path();
+        fs::write(&path, list)
+            .with_context(|| format!("Failed to write chunk list {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Join the cached chunks, mixing music and normalising loudness on the way
+    fn assemble(&self, concat_list: &Path) -> Result<Option<LoudnessMeasurement>> {
+        let options = FfmpegRunOptions::new(Some(self.timeline.total_duration()), self.verbose);
+        let compiler = self.compiler(None);
+        let loudness =
+            match compiler.compile_assembly_loudness_analysis(concat_list, &self.timeline)? {
+                Some(analysis) => {
+                    let stderr = self.runner.run_capture(&analysis, options.clone())?;
+                    Some(parse_loudnorm_output(&stderr)?)
+                }
+                None => None,
+            };
+
+        let assembly = self.compiler(loudness).compile_assembly(
+            self.output.clone(),
+            concat_list,
+            &self.timeline,
+        )?;
+        self.runner.run(&assembly.args, options)?;
+        Ok(loudness)
     }
 
     pub(super) fn execute_preview(&self) -> Result<()> {
@@ -41,15 +305,29 @@ impl<'a> RenderPipeline<'a> {
         player.play(&args)
     }
 
-    fn build_args(&self) -> Result<Vec<String>> {
+    fn compile(&self) -> Result<FfmpegCompileOutput> {
+        self.compile_with(None)
+    }
+
+    fn compiler(&self, loudness: Option<LoudnessMeasurement>) -> FfmpegCompiler {
+        let mut render_config = self.render_config.clone();
+        if let Some(loudness) = loudness {
+            render_config = render_config.with_loudness(loudness);
+        }
+        FfmpegCompiler::new(self.dimensions, render_config)
+    }
+
+    fn compile_with(&self, loudness: Option<LoudnessMeasurement>) -> Result<FfmpegCompileOutput> {
+        self.compiler(loudness).compile(
+            self.output.clone(),
+            &self.timeline,
+            self.audio_source.clone(),
+        )
+    }
+
+    fn loudness_analysis_args(&self) -> Result<Option<Vec<String>>> {
         let compiler = FfmpegCompiler::new(self.dimensions, self.render_config.clone());
-        Ok(compiler
-            .compile(
-                self.output.clone(),
-                &self.timeline,
-                self.audio_source.clone(),
-            )?
-            .args)
+        compiler.compile_loudness_analysis(&self.timeline, self.audio_source.clone())
     }
 
     fn build_preview_args(&self) -> Result<Vec<String>> {
@@ -59,3 +337,23 @@ impl<'a> RenderPipeline<'a> {
             .map(|o| o.args)
     }
 }
+
+/// Remove the statistics files ffmpeg and x265 leave next to a two-pass output
+fn remove_passlog_files(passlog: &Path) {
+    let (Some(dir), Some(prefix)) = (passlog.parent(), passlog.file_name()) else {
+        return;
+    };
+    let prefix = prefix.to_string_lossy();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(prefix.as_ref())
+        {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Publishing extras for rendered videos: chapter markers, soft subtitles and a
+//! ready-to-paste upload description.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::ui::prelude::Level;
+use crate::video::cli::RenderArgs;
+use crate::video::render::logging::log_event;
+use crate::video::render::timeline::{Chapter, Timeline};
+use crate::video::subtitles::{generate_srt, generate_webvtt, remap_subtitles_to_timeline};
+use crate::video::support::transcript::TranscriptCue;
+
+/// YouTube ignores chapter lists with fewer entries
+const YOUTUBE_MIN_CHAPTERS: usize = 3;
+/// YouTube ignores chapter lists containing shorter chapters
+const YOUTUBE_MIN_CHAPTER_SECONDS: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct PublishOptions {
+    pub chapters: bool,
+    pub sidecar_subtitles: bool,
+    pub soft_subtitles: bool,
+    pub description: bool,
+}
+
+impl PublishOptions {
+    pub fn any(&self) -> bool {
+        self.chapters || self.sidecar_subtitles || self.soft_subtitles || self.description
+    }
+
+    pub fn from_args(args: &RenderArgs) -> Self {
+        Self {
+            chapters: args.chapters || args.publish,
+            sidecar_subtitles: args.sidecar_subtitles || args.publish,
+            soft_subtitles: args.soft_subtitles || args.publish,
+            description: args.description || args.publish,
+        }
+    }
+}
+
+/// Files produced next to an output; `mux_*` are extra ffmpeg inputs
+#[derive(Debug, Default)]
+pub(super) struct PublishArtifacts {
+    pub mux_chapters: Option<PathBuf>,
+    pub mux_subtitles: Option<PathBuf>,
+    /// Intermediate files to delete once ffmpeg has finished
+    pub temporary: Vec<PathBuf>,
+}
+
+pub(super) fn write_publish_files(
+    timeline: &Timeline,
+    cues: &[TranscriptCue],
+    output_path: &Path,
+    title: &str,
+    options: PublishOptions,
+) -> Result<PublishArtifacts> {
+    let mut artifacts = PublishArtifacts::default();
+    // The first top-level heading titles the video, falling back to the markdown name
+    let title = timeline
+        .chapters
+        .iter()
+        .find(|chapter| chapter.level == 1)
+        .map_or(title, |chapter| chapter.title.as_str());
+    let total_duration = timeline.total_duration();
+    let chapters = publish_chapters(timeline);
+
+    if options.chapters && !chapters.is_empty() {
+        let path = output_path.with_extension("ffmeta");
+        write_file(&path, &ffmetadata(title, &chapters, total_duration))?;
+        artifacts.mux_chapters = Some(path.clone());
+        artifacts.temporary.push(path);
+    }
+
+    if options.sidecar_subtitles || options.soft_subtitles {
+        let subtitles = remap_subtitles_to_timeline(timeline, cues);
+        let srt_path = output_path.with_extension("srt");
+        write_file(&srt_path, &generate_srt(&subtitles))?;
+        if options.sidecar_subtitles {
+            write_file(
+                &output_path.with_extension("vtt"),
+                &generate_webvtt(&subtitles),
+            )?;
+            log_event(
+                Level::Info,
+                "video.render.subtitles.sidecar",
+                format!(
+                    "Wrote SRT and WebVTT subtitles next to {}",
+                    output_path.display()
+                ),
+            );
+        } else {
+            artifacts.temporary.push(srt_path.clone());
+        }
+        if options.soft_subtitles {
+            artifacts.mux_subtitles = Some(srt_path);
+        }
+    }
+
+    if options.description {
+        let path = description_path(output_path);
+        write_file(&path, &upload_description(title, &chapters, total_duration))?;
+        log_event(
+            Level::Info,
+            "video.render.description",
+            format!("Wrote upload description to {}", path.display()),
+        );
+        warn_unusable_chapters(&chapters, total_duration);
+    }
+
+    Ok(artifacts)
+}
+
+/// Chapters from the two outermost heading levels, one per start time
+pub(super) fn publish_chapters(timeline: &Timeline) -> Vec<Chapter> {
+    let total_duration = timeline.total_duration();
+    let Some(top_level) = timeline.chapters.iter().map(|c| c.level).min() else {
+        return Vec::new();
+    };
+
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for chapter in &timeline.chapters {
+        if chapter.level > top_level + 1 || chapter.start_time >= total_duration {
+            continue;
+        }
+        // Consecutive headings share a start; the outer one names the chapter
+        if chapters
+            .last()
+            .is_some_and(|last| (last.start_time - chapter.start_time).abs() < 0.001)
+        {
+            continue;
+        }
+        chapters.push(chapter.clone());
+    }
+    chapters
+}
+
+/// FFmpeg metadata file with one `[CHAPTER]` per chapter, ending at the next chapter
+pub(super) fn ffmetadata(title: &str, chapters: &[Chapter], total_duration: f64) -> String {
+    let mut out = format!(";FFMETADATA1\ntitle={}\n", escape_ffmetadata(title));
+    for (idx, chapter) in chapters.iter().enumerate() {
+        let end = chapters
+            .get(idx + 1)
+            .map_or(total_duration, |next| next.start_time);
+        out.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.start_time * 1000.0).round() as u64,
+            (end * 1000.0).round() as u64,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    out
+}
+
+/// Title plus a YouTube/PeerTube style chapter list starting at 0:00
+pub(super) fn upload_description(title: &str, chapters: &[Chapter], total_duration: f64) -> String {
+    let mut out = format!("{title}\n");
+    if chapters.is_empty() {
+        return out;
+    }
+
+    out.push_str("\nChapters:\n");
+    if chapters[0].start_time > 0.0 {
+        out.push_str(&format!("{} Intro\n", timestamp(0.0, total_duration)));
+    }
+    for chapter in chapters {
+        out.push_str(&format!(
+            "{} {}\n",
+            timestamp(chapter.start_time, total_duration),
+            chapter.title
+        ));
+    }
+    out
+}
+
+fn warn_unusable_chapters(chapters: &[Chapter], total_duration: f64) {
+    let mut starts: Vec<f64> = chapters.iter().map(|c| c.start_time).collect();
+    if starts.first().is_some_and(|&start| start > 0.0) {
+        starts.insert(0, 0.0);
+    }
+    if starts.is_empty() {
+        return;
+    }
+    starts.push(total_duration);
+    let shortest = starts
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .fold(f64::INFINITY, f64::min);
+
+    if starts.len() - 1 < YOUTUBE_MIN_CHAPTERS || shortest < YOUTUBE_MIN_CHAPTER_SECONDS {
+        log_event(
+            Level::Warn,
+            "video.render.chapters.youtube",
+            format!(
+                "YouTube only shows chapters with at least {YOUTUBE_MIN_CHAPTERS} entries of {YOUTUBE_MIN_CHAPTER_SECONDS:.0}s or more"
+            ),
+        );
+    }
+}
+
+/// `M:SS`, or `H:MM:SS` for videos of an hour or longer
+fn timestamp(seconds: f64, total_duration: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if total_duration >= 3600.0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{}:{secs:02}", hours * 60 + minutes)
+    }
+}
+
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+pub(super) fn description_path(output_path: &Path) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    output_path.with_file_name(format!("{stem}.description.txt"))
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(title: &str, level: u32, start_time: f64) -> Chapter {
+        Chapter {
+            title: title.to_string(),
+            level,
+            start_time,
+        }
+    }
+
+    fn timeline_with(chapters: Vec<Chapter>, duration: f64) -> Timeline {
+        let mut timeline = Timeline::new();
+        timeline.add_segment(crate::video::render::timeline::Segment::new_music(
+            0.0,
+            duration,
+            PathBuf::from("music.mp3"),
+        ));
+        timeline.chapters = chapters;
+        timeline
+    }
+
+    #[test]
+    fn publish_chapters_keep_outer_levels_and_dedupe_starts() {
+        let timeline = timeline_with(
+            vec![
+                chapter("Talk", 1, 0.0),
+                chapter("Intro", 2, 0.0),
+                chapter("Setup", 2, 30.0),
+                chapter("Detail", 3, 45.0),
+                chapter("Past end", 1, 120.0),
+            ],
+            90.0,
+        );
+        let titles: Vec<_> = publish_chapters(&timeline)
+            .into_iter()
+            .map(|c| c.title)
+            .collect();
+        assert_eq!(titles, ["Talk", "Setup"]);
+    }
+
+    #[test]
+    fn ffmetadata_chapters_end_at_next_chapter() {
+        let chapters = vec![chapter("A = 1", 1, 0.0), chapter("B", 1, 12.5)];
+        assert_eq!(
+            ffmetadata("Demo", &chapters, 20.0),
+            ";FFMETADATA1\ntitle=Demo\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=12500\ntitle=A \\= 1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=12500\nEND=20000\ntitle=B\n"
+        );
+    }
+
+    #[test]
+    fn description_starts_chapters_at_zero() {
+        let chapters = vec![chapter("Setup", 1, 75.0), chapter("Wrap up", 1, 3_700.0)];
+        assert_eq!(
+            upload_description("Demo", &chapters, 3_800.0),
+            "Demo\n\nChapters:\n0:00:00 Intro\n0:01:15 Setup\n1:01:40 Wrap up\n"
+        );
+        assert_eq!(
+            upload_description("Demo", &chapters[..1], 600.0),
+            "Demo\n\nChapters:\n0:00 Intro\n1:15 Setup\n"
+        );
+    }
+
+    #[test]
+    fn description_path_sits_next_to_output() {
+        assert_eq!(
+            description_path(Path::new("/videos/talk_edit.mp4")),
+            PathBuf::from("/videos/talk_edit.description.txt")
+        );
+    }
+}
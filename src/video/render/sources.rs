@@ -43,6 +43,11 @@ pub(super) fn validate_timeline_sources(
     for block in &document.blocks {
         if let crate::video::document::DocumentBlock::Segment(segment) = block {
             referenced_sources.insert(segment.source_id.clone());
+            if let Some(angle) = &segment.angle
+                && !sources.iter().any(|source| &source.id == angle)
+            {
+                bail!("Segment angle references unknown source `{}`", angle);
+            }
         }
     }
 
@@ -82,7 +87,11 @@ pub(crate) async fn resolve_video_sources(
     let mut resolved = Vec::new();
     for source in &metadata.sources {
         let resolved_source = resolve_source_path(&source.source, project_dir)?;
-        let resolved_transcript = resolve_source_path(&source.transcript, project_dir)?;
+        let resolved_transcript = if source.transcript.as_os_str().is_empty() {
+            PathBuf::new()
+        } else {
+            resolve_source_path(&source.transcript, project_dir)?
+        };
         let resolved_audio = resolve_audio_path(&resolved_source, config).await?;
         let canonical = canonicalize_existing(&resolved_source)?;
         log_event(
@@ -97,6 +106,7 @@ pub(crate) async fn resolve_video_sources(
             transcript: resolved_transcript,
             audio: resolved_audio,
             hash: source.hash.clone(),
+            sync: source.sync.clone(),
         });
     }
 
@@ -16,6 +16,8 @@
 
 use std::path::PathBuf;
 
+use crate::video::document::directives::{OverlayPosition, PanDirection};
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct TimeWindow {
     pub start: f64,
@@ -61,6 +63,18 @@ pub struct Timeline {
     pub segments: Vec<Segment>,
     /// Track if timeline contains overlay segments
     pub has_overlays: bool,
+    /// Chapter markers derived from markdown headings, in timeline order
+    pub chapters: Vec<Chapter>,
+}
+
+/// A named position in the final rendered timeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    /// Heading level the chapter came from (1 = `#`)
+    pub level: u32,
+    /// Start time in the final rendered timeline (in seconds)
+    pub start_time: f64,
 }
 
 /// Groups the video file, audio file, and source identifier that always travel together.
@@ -108,6 +122,13 @@ pub enum SegmentData {
         /// Path to the audio source file
         audio_source: PathBuf,
     },
+    /// Generated name caption drawn over the video
+    LowerThird {
+        title: String,
+        subtitle: Option<String>,
+        /// Placement and fades for the caption box
+        transform: Option<Transform>,
+    },
     /// B-roll video overlay (muted video that plays on top of the main video)
     Broll {
         /// Start time in the source video (in seconds)
@@ -130,6 +151,20 @@ pub struct Transform {
     pub rotate: Option<f32>,
     /// Translation (x, y) in pixels
     pub translate: Option<(f32, f32)>,
+    /// Anchor in the frame instead of the centered default
+    pub position: Option<OverlayPosition>,
+    /// Fade in and out duration in seconds
+    pub fade: Option<f32>,
+    /// Pan/zoom animation filling the frame (Ken Burns)
+    pub motion: Option<Motion>,
+}
+
+/// Ken Burns style pan and zoom across a still image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    pub zoom_from: f32,
+    pub zoom_to: f32,
+    pub pan: PanDirection,
 }
 
 impl Timeline {
@@ -138,6 +173,7 @@ impl Timeline {
         Timeline {
             segments: Vec::new(),
             has_overlays: false,
+            chapters: Vec::new(),
         }
     }
 
@@ -186,6 +222,13 @@ impl Timeline {
             .collect()
     }
 
+    pub fn lower_third_segments(&self) -> Vec<&Segment> {
+        self.segments
+            .iter()
+            .filter(|s| matches!(s.data, SegmentData::LowerThird { .. }))
+            .collect()
+    }
+
     pub fn broll_segments(&self) -> Vec<&Segment> {
         self.segments
             .iter()
@@ -238,11 +281,38 @@ impl Timeline {
             }
         }
 
+        let chapters = self
+            .chapters
+            .iter()
+            .filter(|chapter| chapter.start_time >= seek_time)
+            .map(|chapter| Chapter {
+                start_time: chapter.start_time - seek_time,
+                ..chapter.clone()
+            })
+            .collect();
+
         Timeline {
             segments,
             has_overlays: self.has_overlays,
+            chapters,
         }
     }
+
+    /// Create a new timeline covering only `window`, shifted to begin at 0.
+    ///
+    /// Segments crossing either edge are trimmed like in [`Timeline::truncate_before`].
+    pub fn slice(&self, window: TimeWindow) -> Timeline {
+        let length = window.duration();
+        let mut sliced = self.truncate_before(window.start);
+        sliced.segments.retain(|seg| seg.start_time < length);
+        for seg in &mut sliced.segments {
+            seg.duration = seg.duration.min(length - seg.start_time);
+        }
+        sliced
+            .chapters
+            .retain(|chapter| chapter.start_time < length);
+        sliced
+    }
 }
 
 impl Segment {
@@ -293,6 +363,25 @@ impl Segment {
         }
     }
 
+    /// Create a new lower third caption segment
+    pub fn new_lower_third(
+        start_time: f64,
+        duration: f64,
+        title: String,
+        subtitle: Option<String>,
+        transform: Option<Transform>,
+    ) -> Self {
+        Segment {
+            start_time,
+            duration,
+            data: SegmentData::LowerThird {
+                title,
+                subtitle,
+                transform,
+            },
+        }
+    }
+
     /// Create a new B-roll segment (muted video overlay)
     pub fn new_broll(
         start_time: f64,
@@ -327,6 +416,9 @@ impl Transform {
             scale: None,
             rotate: None,
             translate: None,
+            position: None,
+            fade: None,
+            motion: None,
         }
     }
 
@@ -334,32 +426,34 @@ impl Transform {
     pub fn with_scale(scale: f32) -> Self {
         Transform {
             scale: Some(scale),
-            rotate: None,
-            translate: None,
+            ..Self::new()
         }
     }
 
     /// Create a transform with only rotation
     pub fn with_rotation(degrees: f32) -> Self {
         Transform {
-            scale: None,
             rotate: Some(degrees),
-            translate: None,
+            ..Self::new()
         }
     }
 
     /// Create a transform with only translation
     pub fn with_translation(x: f32, y: f32) -> Self {
         Transform {
-            scale: None,
-            rotate: None,
             translate: Some((x, y)),
+            ..Self::new()
         }
     }
 
     /// Check if this transform has any operations
     pub fn is_identity(&self) -> bool {
-        self.scale.is_none() && self.rotate.is_none() && self.translate.is_none()
+        self.scale.is_none()
+            && self.rotate.is_none()
+            && self.translate.is_none()
+            && self.position.is_none()
+            && self.fade.is_none()
+            && self.motion.is_none()
     }
 }
 
@@ -376,6 +470,7 @@ impl SegmentData {
             SegmentData::VideoSubset { source, .. } => Some(&source.video),
             SegmentData::Image { source_image, .. } => Some(source_image),
             SegmentData::Music { audio_source } => Some(audio_source),
+            SegmentData::LowerThird { .. } => None,
             SegmentData::Broll { source_video, .. } => Some(source_video),
         }
     }
@@ -393,6 +488,7 @@ impl SegmentData {
             SegmentData::VideoSubset { transform, .. } => transform.as_ref(),
             SegmentData::Image { transform, .. } => transform.as_ref(),
             SegmentData::Music { .. } => None,
+            SegmentData::LowerThird { transform, .. } => transform.as_ref(),
             SegmentData::Broll { transform, .. } => transform.as_ref(),
         }
     }
@@ -1,15 +1,28 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 
-use crate::video::document::VideoSource;
-use crate::video::planning::{BrollPlan, StandalonePlan, TimelinePlan, TimelinePlanItem};
+use crate::video::document::directives::{KenBurns, LowerThird, PipLayout};
+use crate::video::document::{VideoSource, map_source_time};
+use crate::video::planning::{
+    BrollPlan, ChapterPlan, StandalonePlan, TimelinePlan, TimelinePlanItem,
+};
 use crate::video::render::ffmpeg::services::{DefaultMusicSourceResolver, MusicSourceResolver};
-use crate::video::render::timeline::{AvSourceRef, Segment, Timeline};
+use crate::video::render::timeline::{AvSourceRef, Chapter, Motion, Segment, Timeline, Transform};
 
 pub(super) trait SlideProvider {
     fn overlay_slide_image(&self, markdown: &str) -> Result<std::path::PathBuf>;
     fn standalone_slide_video(&self, markdown: &str, duration: f64) -> Result<std::path::PathBuf>;
+
+    /// Step `step` (1-based) of a build slide; providers without builds show the whole slide
+    fn build_step_video(
+        &self,
+        markdown: &str,
+        _step: usize,
+        duration: f64,
+    ) -> Result<std::path::PathBuf> {
+        self.standalone_slide_video(markdown, duration)
+    }
 }
 
 pub(super) struct TimelineStats {
@@ -32,10 +45,17 @@ pub(super) fn build_nle_timeline(
     };
 
     let mut state = TimelineBuildState::new(project_dir);
+    let mut chapters = plan.chapters.into_iter().peekable();
 
-    for item in plan.items {
+    for (idx, item) in plan.items.into_iter().enumerate() {
+        while let Some(chapter) = chapters.next_if(|chapter| chapter.item_index <= idx) {
+            state.add_chapter(chapter);
+        }
         state.apply_plan_item(item, generator, sources)?;
     }
+    for chapter in chapters {
+        state.add_chapter(chapter);
+    }
 
     state.finalize();
 
@@ -49,6 +69,7 @@ pub(super) fn build_nle_timeline(
 
 struct TimelineBuildState {
     timeline: Timeline,
+    project_dir: PathBuf,
     current_time: f64,
     music_resolver: Box<dyn MusicSourceResolver>,
     active_music: Option<ActiveMusic>,
@@ -58,6 +79,7 @@ impl TimelineBuildState {
     fn new(project_dir: &Path) -> Self {
         Self {
             timeline: Timeline::new(),
+            project_dir: project_dir.to_path_buf(),
             current_time: 0.0,
             music_resolver: Box::new(DefaultMusicSourceResolver::new(project_dir)),
             active_music: None,
@@ -110,6 +132,14 @@ impl TimelineBuildState {
         );
         self.timeline.add_segment(segment);
 
+        if let Some(angle_id) = &clip_plan.angle {
+            self.add_angle(&clip_plan, angle_id, duration, sources)?;
+        }
+
+        if let Some(still) = &clip_plan.still {
+            self.add_still(still, duration);
+        }
+
         if let Some(overlay_plan) = clip_plan.overlay {
             self.add_overlay(&overlay_plan.markdown, duration, generator)?;
         }
@@ -118,10 +148,92 @@ impl TimelineBuildState {
             self.add_broll(&broll_plan, duration, sources)?;
         }
 
+        if let Some(lower_third) = &clip_plan.lower_third {
+            self.add_lower_third(lower_third);
+        }
+
         self.current_time += duration;
         Ok(())
     }
 
+    /// Cover the clip with the same moment from a synced camera angle
+    fn add_angle(
+        &mut self,
+        clip_plan: &crate::video::planning::ClipPlan,
+        angle_id: &str,
+        duration: f64,
+        sources: &[VideoSource],
+    ) -> Result<()> {
+        let angle = sources
+            .iter()
+            .find(|source| source.id == angle_id)
+            .ok_or_else(|| anyhow!("No source configured for camera angle `{}`", angle_id))?;
+        let angle_start = map_source_time(
+            sources,
+            &clip_plan.source_id,
+            angle_id,
+            clip_plan.time_window.start,
+        )?;
+        if angle_start < 0.0 {
+            bail!(
+                "Camera angle `{}` has no footage at {:.2}s of source `{}`",
+                angle_id,
+                clip_plan.time_window.start,
+                clip_plan.source_id
+            );
+        }
+
+        let segment = Segment::new_broll(
+            self.current_time,
+            duration,
+            angle_start,
+            angle.source.clone(),
+            angle_id.to_string(),
+            Some(Transform::with_scale(1.0)),
+        );
+        self.timeline.add_segment(segment);
+        Ok(())
+    }
+
+    fn add_lower_third(&mut self, lower_third: &LowerThird) {
+        let transform = Transform {
+            position: Some(lower_third.position),
+            fade: fade_seconds(lower_third.fade),
+            ..Transform::new()
+        };
+        self.timeline.add_segment(Segment::new_lower_third(
+            self.current_time,
+            lower_third.duration,
+            lower_third.title.trim().to_string(),
+            lower_third.subtitle.clone(),
+            Some(transform),
+        ));
+    }
+
+    fn add_still(&mut self, still: &KenBurns, clip_duration: f64) {
+        let image = Path::new(&still.image);
+        let image = if image.is_absolute() {
+            image.to_path_buf()
+        } else {
+            self.project_dir.join(image)
+        };
+        let transform = Transform {
+            fade: fade_seconds(still.fade),
+            motion: Some(Motion {
+                zoom_from: still.zoom[0] as f32,
+                zoom_to: still.zoom[1] as f32,
+                pan: still.pan,
+            }),
+            ..Transform::new()
+        };
+        self.timeline.add_segment(Segment::new_image(
+            self.current_time,
+            still.duration.unwrap_or(clip_duration),
+            image,
+            Some(transform),
+        ));
+    }
+
     fn add_broll(
         &mut self,
         broll_plan: &BrollPlan,
@@ -140,6 +252,7 @@ impl TimelineBuildState {
 
         let broll_start = self.current_time;
         let mut elapsed = 0.0;
+        let transform = broll_plan.layout.as_ref().map(pip_transform);
 
         for (i, clip) in broll_plan.clips.iter().enumerate() {
             let source = sources
@@ -177,7 +290,7 @@ impl TimelineBuildState {
                 clip.time_window.start,
                 source.source.clone(),
                 clip.source_id.clone(),
-                None,
+                transform.clone(),
             );
             self.timeline.add_segment(segment);
             elapsed += clip_duration;
@@ -207,21 +320,30 @@ impl TimelineBuildState {
         standalone_plan: StandalonePlan,
         generator: &dyn SlideProvider,
     ) -> Result<()> {
-        self.add_standalone_slide(
-            &standalone_plan.markdown,
-            standalone_plan.duration_seconds,
-            generator,
-        )
-    }
+        let markdown = &standalone_plan.markdown;
+        let duration = standalone_plan.duration_seconds;
+        if standalone_plan.build_steps == 0 {
+            let video_path = generator.standalone_slide_video(markdown, duration)?;
+            self.add_slide_video(video_path, duration);
+            return Ok(());
+        }
 
-    fn add_standalone_slide(
-        &mut self,
-        markdown: &str,
-        duration: f64,
-        generator: &dyn SlideProvider,
-    ) -> Result<()> {
-        let video_path = generator.standalone_slide_video(markdown, duration)?;
+        // Each step gets an equal share; the last one keeps the rounding remainder
+        let steps = standalone_plan.build_steps;
+        let step_duration = duration / steps as f64;
+        for step in 1..=steps {
+            let duration = if step == steps {
+                duration - step_duration * (steps - 1) as f64
+            } else {
+                step_duration
+            };
+            let video_path = generator.build_step_video(markdown, step, duration)?;
+            self.add_slide_video(video_path, duration);
+        }
+        Ok(())
+    }
 
+    fn add_slide_video(&mut self, video_path: std::path::PathBuf, duration: f64) {
         let segment = Segment::new_video_subset(
             self.current_time,
             duration,
@@ -236,7 +358,14 @@ impl TimelineBuildState {
         );
         self.timeline.add_segment(segment);
         self.current_time += duration;
-        Ok(())
+    }
+
+    fn add_chapter(&mut self, chapter: ChapterPlan) {
+        self.timeline.chapters.push(Chapter {
+            title: chapter.title,
+            level: chapter.level,
+            start_time: self.current_time,
+        });
     }
 
     fn add_music_directive(&mut self, music_plan: crate::video::planning::MusicPlan) -> Result<()> {
@@ -279,3 +408,16 @@ fn finalize_music_segment(
         timeline.add_segment(Segment::new_music(state.start_time, duration, state.path));
     }
 }
+
+fn pip_transform(layout: &PipLayout) -> Transform {
+    Transform {
+        scale: Some(layout.scale as f32),
+        position: Some(layout.position),
+        fade: fade_seconds(layout.fade),
+        ..Transform::new()
+    }
+}
+
+fn fade_seconds(fade: f64) -> Option<f32> {
+    (fade > 0.0).then_some(fade as f32)
+}
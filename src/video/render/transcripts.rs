@@ -7,7 +7,7 @@ use crate::ui::prelude::Level;
 use crate::video::document::VideoSource;
 use crate::video::render::logging::log_event;
 use crate::video::render::sources::resolve_source_path;
-use crate::video::support::transcript::{TranscriptCue, parse_whisper_json};
+use crate::video::support::transcript::{TranscriptCue, TranscriptFormat, parse_transcript};
 use crate::video::support::utils::canonicalize_existing;
 
 pub(crate) fn load_transcript_cues(
@@ -17,6 +17,10 @@ pub(crate) fn load_transcript_cues(
     let mut cues = Vec::new();
 
     for source in sources {
+        // Angle-only sources follow their sync reference's transcript
+        if source.transcript.as_os_str().is_empty() {
+            continue;
+        }
         let transcript_path = resolve_source_path(&source.transcript, project_dir)?;
         let transcript_path = canonicalize_existing(&transcript_path)?;
 
@@ -42,7 +46,8 @@ pub(crate) fn load_transcript_cues(
             "video.render.transcript.parse",
             format!("Parsing transcript cues for {}", source.id),
         );
-        let mut parsed = parse_whisper_json(&transcript_contents)?;
+        let format = TranscriptFormat::detect(&transcript_path, &transcript_contents);
+        let mut parsed = parse_transcript(&transcript_contents, format)?;
         for cue in &mut parsed {
             cue.source_id = source.id.clone();
         }
@@ -0,0 +1,206 @@
+//! Live-reloading preview (`ins video preview --watch`).
+//!
+//! The markdown is polled for changes. On save it is parsed, checked and planned again
+//! against the transcripts loaded at startup; the running mpv is then switched to a stream
+//! starting at the first plan item that changed, so only the edited region is re-rendered.
+//! Errors are printed and the previous stream keeps playing until the document is fixed.
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+
+use super::ffmpeg::compiler::{FfmpegCompiler, RenderConfig};
+use super::ffmpeg::mpv::MpvSession;
+use super::{
+    RenderMode, RenderProject, build_render_timeline, build_timeline_plan, burn_in_subtitles,
+    load_video_document, log_event, validate_timeline_sources,
+};
+use crate::ui::prelude::Level;
+use crate::video::pipeline::check::emit_directive_status;
+use crate::video::pipeline::report::emit_report;
+use crate::video::planning::TimelinePlan;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+pub(super) struct WatchSettings {
+    pub render_mode: RenderMode,
+    pub subtitles: bool,
+    pub seek: f64,
+}
+
+pub(super) fn watch_preview(
+    markdown_path: &Path,
+    project: RenderProject,
+    settings: WatchSettings,
+) -> Result<()> {
+    let mut session = MpvSession::start()?;
+    let mut plan = project.plan.clone();
+    play_from(&mut session, &project, &plan, &settings, settings.seek)?;
+    log_event(
+        Level::Info,
+        "video.preview.watch",
+        format!(
+            "Watching {} for changes (close mpv to stop)",
+            markdown_path.display()
+        ),
+    );
+
+    let mut modified = modified_time(markdown_path);
+    while session.is_running() {
+        thread::sleep(POLL_INTERVAL);
+        let current = modified_time(markdown_path);
+        if current == modified {
+            continue;
+        }
+        modified = current;
+
+        let new_plan = match replan(markdown_path, &project) {
+            Ok(new_plan) => new_plan,
+            Err(err) => {
+                log_event(
+                    Level::Error,
+                    "video.preview.watch.error",
+                    format!("{err:#}"),
+                );
+                let _ = session.show_text("Markdown has errors; see terminal");
+                continue;
+            }
+        };
+        let Some(index) = first_changed_item(&plan, &new_plan) else {
+            log_event(
+                Level::Info,
+                "video.preview.watch.unchanged",
+                "Saved without timeline changes",
+            );
+            continue;
+        };
+
+        let offset = plan_offset(&new_plan, index);
+        plan = new_plan;
+        log_event(
+            Level::Info,
+            "video.preview.watch.reload",
+            format!("Timeline changed at {offset:.1}s; reloading preview"),
+        );
+        if let Err(err) = play_from(&mut session, &project, &plan, &settings, offset) {
+            log_event(
+                Level::Error,
+                "video.preview.watch.error",
+                format!("{err:#}"),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Parse, check and plan the document again with the sources loaded at startup
+fn replan(markdown_path: &Path, project: &RenderProject) -> Result<TimelinePlan> {
+    let document = load_video_document(markdown_path)?;
+    validate_timeline_sources(&document, &project.sources, &project.cues)?;
+    let plan = build_timeline_plan(&document, &project.cues, markdown_path)?;
+
+    let mut report = Vec::new();
+    emit_directive_status(&document, &project.project_dir, &mut report);
+    report.retain(|line| !matches!(line.level, Level::Info));
+    emit_report(&report);
+    Ok(plan)
+}
+
+fn play_from(
+    session: &mut MpvSession,
+    project: &RenderProject,
+    plan: &TimelinePlan,
+    settings: &WatchSettings,
+    offset: f64,
+) -> Result<()> {
+    let (timeline, target_dims) = build_render_timeline(project, plan, settings.render_mode)?;
+    let timeline = timeline.truncate_before(offset);
+
+    let subtitle_path = if settings.subtitles {
+        Some(burn_in_subtitles(
+            &timeline,
+            &project.cues,
+            &project.project_dir.join("preview_temp.mkv"),
+            target_dims,
+            settings.render_mode,
+        )?)
+    } else {
+        None
+    };
+    let render_config = RenderConfig::new(
+        settings.render_mode,
+        project.video_config.clone(),
+        subtitle_path,
+    );
+    let compiled = FfmpegCompiler::new(target_dims, render_config)
+        .compile_preview(&timeline, project.default_source.source.clone())?;
+    session.play(&compiled.args, offset)?;
+    let _ = session.show_text(&format!("Reloaded at {offset:.1}s"));
+    Ok(())
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Index of the first plan item that differs, or `None` when the plans match
+fn first_changed_item(old: &TimelinePlan, new: &TimelinePlan) -> Option<usize> {
+    let changed = old
+        .items
+        .iter()
+        .zip(&new.items)
+        .position(|(old, new)| format!("{old:?}") != format!("{new:?}"));
+    match changed {
+        Some(index) => Some(index),
+        None if old.items.len() != new.items.len() => Some(old.items.len().min(new.items.len())),
+        None => None,
+    }
+}
+
+/// Output time at which plan item `index` starts
+fn plan_offset(plan: &TimelinePlan, index: usize) -> f64 {
+    plan.items[..index.min(plan.items.len())]
+        .iter()
+        .map(|item| item.duration_seconds())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::document::parse_video_document;
+
+    fn plan(body: &str) -> TimelinePlan {
+        let markdown = format!(
+            "---\nsources:\n- id: a\n  source: video_a.mp4\n  transcript: a.json\n---\n{body}"
+        );
+        parse_video_document(&markdown, Path::new("test.md"))
+            .unwrap()
+            .plan_timeline()
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_first_edited_item_and_its_output_time() {
+        let old = plan(
+            "`a@00:00.0-00:02.0` one\n\n`a@00:05.0-00:08.0` two\n\n`a@00:10.0-00:11.0` three\n",
+        );
+        let edited = plan(
+            "`a@00:00.0-00:02.0` one\n\n`a@00:05.0-00:08.0` two\n\n`a@00:10.0-00:12.0` three\n",
+        );
+        let index = first_changed_item(&old, &edited).unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(plan_offset(&edited, index), 5.0);
+
+        assert_eq!(first_changed_item(&old, &old), None);
+
+        let appended = plan(
+            "`a@00:00.0-00:02.0` one\n\n`a@00:05.0-00:08.0` two\n\n`a@00:10.0-00:11.0` three\n\n`a@00:12.0-00:13.0` four\n",
+        );
+        assert_eq!(first_changed_item(&old, &appended), Some(3));
+        assert_eq!(plan_offset(&appended, 3), 6.0);
+    }
+}
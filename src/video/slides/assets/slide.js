@@ -241,3 +241,15 @@ window.addEventListener("load", () => {
         container.insertBefore(header, pre);
     });
 });
+
+// Build slides reveal one list item per step; hidden items keep their space so the
+// layout and font scale stay identical across steps.
+window.addEventListener("load", () => {
+    const step = window.slideBuildStep;
+    if (step === undefined) return;
+    document.querySelectorAll(".content li").forEach((item, index) => {
+        if (index >= step) {
+            item.style.visibility = "hidden";
+        }
+    });
+});
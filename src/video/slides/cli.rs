@@ -1,11 +1,37 @@
 use std::fs;
+use std::path::Path;
 
 use anyhow::{Context, Result};
+use serde::Deserialize;
 
-use super::SlideGenerator;
-use crate::video::cli::SlideArgs;
+use super::{SlideGenerator, SlideTheme, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH};
+use crate::video::cli::{SlideArgs, ThumbnailArgs};
 use crate::video::document::frontmatter::split_frontmatter;
 
+/// The part of the front matter that affects slides; other keys are ignored
+#[derive(Debug, Default, Deserialize)]
+struct SlideFrontMatter {
+    #[serde(default)]
+    slide_theme: Option<SlideTheme>,
+}
+
+fn read_slide_theme(frontmatter: Option<&str>, markdown_path: &Path) -> Result<Option<SlideTheme>> {
+    let Some(frontmatter) = frontmatter else {
+        return Ok(None);
+    };
+    let parsed: SlideFrontMatter = serde_yaml::from_str(frontmatter).with_context(|| {
+        format!(
+            "Failed to parse front matter of {}",
+            markdown_path.display()
+        )
+    })?;
+    Ok(parsed.slide_theme)
+}
+
+fn theme_base_dir(markdown_path: &Path) -> &Path {
+    markdown_path.parent().unwrap_or_else(|| Path::new("."))
+}
+
 pub fn handle_slide(args: SlideArgs) -> Result<()> {
     let markdown_path = args.markdown.canonicalize().with_context(|| {
         format!(
@@ -18,7 +44,8 @@ pub fn handle_slide(args: SlideArgs) -> Result<()> {
         .with_context(|| format!("Failed to read markdown file {}", markdown_path.display()))?;
 
     // Strip YAML frontmatter if present
-    let (_, content, _) = split_frontmatter(&markdown_contents)?;
+    let (frontmatter, content, _) = split_frontmatter(&markdown_contents)?;
+    let theme = read_slide_theme(frontmatter, &markdown_path)?;
 
     // Determine output path
     let output_path = if let Some(out) = args.out_file {
@@ -37,7 +64,8 @@ pub fn handle_slide(args: SlideArgs) -> Result<()> {
         (1920, 1080)
     };
 
-    let generator = SlideGenerator::new(width, height)?;
+    let generator = SlideGenerator::new(width, height)?
+        .with_theme(theme.as_ref(), theme_base_dir(&markdown_path))?;
 
     let asset = generator.markdown_slide(content)?;
 
@@ -54,3 +82,80 @@ pub fn handle_slide(args: SlideArgs) -> Result<()> {
 
     Ok(())
 }
+
+pub fn handle_thumbnail(args: ThumbnailArgs) -> Result<()> {
+    let markdown_path = args.markdown.canonicalize().with_context(|| {
+        format!(
+            "Failed to resolve markdown path {}",
+            args.markdown.display()
+        )
+    })?;
+
+    let markdown_contents = fs::read_to_string(&markdown_path)
+        .with_context(|| format!("Failed to read markdown file {}", markdown_path.display()))?;
+    let (frontmatter, content, _) = split_frontmatter(&markdown_contents)?;
+    let theme = read_slide_theme(frontmatter, &markdown_path)?;
+
+    let title = args
+        .title
+        .or_else(|| first_heading(content))
+        .unwrap_or_else(|| {
+            markdown_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+
+    let output_path = args.out_file.unwrap_or_else(|| {
+        let mut path = markdown_path.clone();
+        path.set_extension("thumbnail.jpg");
+        path
+    });
+
+    let generator = SlideGenerator::new(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)?
+        .with_theme(theme.as_ref(), theme_base_dir(&markdown_path))?;
+    let thumbnail = generator.thumbnail(&format!("# {}", title.trim()))?;
+
+    fs::copy(&thumbnail, &output_path)
+        .with_context(|| format!("Failed to copy thumbnail to {}", output_path.display()))?;
+
+    println!("Thumbnail saved to: {}", output_path.display());
+
+    Ok(())
+}
+
+/// Text of the first ATX heading outside fenced code blocks
+fn first_heading(content: &str) -> Option<String> {
+    let mut in_fence = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let text = trimmed.trim_start_matches('#');
+        if text.len() < trimmed.len() && (text.is_empty() || text.starts_with(' ')) {
+            let text = text.trim().trim_end_matches('#').trim();
+            if !text.is_empty() {
+                return Some(text.to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnail_title_is_first_heading_outside_code() {
+        let content =
+            "```bash\n# not a heading\n```\n\n#hashtag\n\n## Intro to Rust ##\n\n# Later\n";
+        assert_eq!(first_heading(content).as_deref(), Some("Intro to Rust"));
+        assert_eq!(first_heading("no headings here\n"), None);
+    }
+}
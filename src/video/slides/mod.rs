@@ -10,9 +10,19 @@ use sha2::{Digest, Sha256};
 use crate::video::support::ffmpeg::{PROFILE_SLIDE_VIDEO, run_ffmpeg_output};
 
 pub mod cli;
+pub mod theme;
+
+pub use self::theme::SlideTheme;
+use self::theme::{LoadedTheme, file_url};
 
 const DEFAULT_CSS: &str = include_str!("assets/slide.css");
 const DEFAULT_JS: &str = include_str!("assets/slide.js");
+/// Token colours, left out when the theme picks a Pandoc highlight style
+const CODE_CSS: &str = include_str!("assets/code.css");
+const DEFAULT_HIGHLIGHT_STYLE: &str = "pygments";
+/// YouTube thumbnail size
+pub const THUMBNAIL_WIDTH: u32 = 1280;
+pub const THUMBNAIL_HEIGHT: u32 = 720;
 
 // Workaround for Chromium "new" headless mode viewport bug (grey bar artifacts).
 // Set to 0 to disable the workaround (oversize rendering + cropping) once Chromium fixes the issue.
@@ -22,6 +32,7 @@ pub struct SlideGenerator {
     cache_dir: PathBuf,
     width: u32,
     height: u32,
+    theme: Option<LoadedTheme>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,11 +61,53 @@ impl SlideGenerator {
             cache_dir: cache_root,
             width,
             height,
+            theme: None,
         })
     }
 
+    /// Style slides with a project theme; paths in it are relative to `base_dir`
+    pub fn with_theme(mut self, theme: Option<&SlideTheme>, base_dir: &Path) -> Result<Self> {
+        self.theme = theme.map(|theme| theme.load(base_dir)).transpose()?;
+        Ok(self)
+    }
+
     pub fn markdown_slide(&self, markdown_content: &str) -> Result<SlideAsset> {
-        let cache_key = self.build_markdown_cache_key(markdown_content);
+        self.render_slide(markdown_content, None)
+    }
+
+    /// Step `step` (1-based) of a build slide: the first `step` list items are visible
+    pub fn build_slide_step(&self, markdown_content: &str, step: usize) -> Result<SlideAsset> {
+        self.render_slide(markdown_content, Some(step))
+    }
+
+    /// Render a title slide and convert it to a JPEG thumbnail, cached next to the slide
+    pub fn thumbnail(&self, markdown_content: &str) -> Result<PathBuf> {
+        let asset = self.markdown_slide(markdown_content)?;
+        let thumbnail = asset.slide_dir.join("thumbnail.jpg");
+        if !thumbnail.exists() {
+            run_ffmpeg_output(
+                &[
+                    "-y",
+                    "-v",
+                    "error",
+                    "-i",
+                    &asset.image_path.to_string_lossy(),
+                    "-q:v",
+                    "2",
+                    &thumbnail.to_string_lossy(),
+                ],
+                "for thumbnail conversion",
+            )?;
+        }
+        Ok(thumbnail)
+    }
+
+    fn render_slide(
+        &self,
+        markdown_content: &str,
+        build_step: Option<usize>,
+    ) -> Result<SlideAsset> {
+        let cache_key = self.build_markdown_cache_key(markdown_content, build_step);
         let slide_dir = self.cache_dir.join(&cache_key);
         let markdown_path = slide_dir.join("input.md");
         let css_path = slide_dir.join("slide.css");
@@ -72,7 +125,7 @@ impl SlideGenerator {
             })?;
             self.write_css(&css_path)?;
             self.run_pandoc(&markdown_path, &html_path, &css_path)?;
-            self.post_process_html(&html_path)?;
+            self.post_process_html(&html_path, build_step)?;
             self.capture_screenshot(&html_path, &image_path)?;
         }
 
@@ -103,23 +156,48 @@ impl SlideGenerator {
         })
     }
 
-    /// Build cache key from dimensions, CSS/JS content, and markdown.
-    /// Automatically invalidates when CSS or JS files change.
-    fn build_markdown_cache_key(&self, markdown_content: &str) -> String {
+    /// Build cache key from dimensions, CSS/JS content, theme, build step and markdown.
+    /// Automatically invalidates when CSS, JS or theme files change.
+    fn build_markdown_cache_key(
+        &self,
+        markdown_content: &str,
+        build_step: Option<usize>,
+    ) -> String {
         let mut hasher = Sha256::new();
         hasher.update(self.width.to_le_bytes());
         hasher.update(self.height.to_le_bytes());
         hasher.update(DEFAULT_CSS.as_bytes());
         hasher.update(DEFAULT_JS.as_bytes());
+        hasher.update(CODE_CSS.as_bytes());
+        if let Some(theme) = &self.theme {
+            hasher.update(theme.fingerprint.as_bytes());
+        }
+        if let Some(step) = build_step {
+            hasher.update(format!("build:{step}").as_bytes());
+        }
         hasher.update(markdown_content.as_bytes());
         hex::encode(hasher.finalize())
     }
 
+    fn highlight_style(&self) -> &str {
+        self.theme
+            .as_ref()
+            .and_then(|theme| theme.code_style.as_deref())
+            .unwrap_or(DEFAULT_HIGHLIGHT_STYLE)
+    }
+
     fn write_css(&self, path: &Path) -> Result<()> {
         let mut file = fs::File::create(path)
             .with_context(|| format!("Failed to create CSS file at {}", path.display()))?;
         file.write_all(DEFAULT_CSS.as_bytes())
             .with_context(|| format!("Failed to write CSS to {}", path.display()))?;
+        let theme_css = match &self.theme {
+            Some(theme) if theme.code_style.is_some() => theme.stylesheet(),
+            Some(theme) => format!("{CODE_CSS}{}", theme.stylesheet()),
+            None => CODE_CSS.to_string(),
+        };
+        file.write_all(theme_css.as_bytes())
+            .with_context(|| format!("Failed to write theme CSS to {}", path.display()))?;
 
         // Append explicit dimensions to ensure full coverage.
         // This is part of the workaround for headless viewport issues: we force the content
@@ -139,7 +217,7 @@ impl SlideGenerator {
             .arg(html)
             .arg("--standalone")
             .arg("--katex")
-            .arg("--highlight-style=pygments") // Use pygments style which outputs clean CSS classes
+            .arg(format!("--highlight-style={}", self.highlight_style()))
             .arg("--css")
             .arg(css)
             .status()
@@ -152,7 +230,7 @@ impl SlideGenerator {
         Ok(())
     }
 
-    fn post_process_html(&self, html_path: &Path) -> Result<()> {
+    fn post_process_html(&self, html_path: &Path, build_step: Option<usize>) -> Result<()> {
         let content = fs::read_to_string(html_path).with_context(|| {
             format!(
                 "Failed to read HTML for post-processing at {}",
@@ -169,11 +247,22 @@ impl SlideGenerator {
         let body_content = &content[body_start..body_end];
         let after_body = &content[body_end..];
 
-        let script = format!("<script>{}</script>", DEFAULT_JS);
+        let mut script = String::new();
+        if let Some(step) = build_step {
+            script.push_str(&format!("<script>window.slideBuildStep = {step};</script>"));
+        }
+        script.push_str(&format!("<script>{}</script>", DEFAULT_JS));
+
+        let logo = self
+            .theme
+            .as_ref()
+            .and_then(|theme| theme.logo.as_deref())
+            .map(|logo| format!("<img class=\"slide-logo\" src=\"{}\">", file_url(logo)))
+            .unwrap_or_default();
 
         let new_content = format!(
-            "{}<div class=\"content\">{}</div>{}{}",
-            before_body, body_content, script, after_body
+            "{}<div class=\"content\">{}</div>{}{}{}",
+            before_body, body_content, logo, script, after_body
         );
 
         fs::write(html_path, new_content).with_context(|| {
@@ -0,0 +1,163 @@
+//! Per-project slide themes referenced from the video front matter.
+//!
+//! ```yaml
+//! slide_theme:
+//!   css: theme/course.css
+//!   fonts: [theme/Inter.woff2]
+//!   logo: theme/logo.svg
+//!   background: theme/background.jpg
+//!   code_style: breezedark
+//! ```
+//!
+//! Paths are relative to the markdown file. Fonts are registered under their file stem
+//! (`Inter` above) so the theme CSS can use them by name. `code_style` is a Pandoc
+//! highlight style name or `.theme` file and replaces the built-in code colours.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlideTheme {
+    pub css: Option<PathBuf>,
+    #[serde(default)]
+    pub fonts: Vec<PathBuf>,
+    pub logo: Option<PathBuf>,
+    pub background: Option<PathBuf>,
+    pub code_style: Option<String>,
+}
+
+/// A theme with its files resolved and fingerprinted for the slide cache
+#[derive(Debug, Clone)]
+pub(super) struct LoadedTheme {
+    pub css: String,
+    pub fonts: Vec<PathBuf>,
+    pub logo: Option<PathBuf>,
+    pub background: Option<PathBuf>,
+    pub code_style: Option<String>,
+    pub fingerprint: String,
+}
+
+impl SlideTheme {
+    pub(super) fn load(&self, base_dir: &Path) -> Result<LoadedTheme> {
+        let mut hasher = Sha256::new();
+        let mut resolve = |path: &Path, what: &str| -> Result<PathBuf> {
+            let resolved = base_dir.join(path);
+            let contents = fs::read(&resolved).with_context(|| {
+                format!("Failed to read slide theme {what} {}", resolved.display())
+            })?;
+            hasher.update(what.as_bytes());
+            hasher.update(&contents);
+            Ok(resolved)
+        };
+
+        let css = match &self.css {
+            Some(path) => {
+                let path = resolve(path, "css")?;
+                fs::read_to_string(&path)
+                    .with_context(|| format!("Slide theme CSS {} is not UTF-8", path.display()))?
+            }
+            None => String::new(),
+        };
+        let fonts = self
+            .fonts
+            .iter()
+            .map(|font| resolve(font, "font"))
+            .collect::<Result<Vec<_>>>()?;
+        let logo = self
+            .logo
+            .as_deref()
+            .map(|logo| resolve(logo, "logo"))
+            .transpose()?;
+        let background = self
+            .background
+            .as_deref()
+            .map(|background| resolve(background, "background"))
+            .transpose()?;
+        let code_style = match self.code_style.as_deref() {
+            Some(style) if style.ends_with(".theme") => Some(
+                resolve(Path::new(style), "code style")?
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            Some(style) if style.trim().is_empty() => bail!("slide_theme.code_style is empty"),
+            Some(style) => {
+                hasher.update(style.as_bytes());
+                Some(style.to_string())
+            }
+            None => None,
+        };
+
+        Ok(LoadedTheme {
+            css,
+            fonts,
+            logo,
+            background,
+            code_style,
+            fingerprint: hex::encode(hasher.finalize()),
+        })
+    }
+}
+
+impl LoadedTheme {
+    /// CSS appended after the built-in stylesheet
+    pub(super) fn stylesheet(&self) -> String {
+        let mut css = String::new();
+        for font in &self.fonts {
+            let family = font
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            css.push_str(&format!(
+                "@font-face {{ font-family: \"{family}\"; src: url(\"{}\"); }}\n",
+                file_url(font)
+            ));
+        }
+        if let Some(background) = &self.background {
+            css.push_str(&format!(
+                "body {{ background: url(\"{}\") center / cover no-repeat; }}\n",
+                file_url(background)
+            ));
+        }
+        css.push_str(&self.css);
+        css.push('\n');
+        css
+    }
+}
+
+pub(super) fn file_url(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_theme_files_relative_to_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("course.css"), "h1 { color: red; }").unwrap();
+        fs::write(dir.path().join("Inter.woff2"), b"font").unwrap();
+
+        let theme: SlideTheme =
+            serde_yaml::from_str("css: course.css\nfonts: [Inter.woff2]\ncode_style: tango\n")
+                .unwrap();
+        let loaded = theme.load(dir.path()).unwrap();
+        let stylesheet = loaded.stylesheet();
+        assert!(stylesheet.contains("font-family: \"Inter\""));
+        assert!(stylesheet.ends_with("h1 { color: red; }\n"));
+        assert_eq!(loaded.code_style.as_deref(), Some("tango"));
+
+        // Editing a theme file changes the cache fingerprint
+        let before = loaded.fingerprint;
+        fs::write(dir.path().join("course.css"), "h1 { color: blue; }").unwrap();
+        assert_ne!(theme.load(dir.path()).unwrap().fingerprint, before);
+
+        let missing: SlideTheme = serde_yaml::from_str("logo: logo.svg\n").unwrap();
+        assert!(missing.load(dir.path()).is_err());
+    }
+}
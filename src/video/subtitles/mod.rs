@@ -2,6 +2,7 @@
 //!
 //! This module provides functionality to:
 //! - Generate ASS (Advanced SubStation Alpha) subtitle files
+//! - Generate SRT and WebVTT sidecar/soft subtitle files
 //! - Remap transcript cue timings from source video time to final timeline time
 //!
 //! The remapping is critical for reels mode where segments may be reordered
@@ -9,6 +10,8 @@
 
 mod ass;
 mod remap;
+mod sidecar;
 
 pub use ass::{AssStyle, generate_ass_file};
-pub use remap::remap_subtitles_to_timeline;
+pub use remap::{RemappedSubtitle, remap_subtitles_to_timeline};
+pub use sidecar::{generate_srt, generate_webvtt};
@@ -0,0 +1,82 @@
+//! Sidecar subtitle formats (SubRip and WebVTT).
+//!
+//! Both formats are written from the same remapped subtitles used for burned-in ASS, so
+//! soft subtitles line up with the rendered timeline.
+
+use std::time::Duration;
+
+use super::remap::RemappedSubtitle;
+
+/// Render subtitles as a SubRip (`.srt`) document
+pub fn generate_srt(subtitles: &[RemappedSubtitle]) -> String {
+    let mut out = String::new();
+    for (idx, subtitle) in subtitles.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            idx + 1,
+            format_timestamp(subtitle.start, ','),
+            format_timestamp(subtitle.end, ','),
+            subtitle.text.trim()
+        ));
+    }
+    out
+}
+
+/// Render subtitles as a WebVTT (`.vtt`) document
+pub fn generate_webvtt(subtitles: &[RemappedSubtitle]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for subtitle in subtitles {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(subtitle.start, '.'),
+            format_timestamp(subtitle.end, '.'),
+            // A blank line would end the cue early
+            subtitle.text.trim().replace("\n\n", "\n")
+        ));
+    }
+    out
+}
+
+fn format_timestamp(time: Duration, millis_separator: char) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        (millis % 3_600_000) / 60_000,
+        (millis % 60_000) / 1000,
+        millis_separator,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subtitle(start_ms: u64, end_ms: u64, text: &str) -> RemappedSubtitle {
+        RemappedSubtitle {
+            start: Duration::from_millis(start_ms),
+            end: Duration::from_millis(end_ms),
+            text: text.to_string(),
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn srt_numbers_cues_with_comma_millis() {
+        let srt = generate_srt(&[
+            subtitle(1_500, 3_000, "Hello"),
+            subtitle(3_661_250, 3_662_000, "Later"),
+        ]);
+        assert_eq!(
+            srt,
+            "1\n00:00:01,500 --> 00:00:03,000\nHello\n\n2\n01:01:01,250 --> 01:01:02,000\nLater\n\n"
+        );
+    }
+
+    #[test]
+    fn webvtt_has_header_and_dot_millis() {
+        let vtt = generate_webvtt(&[subtitle(0, 1_000, "Hi")]);
+        assert_eq!(vtt, "WEBVTT\n\n00:00:00.000 --> 00:00:01.000\nHi\n\n");
+    }
+}
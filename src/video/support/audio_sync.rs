@@ -0,0 +1,292 @@
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use std::process::Command;
+
+use super::ffmpeg::probe_duration_seconds;
+
+/// Envelope frames per second (20 ms RMS windows)
+const ENVELOPE_RATE: f64 = 50.0;
+const DECODE_SAMPLE_RATE: usize = 8000;
+const SAMPLES_PER_FRAME: usize = DECODE_SAMPLE_RATE / ENVELOPE_RATE as usize;
+/// Overlaps shorter than this are too easy to match by chance
+const MIN_OVERLAP_SECONDS: f64 = 10.0;
+/// How much the second (drift) window may move relative to the first
+const DRIFT_SEARCH_SECONDS: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SyncOptions {
+    /// Largest offset searched in either direction
+    pub max_offset: f64,
+    /// Length of reference audio compared per window
+    pub window: f64,
+    /// Estimate clock drift from a second window near the end
+    pub drift: bool,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            max_offset: 120.0,
+            window: 300.0,
+            drift: false,
+        }
+    }
+}
+
+/// Result of aligning a source to a reference: `source = offset + reference * (1 + drift)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncEstimate {
+    pub offset: f64,
+    pub drift: f64,
+    /// Normalized correlation at the peak, in `[-1, 1]`
+    pub confidence: f64,
+}
+
+/// Align `source` to `reference` by cross-correlating their loudness onsets
+pub fn estimate_sync(
+    reference: &Path,
+    source: &Path,
+    options: SyncOptions,
+) -> Result<SyncEstimate> {
+    let reference_duration = probe_duration_seconds(reference)?;
+    let window = options.window.min(reference_duration);
+    let max_lag = (options.max_offset * ENVELOPE_RATE).round() as isize;
+
+    let reference_env = decode_envelope(reference, 0.0, window)?;
+    let source_env = decode_envelope(source, 0.0, window + options.max_offset)?;
+    let first = cross_correlate(&reference_env, &source_env, -max_lag, max_lag)
+        .context("Not enough overlapping audio to sync; try a larger --max-offset")?;
+    let first_offset = first.lag / ENVELOPE_RATE;
+
+    if !options.drift {
+        return Ok(SyncEstimate {
+            offset: first_offset,
+            drift: 0.0,
+            confidence: first.score,
+        });
+    }
+
+    let second_start = reference_duration - window;
+    if second_start < window {
+        bail!(
+            "Reference is too short to estimate drift ({:.0}s, need {:.0}s)",
+            reference_duration,
+            window * 2.0
+        );
+    }
+
+    // Decode the source around where the first offset predicts the window lands
+    let search = DRIFT_SEARCH_SECONDS;
+    let source_start = (second_start + first_offset - search).max(0.0);
+    let reference_env = decode_envelope(reference, second_start, window)?;
+    let source_env = decode_envelope(source, source_start, window + search * 2.0)?;
+    let expected_lag = ((second_start + first_offset - source_start) * ENVELOPE_RATE).round();
+    let search_lag = (search * ENVELOPE_RATE).round() as isize;
+    let second = cross_correlate(
+        &reference_env,
+        &source_env,
+        expected_lag as isize - search_lag,
+        expected_lag as isize + search_lag,
+    )
+    .context("Not enough overlapping audio near the end to estimate drift")?;
+    let second_offset = source_start + second.lag / ENVELOPE_RATE - second_start;
+
+    let center = window / 2.0;
+    let (offset, drift) = solve_drift(
+        (center, first_offset),
+        (second_start + center, second_offset),
+    );
+    Ok(SyncEstimate {
+        offset,
+        drift,
+        confidence: first.score.min(second.score),
+    })
+}
+
+/// Fit `offset + t * drift` through two measured offsets at reference times `t`
+fn solve_drift(first: (f64, f64), second: (f64, f64)) -> (f64, f64) {
+    let drift = (second.1 - first.1) / (second.0 - first.0);
+    (first.1 - drift * first.0, drift)
+}
+
+fn decode_envelope(path: &Path, start: f64, duration: f64) -> Result<Vec<f64>> {
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-ss"])
+        .arg(format!("{start:.3}"))
+        .arg("-t")
+        .arg(format!("{duration:.3}"))
+        .arg("-i")
+        .arg(path)
+        .args(["-vn", "-ac", "1", "-ar"])
+        .arg(DECODE_SAMPLE_RATE.to_string())
+        .args(["-f", "s16le", "-"])
+        .output()
+        .with_context(|| format!("Failed to spawn ffmpeg for {}", path.display()))?;
+
+    if !output.status.success() {
+        bail!(
+            "ffmpeg failed to decode audio from {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let samples: Vec<f64> = output
+        .stdout
+        .chunks_exact(2)
+        .map(|pair| f64::from(i16::from_le_bytes([pair[0], pair[1]])) / f64::from(i16::MAX))
+        .collect();
+    Ok(onset_envelope(&samples))
+}
+
+/// Rising loudness per frame; robust to different microphones and gain
+fn onset_envelope(samples: &[f64]) -> Vec<f64> {
+    let energy: Vec<f64> = samples
+        .chunks(SAMPLES_PER_FRAME)
+        .map(|frame| {
+            let rms = (frame.iter().map(|s| s * s).sum::<f64>() / frame.len() as f64).sqrt();
+            (rms + 1e-4).ln()
+        })
+        .collect();
+
+    let mut onsets = Vec::with_capacity(energy.len());
+    onsets.push(0.0);
+    onsets.extend(energy.windows(2).map(|pair| (pair[1] - pair[0]).max(0.0)));
+    onsets
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Peak {
+    /// Frames to add to a reference index to reach the matching source index
+    lag: f64,
+    score: f64,
+}
+
+/// Pearson correlation of `reference[i]` against `source[i + lag]` over each lag
+fn cross_correlate(
+    reference: &[f64],
+    source: &[f64],
+    min_lag: isize,
+    max_lag: isize,
+) -> Option<Peak> {
+    let min_overlap = (MIN_OVERLAP_SECONDS * ENVELOPE_RATE) as usize;
+    let prefix = |values: &[f64]| {
+        let mut sums = vec![(0.0, 0.0); values.len() + 1];
+        for (i, value) in values.iter().enumerate() {
+            sums[i + 1] = (sums[i].0 + value, sums[i].1 + value * value);
+        }
+        sums
+    };
+    let reference_sums = prefix(reference);
+    let source_sums = prefix(source);
+    let stats = |sums: &[(f64, f64)], start: usize, end: usize| {
+        let n = (end - start) as f64;
+        let sum = sums[end].0 - sums[start].0;
+        let squares = sums[end].1 - sums[start].1;
+        (sum / n, (squares - sum * sum / n).max(0.0))
+    };
+
+    let mut scores = Vec::new();
+    for lag in min_lag..=max_lag {
+        let start = 0.max(-lag) as usize;
+        let end = (reference.len() as isize).min(source.len() as isize - lag);
+        if end <= start as isize || (end as usize - start) < min_overlap {
+            scores.push(None);
+            continue;
+        }
+        let end = end as usize;
+        let (reference_mean, reference_var) = stats(&reference_sums, start, end);
+        let source_start = (start as isize + lag) as usize;
+        let source_end = (end as isize + lag) as usize;
+        let (source_mean, source_var) = stats(&source_sums, source_start, source_end);
+        if reference_var <= f64::EPSILON || source_var <= f64::EPSILON {
+            scores.push(None);
+            continue;
+        }
+
+        let covariance: f64 = reference[start..end]
+            .iter()
+            .zip(&source[source_start..source_end])
+            .map(|(a, b)| (a - reference_mean) * (b - source_mean))
+            .sum();
+        scores.push(Some(covariance / (reference_var * source_var).sqrt()));
+    }
+
+    let (best, score) = scores
+        .iter()
+        .enumerate()
+        .filter_map(|(i, score)| score.map(|score| (i, score)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    // Parabolic interpolation for sub-frame precision
+    let mut refinement = 0.0;
+    if let (Some(Some(before)), Some(Some(after))) =
+        (best.checked_sub(1).map(|i| scores[i]), scores.get(best + 1))
+    {
+        let curvature = before - 2.0 * score + after;
+        if curvature < 0.0 {
+            refinement = 0.5 * (before - after) / curvature;
+        }
+    }
+
+    Some(Peak {
+        lag: min_lag as f64 + best as f64 + refinement,
+        score,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random_envelope(len: usize) -> Vec<f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % 1000) as f64 / 1000.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cross_correlation_finds_positive_and_negative_offsets() {
+        let signal = pseudo_random_envelope(3000);
+        let reference = signal[500..2500].to_vec();
+
+        // Source started recording 300 frames before the reference
+        let peak = cross_correlate(&reference, &signal[200..], -1000, 1000).unwrap();
+        assert!((peak.lag - 300.0).abs() < 0.5);
+        assert!(peak.score > 0.99);
+
+        // Source started 150 frames after the reference
+        let peak = cross_correlate(&reference, &signal[650..], -1000, 1000).unwrap();
+        assert!((peak.lag + 150.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn cross_correlation_requires_minimum_overlap() {
+        let signal = pseudo_random_envelope(100);
+        assert!(cross_correlate(&signal, &signal, 0, 0).is_none());
+    }
+
+    #[test]
+    fn drift_is_solved_from_two_windows() {
+        let (offset, drift) = solve_drift((150.0, 2.015), (3450.0, 2.345));
+        assert!((drift - 0.0001).abs() < 1e-9);
+        assert!((offset - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn onset_envelope_marks_rising_loudness() {
+        let mut samples = vec![0.0; SAMPLES_PER_FRAME * 2];
+        samples.extend(vec![0.5; SAMPLES_PER_FRAME * 2]);
+        let envelope = onset_envelope(&samples);
+        assert_eq!(envelope.len(), 4);
+        assert_eq!(envelope[1], 0.0);
+        assert!(envelope[2] > 0.0);
+        assert_eq!(envelope[3], 0.0);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod audio_sync;
 pub mod ffmpeg;
 pub mod music;
 pub mod transcript;
@@ -0,0 +1,358 @@
+//! Importers for transcripts produced outside WhisperX.
+//!
+//! Every format is converted into the WhisperX segment/word layout so the rest of the
+//! pipeline only deals with one model. Formats without word timing (SRT, most WebVTT and
+//! whisper.cpp output without token timestamps) get word timings spread across each cue
+//! in proportion to word length.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde::Deserialize;
+
+use super::{WhisperOutput, WhisperSegment, WhisperWord};
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+static VTT_TIMESTAMP_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<((?:\d+:)?\d{2}:\d{2}\.\d{3})>").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// WhisperX JSON (`segments` with aligned `words`)
+    WhisperX,
+    /// whisper.cpp full JSON output (`-ojf`)
+    WhisperCpp,
+    WebVtt,
+    Srt,
+}
+
+impl TranscriptFormat {
+    /// Guess the format from the file extension, falling back to the contents
+    pub fn detect(path: &Path, contents: &str) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("srt") => return Self::Srt,
+            Some("vtt") => return Self::WebVtt,
+            _ => {}
+        }
+
+        let trimmed = contents.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with("WEBVTT") {
+            Self::WebVtt
+        } else if trimmed.starts_with('{') {
+            if trimmed.contains("\"transcription\"") {
+                Self::WhisperCpp
+            } else {
+                Self::WhisperX
+            }
+        } else if trimmed.contains("-->") {
+            Self::Srt
+        } else {
+            Self::WhisperX
+        }
+    }
+}
+
+pub(super) fn import_transcript(contents: &str, format: TranscriptFormat) -> Result<WhisperOutput> {
+    let segments = match format {
+        TranscriptFormat::WhisperX => {
+            return serde_json::from_str(contents).context("Failed to parse WhisperX JSON output");
+        }
+        TranscriptFormat::WhisperCpp => parse_whisper_cpp(contents)?,
+        TranscriptFormat::WebVtt => parse_subtitle_blocks(contents, true)?,
+        TranscriptFormat::Srt => parse_subtitle_blocks(contents, false)?,
+    };
+    Ok(WhisperOutput { segments })
+}
+
+#[derive(Debug, Deserialize)]
+struct CppOutput {
+    transcription: Vec<CppSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CppSegment {
+    offsets: CppOffsets,
+    text: String,
+    #[serde(default)]
+    tokens: Vec<CppToken>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CppToken {
+    text: String,
+    offsets: CppOffsets,
+}
+
+/// Milliseconds from the start of the audio
+#[derive(Debug, Deserialize)]
+struct CppOffsets {
+    from: u64,
+    to: u64,
+}
+
+fn parse_whisper_cpp(contents: &str) -> Result<Vec<WhisperSegment>> {
+    let output: CppOutput =
+        serde_json::from_str(contents).context("Failed to parse whisper.cpp JSON output")?;
+
+    let mut segments = Vec::new();
+    for segment in output.transcription {
+        let text = segment.text.trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        let start = segment.offsets.from as f64 / 1000.0;
+        let end = segment.offsets.to as f64 / 1000.0;
+
+        // Tokens are sub-word pieces; a leading space starts a new word
+        let mut words: Vec<WhisperWord> = Vec::new();
+        for token in &segment.tokens {
+            if token.text.starts_with("[_") || token.text.trim().is_empty() {
+                continue;
+            }
+            let token_start = token.offsets.from as f64 / 1000.0;
+            let token_end = token.offsets.to as f64 / 1000.0;
+            match words.last_mut() {
+                Some(word) if !token.text.starts_with(' ') => {
+                    word.word.push_str(&token.text);
+                    word.end = token_end;
+                }
+                _ => words.push(WhisperWord {
+                    word: token.text.trim().to_string(),
+                    start: token_start,
+                    end: token_end,
+                    score: 0.0,
+                }),
+            }
+        }
+        if words.is_empty() {
+            words = estimate_word_timings(&text, start, end);
+        }
+
+        segments.push(WhisperSegment {
+            words,
+            start,
+            end,
+            text,
+        });
+    }
+    Ok(segments)
+}
+
+/// Parse SRT or WebVTT cue blocks
+fn parse_subtitle_blocks(contents: &str, webvtt: bool) -> Result<Vec<WhisperSegment>> {
+    let normalized = contents
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n");
+
+    let mut segments = Vec::new();
+    for block in normalized.split("\n\n") {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+        // Cue identifiers (SRT counters, optional WebVTT ids) precede the timing line
+        let Some(timing_idx) = lines.iter().position(|line| line.contains("-->")) else {
+            continue;
+        };
+        let (start, end) = parse_timing_line(lines[timing_idx])
+            .with_context(|| format!("Invalid cue timing `{}`", lines[timing_idx].trim()))?;
+        let raw_text = lines[timing_idx + 1..].join(" ");
+
+        let words = if webvtt && VTT_TIMESTAMP_TAG_RE.is_match(&raw_text) {
+            words_from_vtt_timestamps(&raw_text, start, end)?
+        } else {
+            estimate_word_timings(&clean_cue_text(&raw_text), start, end)
+        };
+        if words.is_empty() {
+            continue;
+        }
+        let text = words
+            .iter()
+            .map(|w| w.word.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        segments.push(WhisperSegment {
+            words,
+            start,
+            end,
+            text,
+        });
+    }
+
+    if segments.is_empty() && !normalized.trim().is_empty() {
+        bail!("No subtitle cues found");
+    }
+    Ok(segments)
+}
+
+fn parse_timing_line(line: &str) -> Result<(f64, f64)> {
+    let (start, rest) = line.split_once("-->").context("Missing `-->`")?;
+    // WebVTT cue settings follow the end time
+    let end = rest.split_whitespace().next().context("Missing end time")?;
+    let start = parse_timestamp(start.trim())?;
+    let end = parse_timestamp(end)?;
+    Ok((start, end.max(start)))
+}
+
+/// `HH:MM:SS,mmm`, `HH:MM:SS.mmm` or `MM:SS.mmm`
+fn parse_timestamp(value: &str) -> Result<f64> {
+    let value = value.replace(',', ".");
+    let parts: Vec<&str> = value.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (h.parse::<f64>()?, m.parse::<f64>()?, s.parse::<f64>()?),
+        [m, s] => (0.0, m.parse::<f64>()?, s.parse::<f64>()?),
+        _ => bail!("Invalid timestamp `{value}`"),
+    };
+    Ok(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Use inline `<00:00:01.500>` timestamps (e.g. YouTube captions) as word starts
+fn words_from_vtt_timestamps(text: &str, start: f64, end: f64) -> Result<Vec<WhisperWord>> {
+    let mut chunks: Vec<(f64, &str)> = Vec::new();
+    let mut chunk_start = start;
+    let mut last = 0;
+    for caps in VTT_TIMESTAMP_TAG_RE.captures_iter(text) {
+        let tag = caps.get(0).expect("whole match");
+        chunks.push((chunk_start, &text[last..tag.start()]));
+        chunk_start = parse_timestamp(&caps[1])?;
+        last = tag.end();
+    }
+    chunks.push((chunk_start, &text[last..]));
+
+    let mut words = Vec::new();
+    for (idx, (chunk_start, chunk)) in chunks.iter().enumerate() {
+        let chunk_end = chunks.get(idx + 1).map_or(end, |(next, _)| *next);
+        words.extend(estimate_word_timings(
+            &clean_cue_text(chunk),
+            *chunk_start,
+            chunk_end.max(*chunk_start),
+        ));
+    }
+    Ok(words)
+}
+
+fn clean_cue_text(text: &str) -> String {
+    TAG_RE
+        .replace_all(text, "")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Spread a cue's duration over its words in proportion to their length
+pub(super) fn estimate_word_timings(text: &str, start: f64, end: f64) -> Vec<WhisperWord> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let total_chars: usize = words.iter().map(|w| w.chars().count()).sum();
+    if words.is_empty() || total_chars == 0 {
+        return Vec::new();
+    }
+
+    let per_char = (end - start).max(0.0) / total_chars as f64;
+    let mut cursor = start;
+    words
+        .iter()
+        .enumerate()
+        .map(|(idx, word)| {
+            let word_start = cursor;
+            cursor = if idx + 1 == words.len() {
+                end
+            } else {
+                cursor + per_char * word.chars().count() as f64
+            };
+            WhisperWord {
+                word: (*word).to_string(),
+                start: word_start,
+                end: cursor,
+                score: 0.0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_transcript;
+    use super::*;
+
+    #[test]
+    fn detects_formats_from_extension_and_contents() {
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("a.srt"), ""),
+            TranscriptFormat::Srt
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("a.txt"), "WEBVTT\n\n"),
+            TranscriptFormat::WebVtt
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("a.json"), r#"{"transcription": []}"#),
+            TranscriptFormat::WhisperCpp
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("a.json"), r#"{"segments": []}"#),
+            TranscriptFormat::WhisperX
+        );
+    }
+
+    #[test]
+    fn srt_cues_get_estimated_word_timings() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:03,000\r\n<i>Hi</i> there\r\n\r\n2\r\n00:00:05,000 --> 00:00:06,000\r\nBye\r\n";
+        let cues = parse_transcript(srt, TranscriptFormat::Srt).unwrap();
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "Hi there");
+        let words = &cues[0].words;
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].start.as_secs_f64(), 1.0);
+        // "Hi" takes 2 of 7 characters of the two-second cue
+        assert!((words[0].end.as_secs_f64() - (1.0 + 2.0 * 2.0 / 7.0)).abs() < 1e-9);
+        assert_eq!(words[1].end.as_secs_f64(), 3.0);
+        assert_eq!(cues[1].text, "Bye");
+    }
+
+    #[test]
+    fn webvtt_uses_inline_word_timestamps() {
+        let vtt = "WEBVTT\nKind: captions\n\nintro\n00:01.000 --> 00:03.000 align:start position:0%\nhello<00:00:02.000><c> world</c>\n";
+        let cues = parse_transcript(vtt, TranscriptFormat::WebVtt).unwrap();
+
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].text, "hello world");
+        assert_eq!(cues[0].words[0].end.as_secs_f64(), 2.0);
+        assert_eq!(cues[0].words[1].start.as_secs_f64(), 2.0);
+        assert_eq!(cues[0].words[1].end.as_secs_f64(), 3.0);
+    }
+
+    #[test]
+    fn whisper_cpp_tokens_merge_into_words() {
+        let json = r#"{
+            "transcription": [{
+                "offsets": {"from": 0, "to": 2000},
+                "text": " Hello world",
+                "tokens": [
+                    {"text": "[_BEG_]", "offsets": {"from": 0, "to": 0}},
+                    {"text": " Hel", "offsets": {"from": 0, "to": 300}},
+                    {"text": "lo", "offsets": {"from": 300, "to": 600}},
+                    {"text": " world", "offsets": {"from": 700, "to": 1200}}
+                ]
+            }, {
+                "offsets": {"from": 4000, "to": 5000},
+                "text": " Again"
+            }]
+        }"#;
+        let cues = parse_transcript(json, TranscriptFormat::WhisperCpp).unwrap();
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "Hello world");
+        assert_eq!(cues[0].words[0].end.as_secs_f64(), 0.6);
+        assert_eq!(cues[0].words[1].start.as_secs_f64(), 0.7);
+        assert_eq!(cues[1].words[0].start.as_secs_f64(), 4.0);
+        assert_eq!(cues[1].words[0].end.as_secs_f64(), 5.0);
+    }
+}
@@ -1,7 +1,13 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
 use std::time::Duration;
 
+mod import;
+
+pub use import::TranscriptFormat;
+
 /// A single word with its timing information.
 #[derive(Debug, Clone)]
 pub struct WordTiming {
@@ -21,12 +27,13 @@ pub struct TranscriptCue {
     pub source_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// WhisperX JSON layout, also used as the normalized cache format for other backends
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct WhisperOutput {
     segments: Vec<WhisperSegment>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct WhisperSegment {
     #[serde(default)]
     words: Vec<WhisperWord>,
@@ -39,13 +46,12 @@ struct WhisperSegment {
     text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct WhisperWord {
     word: String,
     start: f64,
     end: f64,
     #[serde(default)]
-    #[allow(dead_code)]
     score: f64,
 }
 
@@ -55,7 +61,37 @@ const PAUSE_THRESHOLD_SECONDS: f64 = 0.6;
 pub fn parse_whisper_json(json_str: &str) -> Result<Vec<TranscriptCue>> {
     let output: WhisperOutput =
         serde_json::from_str(json_str).context("Failed to parse WhisperX JSON output")?;
+    Ok(cues_from_output(output))
+}
+
+/// Parse a transcript in any supported format
+pub fn parse_transcript(contents: &str, format: TranscriptFormat) -> Result<Vec<TranscriptCue>> {
+    match format {
+        TranscriptFormat::WhisperX => parse_whisper_json(contents),
+        _ => Ok(cues_from_output(import::import_transcript(
+            contents, format,
+        )?)),
+    }
+}
+
+/// Read a transcript file, detecting its format from the extension and contents
+pub fn read_transcript(path: &Path) -> Result<Vec<TranscriptCue>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transcript file {}", path.display()))?;
+    parse_transcript(&contents, TranscriptFormat::detect(path, &contents))
+        .with_context(|| format!("Failed to parse transcript {}", path.display()))
+}
+
+/// Convert a transcript into the WhisperX JSON layout stored in the cache and project data
+pub fn normalize_transcript(contents: &str, format: TranscriptFormat) -> Result<String> {
+    if format == TranscriptFormat::WhisperX {
+        return Ok(contents.to_string());
+    }
+    let output = import::import_transcript(contents, format)?;
+    serde_json::to_string_pretty(&output).context("Failed to serialize normalized transcript")
+}
 
+fn cues_from_output(output: WhisperOutput) -> Vec<TranscriptCue> {
     let mut cues = Vec::new();
     let mut current_cluster: Vec<WhisperWord> = Vec::new();
 
@@ -103,7 +139,7 @@ pub fn parse_whisper_json(json_str: &str) -> Result<Vec<TranscriptCue>> {
             .partial_cmp(&b.start)
             .unwrap_or(std::cmp::Ordering::Equal)
     });
-    Ok(cues)
+    cues
 }
 
 fn create_cue_from_cluster(cluster: &[WhisperWord]) -> TranscriptCue {
@@ -0,0 +1,31 @@
+//! Speech-to-text with pluggable backends
+//!
+//! Supports multiple transcription backends:
+//! - `Whisperx`: WhisperX through uvx (Python, forced word alignment)
+//! - `WhisperCpp`: the whisper.cpp CLI, for machines without Python or CUDA
+//!
+//! Every backend's output is normalized into the WhisperX JSON layout before it is cached.
+
+mod types;
+mod whisper_cpp;
+mod whisperx;
+
+pub use types::{TranscribeRequest, Transcriber, TranscriberType};
+
+use super::config::{VideoConfig, VideoDirectories};
+
+/// Create a transcriber instance based on type
+pub fn create_transcriber(
+    transcriber_type: TranscriberType,
+    config: &VideoConfig,
+    directories: &VideoDirectories,
+) -> Box<dyn Transcriber> {
+    match transcriber_type {
+        TranscriberType::Whisperx => Box::new(whisperx::WhisperxTranscriber),
+        TranscriberType::WhisperCpp => Box::new(whisper_cpp::WhisperCppTranscriber::new(
+            config.whisper_cpp_binary.clone(),
+            config.whisper_cpp_model.clone(),
+            directories.whisper_cpp_models_dir(),
+        )),
+    }
+}
@@ -0,0 +1,56 @@
+//! Core types for speech-to-text backends
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::video::transcript_language::TranscriptLanguage;
+
+/// Speech-to-text backend used by `ins video transcribe` and `ins video convert`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TranscriberType {
+    /// WhisperX via uvx (Python, word alignment, optional CUDA)
+    #[default]
+    Whisperx,
+    /// whisper.cpp CLI (native, CPU friendly)
+    WhisperCpp,
+}
+
+impl std::fmt::Display for TranscriberType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriberType::Whisperx => write!(f, "WhisperX (uvx, Python)"),
+            TranscriberType::WhisperCpp => write!(f, "whisper.cpp (native CLI)"),
+        }
+    }
+}
+
+/// Options shared by all backends; backends ignore what they do not support
+pub struct TranscribeRequest<'a> {
+    pub language: TranscriptLanguage,
+    pub model: Option<&'a str>,
+    pub compute_type: &'a str,
+    pub device: &'a str,
+    pub vad_method: &'a str,
+}
+
+/// Trait for speech-to-text backends
+pub trait Transcriber {
+    /// Transcribe `input`, writing the backend's native output into `output_dir`
+    ///
+    /// Returns the path of the transcript file; its format is detected by the caller.
+    fn transcribe(
+        &self,
+        input: &Path,
+        output_dir: &Path,
+        request: &TranscribeRequest<'_>,
+    ) -> Result<PathBuf>;
+
+    /// Human-readable name of the backend for logging
+    fn name(&self) -> &'static str;
+
+    /// Check if the backend's dependencies are available
+    fn is_available(&self) -> bool;
+}
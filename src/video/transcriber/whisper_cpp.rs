@@ -0,0 +1,141 @@
+//! whisper.cpp backend
+//!
+//! Pipeline:
+//! 1. Extract 16 kHz mono WAV (the only input whisper.cpp accepts)
+//! 2. Run the whisper.cpp CLI with full JSON output (token timestamps)
+
+use anyhow::{Context, Result, bail};
+use duct::cmd;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::types::{TranscribeRequest, Transcriber};
+use crate::common::commands::command_exists;
+
+/// Binary names used by whisper.cpp releases and distro packages, newest first
+const WHISPER_CPP_BINARIES: &[&str] = &["whisper-cli", "whisper-cpp", "whisper.cpp"];
+const DEFAULT_MODEL: &str = "base";
+
+pub struct WhisperCppTranscriber {
+    binary: Option<String>,
+    model: Option<String>,
+    models_dir: PathBuf,
+}
+
+impl WhisperCppTranscriber {
+    pub fn new(binary: Option<String>, model: Option<String>, models_dir: PathBuf) -> Self {
+        Self {
+            binary,
+            model,
+            models_dir,
+        }
+    }
+
+    fn resolve_binary(&self) -> Option<String> {
+        match &self.binary {
+            Some(binary) => {
+                (command_exists(binary) || Path::new(binary).is_file()).then(|| binary.clone())
+            }
+            None => WHISPER_CPP_BINARIES
+                .iter()
+                .find(|name| command_exists(name))
+                .map(|name| name.to_string()),
+        }
+    }
+
+    /// A model path, or a model name looked up as `ggml-<name>.bin` in the models directory
+    fn resolve_model(&self, requested: Option<&str>) -> Result<PathBuf> {
+        let model = requested.or(self.model.as_deref()).unwrap_or(DEFAULT_MODEL);
+        let as_path = Path::new(model);
+        if as_path.is_file() {
+            return Ok(as_path.to_path_buf());
+        }
+
+        let named = self.models_dir.join(format!("ggml-{model}.bin"));
+        if named.is_file() {
+            return Ok(named);
+        }
+
+        bail!(
+            "whisper.cpp model `{model}` not found at {}. Download it with:\n  curl -L --create-dirs -o {} https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{model}.bin",
+            named.display(),
+            named.display()
+        );
+    }
+
+    fn extract_wav(input: &Path, output: &Path) -> Result<()> {
+        cmd!(
+            "ffmpeg",
+            "-y",
+            "-i",
+            input,
+            "-vn",
+            "-ac",
+            "1",
+            "-ar",
+            "16000",
+            "-c:a",
+            "pcm_s16le",
+            output
+        )
+        .stdout_null()
+        .stderr_null()
+        .run()
+        .with_context(|| format!("Failed to extract audio from {}", input.display()))?;
+        Ok(())
+    }
+}
+
+impl Transcriber for WhisperCppTranscriber {
+    fn transcribe(
+        &self,
+        input: &Path,
+        output_dir: &Path,
+        request: &TranscribeRequest<'_>,
+    ) -> Result<PathBuf> {
+        let binary = self
+            .resolve_binary()
+            .context("whisper.cpp CLI not found (looked for whisper-cli, whisper-cpp)")?;
+        let model = self.resolve_model(request.model)?;
+
+        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+        let wav_path = output_dir.join(format!("{stem}.16k.wav"));
+        Self::extract_wav(input, &wav_path)?;
+
+        // `-of` takes the output path without extension
+        let output_base = output_dir.join(format!("{stem}.whispercpp"));
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get().min(8))
+            .unwrap_or(4)
+            .to_string();
+        let result = cmd!(
+            &binary,
+            "-m",
+            &model,
+            "-f",
+            &wav_path,
+            "-l",
+            request.language.whisper_code(),
+            "-t",
+            &threads,
+            "-ojf",
+            "-of",
+            &output_base
+        )
+        .run()
+        .with_context(|| format!("Failed to run {binary} for {}", input.display()));
+
+        let _ = fs::remove_file(&wav_path);
+        result?;
+
+        Ok(PathBuf::from(format!("{}.json", output_base.display())))
+    }
+
+    fn name(&self) -> &'static str {
+        "whisper.cpp"
+    }
+
+    fn is_available(&self) -> bool {
+        self.resolve_binary().is_some() && command_exists("ffmpeg")
+    }
+}
@@ -0,0 +1,79 @@
+//! WhisperX backend, run through uvx with a pinned Python version
+
+use anyhow::{Context, Result};
+use duct::cmd;
+use std::path::{Path, PathBuf};
+
+use super::types::{TranscribeRequest, Transcriber};
+use crate::common::commands::command_exists;
+use crate::video::support::WHISPERX_UVX_ARGS;
+
+pub struct WhisperxTranscriber;
+
+impl Transcriber for WhisperxTranscriber {
+    fn transcribe(
+        &self,
+        input: &Path,
+        output_dir: &Path,
+        request: &TranscribeRequest<'_>,
+    ) -> Result<PathBuf> {
+        let input_arg = input.to_string_lossy();
+        let output_dir_arg = output_dir.to_string_lossy();
+
+        let mut whisper_args: Vec<&str> = vec![
+            "whisperx",
+            &input_arg,
+            "--language",
+            request.language.whisper_code(),
+            "--output_format",
+            "json",
+            "--output_dir",
+            &output_dir_arg,
+            "--vad_method",
+            request.vad_method,
+            "--compute_type",
+            request.compute_type,
+            "--device",
+            request.device,
+            "--align_model",
+            request.language.align_model(),
+            "--batch_size",
+            "4",
+            "--segment_resolution",
+            "sentence",
+            "--beam_size",
+            "5",
+            "--patience",
+            "1.0",
+            "--max_line_width",
+            "42",
+            "--threads",
+            "8",
+        ];
+
+        if let Some(model) = request.model {
+            whisper_args.push("--model");
+            whisper_args.push(model);
+        }
+
+        // Combine uvx base args with whisperx-specific args
+        let mut full_args: Vec<&str> = WHISPERX_UVX_ARGS.to_vec();
+        full_args.extend(whisper_args);
+
+        cmd("uvx", &full_args)
+            .run()
+            .with_context(|| format!("Failed to run WhisperX for {}", input.display()))?;
+
+        // WhisperX writes `{input_stem}.json` into the output directory
+        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+        Ok(output_dir.join(format!("{stem}.json")))
+    }
+
+    fn name(&self) -> &'static str {
+        "whisperx"
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("uvx")
+    }
+}
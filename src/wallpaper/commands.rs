@@ -9,7 +9,7 @@ use crate::settings::store::{
 };
 use crate::wallpaper::cli::{SetArgs, WallpaperCommands};
 
-use crate::wallpaper::{awww, gnome, instantwm, kwin, sway, x11};
+use crate::wallpaper::{awww, gnome, instantwm, kwin, sway, x11, xfce};
 
 pub async fn handle_wallpaper_command(command: WallpaperCommands, _debug: bool) -> Result<()> {
     match command {
@@ -106,6 +106,10 @@ pub async fn apply_configured_wallpaper() -> Result<()> {
             kwin::apply_wallpaper(&path)?;
             println!("{}", "Wallpaper applied successfully".green());
         }
+        CompositorType::Xfce => {
+            xfce::apply_wallpaper(&path)?;
+            println!("{}", "Wallpaper applied successfully".green());
+        }
         _ => {
             println!(
                 "{}",
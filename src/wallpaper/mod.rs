@@ -9,3 +9,4 @@ pub mod kwin;
 pub mod random;
 pub mod sway;
 pub mod x11;
+pub mod xfce;
@@ -0,0 +1,38 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+pub fn apply_wallpaper(path: &str) -> Result<()> {
+    let abs_path = Path::new(path)
+        .canonicalize()
+        .context("Failed to resolve absolute path for wallpaper")?;
+    let path_str = abs_path.to_string_lossy();
+
+    // xfdesktop keeps one image per monitor and workspace
+    let output = Command::new("xfconf-query")
+        .args(["-c", "xfce4-desktop", "-l"])
+        .output()
+        .context("Failed to list xfdesktop properties with xfconf-query")?;
+    let properties = String::from_utf8_lossy(&output.stdout);
+    let image_properties: Vec<&str> = properties
+        .lines()
+        .filter(|property| property.ends_with("/last-image"))
+        .collect();
+
+    if image_properties.is_empty() {
+        anyhow::bail!("No xfdesktop backdrop found; is xfdesktop running?");
+    }
+
+    for property in image_properties {
+        let output = Command::new("xfconf-query")
+            .args(["-c", "xfce4-desktop", "-p", property, "-s", &path_str])
+            .output()
+            .context("Failed to set wallpaper with xfconf-query")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("xfconf-query failed to set {}: {}", property, stderr.trim());
+        }
+    }
+
+    Ok(())
+}